@@ -38,6 +38,8 @@ pub enum Node<'a> {
     Html(Html<'a>),
     /// Table (GFM extension).
     Table(Table<'a>),
+    /// Display math block (`$$ ... $$`).
+    Math(Math<'a>),
 
     // Inline nodes
     /// Plain text.
@@ -58,6 +60,12 @@ pub enum Node<'a> {
     Delete(Delete<'a>),
     /// Footnote reference (GFM extension).
     FootnoteReference(FootnoteReference<'a>),
+    /// Inline footnote (`^[note]`, Pandoc extension).
+    InlineFootnote(InlineFootnote<'a>),
+    /// Abbreviation occurrence (PHP Markdown Extra).
+    Abbreviation(Abbreviation<'a>),
+    /// Inline math (`$...$`).
+    InlineMath(InlineMath<'a>),
 
     // Definition nodes
     /// Link/image reference definition.
@@ -84,6 +92,11 @@ pub struct Heading<'a> {
     pub depth: u8,
     /// Inline children.
     pub children: Vec<'a, Node<'a>>,
+    /// Explicit ID written as `{#id}` after the heading text, used instead
+    /// of a slug of the text.
+    pub id: Option<&'a str>,
+    /// Classes written as `{.name}` after the heading text.
+    pub classes: Vec<'a, &'a str>,
     /// Source span.
     pub span: Span,
 }
@@ -145,6 +158,64 @@ pub struct CodeBlock<'a> {
     pub span: Span,
 }
 
+impl<'a> CodeBlock<'a> {
+    /// Returns whether the block is a verbatim region: a fence whose meta
+    /// contains the word `raw` (```` ```text raw ````), or content between
+    /// `<!-- raw -->` and `<!-- /raw -->`.
+    #[must_use]
+    pub fn is_raw(&self) -> bool {
+        self.meta.is_some_and(|meta| meta.split_whitespace().any(|word| word == "raw"))
+    }
+
+    /// Returns the `key="value"` attributes in the meta string.
+    ///
+    /// Values may be double-quoted, single-quoted or bare (ending at the next
+    /// whitespace). Words without a `=` are skipped.
+    pub fn meta_attributes(&self) -> impl Iterator<Item = (&'a str, &'a str)> {
+        let mut rest = self.meta.unwrap_or_default();
+        std::iter::from_fn(move || loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                return None;
+            }
+            let key_end = rest.find(|c: char| c == '=' || c.is_whitespace()).unwrap_or(rest.len());
+            let key = &rest[..key_end];
+            rest = &rest[key_end..];
+            let Some(after_eq) = rest.strip_prefix('=') else {
+                continue;
+            };
+            let (value, remaining) = split_meta_value(after_eq);
+            rest = remaining;
+            if !key.is_empty() {
+                return Some((key, value));
+            }
+        })
+    }
+
+    /// Returns the value of the given meta attribute, if present.
+    #[must_use]
+    pub fn meta_value(&self, key: &str) -> Option<&'a str> {
+        self.meta_attributes().find(|(k, _)| *k == key).map(|(_, v)| v)
+    }
+
+    /// Returns the `title` meta attribute (e.g. a file name).
+    #[must_use]
+    pub fn title(&self) -> Option<&'a str> {
+        self.meta_value("title")
+    }
+}
+
+/// Splits a meta attribute value from the text that follows it.
+fn split_meta_value(input: &str) -> (&str, &str) {
+    for quote in ['"', '\''] {
+        if let Some(inner) = input.strip_prefix(quote) {
+            let end = inner.find(quote).unwrap_or(inner.len());
+            return (&inner[..end], inner.get(end + 1..).unwrap_or_default());
+        }
+    }
+    input.split_at(input.find(char::is_whitespace).unwrap_or(input.len()))
+}
+
 /// HTML block.
 #[derive(Debug)]
 pub struct Html<'a> {
@@ -154,6 +225,15 @@ pub struct Html<'a> {
     pub span: Span,
 }
 
+/// Display math block.
+#[derive(Debug)]
+pub struct Math<'a> {
+    /// LaTeX source, without the `$$` delimiters or the `math` code fence.
+    pub value: &'a str,
+    /// Source span.
+    pub span: Span,
+}
+
 /// Table (GFM extension).
 #[derive(Debug)]
 pub struct Table<'a> {
@@ -234,6 +314,15 @@ pub struct InlineCode<'a> {
     pub span: Span,
 }
 
+/// Inline math.
+#[derive(Debug)]
+pub struct InlineMath<'a> {
+    /// LaTeX source, without the `$` delimiters.
+    pub value: &'a str,
+    /// Source span.
+    pub span: Span,
+}
+
 /// Line break.
 #[derive(Debug)]
 pub struct Break {
@@ -287,6 +376,27 @@ pub struct FootnoteReference<'a> {
     pub span: Span,
 }
 
+/// Inline footnote (`^[note]`, Pandoc extension): a footnote whose text is
+/// written where it is referenced.
+#[derive(Debug)]
+pub struct InlineFootnote<'a> {
+    /// Inline children of the note.
+    pub children: Vec<'a, Node<'a>>,
+    /// Source span.
+    pub span: Span,
+}
+
+/// Abbreviation occurrence (PHP Markdown Extra).
+#[derive(Debug)]
+pub struct Abbreviation<'a> {
+    /// The abbreviated text as it appears in the document.
+    pub value: &'a str,
+    /// The expansion from the `*[ABBR]: definition` line.
+    pub title: &'a str,
+    /// Source span.
+    pub span: Span,
+}
+
 // Definition nodes
 
 /// Link/image reference definition.
@@ -331,6 +441,7 @@ impl<'a> Node<'a> {
             Self::CodeBlock(n) => n.span,
             Self::Html(n) => n.span,
             Self::Table(n) => n.span,
+            Self::Math(n) => n.span,
             Self::Text(n) => n.span,
             Self::Emphasis(n) => n.span,
             Self::Strong(n) => n.span,
@@ -340,6 +451,9 @@ impl<'a> Node<'a> {
             Self::Image(n) => n.span,
             Self::Delete(n) => n.span,
             Self::FootnoteReference(n) => n.span,
+            Self::InlineFootnote(n) => n.span,
+            Self::Abbreviation(n) => n.span,
+            Self::InlineMath(n) => n.span,
             Self::Definition(n) => n.span,
             Self::FootnoteDefinition(n) => n.span,
         }
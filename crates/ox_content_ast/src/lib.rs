@@ -5,9 +5,13 @@
 //! providing efficient arena-based allocation.
 
 mod ast;
+mod slug;
 mod span;
 mod visit;
 
 pub use ast::*;
+pub use slug::{
+    collect_text, heading_slug, heading_text, slugify, HeadingAnchor, SlugStyle, UniqueSlugs,
+};
 pub use span::*;
 pub use visit::*;
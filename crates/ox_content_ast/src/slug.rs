@@ -0,0 +1,435 @@
+//! Heading slugs shared by heading IDs and table of contents links.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::{Heading, Node};
+
+/// How heading text is turned into a slug.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SlugStyle {
+    /// Match the anchors GitHub generates for headings, so `#section` links
+    /// work both on GitHub and on the site.
+    ///
+    /// The text is lowercased, every character other than letters, digits,
+    /// combining marks, `_`, `-` and spaces is removed (including emoji),
+    /// and each space becomes `-`. As on GitHub, hyphens are not collapsed:
+    /// `C++ & Rust` becomes `c--rust`.
+    #[default]
+    GitHub,
+    /// Lowercase the text, keep Unicode letters and digits and join words
+    /// with `-`. Headings without letters or digits get an empty slug.
+    Unicode,
+    /// Produce a slug containing only `a-z`, `0-9` and `-`.
+    ///
+    /// Accented Latin letters, Cyrillic and Japanese kana are transliterated.
+    /// When other characters (such as kanji) have to be dropped, a hash of
+    /// the heading text is appended so different headings keep different
+    /// slugs. A heading with nothing left becomes `section-<hash>`.
+    Ascii,
+}
+
+impl SlugStyle {
+    /// Returns the style name (`github`, `unicode` or `ascii`).
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::GitHub => "github",
+            Self::Unicode => "unicode",
+            Self::Ascii => "ascii",
+        }
+    }
+
+    /// Looks up a style by name, ignoring ASCII case.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::GitHub, Self::Unicode, Self::Ascii]
+            .into_iter()
+            .find(|style| style.as_str().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Converts text to a URL-friendly slug.
+#[must_use]
+pub fn slugify(text: &str, style: SlugStyle) -> String {
+    match style {
+        SlugStyle::GitHub => github_slug(text),
+        SlugStyle::Unicode => join_words(
+            &text
+                .to_lowercase()
+                .chars()
+                .map(|c| if c.is_alphanumeric() || c == '-' { c } else { ' ' })
+                .collect::<String>(),
+        ),
+        SlugStyle::Ascii => ascii_slug(text),
+    }
+}
+
+/// Makes heading slugs unique within a document.
+///
+/// The first heading with a slug keeps it; later ones get `-1`, `-2`, ...
+/// appended, skipping suffixed slugs that are already taken, so a heading
+/// titled "Overview 1" never shares an ID with the second "Overview".
+#[derive(Debug, Clone, Default)]
+pub struct UniqueSlugs {
+    seen: HashSet<String>,
+}
+
+impl UniqueSlugs {
+    /// Creates an empty set of slugs.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `slug`, or the first free suffixed variant of it, and marks
+    /// the result as taken.
+    pub fn unique(&mut self, slug: String) -> String {
+        let mut candidate = slug.clone();
+        let mut n = 0;
+        while self.seen.contains(&candidate) {
+            n += 1;
+            candidate = format!("{slug}-{n}");
+        }
+        self.seen.insert(candidate.clone());
+        candidate
+    }
+}
+
+/// A heading ID assigned by a renderer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadingAnchor {
+    /// Heading depth (1-6), after heading normalization.
+    pub depth: u8,
+    /// Plain heading text, without section numbers.
+    pub text: String,
+    /// The `id` attribute, after deduplication.
+    pub slug: String,
+}
+
+/// Returns the plain text of a heading, as used for its slug.
+#[must_use]
+pub fn heading_text(heading: &Heading<'_>) -> String {
+    let mut text = String::new();
+    for child in &heading.children {
+        collect_text(child, &mut text);
+    }
+    text
+}
+
+/// Returns the ID of a heading before deduplication: its explicit `{#id}`,
+/// or a slug of its text.
+#[must_use]
+pub fn heading_slug(heading: &Heading<'_>, style: SlugStyle) -> String {
+    heading.id.map_or_else(|| slugify(&heading_text(heading), style), String::from)
+}
+
+/// Recursively collects text from inline nodes.
+pub fn collect_text(node: &Node<'_>, text: &mut String) {
+    match node {
+        Node::Text(t) => text.push_str(t.value),
+        Node::Abbreviation(a) => text.push_str(a.value),
+        Node::InlineCode(c) => text.push_str(c.value),
+        Node::InlineMath(m) => text.push_str(m.value),
+        Node::Emphasis(e) => e.children.iter().for_each(|child| collect_text(child, text)),
+        Node::Strong(s) => s.children.iter().for_each(|child| collect_text(child, text)),
+        Node::Delete(d) => d.children.iter().for_each(|child| collect_text(child, text)),
+        Node::Link(l) => l.children.iter().for_each(|child| collect_text(child, text)),
+        _ => {}
+    }
+}
+
+/// Slugifies `text` like GitHub's heading anchors (and `github-slugger`).
+fn github_slug(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' => Some(c),
+            _ if c.is_alphanumeric() || is_combining_mark(c) || is_connector(c) => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Combining marks that GitHub keeps but `char::is_alphabetic` does not
+/// cover, such as accents written as separate characters, Indic viramas
+/// and nuktas, Thai tone marks and variation selectors.
+const COMBINING_MARKS: &[(char, char)] = &[
+    ('\u{0300}', '\u{036F}'),
+    ('\u{0483}', '\u{0489}'),
+    ('\u{0591}', '\u{05BD}'),
+    ('\u{064B}', '\u{065F}'),
+    ('\u{093C}', '\u{093C}'),
+    ('\u{094D}', '\u{094D}'),
+    ('\u{0951}', '\u{0954}'),
+    ('\u{09BC}', '\u{09BC}'),
+    ('\u{09CD}', '\u{09CD}'),
+    ('\u{0A3C}', '\u{0A3C}'),
+    ('\u{0A4D}', '\u{0A4D}'),
+    ('\u{0ABC}', '\u{0ABC}'),
+    ('\u{0ACD}', '\u{0ACD}'),
+    ('\u{0B3C}', '\u{0B3C}'),
+    ('\u{0B4D}', '\u{0B4D}'),
+    ('\u{0BCD}', '\u{0BCD}'),
+    ('\u{0C4D}', '\u{0C4D}'),
+    ('\u{0CBC}', '\u{0CBC}'),
+    ('\u{0CCD}', '\u{0CCD}'),
+    ('\u{0D4D}', '\u{0D4D}'),
+    ('\u{0DCA}', '\u{0DCA}'),
+    ('\u{0E47}', '\u{0E4E}'),
+    ('\u{0EC8}', '\u{0ECE}'),
+    ('\u{1AB0}', '\u{1AFF}'),
+    ('\u{1DC0}', '\u{1DFF}'),
+    ('\u{20D0}', '\u{20FF}'),
+    ('\u{3099}', '\u{309A}'),
+    ('\u{FE00}', '\u{FE0F}'),
+    ('\u{FE20}', '\u{FE2F}'),
+];
+
+/// Returns whether `c` is a combining mark missing from `is_alphabetic`.
+fn is_combining_mark(c: char) -> bool {
+    COMBINING_MARKS.iter().any(|&(start, end)| (start..=end).contains(&c))
+}
+
+/// Returns whether `c` is connector punctuation, such as `_`.
+const fn is_connector(c: char) -> bool {
+    matches!(
+        c,
+        '_' | '\u{203F}' | '\u{2040}' | '\u{2054}' | '\u{FE33}' | '\u{FE34}' | '\u{FE4D}'
+            ..='\u{FE4F}' | '\u{FF3F}'
+    )
+}
+
+/// Joins the whitespace-separated words of `text` with `-`.
+fn join_words(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join("-")
+}
+
+fn ascii_slug(text: &str) -> String {
+    let mut ascii = String::with_capacity(text.len());
+    let mut dropped = false;
+    let mut double_next = false;
+    let mut chars = text.chars().flat_map(char::to_lowercase).peekable();
+
+    while let Some(c) = chars.next() {
+        if c.is_ascii_alphanumeric() || c == '-' {
+            ascii.push(c);
+            continue;
+        }
+        if c == 'っ' || c == 'ッ' {
+            double_next = true;
+            continue;
+        }
+        let romaji = kana_romaji(c).map(|syllable| {
+            let small = chars.peek().copied().and_then(small_kana_vowel);
+            match (syllable.strip_suffix('i'), small) {
+                (Some(base), Some(vowel)) if syllable.len() > 1 => {
+                    chars.next();
+                    let glide = if base.ends_with("sh") || base.ends_with("ch") || base == "j" {
+                        ""
+                    } else {
+                        "y"
+                    };
+                    format!("{base}{glide}{vowel}")
+                }
+                _ => syllable.to_string(),
+            }
+        });
+
+        if let Some(romaji) = romaji {
+            if std::mem::take(&mut double_next) {
+                let first = romaji.chars().next().filter(|c| !"aiueon".contains(*c));
+                match first {
+                    Some('c') => ascii.push('t'),
+                    Some(c) => ascii.push(c),
+                    None => {}
+                }
+            }
+            ascii.push_str(&romaji);
+        } else if let Some(folded) = fold_latin(c).or_else(|| cyrillic_latin(c)) {
+            ascii.push_str(folded);
+        } else if c == 'ー' {
+            // Katakana long vowel mark
+        } else if c.is_alphanumeric() {
+            dropped = true;
+            ascii.push(' ');
+        } else {
+            ascii.push(' ');
+        }
+    }
+
+    let slug = join_words(&ascii);
+    if slug.is_empty() {
+        format!("section-{:08x}", fnv1a(text))
+    } else if dropped {
+        let mut slug = slug;
+        let _ = write!(slug, "-{:08x}", fnv1a(text));
+        slug
+    } else {
+        slug
+    }
+}
+
+/// 32-bit FNV-1a hash, stable across platforms and releases.
+fn fnv1a(text: &str) -> u32 {
+    text.bytes().fold(0x811c_9dc5, |hash, byte| (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193))
+}
+
+/// Hepburn romanization of hiragana `U+3041..=U+3096`.
+const HIRAGANA_ROMAJI: [&str; 86] = [
+    "a", "a", "i", "i", "u", "u", "e", "e", "o", "o", "ka", "ga", "ki", "gi", "ku", "gu", "ke",
+    "ge", "ko", "go", "sa", "za", "shi", "ji", "su", "zu", "se", "ze", "so", "zo", "ta", "da",
+    "chi", "ji", "", "tsu", "zu", "te", "de", "to", "do", "na", "ni", "nu", "ne", "no", "ha", "ba",
+    "pa", "hi", "bi", "pi", "fu", "bu", "pu", "he", "be", "pe", "ho", "bo", "po", "ma", "mi", "mu",
+    "me", "mo", "ya", "ya", "yu", "yu", "yo", "yo", "ra", "ri", "ru", "re", "ro", "wa", "wa", "i",
+    "e", "o", "n", "vu", "ka", "ke",
+];
+
+/// Romanizes a hiragana or katakana character.
+fn kana_romaji(c: char) -> Option<&'static str> {
+    let code = match u32::from(c) {
+        code @ 0x3041..=0x3096 => code,
+        code @ 0x30A1..=0x30F6 => code - 0x60,
+        _ => return None,
+    };
+    Some(HIRAGANA_ROMAJI[(code - 0x3041) as usize])
+}
+
+/// Returns the vowel of a small ya/yu/yo, which combines with the previous kana.
+fn small_kana_vowel(c: char) -> Option<&'static str> {
+    match c {
+        'ゃ' | 'ャ' => Some("a"),
+        'ゅ' | 'ュ' => Some("u"),
+        'ょ' | 'ョ' => Some("o"),
+        _ => None,
+    }
+}
+
+/// Folds a lowercase accented Latin letter to ASCII.
+fn fold_latin(c: char) -> Option<&'static str> {
+    let folded = match c {
+        'à'..='å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è'..='ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì'..='ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò'..='ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' => "t",
+        'þ' => "th",
+        'ù'..='ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    };
+    Some(folded)
+}
+
+/// Transliterates a lowercase Russian Cyrillic letter.
+fn cyrillic_latin(c: char) -> Option<&'static str> {
+    const LETTERS: [&str; 32] = [
+        "a", "b", "v", "g", "d", "e", "zh", "z", "i", "y", "k", "l", "m", "n", "o", "p", "r", "s",
+        "t", "u", "f", "kh", "ts", "ch", "sh", "shch", "", "y", "", "e", "yu", "ya",
+    ];
+    match c {
+        'а'..='я' => Some(LETTERS[(u32::from(c) - u32::from('а')) as usize]),
+        'ё' => Some("yo"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unicode_style() {
+        assert_eq!(slugify("Hello, World!", SlugStyle::Unicode), "hello-world");
+        assert_eq!(slugify("日本語の見出し", SlugStyle::Unicode), "日本語の見出し");
+        assert_eq!(slugify("🎉", SlugStyle::Unicode), "");
+    }
+
+    #[test]
+    fn test_github_style() {
+        // Expected anchors as generated by GitHub
+        let cases = [
+            ("Hello, World!", "hello-world"),
+            ("Getting Started", "getting-started"),
+            ("What's new in v2.0?", "whats-new-in-v20"),
+            ("C++ & Rust", "c--rust"),
+            ("Foo -- Bar", "foo----bar"),
+            ("Option `--force`", "option---force"),
+            ("snake_case_name", "snake_case_name"),
+            ("API (v2)", "api-v2"),
+            ("1. Introduction", "1-introduction"),
+            ("a/b: c.d", "ab-cd"),
+            ("🎉 Release Notes 🎉", "-release-notes-"),
+            ("🎉", ""),
+            ("Émojis & Ünïcödé", "émojis--ünïcödé"),
+            ("Cafe\u{301}", "cafe\u{301}"),
+            ("日本語の見出し", "日本語の見出し"),
+            ("Привет, мир", "привет-мир"),
+            ("हिन्दी", "हिन्दी"),
+            ("Tab\tand\u{a0}nbsp", "tabandnbsp"),
+            ("<img> & \"quotes\"", "img--quotes"),
+            ("100% ¥€$", "100-"),
+            ("-leading-and-trailing-", "-leading-and-trailing-"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(slugify(text, SlugStyle::GitHub), expected, "{text}");
+        }
+        assert_eq!(SlugStyle::default(), SlugStyle::GitHub);
+        assert_eq!(SlugStyle::from_name("GitHub"), Some(SlugStyle::GitHub));
+    }
+
+    #[test]
+    fn test_unique_slugs() {
+        let mut slugs = UniqueSlugs::new();
+        let result: Vec<_> = ["overview", "overview", "overview-1", "overview", "other"]
+            .into_iter()
+            .map(|slug| slugs.unique(slug.to_string()))
+            .collect();
+        assert_eq!(result, ["overview", "overview-1", "overview-1-1", "overview-2", "other"]);
+    }
+
+    #[test]
+    fn test_ascii_transliteration() {
+        assert_eq!(slugify("Crème Brûlée", SlugStyle::Ascii), "creme-brulee");
+        assert_eq!(slugify("Straße", SlugStyle::Ascii), "strasse");
+        assert_eq!(slugify("Привет мир", SlugStyle::Ascii), "privet-mir");
+        assert_eq!(slugify("こんにちは", SlugStyle::Ascii), "konnichiha");
+        assert_eq!(slugify("きょうと と ちょっと", SlugStyle::Ascii), "kyouto-to-chotto");
+        assert_eq!(slugify("マッチ", SlugStyle::Ascii), "matchi");
+    }
+
+    #[test]
+    fn test_ascii_never_empty() {
+        let cjk = slugify("日本語", SlugStyle::Ascii);
+        let emoji = slugify("🎉🚀", SlugStyle::Ascii);
+        let punctuation = slugify("?!…", SlugStyle::Ascii);
+        for slug in [&cjk, &emoji, &punctuation] {
+            assert!(slug.starts_with("section-") && slug.len() == 16, "{slug}");
+            assert!(slug.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-'));
+        }
+        assert_ne!(cjk, emoji);
+        assert_ne!(emoji, punctuation);
+        assert_eq!(cjk, slugify("日本語", SlugStyle::Ascii));
+
+        // Dropped kanji keep distinct headings distinct.
+        let tower = slugify("東京タワー", SlugStyle::Ascii);
+        assert!(tower.starts_with("tawa-"), "{tower}");
+        assert_ne!(tower, slugify("大阪タワー", SlugStyle::Ascii));
+    }
+}
@@ -78,6 +78,27 @@ impl Position {
     pub const fn new(line: u32, column: u32, offset: u32) -> Self {
         Self { line, column, offset }
     }
+
+    /// Returns the position of byte `offset` in `source`. Columns count
+    /// characters, so they match what editors show.
+    #[must_use]
+    pub fn from_offset(source: &str, offset: u32) -> Self {
+        let before = source.get(..offset as usize).unwrap_or(source);
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line = before.matches('\n').count() + 1;
+        let column = before[line_start..].chars().count() + 1;
+        Self::new(
+            u32::try_from(line).unwrap_or(u32::MAX),
+            u32::try_from(column).unwrap_or(u32::MAX),
+            offset,
+        )
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
 }
 
 #[cfg(test)]
@@ -116,4 +137,13 @@ mod tests {
         let span = Span::new(0, 5);
         assert_eq!(span.source_text(source), "hello");
     }
+
+    #[test]
+    fn test_position_from_offset() {
+        let source = "ab\n日本語x\n";
+        assert_eq!(Position::from_offset(source, 0), Position::new(1, 1, 0));
+        assert_eq!(Position::from_offset(source, 3), Position::new(2, 1, 3));
+        assert_eq!(Position::from_offset(source, 12), Position::new(2, 4, 12));
+        assert_eq!(Position::from_offset(source, 14).to_string(), "line 3, column 1");
+    }
 }
@@ -53,6 +53,9 @@ pub trait Visit<'a> {
         walk_table(self, table);
     }
 
+    /// Visits a display math block.
+    fn visit_math(&mut self, _math: &Math<'a>) {}
+
     /// Visits a table row.
     fn visit_table_row(&mut self, table_row: &TableRow<'a>) {
         walk_table_row(self, table_row);
@@ -98,6 +101,17 @@ pub trait Visit<'a> {
     /// Visits a footnote reference.
     fn visit_footnote_reference(&mut self, _footnote_ref: &FootnoteReference<'a>) {}
 
+    /// Visits an inline footnote.
+    fn visit_inline_footnote(&mut self, footnote: &InlineFootnote<'a>) {
+        walk_inline_footnote(self, footnote);
+    }
+
+    /// Visits an abbreviation.
+    fn visit_abbreviation(&mut self, _abbreviation: &Abbreviation<'a>) {}
+
+    /// Visits inline math.
+    fn visit_inline_math(&mut self, _inline_math: &InlineMath<'a>) {}
+
     /// Visits a definition.
     fn visit_definition(&mut self, _definition: &Definition<'a>) {}
 
@@ -126,6 +140,7 @@ pub fn walk_node<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, node: &Node<'a>) {
         Node::CodeBlock(n) => visitor.visit_code_block(n),
         Node::Html(n) => visitor.visit_html(n),
         Node::Table(n) => visitor.visit_table(n),
+        Node::Math(n) => visitor.visit_math(n),
         Node::Text(n) => visitor.visit_text(n),
         Node::Emphasis(n) => visitor.visit_emphasis(n),
         Node::Strong(n) => visitor.visit_strong(n),
@@ -135,6 +150,9 @@ pub fn walk_node<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, node: &Node<'a>) {
         Node::Image(n) => visitor.visit_image(n),
         Node::Delete(n) => visitor.visit_delete(n),
         Node::FootnoteReference(n) => visitor.visit_footnote_reference(n),
+        Node::InlineFootnote(n) => visitor.visit_inline_footnote(n),
+        Node::Abbreviation(n) => visitor.visit_abbreviation(n),
+        Node::InlineMath(n) => visitor.visit_inline_math(n),
         Node::Definition(n) => visitor.visit_definition(n),
         Node::FootnoteDefinition(n) => visitor.visit_footnote_definition(n),
     }
@@ -224,6 +242,16 @@ pub fn walk_delete<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, delete: &Delete<'
     }
 }
 
+/// Walks through an inline footnote's children.
+pub fn walk_inline_footnote<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    footnote: &InlineFootnote<'a>,
+) {
+    for child in &footnote.children {
+        visitor.visit_node(child);
+    }
+}
+
 /// Walks through a footnote definition's children.
 pub fn walk_footnote_definition<'a, V: Visit<'a> + ?Sized>(
     visitor: &mut V,
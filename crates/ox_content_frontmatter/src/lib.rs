@@ -0,0 +1,813 @@
+//! Frontmatter parsing for Ox Content.
+//!
+//! Shared by the Node.js and WebAssembly bindings.
+//!
+//! The format is detected from the first line of the file:
+//!
+//! | First line | Format | Ends at |
+//! |------------|--------|---------|
+//! | `---` | YAML | the next `---` line |
+//! | `+++` | TOML | the next `+++` line |
+//! | `;;;` | JSON | the next `;;;` line |
+//! | starts with `{` | JSON | the end of the JSON object |
+//!
+//! Only the first line is checked, so the formats never compete. A file
+//! whose frontmatter block is not closed has no frontmatter. Content starting
+//! with `{` is only treated as frontmatter when it begins with a complete JSON
+//! object followed by a line break; use `;;;` delimiters to get error reports
+//! for malformed JSON.
+//!
+//! YAML supports the subset used in Markdown frontmatter: block mappings and
+//! sequences (also at their key's indentation), flow sequences (`[a, b]`)
+//! and mappings (`{a: 1}`), quoted scalars, plain scalars continued on
+//! indented lines, block scalars (`|` and `>`) and comments. Problems are
+//! reported with line numbers relative to the whole file instead of being
+//! silently dropped.
+
+mod toml;
+
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::toml::parse_toml;
+
+/// A problem found while parsing frontmatter.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("frontmatter line {line}: {message}")]
+pub struct FrontmatterError {
+    /// 1-based line number in the file.
+    pub line: usize,
+    /// Description of the problem.
+    pub message: String,
+}
+
+/// A frontmatter format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrontmatterFormat {
+    /// YAML between `---` lines.
+    Yaml,
+    /// TOML between `+++` lines.
+    Toml,
+    /// JSON between `;;;` lines, or a leading JSON object.
+    Json,
+}
+
+impl FrontmatterFormat {
+    /// Returns the format name (`yaml`, `toml` or `json`).
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Yaml => "yaml",
+            Self::Toml => "toml",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Frontmatter split from a Markdown file.
+#[derive(Debug, Default)]
+pub struct Frontmatter {
+    /// The Markdown after the frontmatter block.
+    pub content: String,
+    /// Parsed values. Entries with errors are left out.
+    pub data: Map<String, Value>,
+    /// Problems found while parsing, in line order.
+    pub errors: Vec<FrontmatterError>,
+    /// The detected format, or `None` when the file has no frontmatter.
+    pub format: Option<FrontmatterFormat>,
+    /// The frontmatter exactly as written between its delimiter lines, or
+    /// the whole object for JSON frontmatter without delimiters. Empty when
+    /// the file has no frontmatter.
+    pub raw: String,
+}
+
+/// Splits the leading frontmatter block from `source` and parses it.
+///
+/// A file without frontmatter is returned unchanged.
+pub fn parse_frontmatter(source: &str) -> Frontmatter {
+    if source.starts_with('{') {
+        if let Some(frontmatter) = leading_json(source) {
+            return frontmatter;
+        }
+    }
+
+    let delimited = [
+        ("---", FrontmatterFormat::Yaml),
+        ("+++", FrontmatterFormat::Toml),
+        (";;;", FrontmatterFormat::Json),
+    ]
+    .into_iter()
+    .find_map(|(delimiter, format)| {
+        split_delimited(source, delimiter).map(|(text, content)| (format, text, content))
+    });
+    let Some((format, text, content)) = delimited else {
+        return Frontmatter { content: source.to_string(), ..Frontmatter::default() };
+    };
+
+    // The frontmatter starts on the line after the opening delimiter.
+    let (data, mut errors) = match format {
+        FrontmatterFormat::Yaml => {
+            let mut parser = YamlParser::new(text, 2);
+            let data = parser.parse_document();
+            (data, parser.errors)
+        }
+        FrontmatterFormat::Toml => parse_toml(text, 2),
+        FrontmatterFormat::Json => parse_json(text, 2),
+    };
+    errors.sort_by_key(|error| error.line);
+    Frontmatter {
+        content: content.to_string(),
+        data,
+        errors,
+        format: Some(format),
+        raw: text.to_string(),
+    }
+}
+
+/// Returns the text between `delimiter` lines and the content after them.
+fn split_delimited<'s>(source: &'s str, delimiter: &str) -> Option<(&'s str, &'s str)> {
+    let rest = source.strip_prefix(delimiter)?;
+    let rest = rest.strip_prefix('\n').or_else(|| rest.strip_prefix("\r\n"))?;
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == delimiter {
+            let content = rest[offset + line.len()..].trim_start_matches(['\r', '\n']);
+            return Some((&rest[..offset], content));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Parses a JSON object at the start of the file, if there is one.
+fn leading_json(source: &str) -> Option<Frontmatter> {
+    let mut stream = serde_json::Deserializer::from_str(source).into_iter::<Map<String, Value>>();
+    let data = stream.next()?.ok()?;
+    let (raw, rest) = source.split_at(stream.byte_offset());
+    let rest = rest.trim_start_matches([' ', '\t']);
+    let content = rest.strip_prefix("\r\n").or_else(|| rest.strip_prefix('\n'));
+    let content = content.or_else(|| rest.is_empty().then_some(rest))?;
+    Some(Frontmatter {
+        content: content.trim_start_matches(['\r', '\n']).to_string(),
+        data,
+        errors: Vec::new(),
+        format: Some(FrontmatterFormat::Json),
+        raw: raw.to_string(),
+    })
+}
+
+/// Parses JSON between `;;;` lines. The braces around the top-level object
+/// may be left out.
+fn parse_json(text: &str, first_line: usize) -> (Map<String, Value>, Vec<FrontmatterError>) {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return (Map::new(), Vec::new());
+    }
+    let result = if trimmed.starts_with('{') {
+        serde_json::from_str::<Value>(text)
+    } else {
+        // Added without a line break, so line numbers stay the same.
+        serde_json::from_str::<Value>(&format!("{{{text}}}"))
+    };
+    match result {
+        Ok(Value::Object(data)) => (data, Vec::new()),
+        Ok(_) => (
+            Map::new(),
+            vec![FrontmatterError {
+                line: first_line,
+                message: "JSON frontmatter must be an object".to_string(),
+            }],
+        ),
+        Err(error) => {
+            let message = error.to_string();
+            let message = message.split(" at line ").next().unwrap_or(&message).to_string();
+            let line = first_line + error.line().saturating_sub(1);
+            (Map::new(), vec![FrontmatterError { line, message }])
+        }
+    }
+}
+
+struct Line<'a> {
+    number: usize,
+    indent: usize,
+    text: &'a str,
+}
+
+struct YamlParser<'a> {
+    lines: Vec<Line<'a>>,
+    raw: Vec<&'a str>,
+    first_line: usize,
+    pos: usize,
+    errors: Vec<FrontmatterError>,
+}
+
+impl<'a> YamlParser<'a> {
+    fn new(yaml: &'a str, first_line: usize) -> Self {
+        let raw: Vec<&str> = yaml.lines().map(|line| line.trim_end_matches('\r')).collect();
+        let mut errors = Vec::new();
+        let lines = raw
+            .iter()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                let text = line.trim_start_matches(' ');
+                if text.is_empty() || text.starts_with('#') {
+                    return None;
+                }
+                let number = first_line + i;
+                if text.starts_with('\t') {
+                    errors.push(FrontmatterError {
+                        line: number,
+                        message: "tab character used for indentation".to_string(),
+                    });
+                    return None;
+                }
+                Some(Line { number, indent: line.len() - text.len(), text: text.trim_end() })
+            })
+            .collect();
+        Self { lines, raw, first_line, pos: 0, errors }
+    }
+
+    fn error(&mut self, line: usize, message: impl Into<String>) {
+        self.errors.push(FrontmatterError { line, message: message.into() });
+    }
+
+    fn parse_document(&mut self) -> Map<String, Value> {
+        let mut data = Map::new();
+        while self.pos < self.lines.len() {
+            let indent = self.lines[self.pos].indent;
+            if self.lines[self.pos].text.starts_with('-') {
+                let line = self.lines[self.pos].number;
+                self.error(line, "expected a mapping at the top level, found a list");
+                self.skip_block(indent);
+                continue;
+            }
+            data.extend(self.parse_mapping(indent));
+            if self.pos < self.lines.len() {
+                let line = self.lines[self.pos].number;
+                self.error(line, "unexpected indentation");
+                self.skip_block(0);
+            }
+        }
+        data
+    }
+
+    /// Skips the current line and every following line indented deeper than `indent`.
+    fn skip_block(&mut self, indent: usize) {
+        self.pos += 1;
+        while self.lines.get(self.pos).is_some_and(|line| line.indent > indent) {
+            self.pos += 1;
+        }
+    }
+
+    fn parse_mapping(&mut self, indent: usize) -> Map<String, Value> {
+        let mut map = Map::new();
+        while let Some(line) = self.lines.get(self.pos) {
+            if line.indent != indent {
+                break;
+            }
+            let (number, text) = (line.number, line.text);
+            if text.starts_with("- ") || text == "-" {
+                self.error(number, "unexpected list item in a mapping");
+                self.skip_block(indent);
+                continue;
+            }
+            let Some((key, value)) = split_key_value(text) else {
+                self.error(number, "expected `key: value`");
+                self.skip_block(indent);
+                continue;
+            };
+            self.pos += 1;
+            let key = match parse_scalar(key) {
+                Ok(Value::String(key)) => key,
+                Ok(other) => other.to_string(),
+                Err(message) => {
+                    self.error(number, message);
+                    self.skip_nested(indent);
+                    continue;
+                }
+            };
+            if let Some(value) = self.parse_key_value(value, indent, number) {
+                if map.contains_key(&key) {
+                    self.error(number, format!("duplicate key `{key}`"));
+                }
+                map.insert(key, value);
+            }
+        }
+        map
+    }
+
+    fn parse_sequence(&mut self, indent: usize) -> Vec<Value> {
+        let mut items = Vec::new();
+        while let Some(line) = self.lines.get(self.pos) {
+            if line.indent != indent || !(line.text.starts_with("- ") || line.text == "-") {
+                break;
+            }
+            let (number, text) = (line.number, line.text);
+            let item = text[1..].trim_start();
+            self.pos += 1;
+
+            if let Some((key, value)) = split_key_value(item).filter(|_| !starts_flow(item)) {
+                // `- key: value` starts a mapping whose other keys are indented
+                // to the first key's column.
+                let item_indent = indent + line_offset(text, item);
+                let mut map = Map::new();
+                let key = parse_scalar(key).map_or_else(
+                    |_| key.to_string(),
+                    |k| match k {
+                        Value::String(key) => key,
+                        other => other.to_string(),
+                    },
+                );
+                if let Some(value) = self.parse_key_value(value, item_indent, number) {
+                    map.insert(key, value);
+                }
+                if self.lines.get(self.pos).is_some_and(|next| next.indent == item_indent) {
+                    map.extend(self.parse_mapping(item_indent));
+                }
+                items.push(Value::Object(map));
+            } else if let Some(value) = self.parse_value(item, indent, number) {
+                items.push(value);
+            }
+        }
+        items
+    }
+
+    /// Parses the value of a key at `indent`. Unlike other values, its
+    /// sequence may start at the key's own indentation, as in `tags:\n- a`.
+    fn parse_key_value(&mut self, value: &str, indent: usize, number: usize) -> Option<Value> {
+        let compact = value.is_empty()
+            && self.lines.get(self.pos).is_some_and(|next| {
+                next.indent == indent && (next.text.starts_with("- ") || next.text == "-")
+            });
+        if compact {
+            return Some(Value::Array(self.parse_sequence(indent)));
+        }
+        self.parse_value(value, indent, number)
+    }
+
+    /// Parses the value after `key:` (or `-`), which may continue on the
+    /// following, more indented lines. Returns `None` after reporting an error.
+    fn parse_value(&mut self, value: &str, indent: usize, number: usize) -> Option<Value> {
+        let nested = self
+            .lines
+            .get(self.pos)
+            .filter(|next| next.indent > indent)
+            .map(|next| (next.indent, next.text.starts_with("- ") || next.text == "-"));
+        if value.is_empty() {
+            return Some(match nested {
+                Some((nested_indent, true)) => Value::Array(self.parse_sequence(nested_indent)),
+                Some((nested_indent, false)) => Value::Object(self.parse_mapping(nested_indent)),
+                None => Value::Null,
+            });
+        }
+        if let Some(style) = block_scalar_style(value) {
+            return Some(Value::String(self.parse_block_scalar(indent, number, style)));
+        }
+        if nested.is_some() {
+            if starts_flow(value) {
+                self.error(
+                    number,
+                    "a quoted or flow value cannot be continued on an indented line",
+                );
+                self.skip_nested(indent);
+                return None;
+            }
+            let text = self.fold_plain_scalar(value, indent, number);
+            return match plain_scalar(&text) {
+                Ok(value) => Some(value),
+                Err(message) => {
+                    self.error(number, message);
+                    None
+                }
+            };
+        }
+        match parse_scalar(value) {
+            Ok(value) => Some(value),
+            Err(message) => {
+                self.error(number, message);
+                None
+            }
+        }
+    }
+
+    /// Joins a plain scalar started on line `number` with its continuation
+    /// lines, indented deeper than `indent`. Line breaks fold into spaces
+    /// and each blank line in between into a newline.
+    fn fold_plain_scalar(&mut self, first: &str, indent: usize, number: usize) -> String {
+        let mut text = strip_comment(first).to_string();
+        let mut previous = number;
+        while let Some(line) = self.lines.get(self.pos).filter(|line| line.indent > indent) {
+            let (line_number, line_text) = (line.number, line.text);
+            let gap = &self.raw[previous + 1 - self.first_line..line_number - self.first_line];
+            let blank_lines = gap.iter().filter(|raw| raw.trim().is_empty()).count();
+            if blank_lines == 0 {
+                text.push(' ');
+            } else {
+                text.push_str(&"\n".repeat(blank_lines));
+            }
+            text.push_str(strip_comment(line_text));
+            previous = line_number;
+            self.pos += 1;
+        }
+        text
+    }
+
+    /// Skips lines nested deeper than `indent`.
+    fn skip_nested(&mut self, indent: usize) {
+        while self.lines.get(self.pos).is_some_and(|line| line.indent > indent) {
+            self.pos += 1;
+        }
+    }
+
+    /// Reads a `|` (literal) or `>` (folded) block scalar started on line `number`.
+    fn parse_block_scalar(&mut self, indent: usize, number: usize, style: BlockScalar) -> String {
+        let mut raw_index = number + 1 - self.first_line;
+        let mut block: Vec<&str> = Vec::new();
+        let mut block_indent = None;
+        while let Some(line) = self.raw.get(raw_index) {
+            let text = line.trim_start_matches(' ');
+            let line_indent = line.len() - text.len();
+            if !text.is_empty() {
+                if line_indent <= indent {
+                    break;
+                }
+                let block_indent = *block_indent.get_or_insert(line_indent);
+                block.push(line.get(block_indent.min(line_indent)..).unwrap_or(""));
+            } else {
+                block.push("");
+            }
+            raw_index += 1;
+        }
+        while block.last() == Some(&"") {
+            block.pop();
+        }
+        let last_line = self.first_line + raw_index;
+        while self.lines.get(self.pos).is_some_and(|line| line.number < last_line) {
+            self.pos += 1;
+        }
+
+        let mut text = if style.folded {
+            block
+                .split(|line| line.is_empty())
+                .map(|paragraph| paragraph.join(" "))
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            block.join("\n")
+        };
+        if style.keep_newline && !text.is_empty() {
+            text.push('\n');
+        }
+        text
+    }
+}
+
+#[derive(Clone, Copy)]
+struct BlockScalar {
+    folded: bool,
+    keep_newline: bool,
+}
+
+fn block_scalar_style(value: &str) -> Option<BlockScalar> {
+    let value = strip_comment(value);
+    let folded = match value.as_bytes().first()? {
+        b'|' => false,
+        b'>' => true,
+        _ => return None,
+    };
+    match &value[1..] {
+        "" | "+" => Some(BlockScalar { folded, keep_newline: true }),
+        "-" => Some(BlockScalar { folded, keep_newline: false }),
+        _ => None,
+    }
+}
+
+/// Returns the byte offset of `part` within `line`.
+fn line_offset(line: &str, part: &str) -> usize {
+    part.as_ptr() as usize - line.as_ptr() as usize
+}
+
+fn starts_flow(text: &str) -> bool {
+    text.starts_with(['[', '{', '"', '\''])
+}
+
+/// Splits `key: value` at the first `:` that is followed by a space or ends
+/// the line and is not inside quotes.
+fn split_key_value(text: &str) -> Option<(&str, &str)> {
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') if i == 0 => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, ':') => {
+                let rest = &text[i + 1..];
+                if rest.is_empty() || rest.starts_with(' ') {
+                    let key = text[..i].trim_end();
+                    return (!key.is_empty()).then_some((key, rest.trim_start()));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Removes a ` #` comment from a plain (unquoted) value.
+fn strip_comment(value: &str) -> &str {
+    value.find(" #").map_or(value, |i| &value[..i]).trim_end()
+}
+
+/// Parses a scalar, flow sequence or flow mapping.
+fn parse_scalar(text: &str) -> Result<Value, String> {
+    let (value, rest) = parse_flow_value(text)?;
+    let rest = rest.trim_start();
+    if !rest.is_empty() && !rest.starts_with('#') {
+        return Err(format!("unexpected `{rest}` after value"));
+    }
+    Ok(value)
+}
+
+/// Parses one flow value from the start of `text`, returning it and the rest.
+fn parse_flow_value(text: &str) -> Result<(Value, &str), String> {
+    let text = text.trim_start();
+    match text.as_bytes().first() {
+        Some(b'"') => parse_double_quoted(text),
+        Some(b'\'') => parse_single_quoted(text),
+        Some(b'[') => parse_flow_collection(text, ']'),
+        Some(b'{') => parse_flow_collection(text, '}'),
+        _ => Ok((plain_scalar(strip_comment(text))?, "")),
+    }
+}
+
+fn parse_double_quoted(text: &str) -> Result<(Value, &str), String> {
+    let mut value = String::new();
+    let mut chars = text.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((Value::String(value), &text[i + 1..])),
+            '\\' => match chars.next().map(|(_, c)| c) {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some(c @ ('"' | '\\' | '/')) => value.push(c),
+                Some(c) => return Err(format!("unknown escape `\\{c}` in string")),
+                None => break,
+            },
+            c => value.push(c),
+        }
+    }
+    Err("unterminated double-quoted string".to_string())
+}
+
+fn parse_single_quoted(text: &str) -> Result<(Value, &str), String> {
+    let mut value = String::new();
+    let mut rest = &text[1..];
+    loop {
+        let Some(end) = rest.find('\'') else {
+            return Err("unterminated single-quoted string".to_string());
+        };
+        value.push_str(&rest[..end]);
+        rest = &rest[end + 1..];
+        match rest.strip_prefix('\'') {
+            Some(after) => {
+                value.push('\'');
+                rest = after;
+            }
+            None => return Ok((Value::String(value), rest)),
+        }
+    }
+}
+
+/// Parses `[a, b]` or `{a: 1, b: 2}` starting at `text`.
+fn parse_flow_collection(text: &str, close: char) -> Result<(Value, &str), String> {
+    let mut rest = text[1..].trim_start();
+    let mut items = Vec::new();
+    let mut map = Map::new();
+    loop {
+        if let Some(after) = rest.strip_prefix(close) {
+            let value = if close == ']' { Value::Array(items) } else { Value::Object(map) };
+            return Ok((value, after));
+        }
+        if rest.is_empty() {
+            let kind = if close == ']' { "sequence" } else { "mapping" };
+            return Err(format!("unterminated flow {kind}, expected `{close}`"));
+        }
+
+        let end = flow_item_end(rest, close);
+        let item = rest[..end].trim();
+        if close == ']' {
+            items.push(flow_item(item)?);
+        } else {
+            let (key, value) = split_key_value(item)
+                .ok_or_else(|| format!("expected `key: value`, found `{item}`"))?;
+            let key = match flow_item(key)? {
+                Value::String(key) => key,
+                other => other.to_string(),
+            };
+            map.insert(key, flow_item(value)?);
+        }
+        rest = rest[end..].trim_start();
+        rest = rest.strip_prefix(',').unwrap_or(rest).trim_start();
+    }
+}
+
+fn flow_item(text: &str) -> Result<Value, String> {
+    if text.starts_with(['"', '\'', '[', '{']) {
+        let (value, rest) = parse_flow_value(text)?;
+        if !rest.trim().is_empty() {
+            return Err(format!("unexpected `{}` in flow collection", rest.trim()));
+        }
+        Ok(value)
+    } else {
+        plain_scalar(text)
+    }
+}
+
+/// Returns the end of the flow item starting at `text`: the next `,` or
+/// `close` outside quotes and nested brackets. Quotes only open a string at
+/// the start of an item or value, so `[don't, b]` has two items.
+fn flow_item_end(text: &str, close: char) -> usize {
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut item_start = true;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') if item_start => quote = Some(c),
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') if depth > 0 => depth -= 1,
+            (None, c) if depth == 0 && (c == ',' || c == close) => return i,
+            // Anything else, including every character inside quotes
+            _ => {}
+        }
+        if quote.is_none() && !c.is_whitespace() {
+            item_start = matches!(c, ',' | ':' | '[' | '{');
+        }
+    }
+    text.len()
+}
+
+/// Interprets an unquoted scalar as null, boolean, number or string.
+fn plain_scalar(text: &str) -> Result<Value, String> {
+    if text.contains(": ") || text.ends_with(':') {
+        return Err("mapping values are not allowed here; quote the value".to_string());
+    }
+    Ok(match text {
+        "" | "~" | "null" | "Null" | "NULL" => Value::Null,
+        "true" | "True" | "TRUE" => Value::Bool(true),
+        "false" | "False" | "FALSE" => Value::Bool(false),
+        _ => text.parse::<i64>().map_or_else(
+            |_| {
+                text.parse::<f64>()
+                    .ok()
+                    .filter(|n| n.is_finite())
+                    .and_then(serde_json::Number::from_f64)
+                    .map_or_else(|| Value::String(text.to_string()), Value::Number)
+            },
+            |n| Value::Number(n.into()),
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_values() {
+        let source = "---\ntitle: \"Hello: World\"\ndraft: false\norder: 3 # comment\ntags: [rust, 'markdown', \"a, b\"]\nauthors:\n  - Ann\n  - name: Bob\n    url: https://bob.dev\nseo: {title: SEO, index: true}\nsummary: >\n  Folded\n  text\n\n  Next\n---\n# Body\n";
+        let frontmatter = parse_frontmatter(source);
+        assert!(frontmatter.errors.is_empty(), "{:?}", frontmatter.errors);
+        assert_eq!(frontmatter.content, "# Body\n");
+        assert_eq!(frontmatter.raw, source["---\n".len()..source.len() - "---\n# Body\n".len()]);
+        assert_eq!(
+            Value::Object(frontmatter.data),
+            json!({
+                "title": "Hello: World",
+                "draft": false,
+                "order": 3,
+                "tags": ["rust", "markdown", "a, b"],
+                "authors": ["Ann", {"name": "Bob", "url": "https://bob.dev"}],
+                "seo": {"title": "SEO", "index": true},
+                "summary": "Folded text\nNext\n",
+            })
+        );
+    }
+
+    #[test]
+    fn test_compact_sequences() {
+        let source = "---\ntags:\n- a\n- b\nauthors:\n- name: Ann\n  links:\n  - x\n- Bob\nempty:\n- a:\n-\n- c\ntitle: T\n---\n";
+        let frontmatter = parse_frontmatter(source);
+        assert!(frontmatter.errors.is_empty(), "{:?}", frontmatter.errors);
+        assert_eq!(
+            Value::Object(frontmatter.data),
+            json!({
+                "tags": ["a", "b"],
+                "authors": [{"name": "Ann", "links": ["x"]}, "Bob"],
+                "empty": [{"a": null}, null, "c"],
+                "title": "T",
+            })
+        );
+    }
+
+    #[test]
+    fn test_multiline_plain_scalars() {
+        let source = "---\ndescription: a long\n  line continued # note\n\n  after a blank line\nlist:\n  - one\n    more\ncount: 1\n  2\nbad: a\n  b: c\nquoted: \"a\n  b\"\n---\n";
+        let frontmatter = parse_frontmatter(source);
+        assert_eq!(
+            Value::Object(frontmatter.data),
+            json!({
+                "description": "a long line continued\nafter a blank line",
+                "list": ["one more"],
+                "count": "1 2",
+            })
+        );
+        let errors: Vec<String> = frontmatter.errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            errors,
+            [
+                "frontmatter line 11: mapping values are not allowed here; quote the value",
+                "frontmatter line 13: a quoted or flow value cannot be continued on an indented line",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flow_items_with_quotes_inside() {
+        let source = "---\nwords: [don't, 'it''s', \"a, b\", rock'n'roll]\nmap: {a: don't, b: 'c, d'}\n---\n";
+        let frontmatter = parse_frontmatter(source);
+        assert!(frontmatter.errors.is_empty(), "{:?}", frontmatter.errors);
+        assert_eq!(
+            Value::Object(frontmatter.data),
+            json!({
+                "words": ["don't", "it's", "a, b", "rock'n'roll"],
+                "map": {"a": "don't", "b": "c, d"},
+            })
+        );
+    }
+
+    #[test]
+    fn test_detect_format() {
+        let cases = [
+            ("---\ntitle: A\n---\nBody", FrontmatterFormat::Yaml),
+            ("+++\ntitle = \"A\"\n+++\nBody", FrontmatterFormat::Toml),
+            (";;;\n\"title\": \"A\"\n;;;\nBody", FrontmatterFormat::Json),
+            (";;;\n{\"title\": \"A\"}\n;;;\nBody", FrontmatterFormat::Json),
+            ("{\"title\": \"A\"}\n\nBody", FrontmatterFormat::Json),
+        ];
+        for (source, format) in cases {
+            let frontmatter = parse_frontmatter(source);
+            assert_eq!(frontmatter.format, Some(format), "{source}");
+            assert_eq!(frontmatter.data.get("title"), Some(&json!("A")), "{source}");
+            assert_eq!(frontmatter.content, "Body", "{source}");
+            assert!(frontmatter.errors.is_empty(), "{source}");
+        }
+
+        let frontmatter = parse_frontmatter("+++\r\ntitle = \"A\" # kept\r\n+++\r\nBody");
+        assert_eq!(frontmatter.raw, "title = \"A\" # kept\r\n");
+        let frontmatter = parse_frontmatter("{\"title\": \"A\"}\n\nBody");
+        assert_eq!(frontmatter.raw, "{\"title\": \"A\"}");
+
+        // Leading braces that are not a JSON object are content.
+        let frontmatter = parse_frontmatter("{not json}\nBody");
+        assert_eq!(frontmatter.format, None);
+        assert_eq!(frontmatter.raw, "");
+        assert_eq!(frontmatter.content, "{not json}\nBody");
+    }
+
+    #[test]
+    fn test_json_errors() {
+        let frontmatter = parse_frontmatter(";;;\n\"title\": \"A\",\n\"tags\": [1,\n;;;\nBody");
+        assert_eq!(frontmatter.format, Some(FrontmatterFormat::Json));
+        assert_eq!(frontmatter.errors.len(), 1);
+        assert_eq!(frontmatter.errors[0].line, 4);
+        assert_eq!(frontmatter.content, "Body");
+    }
+
+    #[test]
+    fn test_no_frontmatter() {
+        let frontmatter = parse_frontmatter("---\nnot closed\n");
+        assert_eq!(frontmatter.content, "---\nnot closed\n");
+        assert!(frontmatter.data.is_empty() && frontmatter.errors.is_empty());
+    }
+
+    #[test]
+    fn test_errors_have_file_lines() {
+        let source = "---\ntitle: Ok\n\tdescription: tabbed\nbroken line\nquote: \"open\ntitle: Again\ntags: [a, b\n---\nBody";
+        let frontmatter = parse_frontmatter(source);
+        let errors: Vec<String> = frontmatter.errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            errors,
+            [
+                "frontmatter line 3: tab character used for indentation",
+                "frontmatter line 4: expected `key: value`",
+                "frontmatter line 5: unterminated double-quoted string",
+                "frontmatter line 6: duplicate key `title`",
+                "frontmatter line 7: unterminated flow sequence, expected `]`",
+            ]
+        );
+        // Valid entries are still returned.
+        assert_eq!(frontmatter.data.get("title"), Some(&json!("Again")));
+        assert_eq!(frontmatter.content, "Body");
+    }
+}
@@ -0,0 +1,467 @@
+//! TOML frontmatter parsing.
+//!
+//! Supports the parts of TOML used in frontmatter: `key = value` pairs with
+//! bare, quoted and dotted keys, `[table]` and `[[array of tables]]` headers,
+//! basic and literal strings (including multi-line), integers, floats,
+//! booleans, arrays, inline tables and comments. Dates and times are kept as
+//! strings.
+
+use std::collections::HashSet;
+
+use serde_json::{Map, Value};
+
+use crate::FrontmatterError;
+
+/// Parses TOML into a JSON map. `first_line` is the file line of the first
+/// TOML line, so errors point into the whole file.
+pub fn parse_toml(text: &str, first_line: usize) -> (Map<String, Value>, Vec<FrontmatterError>) {
+    let mut parser = TomlParser {
+        text,
+        pos: 0,
+        first_line,
+        root: Map::new(),
+        table: Vec::new(),
+        defined: HashSet::new(),
+        errors: Vec::new(),
+    };
+    parser.parse();
+    (parser.root, parser.errors)
+}
+
+struct TomlParser<'a> {
+    text: &'a str,
+    pos: usize,
+    first_line: usize,
+    root: Map<String, Value>,
+    /// Path of the table that `key = value` lines are added to.
+    table: Vec<String>,
+    /// Tables defined by a `[table]` header.
+    defined: HashSet<Vec<String>>,
+    errors: Vec<FrontmatterError>,
+}
+
+impl<'a> TomlParser<'a> {
+    fn parse(&mut self) {
+        loop {
+            self.skip_trivia(true);
+            if self.pos >= self.text.len() {
+                break;
+            }
+            let line = self.line();
+            let result = if self.rest().starts_with("[[") {
+                self.parse_array_table()
+            } else if self.rest().starts_with('[') {
+                self.parse_table()
+            } else {
+                self.parse_key_value()
+            };
+            let result = result.and_then(|()| self.expect_line_end());
+            if let Err(message) = result {
+                self.errors.push(FrontmatterError { line, message });
+                self.skip_line();
+            }
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// File line number at the current position.
+    fn line(&self) -> usize {
+        self.first_line + self.text[..self.pos].matches('\n').count()
+    }
+
+    /// Skips spaces, tabs and comments, and newlines too when `newlines` is set.
+    fn skip_trivia(&mut self, newlines: bool) {
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' | '\r' => self.pos += 1,
+                '\n' if newlines => self.pos += 1,
+                '#' => {
+                    let rest = self.rest();
+                    let end = rest.find('\n').unwrap_or(rest.len());
+                    self.pos += end;
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn skip_line(&mut self) {
+        let rest = self.rest();
+        let end = rest.find('\n').map_or(rest.len(), |i| i + 1);
+        self.pos += end;
+    }
+
+    fn expect_line_end(&mut self) -> Result<(), String> {
+        self.skip_trivia(false);
+        match self.peek() {
+            None | Some('\n') => Ok(()),
+            Some(_) => Err(format!("unexpected `{}` after value", first_token(self.rest()))),
+        }
+    }
+
+    fn eat(&mut self, expected: &str) -> Result<(), String> {
+        if self.rest().starts_with(expected) {
+            self.pos += expected.len();
+            Ok(())
+        } else {
+            Err(format!("expected `{expected}`"))
+        }
+    }
+
+    fn parse_table(&mut self) -> Result<(), String> {
+        self.eat("[")?;
+        let path = self.parse_key()?;
+        self.eat("]")?;
+        if !self.defined.insert(path.clone()) {
+            return Err(format!("table `{}` is defined twice", path.join(".")));
+        }
+        table_mut(&mut self.root, &path)?;
+        self.table = path;
+        Ok(())
+    }
+
+    fn parse_array_table(&mut self) -> Result<(), String> {
+        self.eat("[[")?;
+        let path = self.parse_key()?;
+        self.eat("]]")?;
+        let (last, parent) = path.split_last().ok_or("expected a table name")?;
+        let parent = table_mut(&mut self.root, parent)?;
+        match parent.entry(last.clone()).or_insert_with(|| Value::Array(Vec::new())) {
+            Value::Array(tables) => tables.push(Value::Object(Map::new())),
+            _ => return Err(format!("`{}` is not an array of tables", path.join("."))),
+        }
+        self.table = path;
+        Ok(())
+    }
+
+    fn parse_key_value(&mut self) -> Result<(), String> {
+        let key = self.parse_key()?;
+        self.eat("=")?;
+        self.skip_trivia(false);
+        let value = self.parse_value()?;
+        let table = table_mut(&mut self.root, &self.table)?;
+        insert(table, &key, value)
+    }
+
+    /// Parses a bare, quoted or dotted key.
+    fn parse_key(&mut self) -> Result<Vec<String>, String> {
+        let mut path = Vec::new();
+        loop {
+            self.skip_trivia(false);
+            let part = match self.peek() {
+                Some('"') => self.parse_basic_string()?,
+                Some('\'') => self.parse_literal_string()?,
+                _ => {
+                    let rest = self.rest();
+                    let len = rest
+                        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+                        .unwrap_or(rest.len());
+                    if len == 0 {
+                        return Err("expected `key = value`".to_string());
+                    }
+                    let part = rest[..len].to_string();
+                    self.pos += len;
+                    part
+                }
+            };
+            path.push(part);
+            self.skip_trivia(false);
+            if self.peek() != Some('.') {
+                return Ok(path);
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value, String> {
+        match self.peek() {
+            Some('"') if self.rest().starts_with("\"\"\"") => self.parse_multiline('"'),
+            Some('\'') if self.rest().starts_with("'''") => self.parse_multiline('\''),
+            Some('"') => self.parse_basic_string().map(Value::String),
+            Some('\'') => self.parse_literal_string().map(Value::String),
+            Some('[') => self.parse_array(),
+            Some('{') => self.parse_inline_table(),
+            Some(_) => {
+                let rest = self.rest();
+                let len = rest.find([',', ']', '}', '#', '\n']).unwrap_or(rest.len());
+                let token = rest[..len].trim_end();
+                self.pos += token.len();
+                scalar(token)
+            }
+            None => Err("expected a value".to_string()),
+        }
+    }
+
+    fn parse_basic_string(&mut self) -> Result<String, String> {
+        self.pos += 1;
+        let mut value = String::new();
+        while let Some(c) = self.peek().filter(|&c| c != '\n') {
+            self.pos += c.len_utf8();
+            match c {
+                '"' => return Ok(value),
+                '\\' => value.push(self.parse_escape()?),
+                c => value.push(c),
+            }
+        }
+        Err("unterminated string".to_string())
+    }
+
+    fn parse_escape(&mut self) -> Result<char, String> {
+        let c = self.peek().ok_or("unterminated string")?;
+        self.pos += c.len_utf8();
+        Ok(match c {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            '"' | '\\' => c,
+            'u' | 'U' => {
+                let len = if c == 'u' { 4 } else { 8 };
+                let hex = self.rest().get(..len).ok_or("invalid unicode escape")?;
+                let code = u32::from_str_radix(hex, 16).map_err(|_| "invalid unicode escape")?;
+                self.pos += len;
+                char::from_u32(code).ok_or("invalid unicode escape")?
+            }
+            c => return Err(format!("unknown escape `\\{c}` in string")),
+        })
+    }
+
+    fn parse_literal_string(&mut self) -> Result<String, String> {
+        self.pos += 1;
+        let end = self.rest().find(['\'', '\n']).filter(|&i| self.rest()[i..].starts_with('\''));
+        let end = end.ok_or("unterminated string")?;
+        let value = self.rest()[..end].to_string();
+        self.pos += end + 1;
+        Ok(value)
+    }
+
+    /// Parses a `"""` or `'''` string. A newline right after the opening
+    /// delimiter is dropped.
+    fn parse_multiline(&mut self, quote: char) -> Result<Value, String> {
+        let delimiter = if quote == '"' { "\"\"\"" } else { "'''" };
+        self.pos += 3;
+        if self.rest().starts_with("\r\n") {
+            self.pos += 2;
+        } else if self.rest().starts_with('\n') {
+            self.pos += 1;
+        }
+        let mut value = String::new();
+        loop {
+            if self.rest().starts_with(delimiter) {
+                self.pos += 3;
+                return Ok(Value::String(value));
+            }
+            let c = self.peek().ok_or("unterminated multi-line string")?;
+            self.pos += c.len_utf8();
+            if c == '\\' && quote == '"' {
+                if self.rest().trim_start_matches([' ', '\t', '\r']).starts_with('\n') {
+                    // Line-ending backslash: skip the newline and leading whitespace.
+                    let skip = self.rest().len() - self.rest().trim_start().len();
+                    self.pos += skip;
+                } else {
+                    value.push(self.parse_escape()?);
+                }
+            } else {
+                value.push(c);
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Value, String> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_trivia(true);
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                return Ok(Value::Array(items));
+            }
+            if self.peek().is_none() {
+                return Err("unterminated array, expected `]`".to_string());
+            }
+            items.push(self.parse_value()?);
+            self.skip_trivia(true);
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {}
+                None => return Err("unterminated array, expected `]`".to_string()),
+                Some(_) => {
+                    return Err(format!(
+                        "expected `,` or `]`, found `{}`",
+                        first_token(self.rest())
+                    ))
+                }
+            }
+        }
+    }
+
+    fn parse_inline_table(&mut self) -> Result<Value, String> {
+        self.pos += 1;
+        let mut table = Map::new();
+        loop {
+            self.skip_trivia(false);
+            if self.peek() == Some('}') {
+                self.pos += 1;
+                return Ok(Value::Object(table));
+            }
+            if matches!(self.peek(), None | Some('\n')) {
+                return Err("unterminated inline table, expected `}`".to_string());
+            }
+            let key = self.parse_key()?;
+            self.eat("=")?;
+            self.skip_trivia(false);
+            let value = self.parse_value()?;
+            insert(&mut table, &key, value)?;
+            self.skip_trivia(false);
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {}
+                _ => return Err("unterminated inline table, expected `}`".to_string()),
+            }
+        }
+    }
+}
+
+/// Returns the table at `path`, creating missing tables. A path through an
+/// array of tables continues in its last table.
+fn table_mut<'m>(
+    root: &'m mut Map<String, Value>,
+    path: &[String],
+) -> Result<&'m mut Map<String, Value>, String> {
+    let mut table = root;
+    for key in path {
+        let value = table.entry(key.clone()).or_insert_with(|| Value::Object(Map::new()));
+        let value = match value {
+            Value::Array(tables) => tables.last_mut().ok_or_else(|| not_a_table(key))?,
+            value => value,
+        };
+        table = value.as_object_mut().ok_or_else(|| not_a_table(key))?;
+    }
+    Ok(table)
+}
+
+fn not_a_table(key: &str) -> String {
+    format!("`{key}` is not a table")
+}
+
+/// Inserts `value` at the dotted `key` path in `table`.
+fn insert(table: &mut Map<String, Value>, key: &[String], value: Value) -> Result<(), String> {
+    let (last, parents) = key.split_last().ok_or("expected a key")?;
+    let table = table_mut(table, parents)?;
+    if table.contains_key(last) {
+        return Err(format!("duplicate key `{}`", key.join(".")));
+    }
+    table.insert(last.clone(), value);
+    Ok(())
+}
+
+/// Interprets a bare value: boolean, number, or date/time (kept as a string).
+fn scalar(token: &str) -> Result<Value, String> {
+    match token {
+        "true" => return Ok(Value::Bool(true)),
+        "false" => return Ok(Value::Bool(false)),
+        "" => return Err("expected a value".to_string()),
+        _ => {}
+    }
+    let digits = token.replace('_', "");
+    let unsigned = digits.trim_start_matches(['+', '-']);
+    let radix = [("0x", 16), ("0o", 8), ("0b", 2)]
+        .into_iter()
+        .find_map(|(prefix, radix)| unsigned.strip_prefix(prefix).map(|rest| (rest, radix)));
+    if let Some((rest, radix)) = radix {
+        if let Ok(n) = i64::from_str_radix(rest, radix) {
+            return Ok(Value::Number(n.into()));
+        }
+    } else if let Ok(n) = digits.parse::<i64>() {
+        return Ok(Value::Number(n.into()));
+    } else if let Some(n) = digits.parse::<f64>().ok().and_then(serde_json::Number::from_f64) {
+        return Ok(Value::Number(n));
+    }
+    let is_date_time = token.starts_with(|c: char| c.is_ascii_digit())
+        && token.chars().all(|c| c.is_ascii_alphanumeric() || "-:.+ ".contains(c));
+    if is_date_time {
+        return Ok(Value::String(token.to_string()));
+    }
+    Err(format!("invalid value `{token}`; quote strings"))
+}
+
+/// Returns the first whitespace-delimited token of `text`, for error messages.
+fn first_token(text: &str) -> &str {
+    text.split_whitespace().next().unwrap_or(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_toml() {
+        let text = r#"title = "Hello \"TOML\""  # comment
+draft = false
+weight = 1_000
+ratio = 0.5
+date = 2024-05-01
+tags = [
+  "rust",
+  'markdown', # trailing comma
+]
+seo.description = '''
+Multi
+line'''
+author = { name = "Ann", url = "https://ann.dev" }
+
+[extra]
+toc = true
+
+[[links]]
+name = "One"
+
+[[links]]
+name = "Two"
+"#;
+        let (data, errors) = parse_toml(text, 2);
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(
+            Value::Object(data),
+            json!({
+                "title": "Hello \"TOML\"",
+                "draft": false,
+                "weight": 1000,
+                "ratio": 0.5,
+                "date": "2024-05-01",
+                "tags": ["rust", "markdown"],
+                "seo": {"description": "Multi\nline"},
+                "author": {"name": "Ann", "url": "https://ann.dev"},
+                "extra": {"toc": true},
+                "links": [{"name": "One"}, {"name": "Two"}],
+            })
+        );
+    }
+
+    #[test]
+    fn test_toml_errors() {
+        let text = "title = \"open\nname = bare words\nok = 1\nok = 2\n[t]\n[t]\ntags = [1, 2\n";
+        let (data, errors) = parse_toml(text, 2);
+        let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "frontmatter line 2: unterminated string",
+                "frontmatter line 3: invalid value `bare words`; quote strings",
+                "frontmatter line 5: duplicate key `ok`",
+                "frontmatter line 7: table `t` is defined twice",
+                "frontmatter line 8: unterminated array, expected `]`",
+            ]
+        );
+        assert_eq!(data.get("ok"), Some(&json!(1)));
+    }
+}
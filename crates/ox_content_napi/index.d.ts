@@ -10,6 +10,24 @@ export interface ParseResult {
   /** Parse errors, if any. */
   errors: Array<string>
 }
+/** Parse statistics for performance profiling. */
+export interface JsParseStats {
+  /** Number of AST nodes, including the root. */
+  nodeCount: number
+  /** Parse time in microseconds. */
+  parseMicros: number
+  /** Size of the source in bytes. */
+  bytes: number
+}
+/** Parse result with the AST as JSON and parse statistics. */
+export interface ParseWithStatsResult {
+  /** The AST as a JSON string. */
+  ast: string
+  /** Parse statistics. Empty when parsing failed. */
+  stats: JsParseStats
+  /** Parse errors, if any. */
+  errors: Array<string>
+}
 /** Render result containing the HTML output. */
 export interface RenderResult {
   /** The rendered HTML. */
@@ -26,22 +44,98 @@ export interface TocEntry {
   /** URL-friendly slug. */
   slug: string
 }
+/** A heading ID in the rendered HTML. */
+export interface HeadingAnchor {
+  /** Heading depth (1-6). */
+  depth: number
+  /** Heading text, without section numbers. */
+  text: string
+  /** The heading's `id` attribute. */
+  slug: string
+}
 /** Transform result containing HTML, frontmatter, and TOC. */
 export interface TransformResult {
   /** The rendered HTML. */
   html: string
   /** Parsed frontmatter as JSON string. */
   frontmatter: string
+  /** Detected frontmatter format (`"yaml"`, `"toml"` or `"json"`), if any. */
+  frontmatterFormat?: string
+  /**
+   * The frontmatter exactly as written between its delimiters, comments
+   * included. Empty when there is no frontmatter.
+   */
+  frontmatterRaw: string
   /** Table of contents entries. */
   toc: Array<TocEntry>
+  /**
+   * Whether the TOC is enabled for this page, after applying the `toc`
+   * option and frontmatter. Pass it on as `JsSsgPageData.tocEnabled`.
+   */
+  tocEnabled: boolean
+  /**
+   * Every heading ID in `html`, in document order, after deduplication.
+   * Unlike `toc`, it is not limited by `tocMinDepth` and `tocMaxDepth`
+   * and includes headings nested in other blocks. Empty when
+   * `headingIds` is disabled.
+   */
+  anchors: Array<HeadingAnchor>
   /** Parse/render errors, if any. */
   errors: Array<string>
 }
-/** Transform options for JavaScript. */
+/** Result of [`transform_full`]: the transform result plus the final AST. */
+export interface TransformFullResult {
+  /** The rendered HTML. */
+  html: string
+  /**
+   * The AST the HTML was rendered from, after heading normalization, as
+   * a JSON string in the same shape as `parse()` returns.
+   */
+  ast: string
+  /** Parsed frontmatter as JSON string. */
+  frontmatter: string
+  /** Detected frontmatter format (`"yaml"`, `"toml"` or `"json"`), if any. */
+  frontmatterFormat?: string
+  /**
+   * The frontmatter exactly as written between its delimiters, comments
+   * included. Empty when there is no frontmatter.
+   */
+  frontmatterRaw: string
+  /** Table of contents entries. */
+  toc: Array<TocEntry>
+  /**
+   * Whether the TOC is enabled for this page, after applying the `toc`
+   * option and frontmatter.
+   */
+  tocEnabled: boolean
+  /**
+   * Every heading ID in `html`, in document order, after deduplication.
+   * Unlike `toc`, it is not limited by `tocMinDepth` and `tocMaxDepth`
+   * and includes headings nested in other blocks. Empty when
+   * `headingIds` is disabled.
+   */
+  anchors: Array<HeadingAnchor>
+  /** Parse/render errors, if any. */
+  errors: Array<string>
+}
+/**
+ * Transform options for JavaScript.
+ *
+ * Parser options follow the same precedence as [`JsParserOptions`].
+ */
 export interface JsTransformOptions {
-  /** Enable GFM extensions. */
+  /**
+   * Parser preset applied before the individual options:
+   * `"commonmark"`, `"gfm"` or `"gfm_strict"`.
+   */
+  preset?: 'commonmark' | 'gfm' | 'gfm_strict'
+  /**
+   * Enable GFM extensions. Shorthand for `preset: "gfm"`, ignored when
+   * `preset` is set: `gfm: false` does not turn a preset's extensions
+   * off.
+   */
   gfm?: boolean
-  /** Enable footnotes. */
+  /** Enable footnotes, including inline `^[note]` footnotes. */
   footnotes?: boolean
   /** Enable task lists. */
   taskLists?: boolean
@@ -51,18 +145,304 @@ export interface JsTransformOptions {
   strikethrough?: boolean
   /** Enable autolinks. */
   autolinks?: boolean
+  /** Enable `*[ABBR]: definition` abbreviations. */
+  abbreviations?: boolean
+  /** Read a trailing `{#id}` on headings as the heading ID. */
+  attributes?: boolean
+  /** Parse Markdown between block-level HTML tags such as `<details>`. */
+  parseInsideHtmlBlocks?: boolean
+  /** Enable `$...$` inline and `$$` or ```` ```math ```` block TeX math. */
+  math?: boolean
+  /**
+   * Text that becomes a line break inside table cells, such as `\n`.
+   * `<br>` tags in cells always do.
+   */
+  tableCellBreak?: string
+  /**
+   * Collapse runs of spaces and tabs in text to a single space and drop
+   * the spaces around line breaks (default: false). Code is left as
+   * written.
+   */
+  normalizeWhitespace?: boolean
+  /**
+   * Restrict the recognized Markdown to a feature set: `"full"`
+   * (default), `"basic"` (no headings, thematic breaks, tables, images or
+   * HTML) or `"inline_only"` (paragraphs with links, emphasis and code
+   * spans). Disabled constructs are rendered as escaped source text.
+   * Other names are rejected rather than leaving every feature on.
+   */
+  features?: 'full' | 'basic' | 'inline_only'
+  /**
+   * Columns past a list marker that continue the item after a blank
+   * line, such as `2` for two-space indentation (default: 0, the
+   * CommonMark rule). Diverges from CommonMark.
+   */
+  listIndent?: number
+  /**
+   * Maximum nesting depth for lists, links and emphasis (default: 100).
+   * Deeper input is reported in `errors` instead of crashing.
+   */
+  maxNestingDepth?: number
+  /**
+   * Maximum input size in bytes. Larger input is reported in `errors`
+   * without being parsed.
+   */
+  maxInputBytes?: number
+  /**
+   * Time budget for parsing in milliseconds. Parsing stops with an entry
+   * in `errors` once it is exceeded.
+   */
+  timeBudgetMs?: number
+  /**
+   * Fail parsing, with an entry in `errors`, on input that is otherwise
+   * recovered from silently (default: false): full or collapsed link
+   * references without a definition, footnote references without a
+   * definition, and unclosed code fences or math blocks.
+   */
+  strict?: boolean
+  /** Build the table of contents (default: true). */
+  toc?: boolean
+  /**
+   * Shallowest heading level in the TOC (1-6, default: 2), so the page
+   * title h1 is left out unless this is 1.
+   */
+  tocMinDepth?: number
   /** Maximum TOC depth (1-6). */
   tocMaxDepth?: number
+  /**
+   * Markers replaced by the table of contents when they make up a whole
+   * paragraph (default: `["[[toc]]", "[TOC]"]`). The inline TOC is a
+   * `<nav class="table-of-contents">` of nested lists with the same
+   * entries as `toc`, and is rendered even with `toc: false`. Pass an
+   * empty array to keep markers as text.
+   */
+  tocMarkers?: Array<string>
+  /**
+   * Headings with one of these classes, written as `{.no-toc}` with
+   * `attributes`, are left out of the TOC (default: `["no-toc"]`).
+   */
+  tocExcludeClasses?: Array<string>
+  /**
+   * Headings whose text matches one of these patterns are left out of
+   * the TOC. Matching ignores case and `*` matches any run of
+   * characters, as in `"Changelog*"`.
+   */
+  tocExclude?: Array<string>
   /** Convert `.md` links to `.html` links for SSG output. */
   convertMdLinks?: boolean
   /** Base URL for absolute link conversion (e.g., "/" or "/docs/"). */
   baseUrl?: string
+  /** Line ending for the rendered HTML: `"lf"` (default) or `"crlf"`. */
+  lineEnding?: string
+  /** Ensure the rendered HTML ends with exactly one line ending. */
+  trailingNewline?: boolean
+  /**
+   * Write XHTML: self-closing void elements (`<br />`, `<img … />`) and
+   * boolean attributes with values (`disabled="disabled"`).
+   */
+  xhtml?: boolean
+  /** Prefix headings and TOC entries with section numbers (`1.`, `1.1.`, ...). */
+  numberHeadings?: boolean
+  /** Heading level that gets top-level numbers (default: 2). */
+  numberHeadingsStart?: number
+  /** Add `id` attributes to headings matching the TOC slugs (default: true). */
+  headingIds?: boolean
+  /**
+   * How heading IDs and TOC slugs are generated: `"github"` (default)
+   * matches GitHub's heading anchors, `"unicode"` keeps Unicode letters
+   * and joins words with single hyphens, `"ascii"` transliterates to a
+   * non-empty ASCII-only slug.
+   */
+  slugStyle?: string
+  /**
+   * Adjust heading levels before rendering and building the TOC:
+   * `"none"` (default), `"single_h1_demote"` keeps the first h1 and
+   * demotes later ones with their sections, `"promote_first"` turns the
+   * first heading into the h1 when the page has none.
+   */
+  normalizeHeadings?: string
+  /**
+   * When the frontmatter has a `title`, leave the first h1 out of the
+   * rendered body, the TOC and the anchors, for layouts that already
+   * show the title (default: false). Applied before `normalizeHeadings`.
+   */
+  dropTitleHeading?: boolean
+  /** Append a `#` permalink anchor to every heading with an ID. */
+  headingAnchors?: boolean
+  /**
+   * Emit semantic, ARIA-friendly HTML: `<figure>`/`<figcaption>` for
+   * titled images, labelled heading anchors and DPUB-ARIA footnote roles.
+   */
+  accessible?: boolean
+  /**
+   * Character sequences to encode as numeric entities inside code, such
+   * as `["{{", "}}"]`, so template engines leave code samples alone.
+   */
+  codeEscapes?: Array<string>
+  /**
+   * How math is rendered: `"passthrough"` (default) keeps the TeX source
+   * for a client-side renderer, `"mathml"` converts a supported subset
+   * of TeX to MathML and falls back to passthrough with a `data-latex`
+   * attribute for the rest.
+   */
+  mathOutput?: string
+  /**
+   * Code fence language aliases, such as `{ vue: "html" }`, added to the
+   * defaults (`js` → `javascript`, `ts` → `typescript`, `sh` → `bash`,
+   * ...). Map an alias to itself to keep it unchanged.
+   */
+  languageAliases?: Record<string, string>
+  /**
+   * Wrap tables in `<div class="table-wrapper">` so wide tables scroll
+   * horizontally (styled by the SSG stylesheet).
+   */
+  tableWrapper?: boolean
+  /**
+   * Number nested ordered list items `1.`, `1.1.`, `1.1.1.` by giving
+   * ordered lists `class="nested-numbers"` (styled by the SSG
+   * stylesheet, default: false).
+   */
+  nestedListNumbers?: boolean
+  /**
+   * Guess the language of code blocks written without one, adding a
+   * `language-*` class and `data-detected="true"` (default: false).
+   * Uncertain guesses leave the block untagged.
+   */
+  detectCodeLanguage?: boolean
+  /**
+   * Keep the line break of the last line inside `<code>`, as CommonMark
+   * does (default: true). Set to false for `<code>foo</code>`.
+   */
+  codeFinalNewline?: boolean
+  /**
+   * Escape the `<` of `<script>`, `<style>`, `<title>`, `<textarea>`,
+   * `<iframe>` and the other tags disallowed by the GFM tagfilter in raw
+   * HTML, matching GitHub's output. Defaults to on with the `gfm` and
+   * `gfm_strict` presets.
+   */
+  gfmTagfilter?: boolean
+  /**
+   * Link issue references such as `#123` to this URL, with `{n}`
+   * replaced by the number. References in code, in link text and
+   * escaped as `\#123` are left alone.
+   */
+  issueUrl?: string
+  /** Text that starts an issue reference (default: `"#"`). */
+  issuePrefix?: string
+  /**
+   * Link commit SHAs of 7 to 40 lowercase hex digits to this URL, with
+   * `{sha}` replaced by the full SHA. The link text is the short SHA.
+   */
+  commitUrl?: string
+  /**
+   * Link `@name` mentions to this URL, with `{name}` replaced by the
+   * name. Email-like text such as `a@b`, mentions in code and escaped
+   * `\@name` are left alone.
+   */
+  mentionUrl?: string
+  /**
+   * Add a `data-line` attribute with the 1-based source line to block
+   * elements such as paragraphs, headings, lists and code blocks, for
+   * scroll sync in editor previews (default: false).
+   *
+   * Lines count from the top of the file, frontmatter included. Lines
+   * after an inlined include are shifted by the included lines.
+   */
+  sourceLineAttrs?: boolean
+  /**
+   * Drop soft line breaks between two Chinese or Japanese characters,
+   * which browsers would otherwise show as a space (default: false).
+   * Breaks next to Latin text are kept.
+   */
+  cjkFriendlyBreaks?: boolean
+  /**
+   * Custom emoji shortcodes: `:name:` in text is replaced by the value.
+   * Values starting with `http://`, `https://`, `data:image/`, `/`, `./`
+   * or `../` are rendered as `<img class="emoji custom-emoji">`, others
+   * as text. Keys may be written with or without colons. Unknown
+   * shortcodes stay literal.
+   */
+  customEmoji?: Record<string, string>
+  /**
+   * HTML templates for custom node types in ASTs passed to
+   * `Renderer.renderAst`, keyed by node `type`. `{field}` is replaced by
+   * the node's string, number or boolean field of that name,
+   * HTML-escaped. Nodes of other unknown types render as an
+   * `<!-- unknown node: type -->` comment.
+   */
+  customNodes?: Record<string, string>
+  /**
+   * Markdown partials by path, used to resolve `{{< include "path" >}}`
+   * and `@include(path)` directives before parsing.
+   */
+  includes?: Record<string, string>
+  /** Maximum include nesting depth (default: 8). */
+  maxIncludeDepth?: number
+  /**
+   * Fail the transform when the frontmatter has errors (default: false).
+   *
+   * Frontmatter errors are always reported in `errors`, with line numbers
+   * relative to the file. Without this option the document is still
+   * rendered, using the frontmatter entries that parsed.
+   */
+  strictFrontmatter?: boolean
+  /**
+   * Read `toc`, `tocMinDepth`, `tocMaxDepth`, `gfm` and the
+   * `renderOptions` object from the page frontmatter (default: true).
+   *
+   * `renderOptions` may turn `convertMdLinks`, `headingIds`,
+   * `headingAnchors`, `numberHeadings`, `accessible`, `tableWrapper`,
+   * `nestedListNumbers`, `detectCodeLanguage` and `cjkFriendlyBreaks` on
+   * or off; other keys are ignored. Frontmatter values override the
+   * `defaults` passed to [`transform`] but not the explicit call options.
+   */
+  frontmatterOptions?: boolean
 }
-/** Parser options for JavaScript. */
+/** Feature sets for JavaScript, by the names of [`FeaturePreset`]. */
+export const enum JsFeaturePreset {
+  /** Every construct. */
+  Full = 'full',
+  /**
+   * Comment fields: paragraphs, block quotes, lists, code, links and
+   * emphasis, but no headings, thematic breaks, tables, images or HTML.
+   */
+  Basic = 'basic',
+  /** Paragraphs with links, emphasis and code spans only. */
+  InlineOnly = 'inline_only'
+}
+/** Parser presets for JavaScript, by the names of [`ParserPreset`]. */
+export const enum JsParserPreset {
+  /** Plain CommonMark with every extension disabled. */
+  CommonMark = 'commonmark',
+  /** GitHub Flavored Markdown, including footnotes. */
+  Gfm = 'gfm',
+  /** Only the extensions in the GFM spec: footnotes are disabled. */
+  GfmStrict = 'gfm_strict'
+}
+/**
+ * Parser options for JavaScript.
+ *
+ * Options are resolved in order:
+ * 1. `preset`, or `gfm: true` as shorthand for `preset: "gfm"`. Without
+ *    either, every extension starts disabled (`"commonmark"`). `gfm` only
+ *    picks the preset, so it is ignored when `preset` is set.
+ * 2. Every other field that is set overrides the preset, so
+ *    `{ gfm: true, autolinks: false }` is GFM without autolinks.
+ */
 export interface JsParserOptions {
-  /** Enable GFM extensions. */
+  /**
+   * Parser preset applied before the individual options:
+   * `"commonmark"`, `"gfm"` or `"gfm_strict"` (GFM spec extensions
+   * only, without footnotes). Other names are rejected.
+   */
+  preset?: 'commonmark' | 'gfm' | 'gfm_strict'
+  /**
+   * Enable GFM extensions. Shorthand for `preset: "gfm"`, ignored when
+   * `preset` is set: `gfm: false` does not turn a preset's extensions
+   * off.
+   */
   gfm?: boolean
-  /** Enable footnotes. */
+  /** Enable footnotes, including inline `^[note]` footnotes. */
   footnotes?: boolean
   /** Enable task lists. */
   taskLists?: boolean
@@ -72,6 +452,61 @@ export interface JsParserOptions {
   strikethrough?: boolean
   /** Enable autolinks. */
   autolinks?: boolean
+  /** Enable `*[ABBR]: definition` abbreviations. */
+  abbreviations?: boolean
+  /** Read a trailing `{#id}` on headings as the heading ID. */
+  attributes?: boolean
+  /** Parse Markdown between block-level HTML tags such as `<details>`. */
+  parseInsideHtmlBlocks?: boolean
+  /** Enable `$...$` inline and `$$` or ```` ```math ```` block TeX math. */
+  math?: boolean
+  /**
+   * Text that becomes a line break inside table cells, such as `\n`.
+   * `<br>` tags in cells always do.
+   */
+  tableCellBreak?: string
+  /**
+   * Collapse runs of spaces and tabs in text to a single space and drop
+   * the spaces around line breaks (default: false). Code is left as
+   * written.
+   */
+  normalizeWhitespace?: boolean
+  /**
+   * Restrict the recognized Markdown to a feature set: `"full"`
+   * (default), `"basic"` (no headings, thematic breaks, tables, images or
+   * HTML) or `"inline_only"` (paragraphs with links, emphasis and code
+   * spans). Disabled constructs are rendered as escaped source text.
+   * Other names are rejected rather than leaving every feature on.
+   */
+  features?: 'full' | 'basic' | 'inline_only'
+  /**
+   * Columns past a list marker that continue the item after a blank
+   * line, such as `2` for two-space indentation (default: 0, the
+   * CommonMark rule). Diverges from CommonMark.
+   */
+  listIndent?: number
+  /**
+   * Maximum nesting depth for lists, links and emphasis (default: 100).
+   * Deeper input is reported in `errors` instead of crashing.
+   */
+  maxNestingDepth?: number
+  /**
+   * Maximum input size in bytes. Larger input is reported in `errors`
+   * without being parsed.
+   */
+  maxInputBytes?: number
+  /**
+   * Time budget for parsing in milliseconds. Parsing stops with an entry
+   * in `errors` once it is exceeded.
+   */
+  timeBudgetMs?: number
+  /**
+   * Fail parsing, with an entry in `errors`, on input that is otherwise
+   * recovered from silently (default: false): full or collapsed link
+   * references without a definition, footnote references without a
+   * definition, and unclosed code fences or math blocks.
+   */
+  strict?: boolean
 }
 /**
  * Parses Markdown source into an AST.
@@ -79,22 +514,232 @@ export interface JsParserOptions {
  * Returns the AST as a JSON string for zero-copy transfer to JavaScript.
  */
 export declare function parse(source: string, options?: JsParserOptions | undefined | null): ParseResult
-/** Parses Markdown and renders to HTML. */
+/**
+ * Parses Markdown source into an AST and reports node count, parse time
+ * and input size.
+ */
+export declare function parseWithStats(source: string, options?: JsParserOptions | undefined | null): ParseWithStatsResult
+/** Task list checkboxes in a document. */
+export interface JsTaskProgress {
+  /** Number of task list items. */
+  total: number
+  /** Number of task list items checked with `[x]`. */
+  completed: number
+}
+/**
+ * Counts the checked and unchecked task list items in a document,
+ * including nested lists, without rendering it.
+ *
+ * Task lists are always enabled and frontmatter is skipped.
+ */
+export declare function taskProgress(source: string): JsTaskProgress
+/** Text counts for one section of a document. */
+export interface JsSectionStats {
+  /**
+   * Plain text of the h2 starting the section, or empty for the text
+   * before the first h2.
+   */
+  heading: string
+  /** The heading ID, or empty for the text before the first h2. */
+  slug: string
+  /** Number of words; each Chinese or Japanese character counts as one. */
+  words: number
+  /** Number of characters, not counting whitespace. */
+  chars: number
+}
+/**
+ * Counts the words and characters in each h2 section of a document,
+ * for spotting sections that are too thin or too long.
+ *
+ * Text before the first h2 is a section with an empty heading, if there
+ * is any. Slugs match the heading IDs of `transform` for the same
+ * `slugStyle`. Frontmatter is skipped and GFM is enabled; code blocks are
+ * not counted.
+ */
+export declare function sectionStats(source: string, slugStyle?: string | undefined | null): Array<JsSectionStats>
+/** Options for `linkPreview`. */
+export interface JsLinkPreviewOptions {
+  /** Reading speed used for `readingMinutes` (default: 200). */
+  wordsPerMinute?: number
+}
+/** What a link preview shows about a page. */
+export interface JsLinkPreview {
+  /** Frontmatter `title`, or the text of the first h1. */
+  title?: string
+  /** Frontmatter `description`, or the text of the first paragraph. */
+  description?: string
+  /** Frontmatter `image`, or the URL of the first image in the body. */
+  firstImage?: string
+  /**
+   * Estimated reading time in whole minutes, rounded up; 0 for a page
+   * without text.
+   */
+  readingMinutes: number
+}
+/**
+ * Collects what a link preview shows about a page in one pass: title,
+ * description, first image and reading time.
+ *
+ * Non-empty frontmatter `title`, `description` and `image` strings take
+ * precedence over the body. Words are counted like `sectionStats`, over
+ * the whole page. GFM is enabled.
+ */
+export declare function linkPreview(source: string, options?: JsLinkPreviewOptions | undefined | null): JsLinkPreview
+/** Options for `codeLanguages`. */
+export interface JsCodeLanguagesOptions {
+  /**
+   * Code fence language aliases, added to or overriding the built-in
+   * ones, as in `transform`.
+   */
+  languageAliases?: Record<string, string>
+}
+/**
+ * Returns the distinct languages of the page's fenced code blocks, in
+ * the order they are first used, for loading only the highlighter
+ * grammars a page needs.
+ *
+ * Languages are normalized like the `language-*` classes of `transform`.
+ * Fences without a language and `raw` fences are left out. Frontmatter is
+ * skipped and GFM is enabled.
+ */
+export declare function codeLanguages(source: string, options?: JsCodeLanguagesOptions | undefined | null): Array<string>
+/**
+ * Parses Markdown and renders to HTML.
+ *
+ * When parsing stops at an error, such as an unclosed code fence in
+ * strict mode, the blocks before it are still rendered and the error is
+ * reported in `errors`. Input over `maxInputBytes` renders nothing.
+ */
 export declare function parseAndRender(source: string, options?: JsParserOptions | undefined | null): RenderResult
-/** Renders an AST (provided as JSON) to HTML. */
+/**
+ * Parses Markdown and renders it to an HTML fragment for insertion into
+ * an existing element.
+ *
+ * The HTML is the same as from `parseAndRender`, with leading and
+ * trailing whitespace removed and no wrapping element. Throws if the
+ * source cannot be parsed.
+ */
+export declare function renderFragment(source: string, options?: JsParserOptions | undefined | null): string
+/**
+ * Markup choices for `formatMarkdown`. Unset or unsupported values use
+ * the default.
+ */
+export interface JsMarkdownStyle {
+  /** Bullet list marker: `"-"` (default), `"*"` or `"+"`. */
+  bullet?: string
+  /** Delimiter after ordered list numbers: `"."` (default) or `")"`. */
+  orderedDelimiter?: string
+  /** Emphasis marker: `"*"` (default) or `"_"`. */
+  emphasis?: string
+  /** Strong emphasis marker, written twice: `"*"` (default) or `"_"`. */
+  strong?: string
+  /**
+   * Heading syntax: `"atx"` (`# Title`, default) or `"atx_closed"`
+   * (`# Title #`).
+   */
+  heading?: string
+}
+/**
+ * Rewrites Markdown in one canonical style: the same bullet and ordered
+ * list markers, emphasis delimiters and heading syntax throughout.
+ *
+ * The source is parsed and written back from the AST, so the output is
+ * stable: formatting it again returns it unchanged. Frontmatter is kept
+ * as written. Parsing uses GFM unless `options` are given. Throws if the
+ * source cannot be parsed.
+ */
+export declare function formatMarkdown(source: string, style?: JsMarkdownStyle | undefined | null, options?: JsParserOptions | undefined | null): string
+/**
+ * Renders an AST (provided as JSON) to HTML.
+ *
+ * Accepts the AST format returned by `parse`.
+ */
 export declare function render(astJson: string): RenderResult
+/** A run of changed top-level blocks between two ASTs. */
+export interface AstBlockChange {
+  /** Index of the first changed block in the old AST. */
+  oldStart: number
+  /** Index after the last changed block in the old AST. */
+  oldEnd: number
+  /** Index of the first changed block in the new AST. */
+  newStart: number
+  /** Index after the last changed block in the new AST. */
+  newEnd: number
+}
+/** Result of [`diff_ast`]. */
+export interface AstDiffResult {
+  /**
+   * Runs of blocks that differ, in document order. Blocks outside them
+   * are unchanged and in the same order in both ASTs.
+   */
+  changes: Array<AstBlockChange>
+  /**
+   * A hash of every top-level block of the new AST as 16 hex digits.
+   * It ignores spans, so a block keeps its hash when text before it
+   * changes, and can key a cache of rendered blocks.
+   */
+  hashes: Array<string>
+}
+/**
+ * Compares the top-level blocks of two ASTs in the JSON format returned by
+ * `parse`, for re-rendering only the blocks that changed.
+ *
+ * # Errors
+ *
+ * Returns an error if either AST is not valid JSON with a `children`
+ * array.
+ */
+export declare function diffAst(oldAstJson: string, newAstJson: string): AstDiffResult
+/**
+ * Converts text to a slug with the algorithm used for heading IDs and TOC
+ * links.
+ *
+ * `style` is `"github"` (default), `"unicode"` or `"ascii"`, as in the
+ * `slugStyle`
+ * transform option. Repeated headings additionally get `-1`, `-2`, ...
+ * suffixes in document order; this function does not add them.
+ */
+export declare function slugify(text: string, style?: string | undefined | null): string
 /** Returns the version of ox_content_napi. */
 export declare function version(): string
 /**
  * Transforms Markdown source into HTML, frontmatter, and TOC.
  *
  * This is the main entry point for unplugin-ox-content.
+ *
+ * Options are resolved per field: `options` first, then the known keys in
+ * the page frontmatter, then `defaults`. As with [`parse_and_render`], a
+ * parse error is reported in `errors` along with the blocks before it.
+ */
+export declare function transform(source: string, options?: JsTransformOptions | undefined | null, defaults?: JsTransformOptions | undefined | null): TransformResult
+/**
+ * Transforms Markdown like [`transform`] and also returns the final AST.
+ *
+ * Saves parsing the source a second time when both the HTML and the AST
+ * are needed.
+ */
+export declare function transformFull(source: string, options?: JsTransformOptions | undefined | null, defaults?: JsTransformOptions | undefined | null): TransformFullResult
+/**
+ * Transforms Markdown like [`transform`] but returns only the section
+ * under the heading with ID `slug`, for section permalinks.
+ *
+ * The section is the heading and everything after it up to the next
+ * heading of the same or a higher level. The whole page is rendered and
+ * the section cut out, so heading IDs and numbers match the full page.
+ * Footnote definitions are left out. Only top-level headings can be
+ * selected; a slug no such heading has is reported in `errors`.
+ */
+export declare function renderSection(source: string, slug: string, options?: JsTransformOptions | undefined | null): RenderResult
+/**
+ * Merges per-call `overrides` over `base` options.
+ *
+ * Every field set in `overrides` wins; unset fields are taken from `base`.
  */
-export declare function transform(source: string, options?: JsTransformOptions | undefined | null): TransformResult
+export declare function mergeTransformOptions(base: JsTransformOptions, overrides: JsTransformOptions): JsTransformOptions
 /** Parses Markdown and renders to HTML asynchronously (runs on worker thread). */
 export declare function parseAndRenderAsync(source: string, options?: JsParserOptions | undefined | null): Promise<unknown>
 /** Transforms Markdown source asynchronously (runs on worker thread). */
-export declare function transformAsync(source: string, options?: JsTransformOptions | undefined | null): Promise<unknown>
+export declare function transformAsync(source: string, options?: JsTransformOptions | undefined | null, defaults?: JsTransformOptions | undefined | null): Promise<unknown>
 /** OG image configuration for JavaScript. */
 export interface JsOgImageConfig {
   /** Image width in pixels. */
@@ -109,6 +754,24 @@ export interface JsOgImageConfig {
   titleFontSize?: number
   /** Description font size. */
   descriptionFontSize?: number
+  /** Font family name. With `fontData`, the name given to the embedded font. */
+  fontFamily?: string
+  /**
+   * Font file (TTF, OTF, WOFF or WOFF2) embedded into the SVG as a base64
+   * `@font-face`. When unset, the system UI font is used.
+   */
+  fontData?: Buffer
+  /**
+   * Card layout: `default`, `centered`, `split`, `minimal` or `card`.
+   * Unknown names use `default`.
+   */
+  layout?: string
+  /**
+   * Description shown when the data has none, such as the site tagline.
+   * Without one, the title is centered in the space the description
+   * would take.
+   */
+  fallbackDescription?: string
 }
 /** OG image data for JavaScript. */
 export interface JsOgImageData {
@@ -128,6 +791,14 @@ export interface JsOgImageData {
  * that can be used for social media previews.
  */
 export declare function generateOgImageSvg(data: JsOgImageData, config?: JsOgImageConfig | undefined | null): string
+/**
+ * Generates one OG image as SVG per config from the same data, such as
+ * Open Graph, Twitter and square cards.
+ *
+ * Each image is laid out for its own width and height; unset fields use
+ * the same defaults as `generateOgImageSvg`.
+ */
+export declare function generateOgImageVariants(data: JsOgImageData, configs: Array<JsOgImageConfig>): Array<string>
 /** Search document for JavaScript. */
 export interface JsSearchDocument {
   /** Unique document identifier. */
@@ -142,6 +813,12 @@ export interface JsSearchDocument {
   headings: Array<string>
   /** Code snippets. */
   code: Array<string>
+  /** Ranking multiplier applied to this document's score (defaults to 1.0). */
+  boost?: number
+  /** Tags, matched by the `tags` search option. */
+  tags?: Array<string>
+  /** Facet values by facet name, matched by the `facets` search option. */
+  facets?: Record<string, Array<string>>
 }
 /** Search result for JavaScript. */
 export interface JsSearchResult {
@@ -155,8 +832,29 @@ export interface JsSearchResult {
   score: number
   /** Matched terms. */
   matches: Array<string>
-  /** Content snippet. */
+  /** Field that produced the top match ("title", "heading", "body", or "code"). */
+  matchedField: string
+  /** Content snippet, taken from the matched field. */
+  snippet: string
+  /** Matching sections of the page, best first, when `groupByPage` is set. */
+  subMatches?: Array<JsSearchSubMatch>
+  /** Tags of the document. */
+  tags: Array<string>
+  /** Facet values of the document by facet name. */
+  facets: Record<string, Array<string>>
+}
+/** A matching section within a grouped search result. */
+export interface JsSearchSubMatch {
+  /** Document ID of the section. */
+  id: string
+  /** Section URL, including its fragment. */
+  url: string
+  /** Heading the match was found under. */
+  heading: string
+  /** Content snippet for the match. */
   snippet: string
+  /** Relevance score of the section. */
+  score: number
 }
 /** Search options for JavaScript. */
 export interface JsSearchOptions {
@@ -168,6 +866,28 @@ export interface JsSearchOptions {
   fuzzy?: boolean
   /** Minimum score threshold. */
   threshold?: number
+  /**
+   * Group sections of the same page (URLs differing only in `#fragment`)
+   * into one result with `subMatches`.
+   */
+  groupByPage?: boolean
+  /** Only return documents with all of these tags (case-insensitive). */
+  tags?: Array<string>
+  /**
+   * Only return documents that have, for every facet listed, at least
+   * one of the listed values (case-insensitive).
+   */
+  facets?: Record<string, Array<string>>
+  /**
+   * Maximum snippet length in characters (default: 150). `0` leaves
+   * snippets empty.
+   */
+  snippetLength?: number
+  /**
+   * Characters of context shown before the first match (default: a
+   * third of `snippetLength`).
+   */
+  snippetContext?: number
 }
 /**
  * Builds a search index from documents.
@@ -175,6 +895,13 @@ export interface JsSearchOptions {
  * Takes an array of documents and returns a serialized search index as JSON.
  */
 export declare function buildSearchIndex(documents: Array<JsSearchDocument>): string
+/**
+ * Builds a search index from documents in the compact binary format.
+ *
+ * The result is smaller and faster to load than the JSON index returned by
+ * `buildSearchIndex`, and can be searched with `searchIndexBinary`.
+ */
+export declare function buildSearchIndexBinary(documents: Array<JsSearchDocument>): Buffer
 /**
  * Searches a serialized index.
  *
@@ -182,6 +909,54 @@ export declare function buildSearchIndex(documents: Array<JsSearchDocument>): st
  * Returns an array of search results.
  */
 export declare function searchIndex(indexJson: string, query: string, options?: JsSearchOptions | undefined | null): Array<JsSearchResult>
+/**
+ * Searches a binary index produced by `buildSearchIndexBinary`.
+ *
+ * Returns an array of search results.
+ */
+export declare function searchIndexBinary(index: Buffer, query: string, options?: JsSearchOptions | undefined | null): Array<JsSearchResult>
+/** Client search bundle for JavaScript. */
+export interface JsSearchClientBundle {
+  /** ES module exporting `tokenize`, `createSearch(index)` and `loadSearch(url)`. */
+  js: string
+  /** Compact JSON index to pass to `createSearch` or serve for `loadSearch`. */
+  index: string
+}
+/**
+ * Generates a standalone client search bundle for a JSON index.
+ *
+ * The returned module scores results exactly like `searchIndex`, so it can be
+ * used outside the SSG template.
+ */
+export declare function generateSearchClient(indexJson: string): JsSearchClientBundle
+/** How often a term occurs in a search index. */
+export interface JsTermStats {
+  /** The indexed term, as produced by the tokenizer. */
+  term: string
+  /** Number of documents containing the term. */
+  documents: number
+  /** Occurrences across all documents and fields. */
+  occurrences: number
+}
+/** Summary of what a search index contains. */
+export interface JsIndexStats {
+  /** Number of documents. */
+  documents: number
+  /** Number of distinct terms (the vocabulary size). */
+  terms: number
+  /** Average number of body tokens per document, as used for scoring. */
+  avgDocLength: number
+  /**
+   * The most frequent terms, ranked by the number of documents containing
+   * them and then by occurrences.
+   */
+  topTerms: Array<JsTermStats>
+}
+/**
+ * Returns the size of a JSON index and its `top` most frequent terms
+ * (default: 20), for tuning stop words and spotting indexing problems.
+ */
+export declare function searchIndexStats(indexJson: string, top?: number | undefined | null): JsIndexStats
 /** Navigation item for SSG. */
 export interface JsSsgNavItem {
   /** Display title. */
@@ -208,8 +983,19 @@ export interface JsSsgPageData {
   content: string
   /** Table of contents entries. */
   toc: Array<TocEntry>
+  /**
+   * Show the TOC aside (default: true). When enabled it is shown even
+   * without entries, unless `toc_min_entries` hides it.
+   */
+  tocEnabled?: boolean
   /** URL path. */
   path: string
+  /**
+   * Page language for `<html lang>` and `og:locale`, such as `en` or
+   * `pt-BR`, usually the `lang` frontmatter key. Falls back to the
+   * config `lang`.
+   */
+  lang?: string
 }
 /** SSG configuration. */
 export interface JsSsgConfig {
@@ -219,12 +1005,223 @@ export interface JsSsgConfig {
   base: string
   /** OG image URL. */
   ogImage?: string
+  /** Hide the TOC on pages with fewer entries than this (default: 0). */
+  tocMinEntries?: number
+  /**
+   * Search index JSON (from `buildSearchIndex`) to embed in every page,
+   * so search works without fetching `search-index.json`. Only for small
+   * indexes: it is repeated in each page.
+   */
+  inlineSearchIndex?: string
+  /** Language of pages that do not set their own (default: `en`). */
+  lang?: string
+  /**
+   * Nonce added to the generated `<style>`, `<script>` and stylesheet
+   * `<link>` tags, for a Content-Security-Policy with `'nonce-…'`.
+   */
+  cspNonce?: string
+  /**
+   * Link `ssg.css`, `ssg-theme.js` and `ssg.js` under `base` instead of
+   * inlining them (default: false). Write the files returned by
+   * `generateSsgAssets`.
+   */
+  externalAssets?: boolean
+  /**
+   * Logo image shown in the header (default: `logo.svg`). Site paths,
+   * including those starting with `/`, are resolved against `base`;
+   * full URLs such as `https://cdn.example.com/logo.svg` are used as
+   * they are.
+   */
+  logoPath?: string
+  /**
+   * Search index fetched when search is first used (default:
+   * `search-index.json`).
+   */
+  searchIndexUrl?: string
+  /** Page the site name in the header links to (default: `index.html`). */
+  homeUrl?: string
+}
+/** A stylesheet or script file for pages with `externalAssets`. */
+export interface JsSsgAsset {
+  /** File path relative to the site base, such as `ssg.css`. */
+  path: string
+  /** File content. */
+  content: string
 }
-/** Generates SSG HTML page with navigation and search. */
+/** Generates SSG HTML page with navigation and search.
 export declare function generateSsgHtml(pageData: JsSsgPageData, navGroups: Array<JsSsgNavGroup>, config: JsSsgConfig): string
+/**
+ * Returns the stylesheet and scripts that pages generated with
+ * `externalAssets` link to, for writing under the site base.
+ *
+ * The page script includes the config's search index URL and inlined
+ * search index, so pass the same config as to `generateSsgHtml`.
+ */
+export declare function generateSsgAssets(config: JsSsgConfig): Array<JsSsgAsset>
+/**
+ * Renders the `<title>` and meta, Open Graph and Twitter card tags of a
+ * page head from its frontmatter, one tag per line.
+ *
+ * Takes the frontmatter as JSON, such as the `frontmatter` returned by
+ * `transform`, and reads its `title`, `description`, `image`, `author`,
+ * `lang` and `tags` (as keywords). These are the tags `generateSsgHtml`
+ * writes, for pages whose head is assembled separately.
+ */
+export declare function renderMetaTags(frontmatterJson: string, config: JsSsgConfig): string
+/** Options for extracting searchable content. */
+export interface JsExtractSearchOptions {
+  /** Also include inline code and code blocks in the body text (default: false). */
+  includeCodeInBody?: boolean
+  /** Derive a title from the URL when the page has no title or headings (default: true). */
+  titleFromUrl?: boolean
+  /** Frontmatter keys copied into the document's `facets` (default: none). */
+  facetKeys?: Array<string>
+  /**
+   * When the frontmatter has a `title`, leave the first h1 out of the
+   * headings and body, matching the `dropTitleHeading` transform option
+   * (default: false).
+   */
+  dropTitleHeading?: boolean
+}
 /**
  * Extracts searchable content from Markdown source.
  *
  * Parses the Markdown and extracts title, body text, headings, and code.
+ * The title is taken from frontmatter `title`, then the first H1, then the
+ * first heading of any level, then (unless disabled) the URL slug.
+ * With `dropTitleHeading`, a frontmatter title replaces the first H1
+ * instead of being indexed alongside it; the H1 fallback is unaffected, as
+ * it only applies to pages without a frontmatter title.
+ * Code is kept out of the body unless `includeCodeInBody` is set.
+ * Frontmatter `tags` become the document's tags, and the keys listed in
+ * `facetKeys` become its facets; both accept a list or a comma-separated
+ * string. Other frontmatter values are never indexed.
+ */
+export declare function extractSearchContent(source: string, id: string, url: string, options?: JsParserOptions | undefined | null, extractOptions?: JsExtractSearchOptions | undefined | null): JsSearchDocument
+/** Options for [`build_page`]. */
+export interface JsBuildPageOptions {
+  /**
+   * URL path of the page, used as the SSG page path and the search
+   * document ID.
+   */
+  path: string
+  /** Search document URL (default: `path`). */
+  url?: string
+  /** Transform options, as for `transform`. */
+  transform?: JsTransformOptions
+  /** Transform defaults, as for `transform`. */
+  defaults?: JsTransformOptions
+  /**
+   * Search content options, as for `extractSearchContent`. The title
+   * heading is dropped by the transform `dropTitleHeading` option
+   * instead, so the search document matches the page.
+   */
+  search?: JsExtractSearchOptions
+}
+/** Result of [`build_page`]. */
+export interface JsBuiltPage {
+  /** The full SSG HTML page. */
+  html: string
+  /** The page's search document. */
+  searchDocument: JsSearchDocument
+  /** Parsed frontmatter as JSON string. */
+  frontmatter: string
+  /** Table of contents entries. */
+  toc: Array<TocEntry>
+  /** Parse/render errors, if any. */
+  errors: Array<string>
+}
+/**
+ * Builds an SSG page from Markdown source with a single parse: the same
+ * as calling `transform`, `extractSearchContent` and `generateSsgHtml`.
+ *
+ * The page title is the frontmatter `title`, then the search document
+ * title; the description and language come from the frontmatter
+ * `description` and `lang`. The search document is extracted from the AST
+ * the page is rendered from, so it reflects `dropTitleHeading` and the
+ * parser options in `transform`.
+ */
+export declare function buildPage(source: string, navGroups: Array<JsSsgNavGroup>, config: JsSsgConfig, options: JsBuildPageOptions): JsBuiltPage
+/** Lint rule toggles for JavaScript. Every rule is enabled by default. */
+export interface JsLintOptions {
+  /** Parser options used to build the AST. */
+  parser?: JsParserOptions
+  /** Report headings that produce the same slug. */
+  duplicateHeadingSlug?: boolean
+  /**
+   * Slug style for `duplicateHeadingSlug`, as `slugStyle` in `transform`
+   * (default: `"github"`).
+   */
+  slugStyle?: string
+  /** Report relative links to files outside `context.files`. */
+  brokenRelativeLink?: boolean
+  /** Report images without alt text. */
+  imageMissingAlt?: boolean
+  /** Report heading level skips (e.g. h2 followed by h4). */
+  headingLevelSkip?: boolean
+  /** Report unclosed fenced code blocks and frontmatter. */
+  unclosedContainer?: boolean
+  /**
+   * Report list items whose indentation is ambiguous relative to the
+   * parent item's marker width, or mixes tabs and spaces.
+   */
+  ambiguousListIndent?: boolean
+}
+/** Where the linted source lives, for resolving relative links. */
+export interface JsLintContext {
+  /** Path of the linted file relative to the content root (e.g. `guide/intro.md`). */
+  file?: string
+  /** Known files relative to the content root. Relative links are only checked when set. */
+  files?: Array<string>
+}
+/** A lint issue. */
+export interface JsLintIssue {
+  /** Rule name (e.g. `broken-relative-link`). */
+  rule: string
+  /** Human-readable description. */
+  message: string
+  /** 1-indexed line number. */
+  line: number
+  /** `"warning"` or `"error"`. */
+  severity: string
+}
+/**
+ * Lints Markdown source without rendering it.
+ *
+ * Reports duplicate heading slugs, broken relative links, images without
+ * alt text, heading level skips, unclosed containers and ambiguous list
+ * indentation, in source order.
  */
-export declare function extractSearchContent(source: string, id: string, url: string, options?: JsParserOptions | undefined | null): JsSearchDocument
+export declare function lint(source: string, options?: JsLintOptions | undefined | null, context?: JsLintContext | undefined | null): Array<JsLintIssue>
+/** A link whose target is not a known page. */
+export interface JsBrokenLink {
+  /** The link destination as written. */
+  href: string
+  /** 1-indexed line number. */
+  line: number
+}
+/**
+ * Finds the links in Markdown source that point to a path outside
+ * `known_paths`, without network calls.
+ *
+ * Relative links are resolved against `base_url`, the URL path of the page
+ * (`/guide/intro` or `/guide/`). Paths match with or without a trailing
+ * `/`, `index`, `.html` or `.md`; query strings and fragments are ignored.
+ * External URLs and same-page fragments are skipped. The source is parsed
+ * as GFM.
+ */
+export declare function validateLinks(source: string, knownPaths: Array<string>, baseUrl: string): Array<JsBrokenLink>
+/**
+ * A configured renderer that can be reused across many documents.
+ *
+ * Options are converted once at construction, and the parser arena and
+ * output buffer are reused between renders.
+ */
+export declare class Renderer {
+  /** Creates a renderer with the given options. */
+  constructor(options?: JsTransformOptions | undefined | null)
+  /** Renders an AST (in the format returned by `parse`) to HTML. */
+  renderAst(astJson: string): RenderResult
+  /** Parses Markdown source and renders it to HTML. */
+  renderSource(source: string): RenderResult
+}
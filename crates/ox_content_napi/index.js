@@ -48,14 +48,39 @@ const binding = loadBinding();
 // Export individual functions for ESM compatibility
 module.exports = binding;
 module.exports.parse = binding.parse;
+module.exports.parseWithStats = binding.parseWithStats;
+module.exports.taskProgress = binding.taskProgress;
+module.exports.sectionStats = binding.sectionStats;
+module.exports.linkPreview = binding.linkPreview;
+module.exports.codeLanguages = binding.codeLanguages;
 module.exports.parseAndRender = binding.parseAndRender;
+module.exports.renderFragment = binding.renderFragment;
+module.exports.formatMarkdown = binding.formatMarkdown;
 module.exports.parseAndRenderAsync = binding.parseAndRenderAsync;
 module.exports.render = binding.render;
+module.exports.diffAst = binding.diffAst;
+module.exports.Renderer = binding.Renderer;
+module.exports.JsParserPreset = binding.JsParserPreset;
+module.exports.JsFeaturePreset = binding.JsFeaturePreset;
 module.exports.transform = binding.transform;
+module.exports.transformFull = binding.transformFull;
+module.exports.renderSection = binding.renderSection;
+module.exports.mergeTransformOptions = binding.mergeTransformOptions;
 module.exports.transformAsync = binding.transformAsync;
+module.exports.slugify = binding.slugify;
 module.exports.version = binding.version;
 module.exports.generateOgImageSvg = binding.generateOgImageSvg;
+module.exports.generateOgImageVariants = binding.generateOgImageVariants;
 module.exports.buildSearchIndex = binding.buildSearchIndex;
 module.exports.searchIndex = binding.searchIndex;
+module.exports.buildSearchIndexBinary = binding.buildSearchIndexBinary;
+module.exports.searchIndexBinary = binding.searchIndexBinary;
+module.exports.generateSearchClient = binding.generateSearchClient;
+module.exports.searchIndexStats = binding.searchIndexStats;
 module.exports.extractSearchContent = binding.extractSearchContent;
+module.exports.buildPage = binding.buildPage;
 module.exports.generateSsgHtml = binding.generateSsgHtml;
+module.exports.generateSsgAssets = binding.generateSsgAssets;
+module.exports.renderMetaTags = binding.renderMetaTags;
+module.exports.lint = binding.lint;
+module.exports.validateLinks = binding.validateLinks;
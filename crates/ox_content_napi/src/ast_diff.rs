@@ -0,0 +1,193 @@
+//! Block-level diffing of AST JSON for incremental rendering.
+//!
+//! Each top-level block gets a hash of its JSON with every `span` left out,
+//! so a block that only moved because text before it changed keeps its
+//! hash. Diffing the two hash sequences gives the blocks to re-render.
+
+use std::ops::Range;
+
+use serde_json::Value;
+
+/// Largest number of block pairs compared after trimming the unchanged
+/// start and end. Bigger differences are reported as a single change.
+const MAX_DIFF_CELLS: usize = 1 << 22;
+
+/// A run of changed top-level blocks: `old` blocks were replaced by `new`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockChange {
+    /// Block indices in the old document.
+    pub old: Range<usize>,
+    /// Block indices in the new document.
+    pub new: Range<usize>,
+}
+
+/// Returns the hashes of the top-level blocks of a document in JSON form.
+pub fn block_hashes(document: &Value) -> Result<Vec<u64>, String> {
+    let children = document
+        .get("children")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "expected a document with a `children` array".to_string())?;
+    Ok(children.iter().map(block_hash).collect())
+}
+
+/// Hashes a node with FNV-1a, ignoring `span` fields.
+///
+/// The hash only depends on the node's content, so it is stable across
+/// runs and versions and can be used as a cache key.
+pub fn block_hash(node: &Value) -> u64 {
+    let mut hasher = Fnv1a(0xcbf2_9ce4_8422_2325);
+    hash_value(node, &mut hasher);
+    hasher.0
+}
+
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x100_0000_01b3);
+        }
+    }
+
+    /// Writes a length with the same width on every platform.
+    fn write_len(&mut self, len: usize) {
+        self.write(&(len as u64).to_le_bytes());
+    }
+}
+
+fn hash_value(value: &Value, hasher: &mut Fnv1a) {
+    // A tag per kind keeps e.g. `"1"` and `1` apart
+    match value {
+        Value::Null => hasher.write(b"n"),
+        Value::Bool(b) => hasher.write(if *b { b"t" } else { b"f" }),
+        Value::Number(n) => {
+            hasher.write(b"#");
+            hasher.write(n.to_string().as_bytes());
+        }
+        Value::String(s) => {
+            hasher.write(b"\"");
+            hasher.write_len(s.len());
+            hasher.write(s.as_bytes());
+        }
+        Value::Array(items) => {
+            hasher.write(b"[");
+            hasher.write_len(items.len());
+            for item in items {
+                hash_value(item, hasher);
+            }
+        }
+        Value::Object(object) => {
+            let mut keys: Vec<&String> = object.keys().filter(|key| *key != "span").collect();
+            keys.sort_unstable();
+            hasher.write(b"{");
+            hasher.write_len(keys.len());
+            for key in keys {
+                hasher.write_len(key.len());
+                hasher.write(key.as_bytes());
+                hash_value(&object[key], hasher);
+            }
+        }
+    }
+}
+
+/// Returns the runs of blocks that differ between two hash sequences, in
+/// order.
+///
+/// Blocks outside the runs are unchanged and appear in the same order in
+/// both documents, so their rendered HTML can be reused.
+pub fn diff_blocks(old: &[u64], new: &[u64]) -> Vec<BlockChange> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+    if old_middle.is_empty() && new_middle.is_empty() {
+        return Vec::new();
+    }
+    if old_middle.is_empty()
+        || new_middle.is_empty()
+        || old_middle.len().saturating_mul(new_middle.len()) > MAX_DIFF_CELLS
+    {
+        return vec![BlockChange {
+            old: prefix..prefix + old_middle.len(),
+            new: prefix..prefix + new_middle.len(),
+        }];
+    }
+
+    // Longest common subsequence of the middle parts
+    let (n, m) = (old_middle.len(), new_middle.len());
+    let mut lengths = vec![0u32; (n + 1) * (m + 1)];
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[at(i, j)] = if old_middle[i] == new_middle[j] {
+                lengths[at(i + 1, j + 1)] + 1
+            } else {
+                lengths[at(i + 1, j)].max(lengths[at(i, j + 1)])
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let (mut old_start, mut new_start) = (0, 0);
+    while i < n || j < m {
+        if old_middle.get(i).is_some_and(|hash| new_middle.get(j) == Some(hash)) {
+            if old_start < i || new_start < j {
+                changes.push(BlockChange {
+                    old: prefix + old_start..prefix + i,
+                    new: prefix + new_start..prefix + j,
+                });
+            }
+            i += 1;
+            j += 1;
+            (old_start, new_start) = (i, j);
+        } else if j == m || (i < n && lengths[at(i + 1, j)] >= lengths[at(i, j + 1)]) {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    if old_start < n || new_start < m {
+        changes.push(BlockChange {
+            old: prefix + old_start..prefix + n,
+            new: prefix + new_start..prefix + m,
+        });
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_block_hash_ignores_spans() {
+        let a = json!({ "type": "text", "value": "a", "span": { "start": 0, "end": 1 } });
+        let b = json!({ "type": "text", "value": "a", "span": { "start": 9, "end": 10 } });
+        let c = json!({ "type": "text", "value": "b", "span": { "start": 0, "end": 1 } });
+        assert_eq!(block_hash(&a), block_hash(&b));
+        assert_ne!(block_hash(&a), block_hash(&c));
+        assert_ne!(block_hash(&json!("1")), block_hash(&json!(1)));
+    }
+
+    #[test]
+    fn test_diff_blocks() {
+        let change = |old: Range<usize>, new: Range<usize>| BlockChange { old, new };
+        assert_eq!(diff_blocks(&[1, 2, 3], &[1, 2, 3]), []);
+        assert_eq!(diff_blocks(&[1, 2, 3], &[1, 9, 3]), [change(1..2, 1..2)]);
+        assert_eq!(diff_blocks(&[1, 2, 3], &[1, 2, 4, 3]), [change(2..2, 2..3)]);
+        assert_eq!(diff_blocks(&[1, 2, 3], &[1, 3]), [change(1..2, 1..1)]);
+        assert_eq!(
+            diff_blocks(&[1, 2, 3, 4, 5], &[1, 8, 3, 4, 9]),
+            [change(1..2, 1..2), change(4..5, 4..5)]
+        );
+        assert_eq!(diff_blocks(&[], &[1, 2]), [change(0..0, 0..2)]);
+    }
+}
@@ -0,0 +1,600 @@
+//! JSON representation of the Markdown AST.
+//!
+//! Every node is an object with a `type` field (`paragraph`, `heading`,
+//! `codeBlock`, `inlineCode`, ...), its fields in camelCase and a `span`
+//! with `start`/`end` byte offsets. [`document_from_json`] accepts the same
+//! shape, with `span` and optional fields allowed to be missing. Nodes of
+//! other types are rendered with the renderer's custom node templates.
+
+use ox_content_allocator::{Allocator, Vec};
+use ox_content_ast::{
+    Abbreviation, AlignKind, BlockQuote, Break, CodeBlock, Definition, Delete, Document, Emphasis,
+    FootnoteDefinition, FootnoteReference, Heading, Html, Image, InlineCode, InlineFootnote,
+    InlineMath, Link, List, ListItem, Math, Node, Paragraph, Span, Strong, Table, TableCell,
+    TableRow, Text, ThematicBreak,
+};
+use ox_content_renderer::HtmlRendererOptions;
+use serde_json::{json, Map, Value};
+
+/// Converts a document to its JSON representation.
+pub fn document_to_json(document: &Document<'_>) -> Value {
+    json!({
+        "type": "root",
+        "children": nodes_to_json(&document.children),
+        "span": span_to_json(document.span),
+    })
+}
+
+fn nodes_to_json(nodes: &[Node<'_>]) -> Value {
+    Value::Array(nodes.iter().map(node_to_json).collect())
+}
+
+fn span_to_json(span: Span) -> Value {
+    json!({ "start": span.start, "end": span.end })
+}
+
+fn node_to_json(node: &Node<'_>) -> Value {
+    let mut value = match node {
+        Node::Paragraph(n) => {
+            json!({ "type": "paragraph", "children": nodes_to_json(&n.children) })
+        }
+        Node::Heading(n) => json!({
+            "type": "heading",
+            "depth": n.depth,
+            "id": n.id,
+            "classes": n.classes.as_slice(),
+            "children": nodes_to_json(&n.children),
+        }),
+        Node::ThematicBreak(_) => json!({ "type": "thematicBreak" }),
+        Node::BlockQuote(n) => {
+            json!({ "type": "blockquote", "children": nodes_to_json(&n.children) })
+        }
+        Node::List(n) => json!({
+            "type": "list",
+            "ordered": n.ordered,
+            "start": n.start,
+            "spread": n.spread,
+            "children": n.children.iter().map(list_item_to_json).collect::<std::vec::Vec<_>>(),
+        }),
+        Node::ListItem(n) => return list_item_to_json(n),
+        Node::CodeBlock(n) => json!({
+            "type": "codeBlock",
+            "lang": n.lang,
+            "meta": n.meta,
+            "value": n.value,
+        }),
+        Node::Html(n) => json!({ "type": "html", "value": n.value }),
+        Node::Table(n) => return table_to_json(n),
+        Node::Math(n) => json!({ "type": "math", "value": n.value }),
+        Node::Text(n) => json!({ "type": "text", "value": n.value }),
+        Node::Emphasis(n) => json!({ "type": "emphasis", "children": nodes_to_json(&n.children) }),
+        Node::Strong(n) => json!({ "type": "strong", "children": nodes_to_json(&n.children) }),
+        Node::InlineCode(n) => json!({ "type": "inlineCode", "value": n.value }),
+        Node::Break(_) => json!({ "type": "break" }),
+        Node::Link(n) => json!({
+            "type": "link",
+            "url": n.url,
+            "title": n.title,
+            "children": nodes_to_json(&n.children),
+        }),
+        Node::Image(n) => json!({ "type": "image", "url": n.url, "alt": n.alt, "title": n.title }),
+        Node::Delete(n) => json!({ "type": "delete", "children": nodes_to_json(&n.children) }),
+        Node::FootnoteReference(n) => json!({
+            "type": "footnoteReference",
+            "identifier": n.identifier,
+            "label": n.label,
+        }),
+        Node::InlineFootnote(n) => {
+            json!({ "type": "inlineFootnote", "children": nodes_to_json(&n.children) })
+        }
+        Node::Abbreviation(n) => {
+            json!({ "type": "abbreviation", "value": n.value, "title": n.title })
+        }
+        Node::InlineMath(n) => json!({ "type": "inlineMath", "value": n.value }),
+        Node::Definition(n) => json!({
+            "type": "definition",
+            "identifier": n.identifier,
+            "label": n.label,
+            "url": n.url,
+            "title": n.title,
+        }),
+        Node::FootnoteDefinition(n) => json!({
+            "type": "footnoteDefinition",
+            "identifier": n.identifier,
+            "label": n.label,
+            "children": nodes_to_json(&n.children),
+        }),
+    };
+    value["span"] = span_to_json(node.span());
+    value
+}
+
+fn list_item_to_json(item: &ListItem<'_>) -> Value {
+    json!({
+        "type": "listItem",
+        "spread": item.spread,
+        "checked": item.checked,
+        "children": nodes_to_json(&item.children),
+        "span": span_to_json(item.span),
+    })
+}
+
+fn table_to_json(table: &Table<'_>) -> Value {
+    let align: std::vec::Vec<Value> = table
+        .align
+        .iter()
+        .map(|align| match align {
+            AlignKind::None => Value::Null,
+            AlignKind::Left => "left".into(),
+            AlignKind::Center => "center".into(),
+            AlignKind::Right => "right".into(),
+        })
+        .collect();
+    let rows: std::vec::Vec<Value> = table
+        .children
+        .iter()
+        .map(|row| {
+            let cells: std::vec::Vec<Value> = row
+                .children
+                .iter()
+                .map(|cell| {
+                    json!({
+                        "type": "tableCell",
+                        "children": nodes_to_json(&cell.children),
+                        "span": span_to_json(cell.span),
+                    })
+                })
+                .collect();
+            json!({ "type": "tableRow", "children": cells, "span": span_to_json(row.span) })
+        })
+        .collect();
+    json!({ "type": "table", "align": align, "children": rows, "span": span_to_json(table.span) })
+}
+
+/// Builds a document from its JSON representation, allocating in `allocator`.
+///
+/// Nodes of unknown types become HTML nodes holding
+/// [`HtmlRendererOptions::custom_node_html`] for their type and fields.
+pub fn document_from_json<'a>(
+    allocator: &'a Allocator,
+    value: &Value,
+    options: &HtmlRendererOptions,
+) -> Result<Document<'a>, String> {
+    let reader = Reader { allocator, options };
+    let object = as_object(value)?;
+    match object.get("type").and_then(Value::as_str) {
+        Some("root") => {}
+        Some(other) => return Err(format!("expected a `root` node, found `{other}`")),
+        None => return Err("expected a `root` node".to_string()),
+    }
+    Ok(Document { children: reader.nodes(object)?, span: read_span(object) })
+}
+
+struct Reader<'a, 'o> {
+    allocator: &'a Allocator,
+    options: &'o HtmlRendererOptions,
+}
+
+impl<'a> Reader<'a, '_> {
+    fn str(&self, object: &Map<String, Value>, key: &str) -> Result<&'a str, String> {
+        self.opt_str(object, key)?.ok_or_else(|| format!("missing string field `{key}`"))
+    }
+
+    fn opt_str(&self, object: &Map<String, Value>, key: &str) -> Result<Option<&'a str>, String> {
+        match object.get(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(s)) => Ok(Some(self.allocator.alloc_str(s))),
+            Some(_) => Err(format!("field `{key}` must be a string")),
+        }
+    }
+
+    fn strs(&self, object: &Map<String, Value>, key: &str) -> Result<Vec<'a, &'a str>, String> {
+        let mut strs = self.allocator.new_vec();
+        match object.get(key) {
+            None | Some(Value::Null) => {}
+            Some(Value::Array(values)) => {
+                for value in values {
+                    let s = value
+                        .as_str()
+                        .ok_or_else(|| format!("field `{key}` must be an array of strings"))?;
+                    strs.push(self.allocator.alloc_str(s));
+                }
+            }
+            Some(_) => return Err(format!("field `{key}` must be an array of strings")),
+        }
+        Ok(strs)
+    }
+
+    fn nodes(&self, object: &Map<String, Value>) -> Result<Vec<'a, Node<'a>>, String> {
+        let children = read_children(object)?;
+        let mut nodes = self.allocator.new_vec_with_capacity(children.len());
+        for child in children {
+            nodes.push(self.node(child)?);
+        }
+        Ok(nodes)
+    }
+
+    fn node(&self, value: &Value) -> Result<Node<'a>, String> {
+        let object = as_object(value)?;
+        let span = read_span(object);
+        let node_type = object
+            .get("type")
+            .and_then(Value::as_str)
+            .ok_or_else(|| "AST node is missing `type`".to_string())?;
+
+        let node = match node_type {
+            "paragraph" => Node::Paragraph(Paragraph { children: self.nodes(object)?, span }),
+            "heading" => {
+                let depth = object
+                    .get("depth")
+                    .and_then(Value::as_u64)
+                    .filter(|depth| (1..=6).contains(depth))
+                    .ok_or_else(|| "heading `depth` must be between 1 and 6".to_string())?;
+                #[allow(clippy::cast_possible_truncation)]
+                let depth = depth as u8;
+                Node::Heading(Heading {
+                    depth,
+                    children: self.nodes(object)?,
+                    id: self.opt_str(object, "id")?,
+                    classes: self.strs(object, "classes")?,
+                    span,
+                })
+            }
+            "thematicBreak" => Node::ThematicBreak(ThematicBreak { span }),
+            "blockquote" => Node::BlockQuote(BlockQuote { children: self.nodes(object)?, span }),
+            "list" => Node::List(self.list(object, span)?),
+            "listItem" => Node::ListItem(self.list_item(object, span)?),
+            "codeBlock" => Node::CodeBlock(CodeBlock {
+                lang: self.opt_str(object, "lang")?,
+                meta: self.opt_str(object, "meta")?,
+                value: self.str(object, "value")?,
+                span,
+            }),
+            "html" => Node::Html(Html { value: self.str(object, "value")?, span }),
+            "table" => Node::Table(self.table(object, span)?),
+            "math" => Node::Math(Math { value: self.str(object, "value")?, span }),
+            "text" => Node::Text(Text { value: self.str(object, "value")?, span }),
+            "emphasis" => Node::Emphasis(Emphasis { children: self.nodes(object)?, span }),
+            "strong" => Node::Strong(Strong { children: self.nodes(object)?, span }),
+            "inlineCode" => {
+                Node::InlineCode(InlineCode { value: self.str(object, "value")?, span })
+            }
+            "break" => Node::Break(Break { span }),
+            "link" => Node::Link(Link {
+                url: self.str(object, "url")?,
+                title: self.opt_str(object, "title")?,
+                children: self.nodes(object)?,
+                span,
+            }),
+            "image" => Node::Image(Image {
+                url: self.str(object, "url")?,
+                alt: self.opt_str(object, "alt")?.unwrap_or_default(),
+                title: self.opt_str(object, "title")?,
+                span,
+            }),
+            "delete" => Node::Delete(Delete { children: self.nodes(object)?, span }),
+            "footnoteReference" => Node::FootnoteReference(FootnoteReference {
+                identifier: self.str(object, "identifier")?,
+                label: self.opt_str(object, "label")?,
+                span,
+            }),
+            "inlineFootnote" => {
+                Node::InlineFootnote(InlineFootnote { children: self.nodes(object)?, span })
+            }
+            "abbreviation" => Node::Abbreviation(Abbreviation {
+                value: self.str(object, "value")?,
+                title: self.str(object, "title")?,
+                span,
+            }),
+            "inlineMath" => {
+                Node::InlineMath(InlineMath { value: self.str(object, "value")?, span })
+            }
+            "definition" => Node::Definition(Definition {
+                identifier: self.str(object, "identifier")?,
+                label: self.opt_str(object, "label")?,
+                url: self.str(object, "url")?,
+                title: self.opt_str(object, "title")?,
+                span,
+            }),
+            "footnoteDefinition" => Node::FootnoteDefinition(FootnoteDefinition {
+                identifier: self.str(object, "identifier")?,
+                label: self.opt_str(object, "label")?,
+                children: self.nodes(object)?,
+                span,
+            }),
+            other => {
+                let html = self.options.custom_node_html(other, |name| field_text(object, name));
+                Node::Html(Html { value: self.allocator.alloc_str(&html), span })
+            }
+        };
+        Ok(node)
+    }
+
+    fn list(&self, object: &Map<String, Value>, span: Span) -> Result<List<'a>, String> {
+        let start = match object.get("start") {
+            None | Some(Value::Null) => None,
+            Some(value) => Some(
+                value
+                    .as_u64()
+                    .and_then(|n| u32::try_from(n).ok())
+                    .ok_or_else(|| "list `start` must be a non-negative integer".to_string())?,
+            ),
+        };
+        let children = read_children(object)?;
+        let mut items = self.allocator.new_vec_with_capacity(children.len());
+        for child in children {
+            let item = as_object(child)?;
+            if item.get("type").and_then(Value::as_str) != Some("listItem") {
+                return Err("list children must be `listItem` nodes".to_string());
+            }
+            items.push(self.list_item(item, read_span(item))?);
+        }
+        Ok(List {
+            ordered: read_bool(object, "ordered")?.unwrap_or(false),
+            start,
+            spread: read_bool(object, "spread")?.unwrap_or(false),
+            children: items,
+            span,
+        })
+    }
+
+    fn list_item(&self, object: &Map<String, Value>, span: Span) -> Result<ListItem<'a>, String> {
+        Ok(ListItem {
+            spread: read_bool(object, "spread")?.unwrap_or(false),
+            checked: read_bool(object, "checked")?,
+            children: self.nodes(object)?,
+            span,
+        })
+    }
+
+    fn table(&self, object: &Map<String, Value>, span: Span) -> Result<Table<'a>, String> {
+        let mut align = self.allocator.new_vec();
+        if let Some(values) = object.get("align").and_then(Value::as_array) {
+            for value in values {
+                align.push(match value.as_str() {
+                    Some("left") => AlignKind::Left,
+                    Some("center") => AlignKind::Center,
+                    Some("right") => AlignKind::Right,
+                    _ => AlignKind::None,
+                });
+            }
+        }
+
+        let mut rows = self.allocator.new_vec();
+        for row_value in read_children(object)? {
+            let row = as_object(row_value)?;
+            let mut cells = self.allocator.new_vec();
+            for cell_value in read_children(row)? {
+                let cell = as_object(cell_value)?;
+                cells.push(TableCell { children: self.nodes(cell)?, span: read_span(cell) });
+            }
+            rows.push(TableRow { children: cells, span: read_span(row) });
+        }
+        Ok(Table { align, children: rows, span })
+    }
+}
+
+/// Returns a string, number or boolean field as text.
+fn field_text(object: &Map<String, Value>, key: &str) -> Option<String> {
+    match object.get(key)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn as_object(value: &Value) -> Result<&Map<String, Value>, String> {
+    value.as_object().ok_or_else(|| "expected an AST node object".to_string())
+}
+
+fn read_span(object: &Map<String, Value>) -> Span {
+    let offset = |key| {
+        object
+            .get("span")
+            .and_then(|span| span.get(key))
+            .and_then(Value::as_u64)
+            .and_then(|n| u32::try_from(n).ok())
+            .unwrap_or(0)
+    };
+    Span::new(offset("start"), offset("end"))
+}
+
+fn read_bool(object: &Map<String, Value>, key: &str) -> Result<Option<bool>, String> {
+    match object.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Bool(b)) => Ok(Some(*b)),
+        Some(_) => Err(format!("field `{key}` must be a boolean")),
+    }
+}
+
+fn read_children(object: &Map<String, Value>) -> Result<&[Value], String> {
+    match object.get("children") {
+        None => Ok(&[]),
+        Some(Value::Array(children)) => Ok(children),
+        Some(_) => Err("field `children` must be an array".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Renderer;
+    use ox_content_parser::{Parser, ParserOptions};
+    use ox_content_renderer::HtmlRenderer;
+    use std::collections::BTreeSet;
+
+    const SOURCE: &str = r#"# Title {#intro .lead}
+
+Text with *em*, **strong**, `code`, ~~gone~~, [link](https://example.com "Link"),
+![alt](/a.png "Image"), $x^2$, an HTML note[^1] and one inline^[Inline *note*.].
+
+*[HTML]: Hyper Text Markup Language
+
+3. three
+4. four
+
+- [x] done
+- [ ] todo
+
+---
+
+```rust title="main.rs"
+fn main() {}
+```
+
+<div>raw</div>
+
+| Left | Right |
+|:-----|------:|
+| a<br>b | c |
+
+$$
+x = 1
+$$
+
+[ref]: https://example.com/ref "Ref"
+
+[^1]: Footnote text.
+"#;
+
+    fn collect_types(value: &Value, types: &mut BTreeSet<String>) {
+        if let Some(node_type) = value.get("type").and_then(Value::as_str) {
+            types.insert(node_type.to_string());
+        }
+        if let Some(children) = value.get("children").and_then(Value::as_array) {
+            for child in children {
+                collect_types(child, types);
+            }
+        }
+    }
+
+    fn round_trip(value: &Value) -> String {
+        let allocator = Allocator::new();
+        let options = HtmlRendererOptions::new();
+        let document = document_from_json(&allocator, value, &options).unwrap();
+        HtmlRenderer::with_options(options).render(&document)
+    }
+
+    fn from_json_error(value: &Value) -> String {
+        let allocator = Allocator::new();
+        document_from_json(&allocator, value, &HtmlRendererOptions::new()).unwrap_err()
+    }
+
+    #[test]
+    fn test_round_trip_every_node_kind() {
+        let allocator = Allocator::new();
+        let options = ParserOptions {
+            math: true,
+            abbreviations: true,
+            attributes: true,
+            ..ParserOptions::gfm()
+        };
+        let document = Parser::with_options(&allocator, SOURCE, options).parse().unwrap();
+        let expected = HtmlRenderer::new().render(&document);
+        let value = document_to_json(&document);
+
+        let mut types = BTreeSet::new();
+        collect_types(&value, &mut types);
+        let expected_types = [
+            "root",
+            "heading",
+            "paragraph",
+            "list",
+            "listItem",
+            "thematicBreak",
+            "codeBlock",
+            "html",
+            "table",
+            "tableRow",
+            "tableCell",
+            "math",
+            "definition",
+            "footnoteDefinition",
+            "text",
+            "emphasis",
+            "strong",
+            "inlineCode",
+            "delete",
+            "link",
+            "image",
+            "break",
+            "inlineMath",
+            "footnoteReference",
+            "inlineFootnote",
+            "abbreviation",
+        ];
+        for node_type in expected_types {
+            assert!(types.contains(node_type), "missing `{node_type}` in {types:?}");
+        }
+        assert_eq!(value["children"][0]["id"], "intro");
+        assert_eq!(value["children"][0]["classes"], json!(["lead"]));
+
+        assert_eq!(round_trip(&value), expected);
+    }
+
+    #[test]
+    fn test_round_trip_blockquote() {
+        let value = json!({ "type": "root", "children": [{
+            "type": "blockquote",
+            "children": [{ "type": "paragraph", "children": [{ "type": "text", "value": "Q" }] }],
+        }]});
+        let html = round_trip(&value);
+        assert_eq!(html, "<blockquote>\n<p>Q</p>\n</blockquote>\n");
+
+        let allocator = Allocator::new();
+        let options = HtmlRendererOptions::new();
+        let document = document_from_json(&allocator, &value, &options).unwrap();
+        assert_eq!(round_trip(&document_to_json(&document)), html);
+    }
+
+    #[test]
+    fn test_renderer_render_ast_matches_render_source() {
+        let mut renderer = Renderer::new(None);
+        let source = "# Title\n\nSome *text* and `code`.\n\n- a\n- b\n";
+        let from_source = renderer.render_source(source.to_string());
+        assert_eq!(from_source.errors, std::vec::Vec::<String>::new());
+
+        let allocator = Allocator::new();
+        let document = Parser::new(&allocator, source).parse().unwrap();
+        let ast_json = document_to_json(&document).to_string();
+        let from_ast = renderer.render_ast(ast_json);
+        assert_eq!(from_ast.errors, std::vec::Vec::<String>::new());
+        assert_eq!(from_ast.html, from_source.html);
+
+        let invalid = renderer.render_ast("{".to_string());
+        assert_eq!(invalid.html, "");
+        assert_eq!(invalid.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_from_json_errors() {
+        let heading = |depth: Value| {
+            json!({ "type": "root", "children": [{
+                "type": "heading", "depth": depth, "children": [],
+            }]})
+        };
+        for depth in [json!(0), json!(7), json!("2"), Value::Null] {
+            assert_eq!(from_json_error(&heading(depth)), "heading `depth` must be between 1 and 6");
+        }
+
+        let missing_type = json!({ "type": "root", "children": [{ "value": "x" }] });
+        assert_eq!(from_json_error(&missing_type), "AST node is missing `type`");
+
+        let list = json!({ "type": "root", "children": [{
+            "type": "list",
+            "children": [{ "type": "paragraph", "children": [] }],
+        }]});
+        assert_eq!(from_json_error(&list), "list children must be `listItem` nodes");
+
+        let paragraph = json!({ "type": "paragraph", "children": [] });
+        assert_eq!(from_json_error(&paragraph), "expected a `root` node, found `paragraph`");
+        assert_eq!(from_json_error(&json!({ "children": [] })), "expected a `root` node");
+        assert_eq!(from_json_error(&json!([])), "expected an AST node object");
+
+        let mut renderer = Renderer::new(None);
+        let result = renderer.render_ast(paragraph.to_string());
+        assert_eq!(result.html, "");
+        assert_eq!(result.errors, ["expected a `root` node, found `paragraph`"]);
+    }
+}
@@ -3,16 +3,35 @@
 //! This crate provides NAPI bindings for using Ox Content from Node.js,
 //! enabling zero-copy AST transfer and JavaScript interoperability.
 
+mod ast_diff;
+mod ast_json;
+
 use napi::bindgen_prelude::*;
 use napi::Task;
 use napi_derive::napi;
 use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::time::Duration;
 
 use ox_content_allocator::Allocator;
 use ox_content_ast::{Document, Heading, Node};
-use ox_content_parser::{Parser, ParserOptions};
-use ox_content_renderer::{HtmlRenderer, HtmlRendererOptions};
-use ox_content_search::{DocumentIndexer, SearchIndex, SearchIndexBuilder, SearchOptions};
+use ox_content_frontmatter::{parse_frontmatter, Frontmatter};
+use ox_content_og_image::{OgImageConfig, OgImageData, OgImageGenerator, TemplateLayout};
+use ox_content_parser::{
+    resolve_includes, FeaturePreset, Features, LintContext, LintOptions, Parser, ParserOptions,
+    ParserPreset, DEFAULT_MAX_INCLUDE_DEPTH,
+};
+use ox_content_renderer::{
+    code_languages as collect_code_languages, heading_slug, heading_text,
+    link_preview as collect_link_preview, normalize_headings, remove_title_heading, Emoji,
+    HeadingNormalization, HeadingNumbering, HeadingStyle, HtmlRenderer, HtmlRendererOptions,
+    LineEnding, MarkdownRenderer, MarkdownStyle, MathOutput, ReferenceLinks, SectionStats,
+    SlugStyle, UniqueSlugs,
+};
+use ox_content_search::{
+    title_from_url, DocumentIndexer, IndexStats, IndexerOptions, SearchIndex, SearchIndexBuilder,
+    SearchOptions, TermStats,
+};
 
 /// Parse result containing the AST as JSON.
 #[napi(object)]
@@ -23,6 +42,125 @@ pub struct ParseResult {
     pub errors: Vec<String>,
 }
 
+/// Parse statistics for performance profiling.
+#[napi(object)]
+pub struct JsParseStats {
+    /// Number of AST nodes, including the root.
+    pub node_count: u32,
+    /// Parse time in microseconds.
+    pub parse_micros: f64,
+    /// Size of the source in bytes.
+    pub bytes: u32,
+}
+
+/// Parse result with the AST as JSON and parse statistics.
+#[napi(object)]
+pub struct ParseWithStatsResult {
+    /// The AST as a JSON string.
+    pub ast: String,
+    /// Parse statistics. Empty when parsing failed.
+    pub stats: JsParseStats,
+    /// Parse errors, if any.
+    pub errors: Vec<String>,
+}
+
+/// Task list checkboxes in a document.
+#[napi(object)]
+pub struct JsTaskProgress {
+    /// Number of task list items.
+    pub total: u32,
+    /// Number of task list items checked with `[x]`.
+    pub completed: u32,
+}
+
+/// Text counts for one section of a document.
+#[napi(object)]
+pub struct JsSectionStats {
+    /// Plain text of the h2 starting the section, or empty for the text
+    /// before the first h2.
+    pub heading: String,
+    /// The heading ID, or empty for the text before the first h2.
+    pub slug: String,
+    /// Number of words; each Chinese or Japanese character counts as one.
+    pub words: u32,
+    /// Number of characters, not counting whitespace.
+    pub chars: u32,
+}
+
+impl From<SectionStats> for JsSectionStats {
+    fn from(stats: SectionStats) -> Self {
+        Self {
+            heading: stats.heading,
+            slug: stats.slug,
+            words: u32::try_from(stats.words).unwrap_or(u32::MAX),
+            chars: u32::try_from(stats.chars).unwrap_or(u32::MAX),
+        }
+    }
+}
+
+/// Options for `linkPreview`.
+#[napi(object)]
+#[derive(Default)]
+pub struct JsLinkPreviewOptions {
+    /// Reading speed used for `readingMinutes` (default: 200).
+    pub words_per_minute: Option<u32>,
+}
+
+/// What a link preview shows about a page.
+#[napi(object)]
+pub struct JsLinkPreview {
+    /// Frontmatter `title`, or the text of the first h1.
+    pub title: Option<String>,
+    /// Frontmatter `description`, or the text of the first paragraph.
+    pub description: Option<String>,
+    /// Frontmatter `image`, or the URL of the first image in the body.
+    pub first_image: Option<String>,
+    /// Estimated reading time in whole minutes, rounded up; 0 for a page
+    /// without text.
+    pub reading_minutes: u32,
+}
+
+/// Markup choices for `formatMarkdown`. Unset or unsupported values use
+/// the default.
+#[napi(object)]
+#[derive(Default)]
+pub struct JsMarkdownStyle {
+    /// Bullet list marker: `"-"` (default), `"*"` or `"+"`.
+    pub bullet: Option<String>,
+    /// Delimiter after ordered list numbers: `"."` (default) or `")"`.
+    pub ordered_delimiter: Option<String>,
+    /// Emphasis marker: `"*"` (default) or `"_"`.
+    pub emphasis: Option<String>,
+    /// Strong emphasis marker, written twice: `"*"` (default) or `"_"`.
+    pub strong: Option<String>,
+    /// Heading syntax: `"atx"` (`# Title`, default) or `"atx_closed"`
+    /// (`# Title #`).
+    pub heading: Option<String>,
+}
+
+impl From<JsMarkdownStyle> for MarkdownStyle {
+    fn from(style: JsMarkdownStyle) -> Self {
+        let defaults = Self::new();
+        let marker = |value: Option<String>, allowed: &str, default: char| {
+            value
+                .and_then(|value| value.parse::<char>().ok())
+                .filter(|c| allowed.contains(*c))
+                .unwrap_or(default)
+        };
+        Self {
+            bullet: marker(style.bullet, "-*+", defaults.bullet),
+            ordered_delimiter: marker(style.ordered_delimiter, ".)", defaults.ordered_delimiter),
+            emphasis: marker(style.emphasis, "*_", defaults.emphasis),
+            strong: marker(style.strong, "*_", defaults.strong),
+            heading: style
+                .heading
+                .as_deref()
+                .and_then(HeadingStyle::from_name)
+                .unwrap_or(defaults.heading),
+        }
+    }
+}
+
 /// Render result containing the HTML output.
 #[napi(object)]
 pub struct RenderResult {
@@ -44,6 +182,24 @@ pub struct TocEntry {
     pub slug: String,
 }
 
+/// A heading ID in the rendered HTML.
+#[napi(object)]
+#[derive(Clone)]
+pub struct HeadingAnchor {
+    /// Heading depth (1-6).
+    pub depth: u8,
+    /// Heading text, without section numbers.
+    pub text: String,
+    /// The heading's `id` attribute.
+    pub slug: String,
+}
+
+impl From<ox_content_renderer::HeadingAnchor> for HeadingAnchor {
+    fn from(anchor: ox_content_renderer::HeadingAnchor) -> Self {
+        Self { depth: anchor.depth, text: anchor.text, slug: anchor.slug }
+    }
+}
+
 /// Transform result containing HTML, frontmatter, and TOC.
 #[napi(object)]
 pub struct TransformResult {
@@ -51,19 +207,84 @@ pub struct TransformResult {
     pub html: String,
     /// Parsed frontmatter as JSON string.
     pub frontmatter: String,
+    /// Detected frontmatter format (`"yaml"`, `"toml"` or `"json"`), if any.
+    pub frontmatter_format: Option<String>,
+    /// The frontmatter exactly as written between its delimiters, comments
+    /// included. Empty when there is no frontmatter.
+    pub frontmatter_raw: String,
+    /// Table of contents entries.
+    pub toc: Vec<TocEntry>,
+    /// Whether the TOC is enabled for this page, after applying the `toc`
+    /// option and frontmatter. Pass it on as `JsSsgPageData.tocEnabled`.
+    pub toc_enabled: bool,
+    /// Every heading ID in `html`, in document order, after deduplication.
+    /// Unlike `toc`, it is not limited by `tocMinDepth` and `tocMaxDepth`
+    /// and includes headings nested in other blocks. Empty when
+    /// `headingIds` is disabled.
+    pub anchors: Vec<HeadingAnchor>,
+    /// Parse/render errors, if any.
+    pub errors: Vec<String>,
+}
+
+/// Result of [`transform_full`]: the transform result plus the final AST.
+#[napi(object)]
+pub struct TransformFullResult {
+    /// The rendered HTML.
+    pub html: String,
+    /// The AST the HTML was rendered from, after heading normalization, as
+    /// a JSON string in the same shape as `parse()` returns.
+    pub ast: String,
+    /// Parsed frontmatter as JSON string.
+    pub frontmatter: String,
+    /// Detected frontmatter format (`"yaml"`, `"toml"` or `"json"`), if any.
+    pub frontmatter_format: Option<String>,
+    /// The frontmatter exactly as written between its delimiters, comments
+    /// included. Empty when there is no frontmatter.
+    pub frontmatter_raw: String,
     /// Table of contents entries.
     pub toc: Vec<TocEntry>,
+    /// Whether the TOC is enabled for this page, after applying the `toc`
+    /// option and frontmatter.
+    pub toc_enabled: bool,
+    /// Every heading ID in `html`, in document order, after deduplication.
+    /// Unlike `toc`, it is not limited by `tocMinDepth` and `tocMaxDepth`
+    /// and includes headings nested in other blocks. Empty when
+    /// `headingIds` is disabled.
+    pub anchors: Vec<HeadingAnchor>,
     /// Parse/render errors, if any.
     pub errors: Vec<String>,
 }
 
+impl From<TransformFullResult> for TransformResult {
+    fn from(full: TransformFullResult) -> Self {
+        Self {
+            html: full.html,
+            frontmatter: full.frontmatter,
+            frontmatter_format: full.frontmatter_format,
+            frontmatter_raw: full.frontmatter_raw,
+            toc: full.toc,
+            toc_enabled: full.toc_enabled,
+            anchors: full.anchors,
+            errors: full.errors,
+        }
+    }
+}
+
 /// Transform options for JavaScript.
+///
+/// Parser options follow the same precedence as [`JsParserOptions`].
 #[napi(object)]
 #[derive(Default, Clone)]
 pub struct JsTransformOptions {
-    /// Enable GFM extensions.
+    /// Parser preset applied before the individual options:
+    /// `"commonmark"`, `"gfm"` or `"gfm_strict"`.
+    #[napi(ts_type = "'commonmark' | 'gfm' | 'gfm_strict'")]
+    pub preset: Option<JsParserPreset>,
+    /// Enable GFM extensions. Shorthand for `preset: "gfm"`, ignored when
+    /// `preset` is set: `gfm: false` does not turn a preset's extensions
+    /// off.
     pub gfm: Option<bool>,
-    /// Enable footnotes.
+    /// Enable footnotes, including inline `^[note]` footnotes.
     pub footnotes: Option<bool>,
     /// Enable task lists.
     pub task_lists: Option<bool>,
@@ -73,21 +294,366 @@ pub struct JsTransformOptions {
     pub strikethrough: Option<bool>,
     /// Enable autolinks.
     pub autolinks: Option<bool>,
+    /// Enable `*[ABBR]: definition` abbreviations.
+    pub abbreviations: Option<bool>,
+    /// Read a trailing `{#id}` on headings as the heading ID.
+    pub attributes: Option<bool>,
+    /// Parse Markdown between block-level HTML tags such as `<details>`.
+    pub parse_inside_html_blocks: Option<bool>,
+    /// Enable `$...$` inline and `$$` or ```` ```math ```` block TeX math.
+    pub math: Option<bool>,
+    /// Text that becomes a line break inside table cells, such as `\n`.
+    /// `<br>` tags in cells always do.
+    pub table_cell_break: Option<String>,
+    /// Restrict the recognized Markdown to a feature set: `"full"`
+    /// (default), `"basic"` (no headings, thematic breaks, tables, images or
+    /// HTML) or `"inline_only"` (paragraphs with links, emphasis and code
+    /// spans). Disabled constructs are rendered as escaped source text.
+    /// Other names are rejected rather than leaving every feature on.
+    #[napi(ts_type = "'full' | 'basic' | 'inline_only'")]
+    pub features: Option<JsFeaturePreset>,
+    /// Collapse runs of spaces and tabs in text to a single space and drop
+    /// the spaces around line breaks (default: false). Code is left as
+    /// written.
+    pub normalize_whitespace: Option<bool>,
+    /// Columns past a list marker that continue the item after a blank
+    /// line, such as `2` for two-space indentation (default: 0, the
+    /// CommonMark rule). Diverges from CommonMark.
+    pub list_indent: Option<u32>,
+    /// Maximum nesting depth for lists, links and emphasis (default: 100).
+    /// Deeper input is reported in `errors` instead of crashing.
+    pub max_nesting_depth: Option<u32>,
+    /// Maximum input size in bytes. Larger input is reported in `errors`
+    /// without being parsed.
+    pub max_input_bytes: Option<u32>,
+    /// Time budget for parsing in milliseconds. Parsing stops with an entry
+    /// in `errors` once it is exceeded.
+    pub time_budget_ms: Option<u32>,
+    /// Fail parsing, with an entry in `errors`, on input that is otherwise
+    /// recovered from silently (default: false): full or collapsed link
+    /// references without a definition, footnote references without a
+    /// definition, and unclosed code fences or math blocks.
+    pub strict: Option<bool>,
+    /// Build the table of contents (default: true).
+    pub toc: Option<bool>,
+    /// Shallowest heading level in the TOC (1-6, default: 2), so the page
+    /// title h1 is left out unless this is 1.
+    pub toc_min_depth: Option<u8>,
     /// Maximum TOC depth (1-6).
     pub toc_max_depth: Option<u8>,
+    /// Markers replaced by the table of contents when they make up a whole
+    /// paragraph (default: `["[[toc]]", "[TOC]"]`). The inline TOC is a
+    /// `<nav class="table-of-contents">` of nested lists with the same
+    /// entries as `toc`, and is rendered even with `toc: false`. Pass an
+    /// empty array to keep markers as text.
+    pub toc_markers: Option<Vec<String>>,
+    /// Headings with one of these classes, written as `{.no-toc}` with
+    /// `attributes`, are left out of the TOC (default: `["no-toc"]`).
+    pub toc_exclude_classes: Option<Vec<String>>,
+    /// Headings whose text matches one of these patterns are left out of
+    /// the TOC. Matching ignores case and `*` matches any run of
+    /// characters, as in `"Changelog*"`.
+    pub toc_exclude: Option<Vec<String>>,
     /// Convert `.md` links to `.html` links for SSG output.
     pub convert_md_links: Option<bool>,
     /// Base URL for absolute link conversion (e.g., "/" or "/docs/").
     pub base_url: Option<String>,
+    /// Line ending for the rendered HTML: `"lf"` (default) or `"crlf"`.
+    pub line_ending: Option<String>,
+    /// Ensure the rendered HTML ends with exactly one line ending.
+    pub trailing_newline: Option<bool>,
+    /// Write XHTML: self-closing void elements (`<br />`, `<img … />`) and
+    /// boolean attributes with values (`disabled="disabled"`).
+    pub xhtml: Option<bool>,
+    /// Prefix headings and TOC entries with section numbers (`1.`, `1.1.`, ...).
+    pub number_headings: Option<bool>,
+    /// Heading level that gets top-level numbers (default: 2).
+    pub number_headings_start: Option<u8>,
+    /// Add `id` attributes to headings matching the TOC slugs (default: true).
+    pub heading_ids: Option<bool>,
+    /// How heading IDs and TOC slugs are generated: `"github"` (default)
+    /// matches GitHub's heading anchors, `"unicode"` keeps Unicode letters
+    /// and joins words with single hyphens, `"ascii"` transliterates to a
+    /// non-empty ASCII-only slug.
+    pub slug_style: Option<String>,
+    /// Adjust heading levels before rendering and building the TOC:
+    /// `"none"` (default), `"single_h1_demote"` keeps the first h1 and
+    /// demotes later ones with their sections, `"promote_first"` turns the
+    /// first heading into the h1 when the page has none.
+    pub normalize_headings: Option<String>,
+    /// When the frontmatter has a `title`, leave the first h1 out of the
+    /// rendered body, the TOC and the anchors, for layouts that already
+    /// show the title (default: false). Applied before `normalizeHeadings`.
+    pub drop_title_heading: Option<bool>,
+    /// Append a `#` permalink anchor to every heading with an ID.
+    pub heading_anchors: Option<bool>,
+    /// Emit semantic, ARIA-friendly HTML: `<figure>`/`<figcaption>` for
+    /// titled images, labelled heading anchors and DPUB-ARIA footnote roles.
+    pub accessible: Option<bool>,
+    /// Character sequences to encode as numeric entities inside code, such
+    /// as `["{{", "}}"]`, so template engines leave code samples alone.
+    pub code_escapes: Option<Vec<String>>,
+    /// How math is rendered: `"passthrough"` (default) keeps the TeX source
+    /// for a client-side renderer, `"mathml"` converts a supported subset
+    /// of TeX to MathML and falls back to passthrough with a `data-latex`
+    /// attribute for the rest.
+    pub math_output: Option<String>,
+    /// Code fence language aliases, such as `{ vue: "html" }`, added to the
+    /// defaults (`js` → `javascript`, `ts` → `typescript`, `sh` → `bash`,
+    /// ...). Map an alias to itself to keep it unchanged.
+    pub language_aliases: Option<HashMap<String, String>>,
+    /// Wrap tables in `<div class="table-wrapper">` so wide tables scroll
+    /// horizontally (styled by the SSG stylesheet).
+    pub table_wrapper: Option<bool>,
+    /// Number nested ordered list items `1.`, `1.1.`, `1.1.1.` by giving
+    /// ordered lists `class="nested-numbers"` (styled by the SSG
+    /// stylesheet, default: false).
+    pub nested_list_numbers: Option<bool>,
+    /// Guess the language of code blocks written without one, adding a
+    /// `language-*` class and `data-detected="true"` (default: false).
+    /// Uncertain guesses leave the block untagged.
+    pub detect_code_language: Option<bool>,
+    /// Keep the line break of the last line inside `<code>`, as CommonMark
+    /// does (default: true). Set to false for `<code>foo</code>`.
+    pub code_final_newline: Option<bool>,
+    /// Escape the `<` of `<script>`, `<style>`, `<title>`, `<textarea>`,
+    /// `<iframe>` and the other tags disallowed by the GFM tagfilter in raw
+    /// HTML, matching GitHub's output. Defaults to on with the `gfm` and
+    /// `gfm_strict` presets.
+    pub gfm_tagfilter: Option<bool>,
+    /// Link issue references such as `#123` to this URL, with `{n}`
+    /// replaced by the number. References in code, in link text and
+    /// escaped as `\#123` are left alone.
+    pub issue_url: Option<String>,
+    /// Text that starts an issue reference (default: `"#"`).
+    pub issue_prefix: Option<String>,
+    /// Link commit SHAs of 7 to 40 lowercase hex digits to this URL, with
+    /// `{sha}` replaced by the full SHA. The link text is the short SHA.
+    pub commit_url: Option<String>,
+    /// Link `@name` mentions to this URL, with `{name}` replaced by the
+    /// name. Email-like text such as `a@b`, mentions in code and escaped
+    /// `\@name` are left alone.
+    pub mention_url: Option<String>,
+    /// Add a `data-line` attribute with the 1-based source line to block
+    /// elements such as paragraphs, headings, lists and code blocks, for
+    /// scroll sync in editor previews (default: false).
+    ///
+    /// Lines count from the top of the file, frontmatter included. Lines
+    /// after an inlined include are shifted by the included lines.
+    pub source_line_attrs: Option<bool>,
+    /// Drop soft line breaks between two Chinese or Japanese characters,
+    /// which browsers would otherwise show as a space (default: false).
+    /// Breaks next to Latin text are kept.
+    pub cjk_friendly_breaks: Option<bool>,
+    /// Custom emoji shortcodes: `:name:` in text is replaced by the value.
+    /// Values starting with `http://`, `https://`, `data:image/`, `/`, `./`
+    /// or `../` are rendered as `<img class="emoji custom-emoji">`, others
+    /// as text. Keys may be written with or without colons. Unknown
+    /// shortcodes stay literal.
+    pub custom_emoji: Option<HashMap<String, String>>,
+    /// HTML templates for custom node types in ASTs passed to
+    /// `Renderer.renderAst`, keyed by node `type`. `{field}` is replaced by
+    /// the node's string, number or boolean field of that name,
+    /// HTML-escaped. Nodes of other unknown types render as an
+    /// `<!-- unknown node: type -->` comment.
+    pub custom_nodes: Option<HashMap<String, String>>,
+    /// Markdown partials by path, used to resolve `{{< include "path" >}}`
+    /// and `@include(path)` directives before parsing.
+    pub includes: Option<HashMap<String, String>>,
+    /// Maximum include nesting depth (default: 8).
+    pub max_include_depth: Option<u32>,
+    /// Fail the transform when the frontmatter has errors (default: false).
+    ///
+    /// Frontmatter errors are always reported in `errors`, with line numbers
+    /// relative to the file. Without this option the document is still
+    /// rendered, using the frontmatter entries that parsed.
+    pub strict_frontmatter: Option<bool>,
+    /// Read `toc`, `tocMinDepth`, `tocMaxDepth`, `gfm` and the
+    /// `renderOptions` object from the page frontmatter (default: true).
+    ///
+    /// `renderOptions` may turn `convertMdLinks`, `headingIds`,
+    /// `headingAnchors`, `numberHeadings`, `accessible`, `tableWrapper`,
+    /// `nestedListNumbers`, `detectCodeLanguage` and `cjkFriendlyBreaks` on
+    /// or off; other keys are ignored. Frontmatter values override the
+    /// `defaults` passed to [`transform`] but not the explicit call options.
+    pub frontmatter_options: Option<bool>,
+}
+
+impl JsTransformOptions {
+    /// Returns these options with every unset field taken from `base`.
+    fn merged_over(self, base: Self) -> Self {
+        Self {
+            preset: self.preset.or(base.preset),
+            gfm: self.gfm.or(base.gfm),
+            footnotes: self.footnotes.or(base.footnotes),
+            task_lists: self.task_lists.or(base.task_lists),
+            tables: self.tables.or(base.tables),
+            strikethrough: self.strikethrough.or(base.strikethrough),
+            autolinks: self.autolinks.or(base.autolinks),
+            abbreviations: self.abbreviations.or(base.abbreviations),
+            attributes: self.attributes.or(base.attributes),
+            parse_inside_html_blocks: self
+                .parse_inside_html_blocks
+                .or(base.parse_inside_html_blocks),
+            math: self.math.or(base.math),
+            table_cell_break: self.table_cell_break.or(base.table_cell_break),
+            normalize_whitespace: self.normalize_whitespace.or(base.normalize_whitespace),
+            list_indent: self.list_indent.or(base.list_indent),
+            features: self.features.or(base.features),
+            max_nesting_depth: self.max_nesting_depth.or(base.max_nesting_depth),
+            max_input_bytes: self.max_input_bytes.or(base.max_input_bytes),
+            time_budget_ms: self.time_budget_ms.or(base.time_budget_ms),
+            strict: self.strict.or(base.strict),
+            toc: self.toc.or(base.toc),
+            toc_min_depth: self.toc_min_depth.or(base.toc_min_depth),
+            toc_max_depth: self.toc_max_depth.or(base.toc_max_depth),
+            toc_markers: self.toc_markers.or(base.toc_markers),
+            toc_exclude_classes: self.toc_exclude_classes.or(base.toc_exclude_classes),
+            toc_exclude: self.toc_exclude.or(base.toc_exclude),
+            convert_md_links: self.convert_md_links.or(base.convert_md_links),
+            base_url: self.base_url.or(base.base_url),
+            line_ending: self.line_ending.or(base.line_ending),
+            trailing_newline: self.trailing_newline.or(base.trailing_newline),
+            xhtml: self.xhtml.or(base.xhtml),
+            number_headings: self.number_headings.or(base.number_headings),
+            number_headings_start: self.number_headings_start.or(base.number_headings_start),
+            heading_ids: self.heading_ids.or(base.heading_ids),
+            slug_style: self.slug_style.or(base.slug_style),
+            normalize_headings: self.normalize_headings.or(base.normalize_headings),
+            drop_title_heading: self.drop_title_heading.or(base.drop_title_heading),
+            heading_anchors: self.heading_anchors.or(base.heading_anchors),
+            accessible: self.accessible.or(base.accessible),
+            code_escapes: self.code_escapes.or(base.code_escapes),
+            math_output: self.math_output.or(base.math_output),
+            language_aliases: self.language_aliases.or(base.language_aliases),
+            table_wrapper: self.table_wrapper.or(base.table_wrapper),
+            nested_list_numbers: self.nested_list_numbers.or(base.nested_list_numbers),
+            detect_code_language: self.detect_code_language.or(base.detect_code_language),
+            code_final_newline: self.code_final_newline.or(base.code_final_newline),
+            gfm_tagfilter: self.gfm_tagfilter.or(base.gfm_tagfilter),
+            issue_url: self.issue_url.or(base.issue_url),
+            issue_prefix: self.issue_prefix.or(base.issue_prefix),
+            commit_url: self.commit_url.or(base.commit_url),
+            mention_url: self.mention_url.or(base.mention_url),
+            source_line_attrs: self.source_line_attrs.or(base.source_line_attrs),
+            cjk_friendly_breaks: self.cjk_friendly_breaks.or(base.cjk_friendly_breaks),
+            custom_emoji: self.custom_emoji.or(base.custom_emoji),
+            custom_nodes: self.custom_nodes.or(base.custom_nodes),
+            includes: self.includes.or(base.includes),
+            max_include_depth: self.max_include_depth.or(base.max_include_depth),
+            strict_frontmatter: self.strict_frontmatter.or(base.strict_frontmatter),
+            frontmatter_options: self.frontmatter_options.or(base.frontmatter_options),
+        }
+    }
+
+    /// Returns the options set by known keys in the page frontmatter.
+    ///
+    /// Only the boolean renderer switches listed on
+    /// [`frontmatter_options`](Self::frontmatter_options) are read from
+    /// `renderOptions`, so a page cannot change URLs, escaping or the
+    /// tagfilter.
+    fn from_frontmatter(data: &serde_json::Map<String, serde_json::Value>) -> Self {
+        let render_options = data.get("renderOptions").and_then(serde_json::Value::as_object);
+        let flag = |key: &str| {
+            render_options.and_then(|map| map.get(key)).and_then(serde_json::Value::as_bool)
+        };
+        Self {
+            toc: data.get("toc").and_then(serde_json::Value::as_bool),
+            toc_min_depth: data
+                .get("tocMinDepth")
+                .and_then(serde_json::Value::as_u64)
+                .and_then(|depth| u8::try_from(depth).ok()),
+            toc_max_depth: data
+                .get("tocMaxDepth")
+                .and_then(serde_json::Value::as_u64)
+                .and_then(|depth| u8::try_from(depth).ok()),
+            gfm: data.get("gfm").and_then(serde_json::Value::as_bool),
+            convert_md_links: flag("convertMdLinks"),
+            heading_ids: flag("headingIds"),
+            heading_anchors: flag("headingAnchors"),
+            number_headings: flag("numberHeadings"),
+            accessible: flag("accessible"),
+            table_wrapper: flag("tableWrapper"),
+            nested_list_numbers: flag("nestedListNumbers"),
+            detect_code_language: flag("detectCodeLanguage"),
+            cjk_friendly_breaks: flag("cjkFriendlyBreaks"),
+            ..Self::default()
+        }
+    }
+}
+
+/// Parser presets for JavaScript, by the names of [`ParserPreset`].
+#[napi(string_enum)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum JsParserPreset {
+    /// Plain CommonMark with every extension disabled.
+    #[napi(value = "commonmark")]
+    CommonMark,
+    /// GitHub Flavored Markdown, including footnotes.
+    #[napi(value = "gfm")]
+    Gfm,
+    /// Only the extensions in the GFM spec: footnotes are disabled.
+    #[napi(value = "gfm_strict")]
+    GfmStrict,
+}
+
+impl From<JsParserPreset> for ParserPreset {
+    fn from(preset: JsParserPreset) -> Self {
+        match preset {
+            JsParserPreset::CommonMark => Self::CommonMark,
+            JsParserPreset::Gfm => Self::Gfm,
+            JsParserPreset::GfmStrict => Self::GfmStrict,
+        }
+    }
+}
+
+/// Feature sets for JavaScript, by the names of [`FeaturePreset`].
+#[napi(string_enum)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum JsFeaturePreset {
+    /// Every construct.
+    #[napi(value = "full")]
+    Full,
+    /// Comment fields: paragraphs, block quotes, lists, code, links and
+    /// emphasis, but no headings, thematic breaks, tables, images or HTML.
+    #[napi(value = "basic")]
+    Basic,
+    /// Paragraphs with links, emphasis and code spans only.
+    #[napi(value = "inline_only")]
+    InlineOnly,
+}
+
+impl From<JsFeaturePreset> for FeaturePreset {
+    fn from(preset: JsFeaturePreset) -> Self {
+        match preset {
+            JsFeaturePreset::Full => Self::Full,
+            JsFeaturePreset::Basic => Self::Basic,
+            JsFeaturePreset::InlineOnly => Self::InlineOnly,
+        }
+    }
 }
 
 /// Parser options for JavaScript.
+///
+/// Options are resolved in order:
+/// 1. `preset`, or `gfm: true` as shorthand for `preset: "gfm"`. Without
+///    either, every extension starts disabled (`"commonmark"`). `gfm` only
+///    picks the preset, so it is ignored when `preset` is set.
+/// 2. Every other field that is set overrides the preset, so
+///    `{ gfm: true, autolinks: false }` is GFM without autolinks.
 #[napi(object)]
 #[derive(Default)]
 pub struct JsParserOptions {
-    /// Enable GFM extensions.
+    /// Parser preset applied before the individual options:
+    /// `"commonmark"`, `"gfm"` or `"gfm_strict"` (GFM spec extensions
+    /// only, without footnotes). Other names are rejected.
+    #[napi(ts_type = "'commonmark' | 'gfm' | 'gfm_strict'")]
+    pub preset: Option<JsParserPreset>,
+    /// Enable GFM extensions. Shorthand for `preset: "gfm"`, ignored when
+    /// `preset` is set: `gfm: false` does not turn a preset's extensions
+    /// off.
     pub gfm: Option<bool>,
-    /// Enable footnotes.
+    /// Enable footnotes, including inline `^[note]` footnotes.
     pub footnotes: Option<bool>,
     /// Enable task lists.
     pub task_lists: Option<bool>,
@@ -97,12 +663,51 @@ pub struct JsParserOptions {
     pub strikethrough: Option<bool>,
     /// Enable autolinks.
     pub autolinks: Option<bool>,
+    /// Enable `*[ABBR]: definition` abbreviations.
+    pub abbreviations: Option<bool>,
+    /// Read a trailing `{#id}` on headings as the heading ID.
+    pub attributes: Option<bool>,
+    /// Parse Markdown between block-level HTML tags such as `<details>`.
+    pub parse_inside_html_blocks: Option<bool>,
+    /// Enable `$...$` inline and `$$` or ```` ```math ```` block TeX math.
+    pub math: Option<bool>,
+    /// Text that becomes a line break inside table cells, such as `\n`.
+    /// `<br>` tags in cells always do.
+    pub table_cell_break: Option<String>,
+    /// Restrict the recognized Markdown to a feature set: `"full"`
+    /// (default), `"basic"` (no headings, thematic breaks, tables, images or
+    /// HTML) or `"inline_only"` (paragraphs with links, emphasis and code
+    /// spans). Disabled constructs are rendered as escaped source text.
+    /// Other names are rejected rather than leaving every feature on.
+    #[napi(ts_type = "'full' | 'basic' | 'inline_only'")]
+    pub features: Option<JsFeaturePreset>,
+    /// Collapse runs of spaces and tabs in text to a single space and drop
+    /// the spaces around line breaks (default: false). Code is left as
+    /// written.
+    pub normalize_whitespace: Option<bool>,
+    /// Columns past a list marker that continue the item after a blank
+    /// line, such as `2` for two-space indentation (default: 0, the
+    /// CommonMark rule). Diverges from CommonMark.
+    pub list_indent: Option<u32>,
+    /// Maximum nesting depth for lists, links and emphasis (default: 100).
+    /// Deeper input is reported in `errors` instead of crashing.
+    pub max_nesting_depth: Option<u32>,
+    /// Maximum input size in bytes. Larger input is reported in `errors`
+    /// without being parsed.
+    pub max_input_bytes: Option<u32>,
+    /// Time budget for parsing in milliseconds. Parsing stops with an entry
+    /// in `errors` once it is exceeded.
+    pub time_budget_ms: Option<u32>,
+    /// Fail parsing, with an entry in `errors`, on input that is otherwise
+    /// recovered from silently (default: false): full or collapsed link
+    /// references without a definition, footnote references without a
+    /// definition, and unclosed code fences or math blocks.
+    pub strict: Option<bool>,
 }
 
 impl From<JsParserOptions> for ParserOptions {
     fn from(opts: JsParserOptions) -> Self {
-        let mut options =
-            if opts.gfm.unwrap_or(false) { ParserOptions::gfm() } else { ParserOptions::default() };
+        let mut options = preset_parser_options(opts.preset, opts.gfm);
 
         if let Some(v) = opts.footnotes {
             options.footnotes = v;
@@ -119,11 +724,60 @@ impl From<JsParserOptions> for ParserOptions {
         if let Some(v) = opts.autolinks {
             options.autolinks = v;
         }
+        if let Some(v) = opts.abbreviations {
+            options.abbreviations = v;
+        }
+        if let Some(v) = opts.attributes {
+            options.attributes = v;
+        }
+        if let Some(v) = opts.parse_inside_html_blocks {
+            options.parse_inside_html_blocks = v;
+        }
+        if let Some(v) = opts.math {
+            options.math = v;
+        }
+        if let Some(v) = opts.table_cell_break {
+            options.table_cell_break = Some(v);
+        }
+        if let Some(v) = opts.features {
+            options.features = Features::from_preset(v.into());
+        }
+        if let Some(v) = opts.normalize_whitespace {
+            options.normalize_whitespace = v;
+        }
+        if let Some(v) = opts.list_indent {
+            options.list_indent = u8::try_from(v).unwrap_or(u8::MAX);
+        }
+        if let Some(v) = opts.max_nesting_depth {
+            options.max_nesting_depth = v as usize;
+        }
+        if let Some(v) = opts.strict {
+            options.strict = v;
+        }
+        options.max_input_bytes = opts.max_input_bytes.map(|v| v as usize);
+        options.time_budget = opts.time_budget_ms.map(|ms| Duration::from_millis(u64::from(ms)));
 
         options
     }
 }
 
+/// Returns the renderer options matching `parser_options`: GFM parsing
+/// also applies the GFM tagfilter.
+fn parser_renderer_options(parser_options: &ParserOptions) -> HtmlRendererOptions {
+    if parser_options.gfm {
+        HtmlRendererOptions::gfm()
+    } else {
+        HtmlRendererOptions::new()
+    }
+}
+
+/// Returns the parser options selected by `preset`, falling back to the
+/// `gfm` shorthand.
+fn preset_parser_options(preset: Option<JsParserPreset>, gfm: Option<bool>) -> ParserOptions {
+    let fallback = if gfm.unwrap_or(false) { ParserPreset::Gfm } else { ParserPreset::CommonMark };
+    ParserOptions::from_preset(preset.map_or(fallback, ParserPreset::from))
+}
+
 /// Parses Markdown source into an AST.
 ///
 /// Returns the AST as a JSON string for zero-copy transfer to JavaScript.
@@ -135,257 +789,849 @@ pub fn parse(source: String, options: Option<JsParserOptions>) -> ParseResult {
 
     let result = parser.parse();
     match result {
-        Ok(_doc) => {
-            // Serialize AST to JSON
-            // Note: In a production implementation, we would use a more efficient
-            // serialization method that avoids the JSON overhead
-            let ast = "{\"type\":\"document\",\"children\":[]}".to_string();
+        Ok(doc) => {
+            let ast = ast_json::document_to_json(&doc).to_string();
             ParseResult { ast, errors: vec![] }
         }
         Err(e) => ParseResult { ast: String::new(), errors: vec![e.to_string()] },
     }
 }
 
-/// Parses Markdown and renders to HTML.
+/// Parses Markdown source into an AST and reports node count, parse time
+/// and input size.
 #[napi]
-pub fn parse_and_render(source: String, options: Option<JsParserOptions>) -> RenderResult {
+pub fn parse_with_stats(source: String, options: Option<JsParserOptions>) -> ParseWithStatsResult {
     let allocator = Allocator::new();
     let parser_options = options.map(ParserOptions::from).unwrap_or_default();
     let parser = Parser::with_options(&allocator, &source, parser_options);
 
-    let result = parser.parse();
+    let result = parser.parse_with_stats();
     match result {
-        Ok(doc) => {
-            let mut renderer = HtmlRenderer::new();
-            let html = renderer.render(&doc);
-            RenderResult { html, errors: vec![] }
-        }
-        Err(e) => RenderResult { html: String::new(), errors: vec![e.to_string()] },
+        Ok((doc, stats)) => ParseWithStatsResult {
+            ast: ast_json::document_to_json(&doc).to_string(),
+            stats: JsParseStats {
+                node_count: u32::try_from(stats.node_count).unwrap_or(u32::MAX),
+                parse_micros: stats.parse_time.as_secs_f64() * 1_000_000.0,
+                bytes: u32::try_from(stats.bytes).unwrap_or(u32::MAX),
+            },
+            errors: vec![],
+        },
+        Err(e) => ParseWithStatsResult {
+            ast: String::new(),
+            stats: JsParseStats { node_count: 0, parse_micros: 0.0, bytes: 0 },
+            errors: vec![e.to_string()],
+        },
     }
 }
 
-/// Renders an AST (provided as JSON) to HTML.
+/// Counts the checked and unchecked task list items in a document,
+/// including nested lists, without rendering it.
+///
+/// Task lists are always enabled and frontmatter is skipped.
 #[napi]
-pub fn render(_ast_json: String) -> RenderResult {
-    // In a production implementation, we would:
-    // 1. Parse the JSON AST
-    // 2. Convert to our internal AST format
-    // 3. Render to HTML
-    //
-    // For now, return an error indicating this is not yet implemented
-    RenderResult {
-        html: String::new(),
-        errors: vec!["render from JSON not yet implemented".to_string()],
+pub fn task_progress(source: String) -> JsTaskProgress {
+    let Frontmatter { content, .. } = parse_frontmatter(&source);
+    let allocator = Allocator::new();
+    let options = ParserOptions { task_lists: true, ..ParserOptions::default() };
+    let progress = Parser::with_options(&allocator, &content, options)
+        .parse()
+        .map(|doc| ox_content_parser::task_progress(&doc))
+        .unwrap_or_default();
+    JsTaskProgress {
+        total: u32::try_from(progress.total).unwrap_or(u32::MAX),
+        completed: u32::try_from(progress.completed).unwrap_or(u32::MAX),
     }
 }
 
-/// Returns the version of ox_content_napi.
+/// Counts the words and characters in each h2 section of a document,
+/// for spotting sections that are too thin or too long.
+///
+/// Text before the first h2 is a section with an empty heading, if there
+/// is any. Slugs match the heading IDs of `transform` for the same
+/// `slugStyle`. Frontmatter is skipped and GFM is enabled; code blocks are
+/// not counted.
 #[napi]
-pub fn version() -> String {
-    env!("CARGO_PKG_VERSION").to_string()
+pub fn section_stats(source: String, slug_style: Option<String>) -> Vec<JsSectionStats> {
+    let Frontmatter { content, .. } = parse_frontmatter(&source);
+    let allocator = Allocator::new();
+    let slug_style = slug_style.as_deref().and_then(SlugStyle::from_name).unwrap_or_default();
+    Parser::with_options(&allocator, &content, ParserOptions::gfm())
+        .parse()
+        .map(|doc| ox_content_renderer::section_stats(&doc, slug_style))
+        .unwrap_or_default()
+        .into_iter()
+        .map(JsSectionStats::from)
+        .collect()
 }
 
-/// Transforms Markdown source into HTML, frontmatter, and TOC.
+/// Collects what a link preview shows about a page in one pass: title,
+/// description, first image and reading time.
 ///
-/// This is the main entry point for unplugin-ox-content.
+/// Non-empty frontmatter `title`, `description` and `image` strings take
+/// precedence over the body. Words are counted like `sectionStats`, over
+/// the whole page. GFM is enabled.
 #[napi]
-pub fn transform(source: String, options: Option<JsTransformOptions>) -> TransformResult {
-    let opts = options.unwrap_or_default();
-    let toc_max_depth = opts.toc_max_depth.unwrap_or(3);
-
-    // Parse frontmatter
-    let (content, frontmatter) = parse_frontmatter(&source);
-
-    // Parse markdown
+pub fn link_preview(source: String, options: Option<JsLinkPreviewOptions>) -> JsLinkPreview {
+    let Frontmatter { content, data: frontmatter, .. } = parse_frontmatter(&source);
+    let words_per_minute = options.and_then(|o| o.words_per_minute).unwrap_or(200).max(1);
     let allocator = Allocator::new();
-    let parser_options = transform_options_to_parser_options(&opts);
-    let parser = Parser::with_options(&allocator, &content, parser_options);
-
-    let result = parser.parse();
-    match result {
-        Ok(doc) => {
-            // Extract TOC from headings
-            let toc = extract_toc(&doc, toc_max_depth);
-
-            // Render to HTML
-            let renderer_options = transform_options_to_renderer_options(&opts);
-            let mut renderer = HtmlRenderer::with_options(renderer_options);
-            let html = renderer.render(&doc);
-
-            TransformResult {
-                html,
-                frontmatter: serde_json::to_string(&frontmatter)
-                    .unwrap_or_else(|_| "{}".to_string()),
-                toc,
-                errors: vec![],
-            }
-        }
-        Err(e) => TransformResult {
-            html: String::new(),
-            frontmatter: "{}".to_string(),
-            toc: vec![],
-            errors: vec![e.to_string()],
-        },
+    let preview = Parser::with_options(&allocator, &content, ParserOptions::gfm())
+        .parse()
+        .map(|doc| collect_link_preview(&doc))
+        .unwrap_or_default();
+    let field = |key: &str| {
+        frontmatter
+            .get(key)
+            .and_then(serde_json::Value::as_str)
+            .filter(|value| !value.trim().is_empty())
+            .map(String::from)
+    };
+    let words = u32::try_from(preview.words).unwrap_or(u32::MAX);
+    JsLinkPreview {
+        title: field("title").or(preview.title),
+        description: field("description").or(preview.description),
+        first_image: field("image").or(preview.image),
+        reading_minutes: words.div_ceil(words_per_minute),
     }
 }
 
-/// Parses YAML frontmatter from Markdown content.
-fn parse_frontmatter(source: &str) -> (String, HashMap<String, serde_json::Value>) {
-    let mut frontmatter = HashMap::new();
+/// Options for `codeLanguages`.
+#[napi(object)]
+#[derive(Default)]
+pub struct JsCodeLanguagesOptions {
+    /// Code fence language aliases, added to or overriding the built-in
+    /// ones, as in `transform`.
+    pub language_aliases: Option<HashMap<String, String>>,
+}
 
-    // Check for frontmatter delimiter
-    if !source.starts_with("---") {
-        return (source.to_string(), frontmatter);
-    }
+/// Returns the distinct languages of the page's fenced code blocks, in
+/// the order they are first used, for loading only the highlighter
+/// grammars a page needs.
+///
+/// Languages are normalized like the `language-*` classes of `transform`.
+/// Fences without a language and `raw` fences are left out. Frontmatter is
+/// skipped and GFM is enabled.
+#[napi]
+pub fn code_languages(source: String, options: Option<JsCodeLanguagesOptions>) -> Vec<String> {
+    let Frontmatter { content, .. } = parse_frontmatter(&source);
+    let mut aliases = HtmlRendererOptions::new().language_aliases;
+    aliases.extend(
+        options
+            .and_then(|o| o.language_aliases)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(alias, language)| (alias.to_ascii_lowercase(), language)),
+    );
+    let allocator = Allocator::new();
+    Parser::with_options(&allocator, &content, ParserOptions::gfm())
+        .parse()
+        .map(|doc| collect_code_languages(&doc, &aliases))
+        .unwrap_or_default()
+}
 
-    // Find the closing delimiter
-    let rest = &source[3..];
-    let Some(end_pos) = rest.find("\n---") else {
-        return (source.to_string(), frontmatter);
-    };
+/// Parses Markdown and renders to HTML.
+///
+/// When parsing stops at an error, such as an unclosed code fence in
+/// strict mode, the blocks before it are still rendered and the error is
+/// reported in `errors`. Input over `maxInputBytes` renders nothing.
+#[napi]
+pub fn parse_and_render(source: String, options: Option<JsParserOptions>) -> RenderResult {
+    render_source(&source, options.map(ParserOptions::from).unwrap_or_default())
+}
 
-    let frontmatter_str = &rest[..end_pos].trim_start_matches('\n');
-    let content = &rest[end_pos + 4..].trim_start_matches('\n');
-
-    // Parse simple YAML key-value pairs
-    for line in frontmatter_str.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') {
-            continue;
-        }
-
-        if let Some(colon_pos) = line.find(':') {
-            let key = line[..colon_pos].trim().to_string();
-            let value_str = line[colon_pos + 1..].trim();
-
-            let value = if value_str == "true" {
-                serde_json::Value::Bool(true)
-            } else if value_str == "false" {
-                serde_json::Value::Bool(false)
-            } else if let Ok(n) = value_str.parse::<i64>() {
-                serde_json::Value::Number(n.into())
-            } else if let Ok(n) = value_str.parse::<f64>() {
-                serde_json::Number::from_f64(n).map_or_else(
-                    || serde_json::Value::String(value_str.to_string()),
-                    serde_json::Value::Number,
-                )
-            } else {
-                // Remove surrounding quotes if present
-                let s = value_str.trim_matches('"').trim_matches('\'');
-                serde_json::Value::String(s.to_string())
-            };
+/// Renders `source` for [`parse_and_render`] and [`parse_and_render_async`].
+fn render_source(source: &str, parser_options: ParserOptions) -> RenderResult {
+    let allocator = Allocator::new();
+    let renderer_options = parser_renderer_options(&parser_options);
+    let (doc, error) = Parser::with_options(&allocator, source, parser_options).parse_partial();
+    let html = HtmlRenderer::with_options(renderer_options).render(&doc);
+    RenderResult { html, errors: error.iter().map(ToString::to_string).collect() }
+}
+
+/// Parses Markdown and renders it to an HTML fragment for insertion into
+/// an existing element.
+///
+/// The HTML is the same as from [`parse_and_render`], with leading and
+/// trailing whitespace removed and no wrapping element.
+///
+/// # Errors
+///
+/// Returns an error if the source cannot be parsed, for example when it
+/// exceeds the configured input size.
+#[napi]
+pub fn render_fragment(source: String, options: Option<JsParserOptions>) -> Result<String> {
+    let allocator = Allocator::new();
+    let parser_options = options.map(ParserOptions::from).unwrap_or_default();
+    let renderer_options = parser_renderer_options(&parser_options);
+    let doc = Parser::with_options(&allocator, &source, parser_options)
+        .parse()
+        .map_err(|e| Error::from_reason(e.to_string()))?;
+    let html = HtmlRenderer::with_options(renderer_options).render(&doc);
+    Ok(html.trim_ascii().to_string())
+}
 
-            frontmatter.insert(key, value);
+/// Rewrites Markdown in one canonical style: the same bullet and ordered
+/// list markers, emphasis delimiters and heading syntax throughout.
+///
+/// The source is parsed and written back from the AST, so the output is
+/// stable: formatting it again returns it unchanged. Frontmatter is kept
+/// as written. Parsing uses GFM unless `options` are given.
+///
+/// # Errors
+///
+/// Returns an error if the source cannot be parsed.
+#[napi]
+pub fn format_markdown(
+    source: String,
+    style: Option<JsMarkdownStyle>,
+    options: Option<JsParserOptions>,
+) -> Result<String> {
+    let Frontmatter { content, .. } = parse_frontmatter(&source);
+    let allocator = Allocator::new();
+    let parser_options = options.map_or_else(ParserOptions::gfm, ParserOptions::from);
+    let doc = Parser::with_options(&allocator, &content, parser_options)
+        .parse()
+        .map_err(|e| Error::from_reason(e.to_string()))?;
+    let markdown = MarkdownRenderer::with_style(style.map(MarkdownStyle::from).unwrap_or_default())
+        .render(&doc);
+
+    let mut formatted = source[..source.len() - content.len()].to_string();
+    if !formatted.is_empty() && !markdown.is_empty() {
+        if !formatted.ends_with('\n') {
+            formatted.push('\n');
         }
+        formatted.push('\n');
     }
+    formatted.push_str(&markdown);
+    Ok(formatted)
+}
 
-    (content.to_string(), frontmatter)
+/// Renders an AST (provided as JSON) to HTML.
+///
+/// Accepts the AST format returned by `parse`.
+#[napi]
+pub fn render(ast_json: String) -> RenderResult {
+    Renderer::new(None).render_ast(ast_json)
 }
 
-/// Extracts table of contents from document headings.
-fn extract_toc(doc: &Document, max_depth: u8) -> Vec<TocEntry> {
-    let mut entries = Vec::new();
+/// A run of changed top-level blocks between two ASTs.
+#[napi(object)]
+pub struct AstBlockChange {
+    /// Index of the first changed block in the old AST.
+    pub old_start: u32,
+    /// Index after the last changed block in the old AST.
+    pub old_end: u32,
+    /// Index of the first changed block in the new AST.
+    pub new_start: u32,
+    /// Index after the last changed block in the new AST.
+    pub new_end: u32,
+}
 
-    for node in &doc.children {
-        if let Node::Heading(heading) = node {
-            if heading.depth <= max_depth {
-                let text = extract_heading_text(heading);
-                let slug = slugify(&text);
-                entries.push(TocEntry { depth: heading.depth, text, slug });
-            }
-        }
-    }
+/// Result of [`diff_ast`].
+#[napi(object)]
+pub struct AstDiffResult {
+    /// Runs of blocks that differ, in document order. Blocks outside them
+    /// are unchanged and in the same order in both ASTs.
+    pub changes: Vec<AstBlockChange>,
+    /// A hash of every top-level block of the new AST as 16 hex digits.
+    /// It ignores spans, so a block keeps its hash when text before it
+    /// changes, and can key a cache of rendered blocks.
+    pub hashes: Vec<String>,
+}
 
-    entries
+/// Compares the top-level blocks of two ASTs in the JSON format returned by
+/// `parse`, for re-rendering only the blocks that changed.
+///
+/// # Errors
+///
+/// Returns an error if either AST is not valid JSON with a `children`
+/// array.
+#[napi]
+pub fn diff_ast(old_ast_json: String, new_ast_json: String) -> Result<AstDiffResult> {
+    let hashes = |json: &str| {
+        serde_json::from_str(json)
+            .map_err(|e| e.to_string())
+            .and_then(|document| ast_diff::block_hashes(&document))
+            .map_err(Error::from_reason)
+    };
+    let old = hashes(&old_ast_json)?;
+    let new = hashes(&new_ast_json)?;
+    let changes = ast_diff::diff_blocks(&old, &new)
+        .into_iter()
+        .map(|change| AstBlockChange {
+            old_start: change.old.start as u32,
+            old_end: change.old.end as u32,
+            new_start: change.new.start as u32,
+            new_end: change.new.end as u32,
+        })
+        .collect();
+    Ok(AstDiffResult { changes, hashes: new.iter().map(|hash| format!("{hash:016x}")).collect() })
 }
 
-/// Extracts plain text from a heading node.
-fn extract_heading_text(heading: &Heading) -> String {
-    let mut text = String::new();
-    for child in &heading.children {
-        collect_text(child, &mut text);
-    }
-    text
+/// A configured renderer that can be reused across many documents.
+///
+/// Options are converted once at construction, and the parser arena and
+/// output buffer are reused between renders.
+#[napi]
+pub struct Renderer {
+    parser_options: ParserOptions,
+    html: HtmlRenderer,
+    allocator: Allocator,
 }
 
-/// Recursively collects text from nodes.
-fn collect_text(node: &Node, text: &mut String) {
-    match node {
-        Node::Text(t) => text.push_str(t.value),
-        Node::Emphasis(e) => {
-            for child in &e.children {
-                collect_text(child, text);
-            }
-        }
-        Node::Strong(s) => {
-            for child in &s.children {
-                collect_text(child, text);
-            }
+#[napi]
+impl Renderer {
+    /// Creates a renderer with the given options.
+    #[napi(constructor)]
+    pub fn new(options: Option<JsTransformOptions>) -> Self {
+        let opts = options.unwrap_or_default();
+        Self {
+            parser_options: transform_options_to_parser_options(&opts),
+            html: HtmlRenderer::with_options(transform_options_to_renderer_options(&opts)),
+            allocator: Allocator::new(),
         }
-        Node::InlineCode(c) => text.push_str(c.value),
-        Node::Delete(d) => {
-            for child in &d.children {
-                collect_text(child, text);
+    }
+
+    /// Renders an AST (in the format returned by `parse`) to HTML.
+    #[napi]
+    pub fn render_ast(&mut self, ast_json: String) -> RenderResult {
+        self.allocator.reset();
+        let value: serde_json::Value = match serde_json::from_str(&ast_json) {
+            Ok(value) => value,
+            Err(e) => return RenderResult { html: String::new(), errors: vec![e.to_string()] },
+        };
+        match ast_json::document_from_json(&self.allocator, &value, self.html.options()) {
+            Ok(doc) => {
+                RenderResult { html: self.html.render_to_buffer(&doc).to_string(), errors: vec![] }
             }
+            Err(e) => RenderResult { html: String::new(), errors: vec![e] },
         }
-        Node::Link(l) => {
-            for child in &l.children {
-                collect_text(child, text);
+    }
+
+    /// Parses Markdown source and renders it to HTML.
+    #[napi]
+    pub fn render_source(&mut self, source: String) -> RenderResult {
+        self.allocator.reset();
+        let parser = Parser::with_options(&self.allocator, &source, self.parser_options.clone());
+        match parser.parse() {
+            Ok(doc) => {
+                RenderResult { html: self.html.render_to_buffer(&doc).to_string(), errors: vec![] }
             }
+            Err(e) => RenderResult { html: String::new(), errors: vec![e.to_string()] },
         }
-        _ => {}
     }
 }
 
-/// Converts text to URL-friendly slug.
-fn slugify(text: &str) -> String {
-    text.to_lowercase()
-        .chars()
-        .map(|c| if c.is_alphanumeric() || c == ' ' || c == '-' { c } else { ' ' })
-        .collect::<String>()
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join("-")
+/// Converts text to a slug with the algorithm used for heading IDs and TOC
+/// links.
+///
+/// `style` is `"github"` (default), `"unicode"` or `"ascii"`, as in the
+/// `slugStyle`
+/// transform option. Repeated headings additionally get `-1`, `-2`, ...
+/// suffixes in document order; this function does not add them.
+#[napi]
+pub fn slugify(text: String, style: Option<String>) -> String {
+    let style = style.as_deref().and_then(SlugStyle::from_name).unwrap_or_default();
+    ox_content_renderer::slugify(&text, style)
 }
 
-/// Converts transform options to parser options.
-fn transform_options_to_parser_options(opts: &JsTransformOptions) -> ParserOptions {
-    let mut options =
-        if opts.gfm.unwrap_or(false) { ParserOptions::gfm() } else { ParserOptions::default() };
-
-    if let Some(v) = opts.footnotes {
-        options.footnotes = v;
-    }
-    if let Some(v) = opts.task_lists {
-        options.task_lists = v;
-    }
-    if let Some(v) = opts.tables {
-        options.tables = v;
-    }
-    if let Some(v) = opts.strikethrough {
-        options.strikethrough = v;
-    }
-    if let Some(v) = opts.autolinks {
-        options.autolinks = v;
-    }
-
-    options
+/// Returns the version of ox_content_napi.
+#[napi]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
 }
 
-/// Converts transform options to renderer options.
-fn transform_options_to_renderer_options(opts: &JsTransformOptions) -> HtmlRendererOptions {
-    let mut options = HtmlRendererOptions::new();
-
-    if let Some(v) = opts.convert_md_links {
-        options.convert_md_links = v;
-    }
+/// Transforms Markdown source into HTML, frontmatter, and TOC.
+///
+/// This is the main entry point for unplugin-ox-content.
+///
+/// Options are resolved per field: `options` first, then the known keys in
+/// the page frontmatter, then `defaults`. As with [`parse_and_render`], a
+/// parse error is reported in `errors` along with the blocks before it.
+#[napi]
+pub fn transform(
+    source: String,
+    options: Option<JsTransformOptions>,
+    defaults: Option<JsTransformOptions>,
+) -> TransformResult {
+    transform_source(&source, options.unwrap_or_default(), defaults.unwrap_or_default())
+}
+
+/// Transforms Markdown like [`transform`] and also returns the final AST.
+///
+/// Saves parsing the source a second time when both the HTML and the AST
+/// are needed.
+#[napi]
+pub fn transform_full(
+    source: String,
+    options: Option<JsTransformOptions>,
+    defaults: Option<JsTransformOptions>,
+) -> TransformFullResult {
+    run_transform(
+        &source,
+        options.unwrap_or_default(),
+        defaults.unwrap_or_default(),
+        true,
+        None,
+        None,
+    )
+}
+
+/// Transforms Markdown like [`transform`] but returns only the section
+/// under the heading with ID `slug`, for section permalinks.
+///
+/// The section is the heading and everything after it up to the next
+/// heading of the same or a higher level. The whole page is rendered and
+/// the section cut out, so heading IDs and numbers match the full page.
+/// Footnote definitions are left out. Only top-level headings can be
+/// selected; a slug no such heading has is reported in `errors`.
+#[napi]
+pub fn render_section(
+    source: String,
+    slug: String,
+    options: Option<JsTransformOptions>,
+) -> RenderResult {
+    let result = run_transform(
+        &source,
+        options.unwrap_or_default(),
+        JsTransformOptions::default(),
+        false,
+        Some(&slug),
+        None,
+    );
+    RenderResult { html: result.html, errors: result.errors }
+}
+
+/// Merges per-call `overrides` over `base` options.
+///
+/// Every field set in `overrides` wins; unset fields are taken from `base`.
+#[napi]
+pub fn merge_transform_options(
+    base: JsTransformOptions,
+    overrides: JsTransformOptions,
+) -> JsTransformOptions {
+    overrides.merged_over(base)
+}
+
+/// Runs the transform pipeline shared by [`transform`] and [`transform_async`].
+fn transform_source(
+    source: &str,
+    options: JsTransformOptions,
+    defaults: JsTransformOptions,
+) -> TransformResult {
+    run_transform(source, options, defaults, false, None, None).into()
+}
+
+/// Runs the transform pipeline, serializing the AST only when `with_ast`
+/// is set, rendering only the section under the heading with ID `section`
+/// when one is given, and feeding the final AST to `indexer` if any.
+fn run_transform(
+    source: &str,
+    options: JsTransformOptions,
+    defaults: JsTransformOptions,
+    with_ast: bool,
+    section: Option<&str>,
+    indexer: Option<&mut DocumentIndexer>,
+) -> TransformFullResult {
+    // Parse frontmatter
+    let Frontmatter { content, data: frontmatter, errors: frontmatter_errors, format, raw } =
+        parse_frontmatter(source);
+
+    // Resolve options: call options > frontmatter > defaults
+    let opts = &if options.frontmatter_options.or(defaults.frontmatter_options).unwrap_or(true) {
+        options
+            .merged_over(JsTransformOptions::from_frontmatter(&frontmatter))
+            .merged_over(defaults)
+    } else {
+        options.merged_over(defaults)
+    };
+    let toc_enabled = opts.toc.unwrap_or(true);
+    let toc_min_depth = opts.toc_min_depth.unwrap_or(2).max(1);
+    let toc_max_depth = opts.toc_max_depth.unwrap_or(3);
+
+    // The content is the end of the source; count the lines before it
+    let frontmatter_lines = source[..source.len() - content.len()].matches('\n').count();
+    let frontmatter_format = format.map(|format| format.as_str().to_string());
+    let frontmatter_errors: Vec<String> =
+        frontmatter_errors.iter().map(ToString::to_string).collect();
+    if opts.strict_frontmatter.unwrap_or(false) && !frontmatter_errors.is_empty() {
+        return TransformFullResult {
+            html: String::new(),
+            ast: String::new(),
+            frontmatter: "{}".to_string(),
+            frontmatter_format,
+            frontmatter_raw: raw,
+            toc: vec![],
+            toc_enabled,
+            anchors: vec![],
+            errors: frontmatter_errors,
+        };
+    }
+
+    // Inline included partials
+    let content = match &opts.includes {
+        Some(includes) => {
+            let max_depth =
+                opts.max_include_depth.map_or(DEFAULT_MAX_INCLUDE_DEPTH, |depth| depth as usize);
+            resolve_includes(&content, |path| includes.get(path).map(String::as_str), max_depth)
+                .source
+        }
+        None => content,
+    };
+
+    // Parse markdown
+    let allocator = Allocator::new();
+    let parser_options = transform_options_to_parser_options(opts);
+    let parser = Parser::with_options(&allocator, &content, parser_options);
+
+    // Render what parsed even if parsing stopped early, for previews
+    let (mut doc, parse_error) = parser.parse_partial();
+    if opts.drop_title_heading.unwrap_or(false) && has_frontmatter_title(&frontmatter) {
+        remove_title_heading(&mut doc);
+    }
+    let normalization = opts
+        .normalize_headings
+        .as_deref()
+        .and_then(HeadingNormalization::from_name)
+        .unwrap_or_default();
+    normalize_headings(&mut doc, normalization);
+
+    // Render to HTML
+    let mut renderer_options = transform_options_to_renderer_options(opts);
+    renderer_options.toc_markers = opts
+        .toc_markers
+        .clone()
+        .unwrap_or_else(|| vec!["[[toc]]".to_string(), "[TOC]".to_string()]);
+    let has_toc_marker =
+        renderer_options.toc_markers.iter().any(|marker| content.contains(marker.as_str()));
+
+    // Extract TOC from headings
+    let toc = if toc_enabled || has_toc_marker {
+        let depths = toc_min_depth..=toc_max_depth;
+        extract_toc(&doc, depths, toc_numbering(opts), slug_style(opts), |heading| {
+            toc_excludes(opts, heading)
+        })
+    } else {
+        vec![]
+    };
+    if has_toc_marker {
+        renderer_options.toc_html = toc_html(&toc);
+    }
+    let toc = if toc_enabled { toc } else { vec![] };
+
+    renderer_options.source_line_offset = u32::try_from(frontmatter_lines).unwrap_or(u32::MAX);
+    let mut renderer = HtmlRenderer::with_options(renderer_options);
+    let mut errors = frontmatter_errors;
+    errors.extend(parse_error.map(|e| {
+        e.with_line_offset(u32::try_from(frontmatter_lines).unwrap_or(u32::MAX)).to_string()
+    }));
+    if toc_min_depth > toc_max_depth {
+        errors.push(format!(
+            "`tocMinDepth` ({toc_min_depth}) is greater than `tocMaxDepth` ({toc_max_depth})"
+        ));
+    }
+    let html = match section {
+        Some(slug) => {
+            renderer.render_section_with_source(&doc, &content, slug).unwrap_or_else(|| {
+                errors.push(format!("no section with heading ID `{slug}`"));
+                String::new()
+            })
+        }
+        None => renderer.render_with_source(&doc, &content),
+    };
+    let anchors = renderer.anchors().iter().cloned().map(HeadingAnchor::from).collect();
+    let ast = if with_ast { ast_json::document_to_json(&doc).to_string() } else { String::new() };
+    if let Some(indexer) = indexer {
+        indexer.extract(&doc);
+    }
+
+    TransformFullResult {
+        html,
+        ast,
+        frontmatter: serde_json::to_string(&frontmatter).unwrap_or_else(|_| "{}".to_string()),
+        frontmatter_format,
+        frontmatter_raw: raw,
+        toc,
+        toc_enabled,
+        anchors,
+        errors,
+    }
+}
+
+/// Extracts table of contents from document headings.
+///
+/// With `numbering`, entry text is prefixed with the same section numbers
+/// the renderer adds to headings; slugs are computed from the unnumbered text
+/// and deduplicated exactly like the renderer's heading IDs, counting the
+/// headings outside `depths` as well. Headings for which `exclude` returns
+/// true are left out together with the headings under them, but still count
+/// for numbering and deduplication.
+fn extract_toc(
+    doc: &Document,
+    depths: RangeInclusive<u8>,
+    mut numbering: Option<HeadingNumbering>,
+    slug_style: SlugStyle,
+    exclude: impl Fn(&Heading) -> bool,
+) -> Vec<TocEntry> {
+    let mut entries = Vec::new();
+    let mut slugs = UniqueSlugs::new();
+    // Depth of the excluded heading whose section is being skipped
+    let mut excluded_depth = None;
+
+    for node in &doc.children {
+        if let Node::Heading(heading) = node {
+            let number = numbering.as_mut().and_then(|n| n.next(heading.depth));
+            let text = heading_text(heading);
+            let slug = slugs.unique(heading_slug(heading, slug_style));
+            if excluded_depth.is_some_and(|depth| heading.depth <= depth) {
+                excluded_depth = None;
+            }
+            if excluded_depth.is_none() && exclude(heading) {
+                excluded_depth = Some(heading.depth);
+            }
+            if depths.contains(&heading.depth) && excluded_depth.is_none() {
+                let text = match number {
+                    Some(number) => format!("{number} {text}"),
+                    None => text,
+                };
+                entries.push(TocEntry { depth: heading.depth, text, slug });
+            }
+        }
+    }
+
+    entries
+}
+
+/// Returns whether `heading` is left out of the TOC by the
+/// `tocExcludeClasses` and `tocExclude` options.
+fn toc_excludes(opts: &JsTransformOptions, heading: &Heading) -> bool {
+    let excluded_class = heading.classes.iter().any(|class| {
+        opts.toc_exclude_classes
+            .as_ref()
+            .map_or(*class == "no-toc", |classes| classes.iter().any(|c| c == class))
+    });
+    excluded_class
+        || opts.toc_exclude.as_ref().is_some_and(|patterns| {
+            let text = heading_text(heading).to_lowercase();
+            patterns.iter().any(|pattern| wildcard_match(&pattern.to_lowercase(), &text))
+        })
+}
+
+/// Matches `text` against `pattern`, where `*` matches any run of
+/// characters.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Renders TOC entries as nested lists, for the inline TOC markers.
+fn toc_html(entries: &[TocEntry]) -> String {
+    let mut html = String::from("<nav class=\"table-of-contents\">\n");
+    // Depths of the open lists, each with an open item
+    let mut depths: Vec<u8> = Vec::new();
+    for entry in entries {
+        while depths.last().is_some_and(|&depth| depth > entry.depth) {
+            html.push_str("</li>\n</ul>\n");
+            depths.pop();
+        }
+        if depths.last() == Some(&entry.depth) {
+            html.push_str("</li>\n");
+        } else {
+            if !depths.is_empty() {
+                html.push('\n');
+            }
+            html.push_str("<ul>\n");
+            depths.push(entry.depth);
+        }
+        html.push_str("<li><a href=\"#");
+        html.push_str(&escape_html(&entry.slug));
+        html.push_str("\">");
+        html.push_str(&escape_html(&entry.text));
+        html.push_str("</a>");
+    }
+    for _ in depths {
+        html.push_str("</li>\n</ul>\n");
+    }
+    html.push_str("</nav>\n");
+    html
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Returns the heading numbering to apply to the TOC, if enabled.
+fn toc_numbering(opts: &JsTransformOptions) -> Option<HeadingNumbering> {
+    opts.number_headings
+        .unwrap_or(false)
+        .then(|| HeadingNumbering::new(opts.number_headings_start.unwrap_or(2)))
+}
+
+/// Converts transform options to parser options.
+fn transform_options_to_parser_options(opts: &JsTransformOptions) -> ParserOptions {
+    let mut options = preset_parser_options(opts.preset, opts.gfm);
+
+    if let Some(v) = opts.footnotes {
+        options.footnotes = v;
+    }
+    if let Some(v) = opts.task_lists {
+        options.task_lists = v;
+    }
+    if let Some(v) = opts.tables {
+        options.tables = v;
+    }
+    if let Some(v) = opts.strikethrough {
+        options.strikethrough = v;
+    }
+    if let Some(v) = opts.autolinks {
+        options.autolinks = v;
+    }
+    if let Some(v) = opts.abbreviations {
+        options.abbreviations = v;
+    }
+    if let Some(v) = opts.attributes {
+        options.attributes = v;
+    }
+    if let Some(v) = opts.parse_inside_html_blocks {
+        options.parse_inside_html_blocks = v;
+    }
+    if let Some(v) = opts.math {
+        options.math = v;
+    }
+    if let Some(v) = &opts.table_cell_break {
+        options.table_cell_break = Some(v.clone());
+    }
+    if let Some(v) = opts.features {
+        options.features = Features::from_preset(v.into());
+    }
+    if let Some(v) = opts.normalize_whitespace {
+        options.normalize_whitespace = v;
+    }
+    if let Some(v) = opts.list_indent {
+        options.list_indent = u8::try_from(v).unwrap_or(u8::MAX);
+    }
+    if let Some(v) = opts.max_nesting_depth {
+        options.max_nesting_depth = v as usize;
+    }
+    if let Some(v) = opts.strict {
+        options.strict = v;
+    }
+    options.max_input_bytes = opts.max_input_bytes.map(|v| v as usize);
+    options.time_budget = opts.time_budget_ms.map(|ms| Duration::from_millis(u64::from(ms)));
+
+    options
+}
+
+/// Converts transform options to renderer options.
+fn transform_options_to_renderer_options(opts: &JsTransformOptions) -> HtmlRendererOptions {
+    let mut options = parser_renderer_options(&preset_parser_options(opts.preset, opts.gfm));
+
+    if let Some(v) = opts.convert_md_links {
+        options.convert_md_links = v;
+    }
     if let Some(ref v) = opts.base_url {
         options.base_url.clone_from(v);
     }
+    if let Some(ref v) = opts.line_ending {
+        options.line_ending =
+            if v.eq_ignore_ascii_case("crlf") { LineEnding::Crlf } else { LineEnding::Lf };
+    }
+    if let Some(v) = opts.trailing_newline {
+        options.trailing_newline = v;
+    }
+    if let Some(v) = opts.xhtml {
+        options.xhtml = v;
+    }
+    if let Some(v) = opts.number_headings {
+        options.number_headings = v;
+    }
+    if let Some(v) = opts.number_headings_start {
+        options.number_headings_start = v;
+    }
+    options.heading_ids = opts.heading_ids.unwrap_or(true);
+    options.slug_style = slug_style(opts);
+    if let Some(v) = opts.heading_anchors {
+        options.heading_anchors = v;
+    }
+    if let Some(v) = opts.accessible {
+        options.accessible = v;
+    }
+    if let Some(v) = &opts.code_escapes {
+        options.code_escapes.clone_from(v);
+    }
+    if let Some(v) = opts.math_output.as_deref().and_then(MathOutput::from_name) {
+        options.math_output = v;
+    }
+    if let Some(v) = opts.table_wrapper {
+        options.table_wrapper = v;
+    }
+    if let Some(v) = opts.nested_list_numbers {
+        options.nested_list_numbers = v;
+    }
+    if let Some(v) = opts.detect_code_language {
+        options.detect_code_language = v;
+    }
+    if let Some(v) = opts.code_final_newline {
+        options.code_final_newline = v;
+    }
+    if let Some(v) = opts.gfm_tagfilter {
+        options.gfm_tagfilter = v;
+    }
+    if let Some(v) = opts.source_line_attrs {
+        options.source_line_attrs = v;
+    }
+    if let Some(v) = opts.cjk_friendly_breaks {
+        options.cjk_friendly_breaks = v;
+    }
+    if let Some(v) = &opts.custom_nodes {
+        options.custom_nodes.clone_from(v);
+    }
+    if let Some(v) = &opts.custom_emoji {
+        options.emoji = v
+            .iter()
+            .map(|(name, value)| (name.trim_matches(':').to_string(), Emoji::from_value(value)))
+            .collect();
+    }
+    if opts.issue_url.is_some() || opts.commit_url.is_some() || opts.mention_url.is_some() {
+        let mut links = ReferenceLinks {
+            issue_url: opts.issue_url.clone(),
+            commit_url: opts.commit_url.clone(),
+            mention_url: opts.mention_url.clone(),
+            ..ReferenceLinks::new()
+        };
+        if let Some(v) = &opts.issue_prefix {
+            links.issue_prefix.clone_from(v);
+        }
+        options.reference_links = Some(links);
+    }
+    if let Some(v) = &opts.language_aliases {
+        options.language_aliases.extend(
+            v.iter().map(|(alias, language)| (alias.to_ascii_lowercase(), language.clone())),
+        );
+    }
 
     options
 }
 
+/// Returns the slug style for heading IDs and TOC entries.
+fn slug_style(opts: &JsTransformOptions) -> SlugStyle {
+    opts.slug_style.as_deref().and_then(SlugStyle::from_name).unwrap_or_default()
+}
+
 // =============================================================================
 // Async (Multi-threaded) API
 // =============================================================================
@@ -401,18 +1647,7 @@ impl Task for ParseAndRenderTask {
     type JsValue = RenderResult;
 
     fn compute(&mut self) -> Result<Self::Output> {
-        let allocator = Allocator::new();
-        let parser = Parser::with_options(&allocator, &self.source, self.options.clone());
-
-        let result = match parser.parse() {
-            Ok(doc) => {
-                let mut renderer = HtmlRenderer::new();
-                let html = renderer.render(&doc);
-                RenderResult { html, errors: vec![] }
-            }
-            Err(e) => RenderResult { html: String::new(), errors: vec![e.to_string()] },
-        };
-        Ok(result)
+        Ok(render_source(&self.source, self.options.clone()))
     }
 
     fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
@@ -434,6 +1669,7 @@ pub fn parse_and_render_async(
 pub struct TransformTask {
     source: String,
     options: JsTransformOptions,
+    defaults: JsTransformOptions,
 }
 
 impl Task for TransformTask {
@@ -441,39 +1677,11 @@ impl Task for TransformTask {
     type JsValue = TransformResult;
 
     fn compute(&mut self) -> Result<Self::Output> {
-        let toc_max_depth = self.options.toc_max_depth.unwrap_or(3);
-
-        // Parse frontmatter
-        let (content, frontmatter) = parse_frontmatter(&self.source);
-
-        // Parse markdown
-        let allocator = Allocator::new();
-        let parser_options = transform_options_to_parser_options(&self.options);
-        let parser = Parser::with_options(&allocator, &content, parser_options);
-
-        let result = match parser.parse() {
-            Ok(doc) => {
-                let toc = extract_toc(&doc, toc_max_depth);
-                let renderer_options = transform_options_to_renderer_options(&self.options);
-                let mut renderer = HtmlRenderer::with_options(renderer_options);
-                let html = renderer.render(&doc);
-
-                TransformResult {
-                    html,
-                    frontmatter: serde_json::to_string(&frontmatter)
-                        .unwrap_or_else(|_| "{}".to_string()),
-                    toc,
-                    errors: vec![],
-                }
-            }
-            Err(e) => TransformResult {
-                html: String::new(),
-                frontmatter: "{}".to_string(),
-                toc: vec![],
-                errors: vec![e.to_string()],
-            },
-        };
-        Ok(result)
+        Ok(transform_source(
+            &self.source,
+            std::mem::take(&mut self.options),
+            std::mem::take(&mut self.defaults),
+        ))
     }
 
     fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
@@ -486,9 +1694,13 @@ impl Task for TransformTask {
 pub fn transform_async(
     source: String,
     options: Option<JsTransformOptions>,
+    defaults: Option<JsTransformOptions>,
 ) -> AsyncTask<TransformTask> {
-    let opts = options.unwrap_or_default();
-    AsyncTask::new(TransformTask { source, options: opts })
+    AsyncTask::new(TransformTask {
+        source,
+        options: options.unwrap_or_default(),
+        defaults: defaults.unwrap_or_default(),
+    })
 }
 
 // =============================================================================
@@ -511,6 +1723,18 @@ pub struct JsOgImageConfig {
     pub title_font_size: Option<u32>,
     /// Description font size.
     pub description_font_size: Option<u32>,
+    /// Font family name. With `font_data`, the name given to the embedded font.
+    pub font_family: Option<String>,
+    /// Font file (TTF, OTF, WOFF or WOFF2) embedded into the SVG as a base64
+    /// `@font-face`. When unset, the system UI font is used.
+    pub font_data: Option<Buffer>,
+    /// Card layout: `default`, `centered`, `split`, `minimal` or `card`.
+    /// Unknown names use `default`.
+    pub layout: Option<String>,
+    /// Description shown when the data has none, such as the site tagline.
+    /// Without one, the title is centered in the space the description
+    /// would take.
+    pub fallback_description: Option<String>,
 }
 
 /// OG image data for JavaScript.
@@ -526,47 +1750,78 @@ pub struct JsOgImageData {
     pub author: Option<String>,
 }
 
+impl From<JsOgImageConfig> for OgImageConfig {
+    fn from(cfg: JsOgImageConfig) -> Self {
+        let mut og_config = Self::default();
+
+        if let Some(w) = cfg.width {
+            og_config.width = w;
+        }
+        if let Some(h) = cfg.height {
+            og_config.height = h;
+        }
+        if let Some(ref bg) = cfg.background_color {
+            og_config.background_color.clone_from(bg);
+        }
+        if let Some(ref tc) = cfg.text_color {
+            og_config.text_color.clone_from(tc);
+        }
+        if let Some(ts) = cfg.title_font_size {
+            og_config.title_font_size = ts;
+        }
+        if let Some(ds) = cfg.description_font_size {
+            og_config.description_font_size = ds;
+        }
+        if let Some(ref family) = cfg.font_family {
+            og_config.font_family = Some(family.clone());
+        }
+        if let Some(ref data) = cfg.font_data {
+            og_config.font_data = Some(data.to_vec());
+        }
+        if let Some(layout) = cfg.layout.as_deref().and_then(TemplateLayout::from_name) {
+            og_config.layout = layout;
+        }
+        og_config.fallback_description = cfg.fallback_description;
+
+        og_config
+    }
+}
+
+impl From<JsOgImageData> for OgImageData {
+    fn from(data: JsOgImageData) -> Self {
+        Self {
+            title: data.title,
+            description: data.description,
+            site_name: data.site_name,
+            author: data.author,
+            date: None,
+            tags: vec![],
+        }
+    }
+}
+
 /// Generates an OG image as SVG.
 ///
 /// This function generates an SVG representation of an OG image
 /// that can be used for social media previews.
 #[napi]
 pub fn generate_og_image_svg(data: JsOgImageData, config: Option<JsOgImageConfig>) -> String {
-    use ox_content_og_image::{OgImageConfig, OgImageData, OgImageGenerator};
-
-    let cfg = config.unwrap_or_default();
-    let mut og_config = OgImageConfig::default();
-
-    if let Some(w) = cfg.width {
-        og_config.width = w;
-    }
-    if let Some(h) = cfg.height {
-        og_config.height = h;
-    }
-    if let Some(ref bg) = cfg.background_color {
-        og_config.background_color.clone_from(bg);
-    }
-    if let Some(ref tc) = cfg.text_color {
-        og_config.text_color.clone_from(tc);
-    }
-    if let Some(ts) = cfg.title_font_size {
-        og_config.title_font_size = ts;
-    }
-    if let Some(ds) = cfg.description_font_size {
-        og_config.description_font_size = ds;
-    }
-
-    let og_data = OgImageData {
-        title: data.title,
-        description: data.description,
-        site_name: data.site_name,
-        author: data.author,
-        date: None,
-        tags: vec![],
-    };
+    let generator = OgImageGenerator::new(config.unwrap_or_default().into());
+    generator.generate_svg(&data.into())
+}
 
-    let generator = OgImageGenerator::new(og_config);
-    generator.generate_svg(&og_data)
+/// Generates one OG image as SVG per config from the same data, such as
+/// Open Graph, Twitter and square cards.
+///
+/// Each image is laid out for its own width and height; unset fields use
+/// the same defaults as `generateOgImageSvg`.
+#[napi]
+pub fn generate_og_image_variants(
+    data: JsOgImageData,
+    configs: Vec<JsOgImageConfig>,
+) -> Vec<String> {
+    let configs = configs.into_iter().map(OgImageConfig::from).collect();
+    OgImageGenerator::generate_svg_variants(&data.into(), configs)
 }
 
 // =============================================================================
@@ -589,6 +1844,12 @@ pub struct JsSearchDocument {
     pub headings: Vec<String>,
     /// Code snippets.
     pub code: Vec<String>,
+    /// Ranking multiplier applied to this document's score (defaults to 1.0).
+    pub boost: Option<f64>,
+    /// Tags, matched by the `tags` search option.
+    pub tags: Option<Vec<String>>,
+    /// Facet values by facet name, matched by the `facets` search option.
+    pub facets: Option<HashMap<String, Vec<String>>>,
 }
 
 /// Search result for JavaScript.
@@ -604,8 +1865,31 @@ pub struct JsSearchResult {
     pub score: f64,
     /// Matched terms.
     pub matches: Vec<String>,
-    /// Content snippet.
+    /// Field that produced the top match ("title", "heading", "body", or "code").
+    pub matched_field: String,
+    /// Content snippet, taken from the matched field.
     pub snippet: String,
+    /// Matching sections of the page, best first, when `groupByPage` is set.
+    pub sub_matches: Option<Vec<JsSearchSubMatch>>,
+    /// Tags of the document.
+    pub tags: Vec<String>,
+    /// Facet values of the document by facet name.
+    pub facets: HashMap<String, Vec<String>>,
+}
+
+/// A matching section within a grouped search result.
+#[napi(object)]
+pub struct JsSearchSubMatch {
+    /// Document ID of the section.
+    pub id: String,
+    /// Section URL, including its fragment.
+    pub url: String,
+    /// Heading the match was found under.
+    pub heading: String,
+    /// Content snippet for the match.
+    pub snippet: String,
+    /// Relevance score of the section.
+    pub score: f64,
 }
 
 /// Search options for JavaScript.
@@ -620,6 +1904,20 @@ pub struct JsSearchOptions {
     pub fuzzy: Option<bool>,
     /// Minimum score threshold.
     pub threshold: Option<f64>,
+    /// Group sections of the same page (URLs differing only in `#fragment`)
+    /// into one result with `subMatches`.
+    pub group_by_page: Option<bool>,
+    /// Only return documents with all of these tags (case-insensitive).
+    pub tags: Option<Vec<String>>,
+    /// Only return documents that have, for every facet listed, at least
+    /// one of the listed values (case-insensitive).
+    pub facets: Option<HashMap<String, Vec<String>>>,
+    /// Maximum snippet length in characters (default: 150). `0` leaves
+    /// snippets empty.
+    pub snippet_length: Option<u32>,
+    /// Characters of context shown before the first match (default: a
+    /// third of `snippetLength`).
+    pub snippet_context: Option<u32>,
 }
 
 impl From<JsSearchOptions> for SearchOptions {
@@ -629,6 +1927,11 @@ impl From<JsSearchOptions> for SearchOptions {
             prefix: opts.prefix.unwrap_or(true),
             fuzzy: opts.fuzzy.unwrap_or(false),
             threshold: opts.threshold.unwrap_or(0.0),
+            group_by_page: opts.group_by_page.unwrap_or(false),
+            tags: opts.tags.unwrap_or_default(),
+            facets: opts.facets.unwrap_or_default().into_iter().collect(),
+            snippet_length: opts.snippet_length.unwrap_or(150) as usize,
+            snippet_context: opts.snippet_context.map(|context| context as usize),
         }
     }
 }
@@ -638,6 +1941,20 @@ impl From<JsSearchOptions> for SearchOptions {
 /// Takes an array of documents and returns a serialized search index as JSON.
 #[napi]
 pub fn build_search_index(documents: Vec<JsSearchDocument>) -> String {
+    build_index(documents).to_json()
+}
+
+/// Builds a search index from documents in the compact binary format.
+///
+/// The result is smaller and faster to load than the JSON index returned by
+/// `buildSearchIndex`, and can be searched with `searchIndexBinary`.
+#[napi]
+pub fn build_search_index_binary(documents: Vec<JsSearchDocument>) -> Buffer {
+    build_index(documents).to_bytes().into()
+}
+
+/// Builds a search index from JavaScript documents.
+fn build_index(documents: Vec<JsSearchDocument>) -> SearchIndex {
     let mut builder = SearchIndexBuilder::new();
 
     for doc in documents {
@@ -648,11 +1965,13 @@ pub fn build_search_index(documents: Vec<JsSearchDocument>) -> String {
             body: doc.body,
             headings: doc.headings,
             code: doc.code,
+            boost: doc.boost.unwrap_or(1.0),
+            tags: doc.tags.unwrap_or_default(),
+            facets: doc.facets.unwrap_or_default().into_iter().collect(),
         });
     }
 
-    let index = builder.build();
-    index.to_json()
+    builder.build()
 }
 
 /// Searches a serialized index.
@@ -669,8 +1988,110 @@ pub fn search_index(
         return Vec::new();
     };
 
+    search_loaded_index(&index, &query, options)
+}
+
+/// Searches a binary index produced by `buildSearchIndexBinary`.
+///
+/// Returns an array of search results.
+#[napi]
+pub fn search_index_binary(
+    index: Buffer,
+    query: String,
+    options: Option<JsSearchOptions>,
+) -> Vec<JsSearchResult> {
+    let Ok(index) = SearchIndex::from_bytes(&index) else {
+        return Vec::new();
+    };
+
+    search_loaded_index(&index, &query, options)
+}
+
+/// Client search bundle for JavaScript.
+#[napi(object)]
+pub struct JsSearchClientBundle {
+    /// ES module exporting `tokenize`, `createSearch(index)` and `loadSearch(url)`.
+    pub js: String,
+    /// Compact JSON index to pass to `createSearch` or serve for `loadSearch`.
+    pub index: String,
+}
+
+/// Generates a standalone client search bundle for a JSON index.
+///
+/// The returned module scores results exactly like `searchIndex`, so it can be
+/// used outside the SSG template.
+#[napi]
+pub fn generate_search_client(index_json: String) -> Result<JsSearchClientBundle> {
+    let bundle = ox_content_search::generate_search_client(&index_json)
+        .map_err(|e| Error::from_reason(e.to_string()))?;
+    Ok(JsSearchClientBundle { js: bundle.js, index: bundle.index })
+}
+
+/// How often a term occurs in a search index.
+#[napi(object)]
+pub struct JsTermStats {
+    /// The indexed term, as produced by the tokenizer.
+    pub term: String,
+    /// Number of documents containing the term.
+    pub documents: u32,
+    /// Occurrences across all documents and fields.
+    pub occurrences: f64,
+}
+
+impl From<TermStats> for JsTermStats {
+    fn from(stats: TermStats) -> Self {
+        Self {
+            term: stats.term,
+            documents: u32::try_from(stats.documents).unwrap_or(u32::MAX),
+            #[allow(clippy::cast_precision_loss)]
+            occurrences: stats.occurrences as f64,
+        }
+    }
+}
+
+/// Summary of what a search index contains.
+#[napi(object)]
+pub struct JsIndexStats {
+    /// Number of documents.
+    pub documents: u32,
+    /// Number of distinct terms (the vocabulary size).
+    pub terms: u32,
+    /// Average number of body tokens per document, as used for scoring.
+    pub avg_doc_length: f64,
+    /// The most frequent terms, ranked by the number of documents containing
+    /// them and then by occurrences.
+    pub top_terms: Vec<JsTermStats>,
+}
+
+impl From<IndexStats> for JsIndexStats {
+    fn from(stats: IndexStats) -> Self {
+        Self {
+            documents: u32::try_from(stats.documents).unwrap_or(u32::MAX),
+            terms: u32::try_from(stats.terms).unwrap_or(u32::MAX),
+            avg_doc_length: stats.avg_doc_length,
+            top_terms: stats.top_terms.into_iter().map(JsTermStats::from).collect(),
+        }
+    }
+}
+
+/// Returns the size of a JSON index and its `top` most frequent terms
+/// (default: 20), for tuning stop words and spotting indexing problems.
+#[napi]
+pub fn search_index_stats(index_json: String, top: Option<u32>) -> Result<JsIndexStats> {
+    let index =
+        SearchIndex::from_json(&index_json).map_err(|e| Error::from_reason(e.to_string()))?;
+    Ok(index.stats(top.map_or(20, |top| top as usize)).into())
+}
+
+/// Runs a query against a deserialized index.
+fn search_loaded_index(
+    index: &SearchIndex,
+    query: &str,
+    options: Option<JsSearchOptions>,
+) -> Vec<JsSearchResult> {
     let opts = options.map(SearchOptions::from).unwrap_or_default();
-    let results = index.search(&query, &opts);
+    let grouped = opts.group_by_page;
+    let results = index.search(query, &opts);
 
     results
         .into_iter()
@@ -680,7 +2101,22 @@ pub fn search_index(
             url: r.url,
             score: r.score,
             matches: r.matches,
+            matched_field: r.matched_field.as_str().to_string(),
             snippet: r.snippet,
+            sub_matches: grouped.then(|| {
+                r.sub_matches
+                    .into_iter()
+                    .map(|m| JsSearchSubMatch {
+                        id: m.id,
+                        url: m.url,
+                        heading: m.heading,
+                        snippet: m.snippet,
+                        score: m.score,
+                    })
+                    .collect()
+            }),
+            tags: r.tags,
+            facets: r.facets.into_iter().collect(),
         })
         .collect()
 }
@@ -722,13 +2158,20 @@ pub struct JsSsgPageData {
     pub content: String,
     /// Table of contents entries.
     pub toc: Vec<TocEntry>,
+    /// Show the TOC aside (default: true). When enabled it is shown even
+    /// without entries, unless `toc_min_entries` hides it.
+    pub toc_enabled: Option<bool>,
     /// URL path.
     pub path: String,
+    /// Page language for `<html lang>` and `og:locale`, such as `en` or
+    /// `pt-BR`, usually the `lang` frontmatter key. Falls back to the
+    /// config `lang`.
+    pub lang: Option<String>,
 }
 
 /// SSG configuration.
 #[napi(object)]
-#[derive(Clone)]
+#[derive(Default, Clone)]
 pub struct JsSsgConfig {
     /// Site name.
     pub site_name: String,
@@ -736,6 +2179,40 @@ pub struct JsSsgConfig {
     pub base: String,
     /// OG image URL.
     pub og_image: Option<String>,
+    /// Hide the TOC on pages with fewer entries than this (default: 0).
+    pub toc_min_entries: Option<u32>,
+    /// Search index JSON (from `buildSearchIndex`) to embed in every page,
+    /// so search works without fetching `search-index.json`. Only for small
+    /// indexes: it is repeated in each page.
+    pub inline_search_index: Option<String>,
+    /// Language of pages that do not set their own (default: `en`).
+    pub lang: Option<String>,
+    /// Nonce added to the generated `<style>`, `<script>` and stylesheet
+    /// `<link>` tags, for a Content-Security-Policy with `'nonce-…'`.
+    pub csp_nonce: Option<String>,
+    /// Link `ssg.css`, `ssg-theme.js` and `ssg.js` under `base` instead of
+    /// inlining them (default: false). Write the files returned by
+    /// `generateSsgAssets`.
+    pub external_assets: Option<bool>,
+    /// Logo image shown in the header (default: `logo.svg`). Site paths,
+    /// including those starting with `/`, are resolved against `base`;
+    /// full URLs such as `https://cdn.example.com/logo.svg` are used as
+    /// they are.
+    pub logo_path: Option<String>,
+    /// Search index fetched when search is first used (default:
+    /// `search-index.json`).
+    pub search_index_url: Option<String>,
+    /// Page the site name in the header links to (default: `index.html`).
+    pub home_url: Option<String>,
+}
+
+/// A stylesheet or script file for pages with `externalAssets`.
+#[napi(object)]
+pub struct JsSsgAsset {
+    /// File path relative to the site base, such as `ssg.css`.
+    pub path: String,
+    /// File content.
+    pub content: String,
 }
 
 /// Generates SSG HTML page with navigation and search.
@@ -755,7 +2232,9 @@ pub fn generate_ssg_html(
             .into_iter()
             .map(|t| ox_content_ssg::TocEntry { depth: t.depth, text: t.text, slug: t.slug })
             .collect(),
+        toc_enabled: page_data.toc_enabled.unwrap_or(true),
         path: page_data.path,
+        lang: page_data.lang,
     };
 
     let ssg_nav_groups: Vec<ox_content_ssg::NavGroup> = nav_groups
@@ -770,50 +2249,1159 @@ pub fn generate_ssg_html(
         })
         .collect();
 
-    let ssg_config = ox_content_ssg::SsgConfig {
-        site_name: config.site_name,
-        base: config.base,
-        og_image: config.og_image,
-    };
+    ox_content_ssg::generate_html(&ssg_page_data, &ssg_nav_groups, &config.into())
+}
+
+/// Renders the `<title>` and meta, Open Graph and Twitter card tags of a
+/// page head from its frontmatter, one tag per line.
+///
+/// Takes the frontmatter as JSON, such as the `frontmatter` returned by
+/// `transform`, and reads its `title`, `description`, `image`, `author`,
+/// `lang` and `tags` (as keywords). These are the tags `generateSsgHtml`
+/// writes, for pages whose head is assembled separately.
+#[napi]
+pub fn render_meta_tags(frontmatter_json: String, config: JsSsgConfig) -> Result<String> {
+    let frontmatter: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(&frontmatter_json)
+            .map_err(|e| Error::from_reason(format!("invalid frontmatter JSON: {e}")))?;
+    let meta = ox_content_ssg::PageMeta::from_frontmatter(&frontmatter);
+    Ok(ox_content_ssg::render_meta_tags(&meta, &config.into()))
+}
+
+impl From<JsSsgConfig> for ox_content_ssg::SsgConfig {
+    fn from(config: JsSsgConfig) -> Self {
+        Self {
+            site_name: config.site_name,
+            base: config.base,
+            og_image: config.og_image,
+            toc_min_entries: config.toc_min_entries.unwrap_or(0) as usize,
+            inline_search_index: config.inline_search_index,
+            lang: config.lang,
+            csp_nonce: config.csp_nonce,
+            external_assets: config.external_assets.unwrap_or(false),
+            logo_path: config.logo_path,
+            search_index_url: config.search_index_url,
+            home_url: config.home_url,
+        }
+    }
+}
+
+/// Returns the stylesheet and scripts that pages generated with
+/// `externalAssets` link to, for writing under the site base.
+///
+/// The page script includes the config's search index URL and inlined
+/// search index, so pass the same config as to `generateSsgHtml`.
+#[napi]
+pub fn generate_ssg_assets(config: JsSsgConfig) -> Vec<JsSsgAsset> {
+    ox_content_ssg::generate_assets(&config.into())
+        .into_iter()
+        .map(|asset| JsSsgAsset { path: asset.path.to_string(), content: asset.content })
+        .collect()
+}
 
-    ox_content_ssg::generate_html(&ssg_page_data, &ssg_nav_groups, &ssg_config)
+/// Options for extracting searchable content.
+#[napi(object)]
+#[derive(Default)]
+pub struct JsExtractSearchOptions {
+    /// Also include inline code and code blocks in the body text (default: false).
+    pub include_code_in_body: Option<bool>,
+    /// Derive a title from the URL when the page has no title or headings (default: true).
+    pub title_from_url: Option<bool>,
+    /// Frontmatter keys copied into the document's `facets` (default: none).
+    pub facet_keys: Option<Vec<String>>,
+    /// When the frontmatter has a `title`, leave the first h1 out of the
+    /// headings and body, matching the `dropTitleHeading` transform option
+    /// (default: false).
+    pub drop_title_heading: Option<bool>,
 }
 
 /// Extracts searchable content from Markdown source.
 ///
 /// Parses the Markdown and extracts title, body text, headings, and code.
+/// The title is taken from frontmatter `title`, then the first H1, then the
+/// first heading of any level, then (unless disabled) the URL slug.
+/// With `dropTitleHeading`, a frontmatter title replaces the first H1
+/// instead of being indexed alongside it; the H1 fallback is unaffected, as
+/// it only applies to pages without a frontmatter title.
+/// Code is kept out of the body unless `includeCodeInBody` is set.
+/// Frontmatter `tags` become the document's tags, and the keys listed in
+/// `facetKeys` become its facets; both accept a list or a comma-separated
+/// string. Other frontmatter values are never indexed.
 #[napi]
 pub fn extract_search_content(
     source: String,
     id: String,
     url: String,
     options: Option<JsParserOptions>,
+    extract_options: Option<JsExtractSearchOptions>,
 ) -> JsSearchDocument {
     let allocator = Allocator::new();
     let parser_options = options.map(ParserOptions::from).unwrap_or_default();
+    let extract_options = extract_options.unwrap_or_default();
 
     // Parse frontmatter first
-    let (content, frontmatter) = parse_frontmatter(&source);
-
-    // Try to get title from frontmatter
-    let frontmatter_title = frontmatter.get("title").and_then(|v| v.as_str()).map(String::from);
+    let Frontmatter { content, data: frontmatter, .. } = parse_frontmatter(&source);
 
     let parser = Parser::with_options(&allocator, &content, parser_options);
+    let indexer = parser.parse().ok().map(|mut doc| {
+        if extract_options.drop_title_heading.unwrap_or(false)
+            && has_frontmatter_title(&frontmatter)
+        {
+            remove_title_heading(&mut doc);
+        }
+        let mut indexer = DocumentIndexer::with_options(indexer_options(&extract_options));
+        indexer.extract(&doc);
+        indexer
+    });
 
-    let result = parser.parse();
-    let (title, body, headings, code) = if let Ok(ref doc) = result {
-        let mut indexer = DocumentIndexer::new();
-        indexer.extract(doc);
+    search_document(id, url, &frontmatter, indexer.as_ref(), &extract_options)
+}
 
-        let title = frontmatter_title
-            .unwrap_or_else(|| indexer.title().map(String::from).unwrap_or_default());
+/// Returns the indexer options selected by `options`.
+fn indexer_options(options: &JsExtractSearchOptions) -> IndexerOptions {
+    IndexerOptions { include_code_in_body: options.include_code_in_body.unwrap_or(false) }
+}
+
+/// Builds a search document from the page frontmatter and the indexer the
+/// page's AST was fed to, or `None` if it failed to parse.
+fn search_document(
+    id: String,
+    url: String,
+    frontmatter: &serde_json::Map<String, serde_json::Value>,
+    indexer: Option<&DocumentIndexer>,
+    extract_options: &JsExtractSearchOptions,
+) -> JsSearchDocument {
+    // Try to get title from frontmatter
+    let frontmatter_title = frontmatter.get("title").and_then(|v| v.as_str()).map(String::from);
+    let tags = frontmatter.get("tags").map(frontmatter_strings).unwrap_or_default();
+    let facets: HashMap<String, Vec<String>> = extract_options
+        .facet_keys
+        .iter()
+        .flatten()
+        .filter_map(|key| {
+            let values = frontmatter_strings(frontmatter.get(key)?);
+            (!values.is_empty()).then(|| (key.clone(), values))
+        })
+        .collect();
+
+    let (title, body, headings, code) = if let Some(indexer) = indexer {
+        let title = frontmatter_title.or_else(|| indexer.title().map(String::from));
 
         (title, indexer.body().to_string(), indexer.headings().to_vec(), indexer.code().to_vec())
     } else {
-        (frontmatter_title.unwrap_or_default(), String::new(), Vec::new(), Vec::new())
+        (frontmatter_title, String::new(), Vec::new(), Vec::new())
     };
-    // Explicitly drop the result to release the borrow
-    drop(result);
+    let title = title.unwrap_or_else(|| {
+        if extract_options.title_from_url.unwrap_or(true) {
+            title_from_url(&url)
+        } else {
+            String::new()
+        }
+    });
+
+    JsSearchDocument {
+        id,
+        title,
+        url,
+        body,
+        headings,
+        code,
+        boost: None,
+        tags: (!tags.is_empty()).then_some(tags),
+        facets: (!facets.is_empty()).then_some(facets),
+    }
+}
 
-    JsSearchDocument { id, title, url, body, headings, code }
+/// Returns whether the frontmatter has a non-empty `title` string.
+fn has_frontmatter_title(frontmatter: &serde_json::Map<String, serde_json::Value>) -> bool {
+    frontmatter
+        .get("title")
+        .and_then(serde_json::Value::as_str)
+        .is_some_and(|title| !title.trim().is_empty())
+}
+
+/// Reads a frontmatter value as a list of strings: each string, number or
+/// boolean item of a list, or the comma-separated parts of a single value.
+fn frontmatter_strings(value: &serde_json::Value) -> Vec<String> {
+    let scalar = |value: &serde_json::Value| match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    };
+    let values = match value {
+        serde_json::Value::Array(items) => items.iter().filter_map(scalar).collect(),
+        _ => scalar(value)
+            .map(|s| s.split(',').map(String::from).collect::<Vec<_>>())
+            .unwrap_or_default(),
+    };
+    values.into_iter().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+}
+
+// =============================================================================
+// Page Build API
+// =============================================================================
+
+/// Options for [`build_page`].
+#[napi(object)]
+pub struct JsBuildPageOptions {
+    /// URL path of the page, used as the SSG page path and the search
+    /// document ID.
+    pub path: String,
+    /// Search document URL (default: `path`).
+    pub url: Option<String>,
+    /// Transform options, as for `transform`.
+    pub transform: Option<JsTransformOptions>,
+    /// Transform defaults, as for `transform`.
+    pub defaults: Option<JsTransformOptions>,
+    /// Search content options, as for `extractSearchContent`. The title
+    /// heading is dropped by the transform `dropTitleHeading` option
+    /// instead, so the search document matches the page.
+    pub search: Option<JsExtractSearchOptions>,
+}
+
+/// Result of [`build_page`].
+#[napi(object)]
+pub struct JsBuiltPage {
+    /// The full SSG HTML page.
+    pub html: String,
+    /// The page's search document.
+    pub search_document: JsSearchDocument,
+    /// Parsed frontmatter as JSON string.
+    pub frontmatter: String,
+    /// Table of contents entries.
+    pub toc: Vec<TocEntry>,
+    /// Parse/render errors, if any.
+    pub errors: Vec<String>,
+}
+
+/// Builds an SSG page from Markdown source with a single parse: the same
+/// as calling `transform`, `extractSearchContent` and `generateSsgHtml`.
+///
+/// The page title is the frontmatter `title`, then the search document
+/// title; the description and language come from the frontmatter
+/// `description` and `lang`. The search document is extracted from the AST
+/// the page is rendered from, so it reflects `dropTitleHeading` and the
+/// parser options in `transform`.
+#[napi]
+pub fn build_page(
+    source: String,
+    nav_groups: Vec<JsSsgNavGroup>,
+    config: JsSsgConfig,
+    options: JsBuildPageOptions,
+) -> JsBuiltPage {
+    let search_options = options.search.unwrap_or_default();
+    let mut indexer = DocumentIndexer::with_options(indexer_options(&search_options));
+    let result = run_transform(
+        &source,
+        options.transform.unwrap_or_default(),
+        options.defaults.unwrap_or_default(),
+        false,
+        None,
+        Some(&mut indexer),
+    );
+
+    let frontmatter: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(&result.frontmatter).unwrap_or_default();
+    let url = options.url.unwrap_or_else(|| options.path.clone());
+    let search_document =
+        search_document(options.path.clone(), url, &frontmatter, Some(&indexer), &search_options);
+
+    let frontmatter_str =
+        |key: &str| frontmatter.get(key).and_then(serde_json::Value::as_str).map(String::from);
+    let page_data = JsSsgPageData {
+        title: frontmatter_str("title").unwrap_or_else(|| search_document.title.clone()),
+        description: frontmatter_str("description"),
+        content: result.html,
+        toc: result.toc.clone(),
+        toc_enabled: Some(result.toc_enabled),
+        path: options.path,
+        lang: frontmatter_str("lang"),
+    };
+
+    JsBuiltPage {
+        html: generate_ssg_html(page_data, nav_groups, config),
+        search_document,
+        frontmatter: result.frontmatter,
+        toc: result.toc,
+        errors: result.errors,
+    }
+}
+
+// =============================================================================
+// Lint API
+// =============================================================================
+
+/// Lint rule toggles for JavaScript. Every rule is enabled by default.
+#[napi(object)]
+#[derive(Default)]
+pub struct JsLintOptions {
+    /// Parser options used to build the AST.
+    pub parser: Option<JsParserOptions>,
+    /// Report headings that produce the same slug.
+    pub duplicate_heading_slug: Option<bool>,
+    /// Slug style for `duplicateHeadingSlug`, as `slugStyle` in `transform`
+    /// (default: `"github"`).
+    pub slug_style: Option<String>,
+    /// Report relative links to files outside `context.files`.
+    pub broken_relative_link: Option<bool>,
+    /// Report images without alt text.
+    pub image_missing_alt: Option<bool>,
+    /// Report heading level skips (e.g. h2 followed by h4).
+    pub heading_level_skip: Option<bool>,
+    /// Report unclosed fenced code blocks and frontmatter.
+    pub unclosed_container: Option<bool>,
+    /// Report list items whose indentation is ambiguous relative to the
+    /// parent item's marker width, or mixes tabs and spaces.
+    pub ambiguous_list_indent: Option<bool>,
+}
+
+/// Where the linted source lives, for resolving relative links.
+#[napi(object)]
+#[derive(Default)]
+pub struct JsLintContext {
+    /// Path of the linted file relative to the content root (e.g. `guide/intro.md`).
+    pub file: Option<String>,
+    /// Known files relative to the content root. Relative links are only checked when set.
+    pub files: Option<Vec<String>>,
+}
+
+/// A lint issue.
+#[napi(object)]
+pub struct JsLintIssue {
+    /// Rule name (e.g. `broken-relative-link`).
+    pub rule: String,
+    /// Human-readable description.
+    pub message: String,
+    /// 1-indexed line number.
+    pub line: u32,
+    /// `"warning"` or `"error"`.
+    pub severity: String,
+}
+
+/// Lints Markdown source without rendering it.
+///
+/// Reports duplicate heading slugs, broken relative links, images without
+/// alt text, heading level skips, unclosed containers and ambiguous list
+/// indentation, in source order.
+#[napi]
+pub fn lint(
+    source: String,
+    options: Option<JsLintOptions>,
+    context: Option<JsLintContext>,
+) -> Vec<JsLintIssue> {
+    let opts = options.unwrap_or_default();
+    let defaults = LintOptions::default();
+    let lint_options = LintOptions {
+        parser: opts.parser.map(ParserOptions::from).unwrap_or_default(),
+        duplicate_heading_slug: opts
+            .duplicate_heading_slug
+            .unwrap_or(defaults.duplicate_heading_slug),
+        slug_style: opts
+            .slug_style
+            .as_deref()
+            .and_then(SlugStyle::from_name)
+            .unwrap_or(defaults.slug_style),
+        broken_relative_link: opts.broken_relative_link.unwrap_or(defaults.broken_relative_link),
+        image_missing_alt: opts.image_missing_alt.unwrap_or(defaults.image_missing_alt),
+        heading_level_skip: opts.heading_level_skip.unwrap_or(defaults.heading_level_skip),
+        unclosed_container: opts.unclosed_container.unwrap_or(defaults.unclosed_container),
+        ambiguous_list_indent: opts.ambiguous_list_indent.unwrap_or(defaults.ambiguous_list_indent),
+    };
+    let context = context.unwrap_or_default();
+    let lint_context = LintContext {
+        file: context.file.unwrap_or_default(),
+        files: context.files.map(|files| files.into_iter().collect()),
+    };
+
+    ox_content_parser::lint(&source, &lint_options, &lint_context)
+        .into_iter()
+        .map(|issue| JsLintIssue {
+            rule: issue.rule.as_str().to_string(),
+            message: issue.message,
+            line: issue.line,
+            severity: issue.severity.as_str().to_string(),
+        })
+        .collect()
+}
+
+/// A link whose target is not a known page.
+#[napi(object)]
+pub struct JsBrokenLink {
+    /// The link destination as written.
+    pub href: String,
+    /// 1-indexed line number.
+    pub line: u32,
+}
+
+/// Finds the links in Markdown source that point to a path outside
+/// `known_paths`, without network calls.
+///
+/// Relative links are resolved against `base_url`, the URL path of the page
+/// (`/guide/intro` or `/guide/`). Paths match with or without a trailing
+/// `/`, `index`, `.html` or `.md`; query strings and fragments are ignored.
+/// External URLs and same-page fragments are skipped. The source is parsed
+/// as GFM.
+#[napi]
+pub fn validate_links(
+    source: String,
+    known_paths: Vec<String>,
+    base_url: String,
+) -> Result<Vec<JsBrokenLink>> {
+    let broken =
+        ox_content_parser::validate_links(&source, &ParserOptions::gfm(), &known_paths, &base_url)
+            .map_err(|e| Error::from_reason(e.to_string()))?;
+    Ok(broken.into_iter().map(|link| JsBrokenLink { href: link.href, line: link.line }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parser_options_precedence() {
+        let options = ParserOptions::from(JsParserOptions {
+            gfm: Some(true),
+            autolinks: Some(false),
+            ..JsParserOptions::default()
+        });
+        assert!(options.gfm && options.tables && options.footnotes);
+        assert!(!options.autolinks);
+
+        let options = ParserOptions::from(JsParserOptions {
+            preset: Some(JsParserPreset::GfmStrict),
+            ..JsParserOptions::default()
+        });
+        assert!(options.strikethrough && !options.footnotes);
+
+        // `preset` wins over the `gfm` shorthand; fields still override it.
+        let options = ParserOptions::from(JsParserOptions {
+            preset: Some(JsParserPreset::CommonMark),
+            gfm: Some(true),
+            tables: Some(true),
+            ..JsParserOptions::default()
+        });
+        assert!(!options.gfm && !options.strikethrough && options.tables);
+        let options = ParserOptions::from(JsParserOptions {
+            preset: Some(JsParserPreset::Gfm),
+            gfm: Some(false),
+            ..JsParserOptions::default()
+        });
+        assert!(options.gfm && options.strikethrough);
+    }
+
+    #[test]
+    fn test_transform_options_precedence() {
+        let source = "---\ntocMaxDepth: 2\ngfm: true\n---\n# A\n## B\n### C\n\n~~gone~~";
+        let defaults = JsTransformOptions {
+            toc_max_depth: Some(3),
+            gfm: Some(false),
+            ..JsTransformOptions::default()
+        };
+
+        // Frontmatter overrides the defaults.
+        let result = transform_source(source, JsTransformOptions::default(), defaults.clone());
+        assert_eq!(result.toc.len(), 1);
+        assert!(result.html.contains("<del>"));
+
+        // Explicit options override the frontmatter.
+        let options =
+            JsTransformOptions { toc_max_depth: Some(3), ..JsTransformOptions::default() };
+        assert_eq!(transform_source(source, options, defaults.clone()).toc.len(), 2);
+
+        let options = JsTransformOptions {
+            frontmatter_options: Some(false),
+            ..JsTransformOptions::default()
+        };
+        let result = transform_source(source, options, defaults);
+        assert_eq!(result.toc.len(), 2);
+        assert!(!result.html.contains("<del>"));
+
+        // Allowlisted renderer switches are read from `renderOptions`.
+        let source = "---\nrenderOptions:\n  convertMdLinks: false\n  headingIds: false\n  \
+                      baseUrl: /evil/\n---\n# A\n\n[guide](./guide.md)";
+        let defaults = JsTransformOptions {
+            convert_md_links: Some(true),
+            base_url: Some("/docs/".to_string()),
+            ..JsTransformOptions::default()
+        };
+        let result = transform_source(source, JsTransformOptions::default(), defaults.clone());
+        assert!(result.html.starts_with("<h1>A</h1>"));
+        assert!(result.html.contains("href=\"./guide.md\""));
+        let options =
+            JsTransformOptions { convert_md_links: Some(true), ..JsTransformOptions::default() };
+        let result = transform_source(source, options, defaults);
+        assert!(result.html.contains("href=\"../guide/index.html\""));
+
+        let result = transform_source(
+            "---\ntoc: false\n---\n# A",
+            JsTransformOptions::default(),
+            JsTransformOptions::default(),
+        );
+        assert!(result.toc.is_empty());
+    }
+
+    #[test]
+    fn test_transform_full_returns_final_ast() {
+        let source = "---\ntitle: Doc\n---\n## Intro\n\nText.";
+        let options = JsTransformOptions {
+            normalize_headings: Some("promote_first".to_string()),
+            ..JsTransformOptions::default()
+        };
+        let full =
+            run_transform(source, options.clone(), JsTransformOptions::default(), true, None, None);
+        let ast: serde_json::Value = serde_json::from_str(&full.ast).unwrap();
+        assert_eq!(ast["type"], "root");
+        assert_eq!(ast["children"][0]["type"], "heading");
+        assert_eq!(ast["children"][0]["depth"], 1);
+        assert!(full.html.starts_with("<h1"));
+        assert_eq!(full.frontmatter, r#"{"title":"Doc"}"#);
+
+        let result = transform_source(source, options, JsTransformOptions::default());
+        assert_eq!(result.html, full.html);
+    }
+
+    #[test]
+    fn test_toc_slugs_match_heading_ids() {
+        let source = "## Overview
+### Deep
+## Overview
+### Deep
+## Overview";
+        let options =
+            JsTransformOptions { toc_max_depth: Some(2), ..JsTransformOptions::default() };
+        let result = transform_source(source, options, JsTransformOptions::default());
+
+        let slugs: Vec<_> = result.toc.iter().map(|entry| entry.slug.as_str()).collect();
+        assert_eq!(slugs, ["overview", "overview-1", "overview-2"]);
+        for id in ["overview", "deep", "overview-1", "deep-1", "overview-2"] {
+            assert!(result.html.contains(&format!(" id=\"{id}\">")), "{id}");
+        }
+    }
+
+    #[test]
+    fn test_heading_id_attribute() {
+        let source = "## Getting started {#install}\n\n## Install\n";
+        let options =
+            JsTransformOptions { attributes: Some(true), ..JsTransformOptions::default() };
+        let result = transform_source(source, options, JsTransformOptions::default());
+
+        let slugs: Vec<_> = result.toc.iter().map(|entry| entry.slug.as_str()).collect();
+        assert_eq!(slugs, ["install", "install-1"]);
+        assert_eq!(result.toc[0].text, "Getting started");
+        assert!(result.html.contains("<h2 id=\"install\">Getting started</h2>"));
+
+        let result =
+            transform_source(source, JsTransformOptions::default(), JsTransformOptions::default());
+        assert_eq!(result.toc[0].slug, "getting-started-install");
+    }
+
+    #[test]
+    fn test_toc_exclude() {
+        let source = "## Usage\n\n## Internals {.no-toc}\n\n### Cache\n\n## Changelog 2.x\n\n\
+                      ### Fixes\n\n## Usage";
+        let options = JsTransformOptions {
+            attributes: Some(true),
+            toc_exclude: Some(vec!["changelog*".to_string()]),
+            ..JsTransformOptions::default()
+        };
+        let result = transform_source(source, options, JsTransformOptions::default());
+        let slugs: Vec<_> = result.toc.iter().map(|entry| entry.slug.as_str()).collect();
+        assert_eq!(slugs, ["usage", "usage-1"]);
+        assert!(result.html.contains("<h2 id=\"internals\" class=\"no-toc\">Internals</h2>"));
+        assert!(result.html.contains("<h3 id=\"cache\">Cache</h3>"));
+
+        // Other classes replace `no-toc`
+        let options = JsTransformOptions {
+            attributes: Some(true),
+            toc_exclude_classes: Some(vec!["hidden".to_string()]),
+            ..JsTransformOptions::default()
+        };
+        let result = transform_source(source, options, JsTransformOptions::default());
+        assert_eq!(result.toc.len(), 6);
+
+        assert!(wildcard_match("a*b*c", "aXbYbc"));
+        assert!(wildcard_match("*", ""));
+        assert!(!wildcard_match("a*a", "a"));
+        assert!(!wildcard_match("changelog", "changelog 2.x"));
+    }
+
+    #[test]
+    fn test_toc_min_depth() {
+        let source = "# Guide\n\n## Install\n\n### npm\n\n### pnpm\n\n## Usage\n\n#### Flags\n";
+        let depths = |options: JsTransformOptions| {
+            let result = transform_source(source, options, JsTransformOptions::default());
+            let depths: Vec<u8> = result.toc.iter().map(|entry| entry.depth).collect();
+            (depths, result.errors)
+        };
+
+        // The page title is left out by default.
+        assert_eq!(depths(JsTransformOptions::default()), (vec![2, 3, 3, 2], vec![]));
+        let options =
+            JsTransformOptions { toc_min_depth: Some(1), ..JsTransformOptions::default() };
+        assert_eq!(depths(options), (vec![1, 2, 3, 3, 2], vec![]));
+
+        // Both ends are inclusive.
+        let options = JsTransformOptions {
+            toc_min_depth: Some(3),
+            toc_max_depth: Some(4),
+            ..JsTransformOptions::default()
+        };
+        assert_eq!(depths(options), (vec![3, 3, 4], vec![]));
+
+        let options = JsTransformOptions {
+            toc_min_depth: Some(3),
+            toc_max_depth: Some(2),
+            ..JsTransformOptions::default()
+        };
+        let (toc, errors) = depths(options);
+        assert!(toc.is_empty());
+        assert_eq!(errors, ["`tocMinDepth` (3) is greater than `tocMaxDepth` (2)"]);
+
+        let source = format!("---\ntocMinDepth: 1\n---\n{source}");
+        let result =
+            transform_source(&source, JsTransformOptions::default(), JsTransformOptions::default());
+        assert_eq!(result.toc[0].text, "Guide");
+    }
+
+    #[test]
+    fn test_toc_marker() {
+        let source = "[[toc]]\n\n# A & B\n\n## One\n\n### Deep\n\n#### Too deep\n\n## Two\n";
+        let options = JsTransformOptions {
+            toc: Some(false),
+            toc_min_depth: Some(1),
+            ..JsTransformOptions::default()
+        };
+        let result = transform_source(source, options, JsTransformOptions::default());
+        assert!(result.toc.is_empty());
+        assert!(result.html.starts_with(
+            "<nav class=\"table-of-contents\">\n<ul>\n<li><a href=\"#a--b\">A &amp; B</a>\n\
+             <ul>\n<li><a href=\"#one\">One</a>\n<ul>\n<li><a href=\"#deep\">Deep</a></li>\n</ul>\n\
+             </li>\n<li><a href=\"#two\">Two</a></li>\n</ul>\n</li>\n</ul>\n</nav>\n<h1"
+        ));
+
+        let options =
+            JsTransformOptions { toc_markers: Some(vec![]), ..JsTransformOptions::default() };
+        let result = transform_source(source, options, JsTransformOptions::default());
+        assert!(result.html.starts_with("<p>[[toc]]</p>"));
+    }
+
+    #[test]
+    fn test_render_section() {
+        let source =
+            "---\ntitle: Guide\n---\n# Guide\n\n## Install\n\nRun it.\n\n## Usage\n\nCall it.";
+        let result = render_section(source.to_string(), "install".to_string(), None);
+        assert_eq!(result.html, "<h2 id=\"install\">Install</h2>\n<p>Run it.</p>\n");
+        assert!(result.errors.is_empty());
+
+        let result = render_section(source.to_string(), "setup".to_string(), None);
+        assert_eq!(result.html, "");
+        assert_eq!(result.errors, ["no section with heading ID `setup`"]);
+    }
+
+    #[test]
+    fn test_strict_mode() {
+        let source = "See [the docs][missing].";
+        let result =
+            transform_source(source, JsTransformOptions::default(), JsTransformOptions::default());
+        assert!(result.errors.is_empty());
+        assert_eq!(result.html, "<p>See [the docs][missing].</p>\n");
+
+        let defaults = JsTransformOptions { strict: Some(true), ..JsTransformOptions::default() };
+        let result = transform_source(source, JsTransformOptions::default(), defaults.clone());
+        assert_eq!(result.html, "");
+        assert_eq!(result.errors, ["undefined link reference [missing] at line 1, column 5"]);
+
+        let source = "---\ntitle: Hi\n---\n\nSee [the docs][missing].";
+        let result = transform_source(source, JsTransformOptions::default(), defaults);
+        assert_eq!(result.errors, ["undefined link reference [missing] at line 5, column 5"]);
+    }
+
+    #[test]
+    fn test_partial_render_on_error() {
+        let options = JsParserOptions { strict: Some(true), ..JsParserOptions::default() };
+        let result = parse_and_render("# Setup\n\n```sh\nnpm i".to_string(), Some(options));
+        assert_eq!(result.html, "<h1>Setup</h1>\n");
+        assert_eq!(result.errors, ["unclosed code fence at line 3, column 1"]);
+
+        let source = "---\ntitle: Hi\n---\n## Setup\n\n```sh\nnpm i";
+        let options = JsTransformOptions { strict: Some(true), ..JsTransformOptions::default() };
+        let result = transform_source(source, options, JsTransformOptions::default());
+        assert_eq!(result.html, "<h2 id=\"setup\">Setup</h2>\n");
+        assert_eq!(result.toc.len(), 1);
+        assert_eq!(result.frontmatter, r#"{"title":"Hi"}"#);
+        assert_eq!(result.errors, ["unclosed code fence at line 6, column 1"]);
+
+        let options = JsParserOptions { max_input_bytes: Some(4), ..JsParserOptions::default() };
+        let result = parse_and_render("# Setup".to_string(), Some(options));
+        assert_eq!(result.html, "");
+        assert_eq!(result.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_gfm_tagfilter_follows_preset() {
+        let source = "<div>\n<script>alert(1)</script>\n</div>";
+        let html = |options| transform_source(source, options, JsTransformOptions::default()).html;
+        let filtered = "<div>\n&lt;script>alert(1)&lt;/script>\n</div>\n";
+
+        assert_eq!(
+            html(JsTransformOptions { gfm: Some(true), ..JsTransformOptions::default() }),
+            filtered
+        );
+        let strict = JsTransformOptions {
+            preset: Some(JsParserPreset::GfmStrict),
+            ..JsTransformOptions::default()
+        };
+        assert_eq!(html(strict), filtered);
+        assert!(html(JsTransformOptions::default()).contains("<script>"));
+        let off = JsTransformOptions {
+            gfm: Some(true),
+            gfm_tagfilter: Some(false),
+            ..JsTransformOptions::default()
+        };
+        assert!(html(off).contains("<script>"));
+    }
+
+    #[test]
+    fn test_anchors_match_heading_ids() {
+        let options = JsTransformOptions {
+            toc_max_depth: Some(2),
+            number_headings: Some(true),
+            ..JsTransformOptions::default()
+        };
+        let source = "## Setup\n#### Deep\n## Setup";
+        let result = transform_source(source, options, JsTransformOptions::default());
+        let anchors: Vec<_> =
+            result.anchors.iter().map(|a| (a.depth, a.text.as_str(), a.slug.as_str())).collect();
+        assert_eq!(anchors, [(2, "Setup", "setup"), (4, "Deep", "deep"), (2, "Setup", "setup-1")]);
+        for anchor in &result.anchors {
+            assert!(result.html.contains(&format!("id=\"{}\"", anchor.slug)));
+        }
+        assert_eq!(result.toc.len(), 2);
+    }
+
+    #[test]
+    fn test_source_line_attrs_count_frontmatter() {
+        let options =
+            JsTransformOptions { source_line_attrs: Some(true), ..JsTransformOptions::default() };
+        let source = "---\ntitle: Hi\n---\n\n# Hi\n\nText";
+        let result = transform_source(source, options, JsTransformOptions::default());
+        assert_eq!(
+            result.html,
+            "<h1 data-line=\"5\" id=\"hi\">Hi</h1>\n<p data-line=\"7\">Text</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_frontmatter_raw() {
+        let source = "---\ntitle: Hi # draft\n---\n\n# Hi";
+        let result =
+            transform_source(source, JsTransformOptions::default(), JsTransformOptions::default());
+        assert_eq!(result.frontmatter_raw, "title: Hi # draft\n");
+        let result =
+            transform_source("# Hi", JsTransformOptions::default(), JsTransformOptions::default());
+        assert_eq!(result.frontmatter_raw, "");
+    }
+
+    #[test]
+    fn test_section_stats() {
+        let source =
+            "---\ntitle: Hi\n---\n# Hi\n\n## Getting Started\n\nTwo words.\n\n## 中文\n\n中文句子";
+        let stats = section_stats(source.to_string(), None);
+        let counts: Vec<_> =
+            stats.iter().map(|s| (s.heading.as_str(), s.slug.as_str(), s.words, s.chars)).collect();
+        assert_eq!(counts, [("Getting Started", "getting-started", 2, 9), ("中文", "中文", 4, 4)]);
+    }
+
+    #[test]
+    fn test_format_markdown() {
+        let source = "---\ntitle: Hi # draft\n---\n* one\n+ two\n\n__bold__ # text\n".to_string();
+        let style = JsMarkdownStyle {
+            bullet: Some("+".to_string()),
+            emphasis: Some("~".to_string()),
+            ..JsMarkdownStyle::default()
+        };
+        let formatted = format_markdown(source, Some(style), None).unwrap();
+        assert_eq!(formatted, "---\ntitle: Hi # draft\n---\n\n+ one\n+ two\n\n**bold** # text\n");
+        assert_eq!(format_markdown(String::new(), None, None).unwrap(), "");
+    }
+
+    #[test]
+    fn test_cjk_friendly_breaks() {
+        let options =
+            JsTransformOptions { cjk_friendly_breaks: Some(true), ..JsTransformOptions::default() };
+        let result =
+            transform_source("中文\n句子 and\nEnglish", options, JsTransformOptions::default());
+        assert_eq!(result.html, "<p>中文句子 and\nEnglish</p>\n");
+    }
+
+    #[test]
+    fn test_render_custom_nodes() {
+        let ast = r#"{"type":"root","children":[
+            {"type":"paragraph","children":[{"type":"text","value":"Intro"}]},
+            {"type":"my-widget","id":"w1","count":3,"label":"<b>"},
+            {"type":"other-widget"}
+        ]}"#;
+        let options = JsTransformOptions {
+            custom_nodes: Some(HashMap::from([(
+                "my-widget".to_string(),
+                "<div class=\"widget\" data-id=\"{id}\">{label} × {count}</div>".to_string(),
+            )])),
+            ..JsTransformOptions::default()
+        };
+        let result = Renderer::new(Some(options)).render_ast(ast.to_string());
+        assert_eq!(result.errors, Vec::<String>::new());
+        assert_eq!(
+            result.html,
+            "<p>Intro</p>\n<div class=\"widget\" data-id=\"w1\">&lt;b&gt; × 3</div>\n<!-- unknown node: other-widget -->\n"
+        );
+    }
+
+    #[test]
+    fn test_render_fragment() {
+        let html = render_fragment("\n\n# Title\n\nText\n\n".to_string(), None).unwrap();
+        assert_eq!(html, "<h1>Title</h1>\n<p>Text</p>");
+        assert_eq!(render_fragment("   ".to_string(), None).unwrap(), "");
+        let options = JsParserOptions { max_input_bytes: Some(4), ..JsParserOptions::default() };
+        assert!(render_fragment("Too long".to_string(), Some(options)).is_err());
+    }
+
+    #[test]
+    fn test_custom_emoji() {
+        let options = JsTransformOptions {
+            custom_emoji: Some(HashMap::from([
+                (":party:".to_string(), "🎉".to_string()),
+                ("logo".to_string(), "https://cdn.example.com/logo.png".to_string()),
+            ])),
+            ..JsTransformOptions::default()
+        };
+        let result =
+            transform_source(":party: :logo: :other:", options, JsTransformOptions::default());
+        assert_eq!(
+            result.html,
+            "<p>🎉 <img class=\"emoji custom-emoji\" src=\"https://cdn.example.com/logo.png\" alt=\":logo:\"> :other:</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_normalize_whitespace() {
+        let options = JsTransformOptions {
+            normalize_whitespace: Some(true),
+            ..JsTransformOptions::default()
+        };
+        let source = "\n\n\nGenerated    text  \n   here\n\n\n\n\n\n  \n\nMore\n\n\n";
+        let result = transform_source(source, options, JsTransformOptions::default());
+        assert_eq!(result.html, "<p>Generated text\nhere</p>\n<p>More</p>\n");
+    }
+
+    #[test]
+    fn test_list_indent() {
+        let source = "1. Install\n\n  - with npm\n\n  Then run:\n\n      npx ox-content\n";
+        let options = JsTransformOptions { list_indent: Some(2), ..JsTransformOptions::default() };
+        let result = transform_source(source, options, JsTransformOptions::default());
+        assert!(result.html.ends_with(
+            "</ul>\n<p>Then run:</p>\n<pre><code>npx ox-content\n</code></pre>\n</li>\n</ol>\n"
+        ));
+
+        let result =
+            transform_source(source, JsTransformOptions::default(), JsTransformOptions::default());
+        assert!(result.html.starts_with("<ol>\n<li><p>Install</p>\n</li>\n</ol>\n<ul>"));
+    }
+
+    #[test]
+    fn test_xhtml() {
+        let source = "![logo](/logo.svg)\n\n---\n\n- [x] done\n";
+        let defaults = JsTransformOptions {
+            xhtml: Some(true),
+            task_lists: Some(true),
+            ..JsTransformOptions::default()
+        };
+        let result = transform_source(source, JsTransformOptions::default(), defaults);
+        assert!(result.html.contains("<img src=\"/logo.svg\" alt=\"logo\" />"));
+        assert!(result.html.contains("<hr />"));
+        assert!(result.html.contains("checked=\"checked\" disabled=\"disabled\" />"));
+    }
+
+    #[test]
+    fn test_code_languages() {
+        let source = "---\ntitle: Setup\n---\n```sh\nnpm i\n```\n\n```\nplain\n```\n\n\
+                      ```vue\n<a />\n```\n\n```bash\nls\n```";
+        assert_eq!(code_languages(source.to_string(), None), ["bash", "vue"]);
+        let options = JsCodeLanguagesOptions {
+            language_aliases: Some(HashMap::from([("VUE".to_string(), "html".to_string())])),
+        };
+        assert_eq!(code_languages(source.to_string(), Some(options)), ["bash", "html"]);
+    }
+
+    #[test]
+    fn test_link_preview() {
+        let source = "# Intro\n\n![diagram](/img/flow.svg)\n\nOx Content is a\n**fast** parser.";
+        let preview = link_preview(source.to_string(), None);
+        assert_eq!(preview.title.as_deref(), Some("Intro"));
+        assert_eq!(preview.description.as_deref(), Some("Ox Content is a fast parser."));
+        assert_eq!(preview.first_image.as_deref(), Some("/img/flow.svg"));
+        assert_eq!(preview.reading_minutes, 1);
+
+        let source = format!(
+            "---\ntitle: Guide\ndescription: \"\"\nimage: /og.png\n---\n{}",
+            "word ".repeat(250)
+        );
+        let options = JsLinkPreviewOptions { words_per_minute: Some(100) };
+        let preview = link_preview(source, Some(options));
+        assert_eq!(preview.title.as_deref(), Some("Guide"));
+        assert_eq!(preview.description, Some("word ".repeat(250).trim_end().to_string()));
+        assert_eq!(preview.first_image.as_deref(), Some("/og.png"));
+        assert_eq!(preview.reading_minutes, 3);
+    }
+
+    #[test]
+    fn test_task_progress() {
+        let source = "---\ntitle: Plan\n---\n\n- [x] Design\n- [ ] Build\n  - [x] Parser\n  - [ ] Renderer\n- Notes";
+        let progress = task_progress(source.to_string());
+        assert_eq!((progress.total, progress.completed), (4, 2));
+    }
+
+    #[test]
+    fn test_diff_ast() {
+        let ast = |source: &str| parse(source.to_string(), None).ast;
+        let old = ast("# Title\n\nFirst.\n\nSecond.\n\nThird.");
+        let new = ast("# Title\n\nFirst, edited.\n\nSecond.\n\nThird.\n\nFourth.");
+        let diff = diff_ast(old, new.clone()).unwrap();
+        let changes: Vec<_> =
+            diff.changes.iter().map(|c| (c.old_start, c.old_end, c.new_start, c.new_end)).collect();
+        assert_eq!(changes, [(1, 2, 1, 2), (4, 4, 4, 5)]);
+        assert_eq!(diff.hashes.len(), 5);
+        // Shifted but unchanged blocks keep their hashes
+        let unchanged = diff_ast(new.clone(), new).unwrap();
+        assert!(unchanged.changes.is_empty());
+        assert!(diff_ast("{}".to_string(), "[]".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_restricted_features() {
+        let options = JsTransformOptions {
+            features: Some(JsFeaturePreset::Basic),
+            ..JsTransformOptions::default()
+        };
+        let source = "# Hi\n\n<script>alert(1)</script>\n\n![x](x.png) [ok](/ok)";
+        let result = transform_source(source, options, JsTransformOptions::default());
+        assert_eq!(
+            result.html,
+            "<p># Hi</p>\n<p>&lt;script&gt;alert(1)&lt;/script&gt;</p>\n<p>![x](x.png) <a href=\"/ok\">ok</a></p>\n"
+        );
+        assert!(result.toc.is_empty());
+
+        // Names outside these are rejected by napi when the options are
+        // read, so a typo such as "basik" can't leave every feature on.
+        let presets = [
+            (JsFeaturePreset::Full, "full"),
+            (JsFeaturePreset::Basic, "basic"),
+            (JsFeaturePreset::InlineOnly, "inline_only"),
+        ];
+        for (preset, name) in presets {
+            assert_eq!(FeaturePreset::from(preset).as_str(), name);
+        }
+    }
+
+    #[test]
+    fn test_slugify_matches_toc() {
+        let source = "## Getting Started!\n## Café Options";
+        for style in ["github", "unicode", "ascii"] {
+            let options = JsTransformOptions {
+                slug_style: Some(style.to_string()),
+                ..JsTransformOptions::default()
+            };
+            let result = transform_source(source, options, JsTransformOptions::default());
+            let slugs: Vec<_> = ["Getting Started!", "Café Options"]
+                .into_iter()
+                .map(|text| slugify(text.to_string(), Some(style.to_string())))
+                .collect();
+            let toc: Vec<_> = result.toc.iter().map(|entry| entry.slug.clone()).collect();
+            assert_eq!(slugs, toc);
+        }
+        assert_eq!(slugify("Getting Started!".to_string(), None), "getting-started");
+        assert_eq!(slugify("Café Options".to_string(), None), "café-options");
+        assert_eq!(slugify("Café Options".to_string(), Some("ascii".to_string())), "cafe-options");
+    }
+
+    #[test]
+    fn test_render_meta_tags() {
+        let config = || JsSsgConfig {
+            site_name: "Docs".to_string(),
+            base: "/".to_string(),
+            ..JsSsgConfig::default()
+        };
+        let result = transform_source(
+            "---\ntitle: Plugins\ntags: [rust, plugins]\n---\n# Plugins",
+            JsTransformOptions::default(),
+            JsTransformOptions::default(),
+        );
+        let tags = render_meta_tags(result.frontmatter, config()).unwrap();
+        assert!(tags.starts_with("<title>Plugins - Docs</title>\n"));
+        assert!(tags.contains(r#"<meta name="keywords" content="rust, plugins">"#));
+        assert!(render_meta_tags("[1]".to_string(), config()).is_err());
+    }
+
+    #[test]
+    fn test_drop_title_heading() {
+        let source = "---\ntitle: Guide\n---\n# Guide\n\n## Install\n\nText";
+        let options =
+            JsTransformOptions { drop_title_heading: Some(true), ..JsTransformOptions::default() };
+        let result = transform_source(source, options, JsTransformOptions::default());
+        assert_eq!(result.html, "<h2 id=\"install\">Install</h2>\n<p>Text</p>\n");
+        assert_eq!(result.toc.iter().map(|e| e.slug.as_str()).collect::<Vec<_>>(), ["install"]);
+        assert_eq!(result.anchors.len(), 1);
+
+        // Without a frontmatter title the h1 is the title and is kept
+        let options =
+            JsTransformOptions { drop_title_heading: Some(true), ..JsTransformOptions::default() };
+        let result = transform_source("# Guide\n\nText", options, JsTransformOptions::default());
+        assert!(result.html.starts_with("<h1 id=\"guide\">"));
+
+        let extract_options =
+            JsExtractSearchOptions { drop_title_heading: Some(true), ..Default::default() };
+        let doc = extract_search_content(
+            source.to_string(),
+            "guide".to_string(),
+            "/guide".to_string(),
+            None,
+            Some(extract_options),
+        );
+        assert_eq!(doc.title, "Guide");
+        assert_eq!(doc.headings, ["Install"]);
+    }
+
+    #[test]
+    fn test_search_index_stats() {
+        let document = |id: &str, body: &str| JsSearchDocument {
+            id: id.to_string(),
+            title: id.to_string(),
+            url: format!("/{id}"),
+            body: body.to_string(),
+            headings: vec![],
+            code: vec![],
+            boost: None,
+            tags: None,
+            facets: None,
+        };
+        let index = build_search_index(vec![
+            document("install", "Run cargo install, then cargo build."),
+            document("usage", "Call cargo test."),
+        ]);
+        let stats = search_index_stats(index, Some(1)).unwrap();
+        assert_eq!((stats.documents, stats.top_terms.len()), (2, 1));
+        assert!(stats.terms > 1);
+        assert!((stats.avg_doc_length - 4.5).abs() < f64::EPSILON);
+        assert_eq!(stats.top_terms[0].term, "cargo");
+        assert_eq!(stats.top_terms[0].documents, 2);
+
+        assert!(search_index_stats("{".to_string(), None).is_err());
+    }
+
+    #[test]
+    fn test_search_tags_and_facets() {
+        let source =
+            "---\ntags: [Rust, search]\nversion: v2, v3\n---\n# Plugins\n\nWriting plugins.";
+        let extract_options = JsExtractSearchOptions {
+            facet_keys: Some(vec!["version".to_string()]),
+            ..Default::default()
+        };
+        let doc = extract_search_content(
+            source.to_string(),
+            "plugins".to_string(),
+            "/plugins".to_string(),
+            None,
+            Some(extract_options),
+        );
+        assert_eq!(doc.tags, Some(vec!["Rust".to_string(), "search".to_string()]));
+        assert_eq!(
+            doc.facets,
+            Some(HashMap::from([(
+                "version".to_string(),
+                vec!["v2".to_string(), "v3".to_string()]
+            )]))
+        );
+
+        let other = extract_search_content(
+            "# Other plugins".to_string(),
+            "other".to_string(),
+            "/other".to_string(),
+            None,
+            None,
+        );
+        let index = build_search_index(vec![doc, other]);
+        let search = |options: JsSearchOptions| {
+            search_index(index.clone(), "plugins".to_string(), Some(options))
+                .into_iter()
+                .map(|r| r.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(search(JsSearchOptions::default()).len(), 2);
+        let by_tag = JsSearchOptions { tags: Some(vec!["rust".to_string()]), ..Default::default() };
+        assert_eq!(search(by_tag), ["plugins"]);
+        let by_facet = JsSearchOptions {
+            facets: Some(HashMap::from([("version".to_string(), vec!["v1".to_string()])])),
+            ..Default::default()
+        };
+        assert!(search(by_facet).is_empty());
+    }
+
+    #[test]
+    fn test_build_page() {
+        let config = JsSsgConfig {
+            site_name: "Docs".to_string(),
+            base: "/".to_string(),
+            ..JsSsgConfig::default()
+        };
+        let source = "---\ndescription: How to\nlang: fr\ntags: [guide]\n---\n\
+                      # Guide\n\n## Install\n\nRun `npm i`.";
+        let options = JsBuildPageOptions {
+            path: "/guide".to_string(),
+            url: None,
+            transform: None,
+            defaults: None,
+            search: None,
+        };
+        let page = build_page(source.to_string(), vec![], config.clone(), options);
+
+        let transformed =
+            transform_source(source, JsTransformOptions::default(), JsTransformOptions::default());
+        let page_data = JsSsgPageData {
+            title: "Guide".to_string(),
+            description: Some("How to".to_string()),
+            content: transformed.html,
+            toc: transformed.toc,
+            toc_enabled: Some(true),
+            path: "/guide".to_string(),
+            lang: Some("fr".to_string()),
+        };
+        assert_eq!(page.html, generate_ssg_html(page_data, vec![], config));
+        assert_eq!(page.frontmatter, transformed.frontmatter);
+        assert_eq!(page.toc.len(), 1);
+        assert!(page.errors.is_empty());
+
+        let doc = extract_search_content(
+            source.to_string(),
+            "/guide".to_string(),
+            "/guide".to_string(),
+            None,
+            None,
+        );
+        let built = &page.search_document;
+        assert_eq!((&built.id, &built.url, &built.title), (&doc.id, &doc.url, &doc.title));
+        assert_eq!(
+            (&built.body, &built.headings, &built.code),
+            (&doc.body, &doc.headings, &doc.code)
+        );
+        assert_eq!(built.tags, Some(vec!["guide".to_string()]));
+    }
+
+    #[test]
+    fn test_validate_links() {
+        let source = "[Setup](setup) and [Gone](../gone.md)\n\n| a |\n|---|\n| [API](/api/) |\n";
+        let known = vec!["/guide/setup".to_string(), "/api/index.html".to_string()];
+        let broken = validate_links(source.to_string(), known, "/guide/intro".to_string()).unwrap();
+        assert_eq!(broken.len(), 1);
+        assert_eq!((broken[0].href.as_str(), broken[0].line), ("../gone.md", 1));
+    }
 }
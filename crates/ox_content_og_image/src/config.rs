@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::template::TemplateLayout;
+
 /// Configuration for OG image generation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OgImageConfig {
@@ -18,11 +20,32 @@ pub struct OgImageConfig {
     /// Description font size.
     pub description_font_size: u32,
     /// Font family name.
+    ///
+    /// Without `font_data` this names an installed font; with `font_data` it
+    /// is the family name given to the embedded font.
     pub font_family: Option<String>,
+    /// Font file (TTF, OTF, WOFF or WOFF2) used for all text.
+    ///
+    /// SVG output embeds it as a base64 `@font-face`, so the image renders
+    /// the same on every machine. When unset, the system UI font is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_data: Option<Vec<u8>>,
     /// Logo path.
     pub logo_path: Option<String>,
     /// Output format.
     pub format: ImageFormat,
+    /// Card layout used by [`OgImageGenerator::new`]. A template passed to
+    /// [`OgImageGenerator::with_template`] chooses its own layout.
+    ///
+    /// [`OgImageGenerator::new`]: crate::OgImageGenerator::new
+    /// [`OgImageGenerator::with_template`]: crate::OgImageGenerator::with_template
+    #[serde(default)]
+    pub layout: TemplateLayout,
+    /// Description shown on cards whose data has none, such as the site
+    /// tagline. Without one, layouts center the title in the space the
+    /// description would take.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_description: Option<String>,
 }
 
 impl Default for OgImageConfig {
@@ -35,8 +58,11 @@ impl Default for OgImageConfig {
             title_font_size: 64,
             description_font_size: 32,
             font_family: None,
+            font_data: None,
             logo_path: None,
             format: ImageFormat::Png,
+            layout: TemplateLayout::Default,
+            fallback_description: None,
         }
     }
 }
@@ -0,0 +1,114 @@
+//! Custom font embedding.
+
+use std::fmt::Write;
+
+/// Font family used when no font is configured.
+pub const DEFAULT_FONT_FAMILY: &str = "system-ui, sans-serif";
+
+/// Family name given to embedded font data when `font_family` is not set.
+pub const EMBEDDED_FONT_FAMILY: &str = "OgImageFont";
+
+/// Font file format, detected from the file signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontFormat {
+    /// TrueType (`.ttf`).
+    TrueType,
+    /// OpenType with CFF outlines (`.otf`).
+    OpenType,
+    /// WOFF (`.woff`).
+    Woff,
+    /// WOFF2 (`.woff2`).
+    Woff2,
+}
+
+impl FontFormat {
+    /// Detects the format from the first bytes of a font file.
+    #[must_use]
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        match data.get(..4)? {
+            [0, 1, 0, 0] | b"true" => Some(Self::TrueType),
+            b"OTTO" => Some(Self::OpenType),
+            b"wOFF" => Some(Self::Woff),
+            b"wOF2" => Some(Self::Woff2),
+            _ => None,
+        }
+    }
+
+    /// Returns the MIME type of the format.
+    #[must_use]
+    pub const fn mime_type(self) -> &'static str {
+        match self {
+            Self::TrueType => "font/ttf",
+            Self::OpenType => "font/otf",
+            Self::Woff => "font/woff",
+            Self::Woff2 => "font/woff2",
+        }
+    }
+
+    /// Returns the CSS `format()` hint of the format.
+    #[must_use]
+    pub const fn css_format(self) -> &'static str {
+        match self {
+            Self::TrueType => "truetype",
+            Self::OpenType => "opentype",
+            Self::Woff => "woff",
+            Self::Woff2 => "woff2",
+        }
+    }
+}
+
+/// Builds a CSS `@font-face` rule embedding `data` as a base64 data URL.
+pub fn font_face(family: &str, data: &[u8], format: FontFormat) -> String {
+    let mut css = String::with_capacity(data.len() * 4 / 3 + 128);
+    let _ = write!(
+        css,
+        "@font-face {{ font-family: \"{family}\"; src: url(data:{};base64,",
+        format.mime_type()
+    );
+    encode_base64(data, &mut css);
+    let _ = write!(css, ") format(\"{}\"); }}", format.css_format());
+    css
+}
+
+/// Appends the standard base64 encoding of `data` to `out`.
+fn encode_base64(data: &[u8], out: &mut String) {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    for chunk in data.chunks(3) {
+        let bytes =
+            [chunk[0], chunk.get(1).copied().unwrap_or(0), chunk.get(2).copied().unwrap_or(0)];
+        let n = u32::from(bytes[0]) << 16 | u32::from(bytes[1]) << 8 | u32::from(bytes[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_base64() {
+        for (input, expected) in
+            [("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"), ("foobar", "Zm9vYmFy")]
+        {
+            let mut out = String::new();
+            encode_base64(input.as_bytes(), &mut out);
+            assert_eq!(out, expected);
+        }
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(FontFormat::detect(&[0, 1, 0, 0, 0, 12]), Some(FontFormat::TrueType));
+        assert_eq!(FontFormat::detect(b"OTTO\0\0"), Some(FontFormat::OpenType));
+        assert_eq!(FontFormat::detect(b"wOF2\0\0"), Some(FontFormat::Woff2));
+        assert_eq!(FontFormat::detect(b"<svg"), None);
+        assert_eq!(FontFormat::detect(b"wO"), None);
+    }
+}
@@ -1,7 +1,11 @@
 //! OG image generator.
 
 use crate::config::OgImageConfig;
-use crate::template::{OgImageData, OgImageTemplate};
+use crate::font::{font_face, FontFormat, DEFAULT_FONT_FAMILY, EMBEDDED_FONT_FAMILY};
+use crate::measure::TextMeasurer;
+use crate::template::{OgImageData, OgImageTemplate, TemplateLayout};
+use std::fmt::Write;
+
 use thiserror::Error;
 
 /// Result type for OG image operations.
@@ -27,6 +31,13 @@ pub enum OgImageError {
     InvalidConfig(String),
 }
 
+/// Left and right margin of the title and description, in pixels.
+const TEXT_X: u32 = 80;
+
+/// Image height the layouts are designed for. Taller images center the
+/// top-anchored content in the extra height.
+const DESIGN_HEIGHT: u32 = 630;
+
 /// OG image generator.
 pub struct OgImageGenerator {
     config: OgImageConfig,
@@ -34,10 +45,12 @@ pub struct OgImageGenerator {
 }
 
 impl OgImageGenerator {
-    /// Creates a new generator with the given configuration.
+    /// Creates a new generator with the given configuration, using the
+    /// configured layout.
     #[must_use]
     pub fn new(config: OgImageConfig) -> Self {
-        Self { config, template: OgImageTemplate::default() }
+        let template = OgImageTemplate { layout: config.layout, ..OgImageTemplate::default() };
+        Self { config, template }
     }
 
     /// Creates a new generator with custom config and template.
@@ -62,12 +75,21 @@ impl OgImageGenerator {
     ///
     /// Returns the image as a byte vector.
     pub fn generate(&self, data: &OgImageData) -> OgImageResult<Vec<u8>> {
+        self.embedded_font()?;
         // TODO: Implement actual image generation using an image library
         // For now, return a placeholder
         let _ = data;
         Err(OgImageError::Encode("Image generation not yet implemented".to_string()))
     }
 
+    /// Generates one SVG image per configuration from the same data, such
+    /// as a 1200×630 Open Graph card, a 1200×600 Twitter card and a
+    /// 1080×1080 square. Each image is laid out for its own size.
+    #[must_use]
+    pub fn generate_svg_variants(data: &OgImageData, configs: Vec<OgImageConfig>) -> Vec<String> {
+        configs.into_iter().map(|config| Self::new(config).generate_svg(data)).collect()
+    }
+
     /// Generates an OG image and saves it to a file.
     pub fn generate_to_file(
         &self,
@@ -79,9 +101,35 @@ impl OgImageGenerator {
         Ok(())
     }
 
+    /// Returns the format of the configured font data, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OgImageError::FontLoad`] when the data is not a TTF, OTF,
+    /// WOFF or WOFF2 font.
+    pub fn embedded_font(&self) -> OgImageResult<Option<FontFormat>> {
+        self.config
+            .font_data
+            .as_deref()
+            .map(|data| {
+                FontFormat::detect(data).ok_or_else(|| {
+                    OgImageError::FontLoad("unrecognized font file format".to_string())
+                })
+            })
+            .transpose()
+    }
+
     /// Generates an SVG representation of the OG image.
     ///
     /// This can be useful for debugging or for rendering in web contexts.
+    /// The template's layout arranges the text; titles, descriptions and
+    /// other text are wrapped to the lines the layout allows, with the last
+    /// line cut short with `...` when the text does not fit.
+    /// An empty title falls back to the site name and a missing description
+    /// to the configured fallback; with neither, the title is centered in
+    /// the space the description would take.
+    /// Configured font data is embedded as a base64 `@font-face`; data in an
+    /// unrecognized format is ignored and the system UI font is used.
     #[must_use]
     pub fn generate_svg(&self, data: &OgImageData) -> String {
         let width = self.config.width;
@@ -89,30 +137,50 @@ impl OgImageGenerator {
         let bg = &self.config.background_color;
         let text_color = &self.config.text_color;
 
-        // Truncate and escape text
-        let title = truncate_text(&data.title, 50);
-        let title = escape_xml(&title);
-        let description = data
-            .description
-            .as_ref()
-            .map(|d| truncate_text(d, 120))
-            .map(|d| escape_xml(&d))
-            .unwrap_or_default();
-        let site_name =
-            data.site_name.as_ref().map_or_else(|| "Ox Content".to_string(), |s| escape_xml(s));
-
-        // Generate description lines (wrap at ~60 chars)
-        let desc_lines = wrap_text(&description, 60);
-        let desc_svg = desc_lines.iter().enumerate().fold(String::new(), |mut acc, (i, line)| {
-            use std::fmt::Write;
-            let dy = if i == 0 { "0" } else { "1.4em" };
-            let _ = write!(acc, r#"<tspan x="80" dy="{dy}">{line}</tspan>"#);
-            acc
-        });
+        let family = self.config.font_family.as_deref().map(sanitize_font_family);
+        let embedded = match (&self.config.font_data, self.embedded_font()) {
+            (Some(font_data), Ok(Some(format))) => {
+                let family = family.as_deref().unwrap_or(EMBEDDED_FONT_FAMILY);
+                Some((font_face(family, font_data, format), family.to_string()))
+            }
+            _ => None,
+        };
+        let (font_style, font) = match (&embedded, &family) {
+            (Some((css, name)), _) => (
+                format!("\n    <style><![CDATA[{css}]]></style>"),
+                format!("&quot;{name}&quot;, {DEFAULT_FONT_FAMILY}"),
+            ),
+            (None, Some(name)) => {
+                (String::new(), format!("&quot;{name}&quot;, {DEFAULT_FONT_FAMILY}"))
+            }
+            (None, None) => (String::new(), DEFAULT_FONT_FAMILY.to_string()),
+        };
+
+        let canvas = SvgCanvas {
+            width,
+            height,
+            font: &font,
+            color: text_color,
+            measurer: TextMeasurer::new(self.config.font_data.as_deref()),
+        };
+        let site_name = data.site_name.as_deref().unwrap_or("Ox Content");
+        let data = self.with_fallbacks(data, site_name);
+        let body = match self.template.layout {
+            TemplateLayout::Default => default_layout(&canvas, &data, site_name),
+            TemplateLayout::Centered => centered_layout(&canvas, &data, site_name),
+            TemplateLayout::Split => split_layout(&canvas, &data, site_name),
+            TemplateLayout::Minimal => minimal_layout(&canvas, &data, site_name),
+            TemplateLayout::Card => card_layout(&canvas, &data, site_name),
+        };
+        let background = if self.template.layout == TemplateLayout::Minimal {
+            escape_xml(bg)
+        } else {
+            "url(#bgGrad)".to_string()
+        };
 
         format!(
             r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">
-  <defs>
+  <defs>{font_style}
     <linearGradient id="bgGrad" x1="0%" y1="0%" x2="100%" y2="100%">
       <stop offset="0%" style="stop-color:{bg}"/>
       <stop offset="100%" style="stop-color:#2d2d4a"/>
@@ -124,74 +192,274 @@ impl OgImageGenerator {
   </defs>
 
   <!-- Background -->
-  <rect width="100%" height="100%" fill="url(#bgGrad)"/>
+  <rect width="100%" height="100%" fill="{background}"/>
+{body}</svg>"#
+        )
+    }
 
-  <!-- Accent bar -->
-  <rect x="0" y="0" width="8" height="100%" fill="url(#accentGrad)"/>
+    /// Returns `data` with an empty title replaced by the site name and a
+    /// missing or empty description by the configured fallback.
+    fn with_fallbacks(&self, data: &OgImageData, site_name: &str) -> OgImageData {
+        let mut data = data.clone();
+        if data.title.trim().is_empty() {
+            data.title = site_name.to_string();
+        }
+        if data.description.as_deref().is_none_or(|d| d.trim().is_empty()) {
+            data.description.clone_from(&self.config.fallback_description);
+        }
+        data
+    }
+}
 
-  <!-- Logo circle -->
-  <circle cx="120" cy="120" r="50" fill="url(#accentGrad)" opacity="0.9"/>
-  <text x="120" y="135" text-anchor="middle" fill="{text_color}" font-size="40" font-weight="bold" font-family="system-ui, sans-serif">Ox</text>
+/// Title font size in the default layout.
+const TITLE_SIZE: f32 = 56.0;
+/// Description font size in the default layout.
+const DESCRIPTION_SIZE: f32 = 28.0;
+
+/// Fill of accent-colored shapes.
+const ACCENT: &str = "url(#accentGrad)";
+
+/// Text measurement and shared styling for laying out an SVG card.
+struct SvgCanvas<'a> {
+    width: u32,
+    height: u32,
+    font: &'a str,
+    color: &'a str,
+    measurer: TextMeasurer<'a>,
+}
 
-  <!-- Site name -->
-  <text x="190" y="125" fill="{text_color}" font-size="24" font-family="system-ui, sans-serif" opacity="0.7">{site_name}</text>
+impl SvgCanvas<'_> {
+    /// Offset added to the positions of content laid out from the top, to
+    /// center it on images taller than [`DESIGN_HEIGHT`].
+    const fn top(&self) -> u32 {
+        self.height.saturating_sub(DESIGN_HEIGHT) / 2
+    }
 
-  <!-- Title -->
-  <text x="80" y="280" fill="{text_color}" font-size="56" font-weight="bold" font-family="system-ui, sans-serif">{title}</text>
+    /// Wraps `text` into at most `max_lines` lines of `max_width` pixels at
+    /// `size`, ending the last line with `...` when the text does not fit.
+    /// Single-line text is cut by character rather than at a word. Lines are
+    /// XML-escaped; empty text has no lines.
+    fn wrap(&self, text: &str, size: f32, max_width: u32, max_lines: usize) -> Vec<String> {
+        #[allow(clippy::cast_precision_loss)]
+        let max_width = max_width as f32;
+        if text.trim().is_empty() {
+            return Vec::new();
+        }
+        if max_lines == 1 {
+            return vec![escape_xml(&self.measurer.truncate(text, size, max_width))];
+        }
+        self.measurer
+            .wrap(text, size, max_width, max_lines)
+            .iter()
+            .map(|line| escape_xml(line))
+            .collect()
+    }
 
-  <!-- Description -->
-  <text x="80" y="380" fill="{text_color}" font-size="28" font-family="system-ui, sans-serif" opacity="0.8">{desc_svg}</text>
+    /// Writes `lines` as one `<text>` element with its first baseline at
+    /// (`x`, `y`). A single line is written directly; more become `<tspan>`s
+    /// `line_height` apart. `style` holds the remaining attributes, such as
+    /// `font-size`. Nothing is written for no lines.
+    fn text(
+        &self,
+        out: &mut String,
+        lines: &[String],
+        (x, y): (u32, u32),
+        line_height: &str,
+        style: &str,
+    ) {
+        let content = match lines {
+            [] => return,
+            [line] => line.clone(),
+            _ => lines.iter().enumerate().fold(String::new(), |mut acc, (i, line)| {
+                let dy = if i == 0 { "0" } else { line_height };
+                let _ = write!(acc, r#"<tspan x="{x}" dy="{dy}">{line}</tspan>"#);
+                acc
+            }),
+        };
+        let _ = writeln!(
+            out,
+            r#"  <text x="{x}" y="{y}" fill="{}" {style} font-family="{}">{content}</text>"#,
+            self.color, self.font
+        );
+    }
 
-  <!-- Bottom decoration -->
-  <rect x="80" y="540" width="200" height="4" rx="2" fill="url(#accentGrad)" opacity="0.6"/>
-</svg>"#
-        )
+    /// Writes the logo circle, filled with `fill`, and its `Ox` mark.
+    fn logo(&self, out: &mut String, (cx, cy): (u32, u32), radius: u32, fill: &str) {
+        let _ = writeln!(
+            out,
+            r#"  <circle cx="{cx}" cy="{cy}" r="{radius}" fill="{fill}" opacity="0.9"/>"#
+        );
+        let size = radius * 4 / 5;
+        let lines = ["Ox".to_string()];
+        let style = format!(r#"text-anchor="middle" font-size="{size}" font-weight="bold""#);
+        self.text(out, &lines, (cx, cy + size * 3 / 8), "", &style);
     }
 }
 
-/// Truncates text to a maximum length, adding ellipsis if needed.
-fn truncate_text(text: &str, max_len: usize) -> String {
-    if text.chars().count() <= max_len {
-        text.to_string()
-    } else {
-        let truncated: String = text.chars().take(max_len - 3).collect();
-        format!("{truncated}...")
-    }
+/// Left-aligned title and description under the logo and site name.
+fn default_layout(canvas: &SvgCanvas<'_>, data: &OgImageData, site_name: &str) -> String {
+    let text_width = canvas.width.saturating_sub(2 * TEXT_X);
+    let mut out = String::from(
+        "\n  <!-- Accent bar -->\n  <rect x=\"0\" y=\"0\" width=\"8\" height=\"100%\" fill=\"url(#accentGrad)\"/>\n\n",
+    );
+    let top = canvas.top();
+    canvas.logo(&mut out, (120, top + 120), 50, ACCENT);
+    let site = canvas.wrap(site_name, 24.0, canvas.width.saturating_sub(190 + TEXT_X), 1);
+    canvas.text(&mut out, &site, (190, top + 125), "", r#"font-size="24" opacity="0.7""#);
+
+    let title = canvas.wrap(&data.title, TITLE_SIZE, text_width, 1);
+    let description = data.description.as_deref().unwrap_or_default();
+    let description = canvas.wrap(description, DESCRIPTION_SIZE, text_width, 2);
+    let y = top + 280 + centering_shift(&description, 100);
+    let style = r#"font-size="56" font-weight="bold""#;
+    canvas.text(&mut out, &title, (TEXT_X, y), "", style);
+    let style = r#"font-size="28" opacity="0.8""#;
+    canvas.text(&mut out, &description, (TEXT_X, top + 380), "1.4em", style);
+
+    let _ = writeln!(
+        out,
+        r#"  <rect x="{TEXT_X}" y="{}" width="200" height="4" rx="2" fill="url(#accentGrad)" opacity="0.6"/>"#,
+        canvas.height.saturating_sub(90)
+    );
+    let author = data.author.as_deref().unwrap_or_default();
+    let author = canvas.wrap(author, 24.0, text_width.saturating_sub(240), 1);
+    let right = canvas.width.saturating_sub(TEXT_X);
+    let style = r#"text-anchor="end" font-size="24" opacity="0.7""#;
+    canvas.text(&mut out, &author, (right, canvas.height.saturating_sub(80)), "", style);
+    out
 }
 
-/// Wraps text into lines of approximately max_chars length.
-fn wrap_text(text: &str, max_chars: usize) -> Vec<String> {
-    let mut lines = Vec::new();
-    let mut current_line = String::new();
+/// Logo, site name, title, description and author stacked and centered.
+fn centered_layout(canvas: &SvgCanvas<'_>, data: &OgImageData, site_name: &str) -> String {
+    let center = canvas.width / 2;
+    let text_width = canvas.width.saturating_sub(2 * TEXT_X);
+    let top = canvas.top();
+    let mut out = String::from("\n");
+    canvas.logo(&mut out, (center, top + 110), 44, ACCENT);
+    let site = canvas.wrap(site_name, 24.0, text_width, 1);
+    canvas.text(
+        &mut out,
+        &site,
+        (center, top + 200),
+        "",
+        r#"text-anchor="middle" font-size="24" opacity="0.7""#,
+    );
+
+    let title = canvas.wrap(&data.title, TITLE_SIZE, text_width, 2);
+    let description = data.description.as_deref().unwrap_or_default();
+    let description = canvas.wrap(description, DESCRIPTION_SIZE, text_width, 2);
+    let y = top + 300 + centering_shift(&description, 80);
+    let style = r#"text-anchor="middle" font-size="56" font-weight="bold""#;
+    canvas.text(&mut out, &title, (center, y), "1.2em", style);
+    let y = top + 300 + line_offset(title.len(), 67) + 80;
+    let style = r#"text-anchor="middle" font-size="28" opacity="0.8""#;
+    canvas.text(&mut out, &description, (center, y), "1.4em", style);
+
+    let bottom = canvas.height.saturating_sub(90);
+    let _ = writeln!(
+        out,
+        r#"  <rect x="{}" y="{bottom}" width="200" height="4" rx="2" fill="url(#accentGrad)" opacity="0.6"/>"#,
+        center.saturating_sub(100)
+    );
+    let author = canvas.wrap(data.author.as_deref().unwrap_or_default(), 24.0, text_width, 1);
+    let style = r#"text-anchor="middle" font-size="24" opacity="0.7""#;
+    canvas.text(&mut out, &author, (center, canvas.height.saturating_sub(45)), "", style);
+    out
+}
 
-    for word in text.split_whitespace() {
-        if current_line.is_empty() {
-            current_line = word.to_string();
-        } else if current_line.len() + 1 + word.len() <= max_chars {
-            current_line.push(' ');
-            current_line.push_str(word);
-        } else {
-            lines.push(current_line);
-            current_line = word.to_string();
-        }
-    }
+/// Logo and site name on an accent panel at the left, text at the right.
+fn split_layout(canvas: &SvgCanvas<'_>, data: &OgImageData, site_name: &str) -> String {
+    let panel = canvas.width * 2 / 5;
+    let middle = canvas.height / 2;
+    let mut out =
+        format!("\n  <rect width=\"{panel}\" height=\"100%\" fill=\"url(#accentGrad)\"/>\n");
+    // The panel is accent-colored, so the logo takes the background
+    canvas.logo(&mut out, (panel / 2, middle.saturating_sub(40)), 80, "url(#bgGrad)");
+    let site = canvas.wrap(site_name, 28.0, panel.saturating_sub(2 * 48), 1);
+    let style = r#"text-anchor="middle" font-size="28" font-weight="bold""#;
+    canvas.text(&mut out, &site, (panel / 2, middle + 100), "", style);
+
+    let x = panel + 64;
+    let text_width = canvas.width.saturating_sub(x + 64);
+    let title = canvas.wrap(&data.title, 48.0, text_width, 3);
+    let description = data.description.as_deref().unwrap_or_default();
+    let description = canvas.wrap(description, 26.0, text_width, 3);
+    let y = canvas.top() + 200 + centering_shift(&description, 70);
+    let style = r#"font-size="48" font-weight="bold""#;
+    canvas.text(&mut out, &title, (x, y), "1.2em", style);
+    let y = canvas.top() + 200 + line_offset(title.len(), 58) + 70;
+    canvas.text(&mut out, &description, (x, y), "1.4em", r#"font-size="26" opacity="0.8""#);
+
+    let author = canvas.wrap(data.author.as_deref().unwrap_or_default(), 22.0, text_width, 1);
+    let y = canvas.height.saturating_sub(70);
+    canvas.text(&mut out, &author, (x, y), "", r#"font-size="22" opacity="0.7""#);
+    out
+}
 
-    if !current_line.is_empty() {
-        lines.push(current_line);
-    }
+/// A large, vertically centered title with the site name as a footer.
+fn minimal_layout(canvas: &SvgCanvas<'_>, data: &OgImageData, site_name: &str) -> String {
+    let text_width = canvas.width.saturating_sub(2 * TEXT_X);
+    let mut out = String::from("\n");
+    let title = canvas.wrap(&data.title, 64.0, text_width, 3);
+    let y = (canvas.height + 44) / 2 - line_offset(title.len(), 77) / 2;
+    canvas.text(&mut out, &title, (TEXT_X, y), "1.2em", r#"font-size="64" font-weight="bold""#);
+
+    let site = canvas.wrap(site_name, 22.0, text_width, 1);
+    let y = canvas.height.saturating_sub(60);
+    canvas.text(&mut out, &site, (TEXT_X, y), "", r#"font-size="22" opacity="0.6""#);
+    out
+}
 
-    // Limit to 2 lines
-    if lines.len() > 2 {
-        lines.truncate(2);
-        if let Some(last) = lines.last_mut() {
-            if last.len() > 3 {
-                last.truncate(last.len() - 3);
-                last.push_str("...");
-            }
-        }
+/// Text on an outlined panel, with the author and tags in the footer.
+fn card_layout(canvas: &SvgCanvas<'_>, data: &OgImageData, site_name: &str) -> String {
+    const INSET: u32 = 48;
+    const X: u32 = 104;
+    let text_width = canvas.width.saturating_sub(2 * X);
+    let mut out = format!(
+        "\n  <rect x=\"{INSET}\" y=\"{INSET}\" width=\"{}\" height=\"{}\" rx=\"24\" fill=\"{}\" fill-opacity=\"0.06\" stroke=\"url(#accentGrad)\" stroke-width=\"2\"/>\n",
+        canvas.width.saturating_sub(2 * INSET),
+        canvas.height.saturating_sub(2 * INSET),
+        canvas.color
+    );
+    let top = canvas.top();
+    canvas.logo(&mut out, (X + 32, top + 136), 32, ACCENT);
+    let site = canvas.wrap(site_name, 24.0, text_width.saturating_sub(80), 1);
+    canvas.text(&mut out, &site, (X + 80, top + 145), "", r#"font-size="24" opacity="0.7""#);
+
+    let title = canvas.wrap(&data.title, 52.0, text_width, 2);
+    let description = data.description.as_deref().unwrap_or_default();
+    let description = canvas.wrap(description, 26.0, text_width, 2);
+    let y = top + 280 + centering_shift(&description, 70);
+    let style = r#"font-size="52" font-weight="bold""#;
+    canvas.text(&mut out, &title, (X, y), "1.2em", style);
+    let y = top + 280 + line_offset(title.len(), 62) + 70;
+    canvas.text(&mut out, &description, (X, y), "1.4em", r#"font-size="26" opacity="0.8""#);
+
+    let footer = canvas.height.saturating_sub(INSET + 52);
+    let half = text_width / 2;
+    let author = canvas.wrap(data.author.as_deref().unwrap_or_default(), 22.0, half, 1);
+    canvas.text(&mut out, &author, (X, footer), "", r#"font-size="22" opacity="0.7""#);
+    let tags = data.tags.iter().map(|tag| format!("#{tag}")).collect::<Vec<_>>().join("  ");
+    let tags = canvas.wrap(&tags, 22.0, half, 1);
+    let right = canvas.width.saturating_sub(X);
+    let style = r#"text-anchor="end" font-size="22" opacity="0.7""#;
+    canvas.text(&mut out, &tags, (right, footer), "", style);
+    out
+}
+
+/// Returns how far to move the title down so that, without a description,
+/// it is centered in the space the title and a `gap` below it would take.
+const fn centering_shift(description: &[String], gap: u32) -> u32 {
+    if description.is_empty() {
+        gap / 2
+    } else {
+        0
     }
+}
 
-    lines
+/// Returns how far the last of `lines` lines sits below the first.
+fn line_offset(lines: usize, line_height: u32) -> u32 {
+    u32::try_from(lines.saturating_sub(1)).unwrap_or(0) * line_height
 }
 
 impl Default for OgImageGenerator {
@@ -200,6 +468,11 @@ impl Default for OgImageGenerator {
     }
 }
 
+/// Removes characters that would break out of a CSS string or XML attribute.
+fn sanitize_font_family(family: &str) -> String {
+    family.chars().filter(|c| !"\"'\\<>&;{}".contains(*c)).collect::<String>().trim().to_string()
+}
+
 /// Escapes special XML characters.
 fn escape_xml(s: &str) -> String {
     s.replace('&', "&amp;")
@@ -229,4 +502,176 @@ mod tests {
         assert!(svg.contains("Test Title"));
         assert!(svg.contains("Test description"));
     }
+
+    #[test]
+    fn test_generate_svg_measures_mixed_scripts() {
+        let svg_for = |title: &str| {
+            let data = OgImageData {
+                title: title.to_string(),
+                description: Some("説明文 ".repeat(30)),
+                site_name: None,
+                author: None,
+                date: None,
+                tags: vec![],
+            };
+            OgImageGenerator::default().generate_svg(&data)
+        };
+
+        // Thirty narrow Latin letters fit; thirty full-width characters do not.
+        let latin = "abcdefghijklmnopqrstuvwxyzabcd";
+        assert!(svg_for(latin).contains(&format!(">{latin}</text>")));
+        let cjk = "日本語".repeat(10);
+        let svg = svg_for(&cjk);
+        assert!(!svg.contains(&cjk) && svg.contains("...</text>"));
+        // The description wraps into two lines with the second cut short.
+        assert_eq!(svg.matches("<tspan").count(), 2);
+        assert!(svg.contains("...</tspan>"));
+    }
+
+    #[test]
+    fn test_generate_svg_layouts() {
+        let data = OgImageData {
+            title: "A fairly long page title that needs more than one line to fit".to_string(),
+            description: Some("Short description".to_string()),
+            site_name: Some("Docs & Guides".to_string()),
+            author: Some("Jane".to_string()),
+            date: None,
+            tags: vec!["rust".to_string()],
+        };
+        let svg_for = |layout| {
+            let config = OgImageConfig { layout, ..OgImageConfig::default() };
+            OgImageGenerator::new(config).generate_svg(&data)
+        };
+
+        // The default layout keeps the title on one line, cut short.
+        let svg = svg_for(TemplateLayout::Default);
+        assert!(svg.contains("Accent bar") && svg.contains("...</text>"));
+        assert!(svg.contains(">Docs &amp; Guides</text>"));
+        assert!(svg.contains(r#"text-anchor="end" font-size="24" opacity="0.7" font-family="system-ui, sans-serif">Jane</text>"#));
+
+        // Centered and split wrap the title instead.
+        let svg = svg_for(TemplateLayout::Centered);
+        assert!(svg.contains(r#"<text x="600" y="300""#));
+        assert!(svg.contains(r#"<tspan x="600" dy="1.2em">"#));
+        let svg = svg_for(TemplateLayout::Split);
+        assert!(svg.contains(r#"<rect width="480" height="100%" fill="url(#accentGrad)"/>"#));
+        assert!(svg.contains(r#"<tspan x="544" dy="0">A fairly long"#));
+
+        // Minimal shows only the title and site name, on a flat background.
+        let svg = svg_for(TemplateLayout::Minimal);
+        assert!(svg.contains(r##"<rect width="100%" height="100%" fill="#1a1a1a"/>"##));
+        assert!(!svg.contains("Short description") && !svg.contains("Jane"));
+        assert!(svg.contains("Docs &amp; Guides"));
+
+        let svg = svg_for(TemplateLayout::Card);
+        assert!(svg.contains(">Jane</text>") && svg.contains(">#rust</text>"));
+
+        // An explicit template chooses its own layout.
+        let generator = OgImageGenerator::with_template(
+            OgImageConfig { layout: TemplateLayout::Minimal, ..OgImageConfig::default() },
+            OgImageTemplate::centered("brand"),
+        );
+        assert!(generator.generate_svg(&data).contains(r#"<text x="600" y="300""#));
+        assert_eq!(TemplateLayout::from_name("Split"), Some(TemplateLayout::Split));
+        assert_eq!(TemplateLayout::from_name("grid"), None);
+    }
+
+    #[test]
+    fn test_generate_svg_variants() {
+        let data = OgImageData {
+            title: "Variants".to_string(),
+            description: Some("Same data, three sizes".to_string()),
+            site_name: None,
+            author: Some("Jane".to_string()),
+            date: None,
+            tags: vec![],
+        };
+        let size = |width, height| OgImageConfig { width, height, ..OgImageConfig::default() };
+        let svgs = OgImageGenerator::generate_svg_variants(
+            &data,
+            vec![size(1200, 630), size(1200, 600), size(1080, 1080)],
+        );
+        assert_eq!(svgs.len(), 3);
+        assert_eq!(svgs[0], OgImageGenerator::default().generate_svg(&data));
+
+        // The footer follows the bottom edge
+        assert!(svgs[0].contains(r#"<rect x="80" y="540" width="200""#));
+        assert!(svgs[1].contains(r#"width="1200" height="600" viewBox="0 0 1200 600""#));
+        assert!(svgs[1].contains(r#"<rect x="80" y="510" width="200""#));
+        assert!(svgs[1].contains(r#"<text x="80" y="280""#));
+        // The square centers the content in its extra height
+        assert!(svgs[2].contains(r#"<rect x="80" y="990" width="200""#));
+        assert!(svgs[2].contains(r#"<text x="80" y="505""#));
+        assert!(svgs[2].contains(r#"<text x="1000" y="1000""#));
+    }
+
+    #[test]
+    fn test_generate_svg_fallbacks() {
+        let data = OgImageData {
+            title: "  ".to_string(),
+            description: None,
+            site_name: Some("Docs".to_string()),
+            author: None,
+            date: None,
+            tags: vec![],
+        };
+
+        // Without a description the title moves down to stay centered
+        let svg = OgImageGenerator::default().generate_svg(&data);
+        assert!(svg.contains(
+            r##"<text x="80" y="330" fill="#ffffff" font-size="56" font-weight="bold" font-family="system-ui, sans-serif">Docs</text>"##
+        ));
+        let config = OgImageConfig { layout: TemplateLayout::Centered, ..OgImageConfig::default() };
+        assert!(OgImageGenerator::new(config)
+            .generate_svg(&data)
+            .contains(r#"<text x="600" y="340""#));
+
+        let config = OgImageConfig {
+            fallback_description: Some("Fast Markdown tooling".to_string()),
+            ..OgImageConfig::default()
+        };
+        let svg = OgImageGenerator::new(config.clone()).generate_svg(&data);
+        assert!(svg.contains(r#"<text x="80" y="280""#));
+        assert!(svg.contains(">Fast Markdown tooling</text>"));
+
+        // A page's own description wins over the fallback
+        let data = OgImageData { description: Some("Own".to_string()), ..data };
+        let svg = OgImageGenerator::new(config).generate_svg(&data);
+        assert!(svg.contains(">Own</text>") && !svg.contains("Fast Markdown"));
+    }
+
+    #[test]
+    fn test_generate_svg_embeds_font() {
+        let data = OgImageData {
+            title: "Title".to_string(),
+            description: None,
+            site_name: None,
+            author: None,
+            date: None,
+            tags: vec![],
+        };
+
+        let svg = OgImageGenerator::default().generate_svg(&data);
+        assert!(!svg.contains("@font-face"));
+        assert!(svg.contains("font-family=\"system-ui, sans-serif\""));
+
+        let config = OgImageConfig {
+            font_family: Some("Inter".to_string()),
+            font_data: Some(b"wOF2font".to_vec()),
+            ..OgImageConfig::default()
+        };
+        let generator = OgImageGenerator::new(config);
+        assert_eq!(generator.embedded_font().unwrap(), Some(FontFormat::Woff2));
+        let svg = generator.generate_svg(&data);
+        assert!(svg.contains(
+            "@font-face { font-family: \"Inter\"; src: url(data:font/woff2;base64,d09GMmZvbnQ=) format(\"woff2\"); }"
+        ));
+        assert!(svg.contains("font-family=\"&quot;Inter&quot;, system-ui, sans-serif\""));
+
+        let config =
+            OgImageConfig { font_data: Some(b"not a font".to_vec()), ..OgImageConfig::default() };
+        let generator = OgImageGenerator::new(config);
+        assert!(matches!(generator.embedded_font(), Err(OgImageError::FontLoad(_))));
+        assert!(!generator.generate_svg(&data).contains("@font-face"));
+    }
 }
@@ -4,9 +4,12 @@
 //! for documentation pages, creating social media preview images.
 
 mod config;
+mod font;
 mod generator;
+mod measure;
 mod template;
 
 pub use config::{ImageFormat, OgImageConfig};
+pub use font::{FontFormat, DEFAULT_FONT_FAMILY, EMBEDDED_FONT_FAMILY};
 pub use generator::{OgImageError, OgImageGenerator, OgImageResult};
 pub use template::{OgImageData, OgImageTemplate, TemplateLayout};
@@ -0,0 +1,452 @@
+//! Text measurement for line breaking and truncation.
+//!
+//! Widths come from the glyph advances of the configured font when it is a
+//! TrueType or OpenType file. Otherwise (no font, WOFF/WOFF2, or glyphs the
+//! font lacks) widths are estimated per character class, with full-width CJK
+//! characters one em wide and Latin letters roughly half that.
+//!
+//! Text is measured and cut by grapheme cluster, so an emoji sequence or a
+//! letter with combining accents is never split and takes the width of its
+//! first character.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Measures text widths in pixels.
+pub struct TextMeasurer<'a> {
+    font: Option<SfntMetrics<'a>>,
+}
+
+impl<'a> TextMeasurer<'a> {
+    /// Creates a measurer using the glyph metrics of `font_data` when it is
+    /// a readable TrueType or OpenType font.
+    pub fn new(font_data: Option<&'a [u8]>) -> Self {
+        Self { font: font_data.and_then(SfntMetrics::parse) }
+    }
+
+    /// Returns the advance width of `c` in pixels at `font_size`.
+    pub fn advance(&self, c: char, font_size: f32) -> f32 {
+        let em = self
+            .font
+            .as_ref()
+            .and_then(|font| font.advance_em(c))
+            .unwrap_or_else(|| estimate_em(c));
+        em * font_size
+    }
+
+    /// Returns the width of `text` in pixels at `font_size`.
+    pub fn width(&self, text: &str, font_size: f32) -> f32 {
+        text.graphemes(true).map(|g| self.grapheme_advance(g, font_size)).sum()
+    }
+
+    /// Returns the advance width of a grapheme cluster, which is that of its
+    /// first character: the rest are combining marks, joiners and modifiers.
+    fn grapheme_advance(&self, grapheme: &str, font_size: f32) -> f32 {
+        grapheme.chars().next().map_or(0.0, |c| self.advance(c, font_size))
+    }
+
+    /// Shortens `text` to fit in `max_width`, ending it with `...` when cut.
+    pub fn truncate(&self, text: &str, font_size: f32, max_width: f32) -> String {
+        if self.width(text, font_size) <= max_width {
+            text.to_string()
+        } else {
+            self.with_ellipsis(text, font_size, max_width)
+        }
+    }
+
+    /// Appends `...` to `text`, dropping trailing graphemes until it fits.
+    fn with_ellipsis(&self, text: &str, font_size: f32, max_width: f32) -> String {
+        let limit = max_width - self.width(ELLIPSIS, font_size);
+        let mut kept = String::new();
+        let mut width = 0.0;
+        for grapheme in text.graphemes(true) {
+            width += self.grapheme_advance(grapheme, font_size);
+            if width > limit {
+                break;
+            }
+            kept.push_str(grapheme);
+        }
+        let mut kept = kept.trim_end().to_string();
+        kept.push_str(ELLIPSIS);
+        kept
+    }
+
+    /// Breaks `text` into at most `max_lines` lines that fit in `max_width`.
+    ///
+    /// Lines break at spaces and between CJK characters. Words wider than a
+    /// line are split by grapheme. The last line is truncated when the text
+    /// does not fit.
+    pub fn wrap(
+        &self,
+        text: &str,
+        font_size: f32,
+        max_width: f32,
+        max_lines: usize,
+    ) -> Vec<String> {
+        let space = self.advance(' ', font_size);
+        let mut lines: Vec<String> = Vec::new();
+        let mut line = String::new();
+        let mut line_width = 0.0;
+
+        for (word, spaced) in break_units(text) {
+            let word_width = self.width(word, font_size);
+            let gap = if spaced && !line.is_empty() { space } else { 0.0 };
+            if line_width + gap + word_width <= max_width {
+                if gap > 0.0 {
+                    line.push(' ');
+                }
+                line.push_str(word);
+                line_width += gap + word_width;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0.0;
+            }
+            // A word wider than a whole line is split by grapheme.
+            for grapheme in word.graphemes(true) {
+                let advance = self.grapheme_advance(grapheme, font_size);
+                if line_width + advance > max_width && !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0.0;
+                }
+                line.push_str(grapheme);
+                line_width += advance;
+            }
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+
+        if lines.len() > max_lines {
+            lines.truncate(max_lines);
+            if let Some(last) = lines.last_mut() {
+                *last = self.with_ellipsis(last, font_size, max_width);
+            }
+        }
+        lines
+    }
+}
+
+const ELLIPSIS: &str = "...";
+
+/// Splits text into units that can start a new line, each paired with
+/// whether whitespace preceded it. Every grapheme starting with a wide
+/// character is its own unit.
+fn break_units(text: &str) -> Vec<(&str, bool)> {
+    let mut units = Vec::new();
+    let mut spaced = false;
+    let mut start = None;
+
+    for (i, grapheme) in text.grapheme_indices(true) {
+        let c = grapheme.chars().next().unwrap_or(' ');
+        if c.is_whitespace() || is_wide(c) {
+            if let Some(s) = start.take() {
+                units.push((&text[s..i], spaced));
+                spaced = false;
+            }
+            if c.is_whitespace() {
+                spaced = true;
+            } else {
+                units.push((grapheme, spaced));
+                spaced = false;
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        units.push((&text[s..], spaced));
+    }
+    units
+}
+
+/// Returns whether `c` is a full-width (East Asian wide) character.
+fn is_wide(c: char) -> bool {
+    matches!(
+        u32::from(c),
+        0x1100..=0x115F
+            | 0x2E80..=0x303E
+            | 0x3041..=0x33FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xA000..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x1F300..=0x1F64F
+            | 0x1F900..=0x1F9FF
+            | 0x20000..=0x3FFFD
+    )
+}
+
+/// Estimated advance in ems for a sans-serif UI font.
+fn estimate_em(c: char) -> f32 {
+    if is_wide(c) {
+        return 1.0;
+    }
+    match c {
+        ' ' | 'i' | 'j' | 'l' | '.' | ',' | ':' | ';' | '\'' | '!' | '|' => 0.28,
+        'f' | 't' | 'r' | 'I' | '(' | ')' | '[' | ']' | '-' | '"' => 0.36,
+        'm' | 'w' | 'M' | 'W' | '@' | '%' => 0.85,
+        'A'..='Z' => 0.66,
+        '0'..='9' => 0.56,
+        _ => 0.53,
+    }
+}
+
+/// Horizontal metrics read from a TrueType or OpenType (`sfnt`) font.
+struct SfntMetrics<'a> {
+    units_per_em: f32,
+    hmtx: &'a [u8],
+    num_h_metrics: usize,
+    cmap: Cmap<'a>,
+}
+
+/// A Unicode character-to-glyph subtable.
+enum Cmap<'a> {
+    /// Format 4: segment mapping for the Basic Multilingual Plane.
+    SegmentMapping(&'a [u8]),
+    /// Format 12: segmented coverage for all planes.
+    SegmentedCoverage(&'a [u8]),
+}
+
+impl<'a> SfntMetrics<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let head = table(data, *b"head")?;
+        let hhea = table(data, *b"hhea")?;
+        let hmtx = table(data, *b"hmtx")?;
+        let cmap = table(data, *b"cmap")?;
+
+        let units_per_em = read_u16(head, 18)?;
+        let num_h_metrics = usize::from(read_u16(hhea, 34)?);
+        if units_per_em == 0 || num_h_metrics == 0 || hmtx.len() < num_h_metrics * 4 {
+            return None;
+        }
+        Some(Self {
+            units_per_em: f32::from(units_per_em),
+            hmtx,
+            num_h_metrics,
+            cmap: unicode_cmap(cmap)?,
+        })
+    }
+
+    /// Advance of `c` in ems, or `None` when the font has no glyph for it.
+    fn advance_em(&self, c: char) -> Option<f32> {
+        let glyph = self.glyph(u32::from(c))?;
+        let index = glyph.min(self.num_h_metrics - 1);
+        Some(f32::from(read_u16(self.hmtx, index * 4)?) / self.units_per_em)
+    }
+
+    fn glyph(&self, code: u32) -> Option<usize> {
+        let glyph = match self.cmap {
+            Cmap::SegmentMapping(table) => segment_mapping_glyph(table, code)?,
+            Cmap::SegmentedCoverage(table) => segmented_coverage_glyph(table, code)?,
+        };
+        (glyph != 0).then_some(glyph)
+    }
+}
+
+/// Finds a table in the font's table directory.
+fn table(data: &[u8], tag: [u8; 4]) -> Option<&[u8]> {
+    let num_tables = usize::from(read_u16(data, 4)?);
+    (0..num_tables).find_map(|i| {
+        let record = 12 + i * 16;
+        if data.get(record..record + 4)? != tag.as_slice() {
+            return None;
+        }
+        let offset = read_u32(data, record + 8)? as usize;
+        let length = read_u32(data, record + 12)? as usize;
+        data.get(offset..offset.checked_add(length)?)
+    })
+}
+
+/// Picks a Unicode subtable, preferring full coverage (format 12).
+fn unicode_cmap(cmap: &[u8]) -> Option<Cmap<'_>> {
+    let num_tables = usize::from(read_u16(cmap, 2)?);
+    let mut segment_mapping = None;
+    for i in 0..num_tables {
+        let record = 4 + i * 8;
+        let platform = read_u16(cmap, record)?;
+        let encoding = read_u16(cmap, record + 2)?;
+        if !(platform == 0 || (platform == 3 && (encoding == 1 || encoding == 10))) {
+            continue;
+        }
+        let subtable = cmap.get(read_u32(cmap, record + 4)? as usize..)?;
+        match read_u16(subtable, 0)? {
+            12 => return Some(Cmap::SegmentedCoverage(subtable)),
+            4 => segment_mapping = segment_mapping.or(Some(Cmap::SegmentMapping(subtable))),
+            _ => {}
+        }
+    }
+    segment_mapping
+}
+
+fn segment_mapping_glyph(table: &[u8], code: u32) -> Option<usize> {
+    let code = u16::try_from(code).ok()?;
+    let seg_count_x2 = usize::from(read_u16(table, 6)?);
+    let end_codes = 14;
+    let start_codes = end_codes + seg_count_x2 + 2;
+    let id_deltas = start_codes + seg_count_x2;
+    let id_range_offsets = id_deltas + seg_count_x2;
+
+    for seg in (0..seg_count_x2).step_by(2) {
+        if read_u16(table, end_codes + seg)? < code {
+            continue;
+        }
+        let start = read_u16(table, start_codes + seg)?;
+        if start > code {
+            return None;
+        }
+        let delta = read_u16(table, id_deltas + seg)?;
+        let range_offset = usize::from(read_u16(table, id_range_offsets + seg)?);
+        if range_offset == 0 {
+            return Some(usize::from(code.wrapping_add(delta)));
+        }
+        let address = id_range_offsets + seg + range_offset + usize::from(code - start) * 2;
+        let glyph = read_u16(table, address)?;
+        return (glyph != 0).then(|| usize::from(glyph.wrapping_add(delta)));
+    }
+    None
+}
+
+fn segmented_coverage_glyph(table: &[u8], code: u32) -> Option<usize> {
+    let num_groups = read_u32(table, 12)? as usize;
+    let (mut low, mut high) = (0, num_groups);
+    while low < high {
+        let mid = (low + high) / 2;
+        let group = 16 + mid * 12;
+        let start = read_u32(table, group)?;
+        let end = read_u32(table, group + 4)?;
+        if code < start {
+            high = mid;
+        } else if code > end {
+            low = mid + 1;
+        } else {
+            return Some((read_u32(table, group + 8)? + (code - start)) as usize);
+        }
+    }
+    None
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a minimal TrueType font mapping `A` (glyph 1, 500 units) and
+    /// `漢` (glyph 2, 1000 units) with 1000 units per em.
+    fn test_font() -> Vec<u8> {
+        fn be16(v: u16) -> [u8; 2] {
+            v.to_be_bytes()
+        }
+
+        let mut head = vec![0; 54];
+        head[18..20].copy_from_slice(&be16(1000));
+        let mut hhea = vec![0; 36];
+        hhea[34..36].copy_from_slice(&be16(3));
+        let hmtx: Vec<u8> = [0u16, 0, 500, 0, 1000, 0].iter().flat_map(|v| be16(*v)).collect();
+
+        // cmap with one format 12 subtable: 'A' -> 1, '漢' -> 2.
+        let mut cmap: Vec<u8> = [0u16, 1, 3, 10].iter().flat_map(|v| be16(*v)).collect();
+        cmap.extend(12u32.to_be_bytes());
+        cmap.extend(be16(12));
+        cmap.extend(be16(0));
+        cmap.extend(40u32.to_be_bytes());
+        cmap.extend(0u32.to_be_bytes());
+        cmap.extend(2u32.to_be_bytes());
+        for (start, end, glyph) in [(0x41u32, 0x41u32, 1u32), (0x6F22, 0x6F22, 2)] {
+            cmap.extend(start.to_be_bytes());
+            cmap.extend(end.to_be_bytes());
+            cmap.extend(glyph.to_be_bytes());
+        }
+
+        let tables: [(&[u8; 4], &[u8]); 4] =
+            [(b"cmap", &cmap), (b"head", &head), (b"hhea", &hhea), (b"hmtx", &hmtx)];
+        let mut font = vec![0, 1, 0, 0];
+        font.extend(be16(4));
+        font.extend([0; 6]);
+        let mut offset = 12 + tables.len() * 16;
+        for (tag, data) in &tables {
+            font.extend(*tag);
+            font.extend([0; 4]);
+            font.extend(u32::try_from(offset).unwrap().to_be_bytes());
+            font.extend(u32::try_from(data.len()).unwrap().to_be_bytes());
+            offset += data.len();
+        }
+        for (_, data) in &tables {
+            font.extend(*data);
+        }
+        font
+    }
+
+    #[test]
+    fn test_font_advances() {
+        let font = test_font();
+        let measurer = TextMeasurer::new(Some(&font));
+        assert!((measurer.advance('A', 40.0) - 20.0).abs() < f32::EPSILON);
+        assert!((measurer.advance('漢', 40.0) - 40.0).abs() < f32::EPSILON);
+        // Missing glyphs fall back to estimates.
+        assert!((measurer.advance('字', 40.0) - 40.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_estimated_widths() {
+        let measurer = TextMeasurer::new(None);
+        assert!(measurer.width("漢字", 10.0) > measurer.width("abc", 10.0));
+        assert!(measurer.width("WWW", 10.0) > measurer.width("iii", 10.0));
+        // Unreadable font data is ignored.
+        assert!(TextMeasurer::new(Some(b"wOF2....")).font.is_none());
+    }
+
+    #[test]
+    fn test_wrap_mixed_scripts() {
+        let measurer = TextMeasurer::new(None);
+        // CJK text without spaces still breaks, between characters.
+        let lines = measurer.wrap("日本語のドキュメント", 10.0, 50.0, 3);
+        assert_eq!(lines, ["日本語のド", "キュメント"]);
+
+        let lines = measurer.wrap("Ox Content で高速な Markdown", 10.0, 60.0, 5);
+        assert!(lines.iter().all(|line| measurer.width(line, 10.0) <= 60.0), "{lines:?}");
+        assert_eq!(lines.concat().replace(' ', ""), "OxContentで高速なMarkdown");
+
+        let lines = measurer.wrap("one two three four five six", 10.0, 40.0, 2);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].ends_with("..."));
+    }
+
+    #[test]
+    fn test_truncate() {
+        let measurer = TextMeasurer::new(None);
+        assert_eq!(measurer.truncate("Short", 10.0, 100.0), "Short");
+        let cut = measurer.truncate("日本語のドキュメント", 10.0, 50.0);
+        assert!(cut.ends_with("...") && measurer.width(&cut, 10.0) <= 50.0, "{cut}");
+    }
+
+    #[test]
+    fn test_grapheme_clusters() {
+        let measurer = TextMeasurer::new(None);
+        // A family emoji is one em wide and never split.
+        let family = "👨\u{200D}👩\u{200D}👧";
+        assert!((measurer.width(family, 10.0) - 10.0).abs() < f32::EPSILON);
+        let cut = measurer.truncate(&family.repeat(8), 10.0, 40.0);
+        assert_eq!(cut, format!("{}...", family.repeat(3)));
+
+        let lines = measurer.wrap(&"👍🏽".repeat(6), 10.0, 30.0, 3);
+        assert_eq!(lines, ["👍🏽👍🏽👍🏽", "👍🏽👍🏽👍🏽"]);
+
+        // Combining accents stay on their letter.
+        let word = "e\u{301}".repeat(12);
+        let lines = measurer.wrap(&word, 10.0, 30.0, 3);
+        assert!(lines.iter().all(|line| !line.starts_with('\u{301}')), "{lines:?}");
+        assert_eq!(lines.concat(), word);
+    }
+}
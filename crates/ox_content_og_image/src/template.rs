@@ -54,18 +54,42 @@ impl Default for OgImageTemplate {
     }
 }
 
-/// Template layout types.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Arrangement of the site name, title, description and author on the card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TemplateLayout {
-    /// Default layout with title and description.
+    /// Left-aligned title and description under a logo and site name.
     #[default]
     Default,
-    /// Centered title layout.
+    /// Everything centered, with the title on up to two lines.
     Centered,
-    /// Split layout with image on one side.
+    /// Logo and site name on a colored panel at the left, text at the right.
     Split,
-    /// Minimal layout with just title.
+    /// A large title on a flat background with the site name as a footer.
     Minimal,
-    /// Card-style layout.
+    /// Text on an outlined panel, with the author and tags in the footer.
     Card,
 }
+
+impl TemplateLayout {
+    /// Returns the layout name (`default`, `centered`, `split`, `minimal`
+    /// or `card`).
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Centered => "centered",
+            Self::Split => "split",
+            Self::Minimal => "minimal",
+            Self::Card => "card",
+        }
+    }
+
+    /// Looks up a layout by name, ignoring ASCII case.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::Default, Self::Centered, Self::Split, Self::Minimal, Self::Card]
+            .into_iter()
+            .find(|layout| layout.as_str().eq_ignore_ascii_case(name))
+    }
+}
@@ -161,5 +161,29 @@ fn bench_parse_large(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_parse_simple, bench_parse_large);
+/// A 2 MB paragraph on a single line, full of brackets, references and
+/// emphasis that never close, as produced by data exports.
+fn long_line() -> String {
+    let unit = "[see ![figure &amp [^note *bold _mixed ";
+    unit.repeat(2_000_000 / unit.len())
+}
+
+fn bench_parse_long_line(c: &mut Criterion) {
+    let source = long_line();
+    let mut group = c.benchmark_group("parse_long_line");
+    group.throughput(Throughput::Bytes(source.len() as u64));
+    group.sample_size(10);
+
+    group.bench_function("long_line_2mb", |b| {
+        b.iter(|| {
+            let allocator = Allocator::new();
+            let parser = Parser::new(&allocator, black_box(&source));
+            let _ = parser.parse();
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse_simple, bench_parse_large, bench_parse_long_line);
 criterion_main!(benches);
@@ -0,0 +1,18 @@
+//! Wall-clock access for time budgets and parse statistics.
+//!
+//! `Instant::now` panics on `wasm32-unknown-unknown`, so every timing in the
+//! parser goes through [`now`], which reports no clock there instead.
+
+use std::time::Instant;
+
+/// Returns the current time.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub fn now() -> Option<Instant> {
+    Some(Instant::now())
+}
+
+/// Returns `None`: `wasm32-unknown-unknown` has no clock.
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub const fn now() -> Option<Instant> {
+    None
+}
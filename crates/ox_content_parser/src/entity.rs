@@ -0,0 +1,108 @@
+//! Entity and numeric character reference decoding.
+//!
+//! Follows the CommonMark rules: named references must match an HTML5
+//! entity name, decimal references have 1-7 digits, and hexadecimal
+//! references have 1-6 digits. All forms must end with `;`. Anything that
+//! does not match is left as literal text.
+
+use std::borrow::Cow;
+
+use crate::entities::ENTITIES;
+
+/// Decodes a single character reference at the start of `input`.
+///
+/// `input` must begin with `&`. Returns the replacement text and the number
+/// of bytes consumed, or `None` if `input` does not start with a valid
+/// reference.
+#[must_use]
+pub fn decode_entity(input: &str) -> Option<(Cow<'static, str>, usize)> {
+    let rest = input.strip_prefix('&')?;
+    // Stop at the first character no reference contains, so text full of
+    // `&` is not scanned to its end for each of them
+    let end = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '#'))?;
+    if !rest[end..].starts_with(';') {
+        return None;
+    }
+    let body = &rest[..end];
+    let consumed = end + 2;
+
+    if let Some(num) = body.strip_prefix('#') {
+        let (digits, radix, max_len) =
+            num.strip_prefix(['x', 'X']).map_or((num, 10, 7), |hex| (hex, 16, 6));
+        if digits.is_empty() || digits.len() > max_len || !digits.chars().all(|c| c.is_digit(radix))
+        {
+            return None;
+        }
+        let code = u32::from_str_radix(digits, radix).ok()?;
+        let ch = char::from_u32(code).filter(|&c| c != '\0').unwrap_or(char::REPLACEMENT_CHARACTER);
+        return Some((Cow::Owned(ch.to_string()), consumed));
+    }
+
+    if body.is_empty() || !body.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        return None;
+    }
+    let idx = ENTITIES.binary_search_by(|(name, _)| (*name).cmp(body)).ok()?;
+    Some((Cow::Borrowed(ENTITIES[idx].1), consumed))
+}
+
+/// Decodes every valid character reference in `input`.
+///
+/// Invalid references are kept verbatim. Borrows `input` when it contains
+/// no references.
+#[must_use]
+pub fn decode_entities(input: &str) -> Cow<'_, str> {
+    if !input.contains('&') {
+        return Cow::Borrowed(input);
+    }
+
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(pos) = rest.find('&') {
+        output.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        if let Some((decoded, len)) = decode_entity(rest) {
+            output.push_str(&decoded);
+            rest = &rest[len..];
+        } else {
+            output.push('&');
+            rest = &rest[1..];
+        }
+    }
+    output.push_str(rest);
+    Cow::Owned(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named() {
+        assert_eq!(decode_entities("&copy; 2024 &amp; &nbsp;"), "\u{a9} 2024 & \u{a0}");
+        assert_eq!(decode_entities("&ngE;"), "\u{2267}\u{338}");
+    }
+
+    #[test]
+    fn test_numeric() {
+        assert_eq!(decode_entities("&#35; &#1234; &#x263A; &#XD06;"), "# \u{4d2} \u{263a} \u{d06}");
+        assert_eq!(decode_entities("&#0;"), "\u{fffd}");
+        assert_eq!(decode_entities("&#xD800;"), "\u{fffd}");
+    }
+
+    #[test]
+    fn test_invalid_stays_literal() {
+        let inputs =
+            ["&nbsp", "&x;", "&#;", "&#x;", "&#87654321;", "&#abcdef0;", "& copy;", "AT&T"];
+        for input in inputs {
+            assert_eq!(decode_entities(input), input);
+        }
+        assert!(matches!(decode_entities("plain"), Cow::Borrowed("plain")));
+    }
+
+    #[test]
+    fn test_decode_entity_length() {
+        let (decoded, len) = decode_entity("&copy; rest").unwrap();
+        assert_eq!(decoded, "\u{a9}");
+        assert_eq!(len, 6);
+    }
+}
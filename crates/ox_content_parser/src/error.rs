@@ -1,19 +1,26 @@
 //! Error types for the parser.
 
-use ox_content_ast::Span;
+use std::time::Duration;
+
+use ox_content_ast::{Position, Span};
 use thiserror::Error;
 
 /// Result type for parsing operations.
 pub type ParseResult<T> = Result<T, ParseError>;
 
 /// Parse error.
+///
+/// Messages give positions as source lines and columns, so they are stable
+/// across runs and can be compared in tests.
 #[derive(Debug, Error)]
 pub enum ParseError {
     /// Unexpected token encountered.
-    #[error("unexpected token at {span:?}: expected {expected}, found {found}")]
+    #[error("unexpected token at {position}: expected {expected}, found {found}")]
     UnexpectedToken {
         /// The span where the error occurred.
         span: Span,
+        /// The line and column of the span start.
+        position: Position,
         /// Expected token description.
         expected: String,
         /// Found token description.
@@ -21,29 +28,91 @@ pub enum ParseError {
     },
 
     /// Unexpected end of input.
-    #[error("unexpected end of input at {span:?}")]
+    #[error("unexpected end of input at {position}")]
     UnexpectedEof {
         /// The span where the error occurred.
         span: Span,
+        /// The line and column of the span start.
+        position: Position,
     },
 
     /// Invalid syntax.
-    #[error("invalid syntax at {span:?}: {message}")]
+    #[error("invalid syntax at {position}: {message}")]
     InvalidSyntax {
         /// The span where the error occurred.
         span: Span,
+        /// The line and column of the span start.
+        position: Position,
         /// Error message.
         message: String,
     },
 
     /// Nesting too deep.
-    #[error("nesting too deep at {span:?}: maximum depth is {max_depth}")]
+    #[error("max nesting depth exceeded at {position}: limit is {max_depth}")]
     NestingTooDeep {
         /// The span where the error occurred.
         span: Span,
+        /// The line and column of the span start.
+        position: Position,
         /// Maximum allowed depth.
         max_depth: usize,
     },
+
+    /// Input larger than the configured size limit.
+    #[error("input too large: {size} bytes exceeds the limit of {limit} bytes")]
+    InputTooLarge {
+        /// Input size in bytes.
+        size: usize,
+        /// Maximum allowed size in bytes.
+        limit: usize,
+    },
+
+    /// Parsing took longer than the configured time budget.
+    #[error("time budget of {} ms exceeded at {position}", budget.as_millis())]
+    TimeBudgetExceeded {
+        /// The span where parsing stopped.
+        span: Span,
+        /// The line and column of the span start.
+        position: Position,
+        /// The configured time budget.
+        budget: Duration,
+    },
+
+    /// A full or collapsed link or image reference without a definition.
+    /// Only reported in strict mode.
+    #[error("undefined link reference [{label}] at {position}")]
+    UndefinedReference {
+        /// The span of the reference.
+        span: Span,
+        /// The line and column of the span start.
+        position: Position,
+        /// The reference label.
+        label: String,
+    },
+
+    /// A footnote reference without a definition. Only reported in strict
+    /// mode.
+    #[error("undefined footnote [^{label}] at {position}")]
+    UndefinedFootnote {
+        /// The span of the reference.
+        span: Span,
+        /// The line and column of the span start.
+        position: Position,
+        /// The footnote label.
+        label: String,
+    },
+
+    /// A fenced code block or math block that is never closed. Only
+    /// reported in strict mode.
+    #[error("unclosed {block} at {position}")]
+    UnclosedBlock {
+        /// The span of the opening line.
+        span: Span,
+        /// The line and column of the span start.
+        position: Position,
+        /// The kind of block, such as `code fence`.
+        block: &'static str,
+    },
 }
 
 impl ParseError {
@@ -52,9 +121,71 @@ impl ParseError {
     pub fn span(&self) -> Span {
         match self {
             Self::UnexpectedToken { span, .. }
-            | Self::UnexpectedEof { span }
+            | Self::UnexpectedEof { span, .. }
             | Self::InvalidSyntax { span, .. }
-            | Self::NestingTooDeep { span, .. } => *span,
+            | Self::NestingTooDeep { span, .. }
+            | Self::TimeBudgetExceeded { span, .. }
+            | Self::UndefinedReference { span, .. }
+            | Self::UndefinedFootnote { span, .. }
+            | Self::UnclosedBlock { span, .. } => *span,
+            Self::InputTooLarge { .. } => Span::empty(),
+        }
+    }
+
+    /// Returns the line and column where the error occurred, or `None` for
+    /// errors about the whole input.
+    #[must_use]
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            Self::UnexpectedToken { position, .. }
+            | Self::UnexpectedEof { position, .. }
+            | Self::InvalidSyntax { position, .. }
+            | Self::NestingTooDeep { position, .. }
+            | Self::TimeBudgetExceeded { position, .. }
+            | Self::UndefinedReference { position, .. }
+            | Self::UndefinedFootnote { position, .. }
+            | Self::UnclosedBlock { position, .. } => Some(*position),
+            Self::InputTooLarge { .. } => None,
+        }
+    }
+
+    /// Moves the reported line down by `lines`, for source that was parsed
+    /// without the lines before it, such as frontmatter. Spans are kept.
+    #[must_use]
+    pub fn with_line_offset(mut self, lines: u32) -> Self {
+        match &mut self {
+            Self::UnexpectedToken { position, .. }
+            | Self::UnexpectedEof { position, .. }
+            | Self::InvalidSyntax { position, .. }
+            | Self::NestingTooDeep { position, .. }
+            | Self::TimeBudgetExceeded { position, .. }
+            | Self::UndefinedReference { position, .. }
+            | Self::UndefinedFootnote { position, .. }
+            | Self::UnclosedBlock { position, .. } => {
+                position.line = position.line.saturating_add(lines);
+            }
+            Self::InputTooLarge { .. } => {}
+        }
+        self
+    }
+
+    /// Replaces the span and position, for errors raised while parsing text
+    /// taken out of the source, such as the contents of a block quote.
+    pub(crate) fn relocated(mut self, new_span: Span, new_position: Position) -> Self {
+        match &mut self {
+            Self::UnexpectedToken { span, position, .. }
+            | Self::UnexpectedEof { span, position, .. }
+            | Self::InvalidSyntax { span, position, .. }
+            | Self::NestingTooDeep { span, position, .. }
+            | Self::TimeBudgetExceeded { span, position, .. }
+            | Self::UndefinedReference { span, position, .. }
+            | Self::UndefinedFootnote { span, position, .. }
+            | Self::UnclosedBlock { span, position, .. } => {
+                *span = new_span;
+                *position = new_position;
+            }
+            Self::InputTooLarge { .. } => {}
         }
+        self
     }
 }
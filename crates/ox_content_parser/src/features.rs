@@ -0,0 +1,252 @@
+//! Feature masks that restrict which Markdown constructs are parsed.
+
+use ox_content_ast::Node;
+
+/// A Markdown construct that can be disabled with [`Features`].
+///
+/// Paragraphs and plain text are always parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// ATX headings (`# Title`).
+    Heading,
+    /// Thematic breaks (`---`).
+    ThematicBreak,
+    /// Block quotes (`> quote`).
+    BlockQuote,
+    /// Fenced and indented code blocks.
+    CodeBlock,
+    /// Block-level HTML.
+    Html,
+    /// Ordered and unordered lists.
+    List,
+    /// GFM tables, when [`ParserOptions::tables`] is also enabled.
+    ///
+    /// [`ParserOptions::tables`]: crate::ParserOptions::tables
+    Table,
+    /// Images (`![alt](url)`).
+    Image,
+    /// Links (`[text](url)` and reference links).
+    Link,
+    /// Emphasis (`*text*`).
+    Emphasis,
+    /// Strong emphasis (`**text**`).
+    Strong,
+    /// Strikethrough, when [`ParserOptions::strikethrough`] is also
+    /// enabled.
+    ///
+    /// [`ParserOptions::strikethrough`]: crate::ParserOptions::strikethrough
+    Strikethrough,
+    /// Code spans (`` `code` ``).
+    InlineCode,
+}
+
+impl Feature {
+    /// Every feature, in declaration order.
+    pub const ALL: [Self; 13] = [
+        Self::Heading,
+        Self::ThematicBreak,
+        Self::BlockQuote,
+        Self::CodeBlock,
+        Self::Html,
+        Self::List,
+        Self::Table,
+        Self::Image,
+        Self::Link,
+        Self::Emphasis,
+        Self::Strong,
+        Self::Strikethrough,
+        Self::InlineCode,
+    ];
+
+    /// Returns the feature name, such as `heading` or `inline_code`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Heading => "heading",
+            Self::ThematicBreak => "thematic_break",
+            Self::BlockQuote => "block_quote",
+            Self::CodeBlock => "code_block",
+            Self::Html => "html",
+            Self::List => "list",
+            Self::Table => "table",
+            Self::Image => "image",
+            Self::Link => "link",
+            Self::Emphasis => "emphasis",
+            Self::Strong => "strong",
+            Self::Strikethrough => "strikethrough",
+            Self::InlineCode => "inline_code",
+        }
+    }
+
+    /// Looks up a feature by name, ignoring ASCII case.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|feature| feature.as_str().eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the feature that produces `node`, if it can be disabled.
+    #[must_use]
+    pub const fn of(node: &Node<'_>) -> Option<Self> {
+        match node {
+            Node::Heading(_) => Some(Self::Heading),
+            Node::ThematicBreak(_) => Some(Self::ThematicBreak),
+            Node::BlockQuote(_) => Some(Self::BlockQuote),
+            Node::CodeBlock(_) => Some(Self::CodeBlock),
+            Node::Html(_) => Some(Self::Html),
+            Node::List(_) => Some(Self::List),
+            Node::Table(_) => Some(Self::Table),
+            Node::Image(_) => Some(Self::Image),
+            Node::Link(_) => Some(Self::Link),
+            Node::Emphasis(_) => Some(Self::Emphasis),
+            Node::Strong(_) => Some(Self::Strong),
+            Node::Delete(_) => Some(Self::Strikethrough),
+            Node::InlineCode(_) => Some(Self::InlineCode),
+            _ => None,
+        }
+    }
+
+    const fn bit(self) -> u16 {
+        1 << self as u16
+    }
+}
+
+/// The set of Markdown constructs the parser recognizes.
+///
+/// Disabled constructs are kept as their literal source text: a disabled
+/// heading line becomes paragraph text and a disabled image stays
+/// `![alt](url)`. The renderer escapes that text like any other, so a
+/// restricted set is safe for user content without filtering the AST.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Features(u16);
+
+impl Features {
+    /// Every construct enabled.
+    #[must_use]
+    pub const fn all() -> Self {
+        Self::from_preset(FeaturePreset::Full)
+    }
+
+    /// Only paragraphs and plain text.
+    #[must_use]
+    pub const fn none() -> Self {
+        Self(0)
+    }
+
+    /// Creates the feature set of a preset.
+    #[must_use]
+    pub const fn from_preset(preset: FeaturePreset) -> Self {
+        let features: &[Feature] = match preset {
+            FeaturePreset::Full => &Feature::ALL,
+            FeaturePreset::Basic => &[
+                Feature::BlockQuote,
+                Feature::CodeBlock,
+                Feature::List,
+                Feature::Link,
+                Feature::Emphasis,
+                Feature::Strong,
+                Feature::Strikethrough,
+                Feature::InlineCode,
+            ],
+            FeaturePreset::InlineOnly => &[
+                Feature::Link,
+                Feature::Emphasis,
+                Feature::Strong,
+                Feature::Strikethrough,
+                Feature::InlineCode,
+            ],
+        };
+        let mut set = Self::none();
+        let mut i = 0;
+        while i < features.len() {
+            set = set.with(features[i]);
+            i += 1;
+        }
+        set
+    }
+
+    /// Returns the set with `feature` enabled.
+    #[must_use]
+    pub const fn with(self, feature: Feature) -> Self {
+        Self(self.0 | feature.bit())
+    }
+
+    /// Returns the set with `feature` disabled.
+    #[must_use]
+    pub const fn without(self, feature: Feature) -> Self {
+        Self(self.0 & !feature.bit())
+    }
+
+    /// Returns whether `feature` is enabled.
+    #[must_use]
+    pub const fn contains(self, feature: Feature) -> bool {
+        self.0 & feature.bit() != 0
+    }
+
+    /// Returns whether `node` may be produced with this set. Nodes that no
+    /// feature controls, such as paragraphs and text, are always allowed.
+    #[must_use]
+    pub fn allows(self, node: &Node<'_>) -> bool {
+        Feature::of(node).is_none_or(|feature| self.contains(feature))
+    }
+}
+
+impl Default for Features {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+/// Named feature sets for restricted Markdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeaturePreset {
+    /// Every construct.
+    Full,
+    /// Comment fields: paragraphs, block quotes, lists, code, links and
+    /// emphasis, but no headings, thematic breaks, tables, images or HTML.
+    Basic,
+    /// Paragraphs with links, emphasis and code spans only.
+    InlineOnly,
+}
+
+impl FeaturePreset {
+    /// Returns the preset name (`full`, `basic` or `inline_only`).
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Basic => "basic",
+            Self::InlineOnly => "inline_only",
+        }
+    }
+
+    /// Looks up a preset by name, ignoring ASCII case.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::Full, Self::Basic, Self::InlineOnly]
+            .into_iter()
+            .find(|preset| preset.as_str().eq_ignore_ascii_case(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        let basic = Features::from_preset(FeaturePreset::Basic);
+        assert!(basic.contains(Feature::Link) && basic.contains(Feature::CodeBlock));
+        assert!(!basic.contains(Feature::Heading) && !basic.contains(Feature::Image));
+        assert!(!basic.contains(Feature::Html));
+
+        let inline = Features::from_preset(FeaturePreset::InlineOnly);
+        assert!(inline.contains(Feature::InlineCode) && !inline.contains(Feature::List));
+        assert!(basic.contains(Feature::BlockQuote) && !inline.contains(Feature::BlockQuote));
+
+        assert!(Feature::ALL.into_iter().all(|feature| Features::default().contains(feature)));
+        assert_eq!(Features::all().without(Feature::Image).with(Feature::Image), Features::all());
+        assert_eq!(FeaturePreset::from_name("INLINE_ONLY"), Some(FeaturePreset::InlineOnly));
+        assert_eq!(FeaturePreset::from_name("basik"), None);
+        assert_eq!(Feature::from_name("thematic_break"), Some(Feature::ThematicBreak));
+    }
+}
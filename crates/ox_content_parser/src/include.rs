@@ -0,0 +1,250 @@
+//! Include directive resolution.
+//!
+//! Include directives are replaced with the referenced Markdown before
+//! parsing. Two forms are recognized, each on a line of its own:
+//!
+//! ```markdown
+//! {{< include "shared/warning.md" >}}
+//! @include(shared/warning.md)
+//! ```
+//!
+//! Directives inside fenced code blocks are left untouched.
+
+use thiserror::Error;
+
+/// Default maximum nesting depth for includes.
+pub const DEFAULT_MAX_INCLUDE_DEPTH: usize = 8;
+
+/// An include that could not be resolved.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum IncludeError {
+    /// The resolver has no content for the path.
+    #[error("include not found: {path}")]
+    NotFound {
+        /// The included path.
+        path: String,
+    },
+
+    /// The file (directly or indirectly) includes itself.
+    #[error("include cycle: {path} includes itself")]
+    Cycle {
+        /// The included path.
+        path: String,
+    },
+
+    /// Includes are nested deeper than the limit.
+    #[error("include depth limit {limit} exceeded at {path}")]
+    TooDeep {
+        /// The included path.
+        path: String,
+        /// The maximum nesting depth.
+        limit: usize,
+    },
+}
+
+/// Source with all include directives replaced.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolvedIncludes {
+    /// The expanded Markdown source.
+    pub source: String,
+    /// Includes that could not be resolved, in source order.
+    ///
+    /// Each one is also left in `source` as an HTML comment.
+    pub errors: Vec<IncludeError>,
+}
+
+/// Replaces include directives with the content returned by `resolve`,
+/// recursively up to `max_depth` levels.
+///
+/// Paths are passed to `resolve` as written, with a leading `./` removed.
+/// An included line keeps the indentation of its directive, so includes
+/// work inside list items. Unresolved includes become
+/// `<!-- include error: ... -->` comments.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use ox_content_parser::resolve_includes;
+///
+/// let files = HashMap::from([("note.md".to_string(), "> Note".to_string())]);
+/// let resolved = resolve_includes("@include(note.md)\n", |path| files.get(path).map(String::as_str), 8);
+/// assert_eq!(resolved.source, "> Note\n");
+/// ```
+pub fn resolve_includes<'r>(
+    source: &str,
+    resolve: impl Fn(&str) -> Option<&'r str>,
+    max_depth: usize,
+) -> ResolvedIncludes {
+    let mut resolver = Resolver { resolve, max_depth, stack: Vec::new(), errors: Vec::new() };
+    let mut output = String::with_capacity(source.len());
+    resolver.expand(source, "", &mut output);
+    ResolvedIncludes { source: output, errors: resolver.errors }
+}
+
+struct Resolver<'r, F: Fn(&str) -> Option<&'r str>> {
+    resolve: F,
+    max_depth: usize,
+    stack: Vec<String>,
+    errors: Vec<IncludeError>,
+}
+
+impl<'r, F: Fn(&str) -> Option<&'r str>> Resolver<'r, F> {
+    fn expand(&mut self, source: &str, indent: &str, output: &mut String) {
+        let mut fence: Option<(u8, usize)> = None;
+
+        for line in source.split_inclusive('\n') {
+            let content = line.trim_end_matches(['\n', '\r']);
+            let newline = &line[content.len()..];
+
+            if let Some(run) = fence_run(content) {
+                match fence {
+                    None => fence = Some(run),
+                    Some((marker, len)) if run.0 == marker && run.1 >= len => fence = None,
+                    Some(_) => {}
+                }
+            }
+
+            let directive = if fence.is_none() { include_path(content) } else { None };
+            let Some(path) = directive else {
+                if !content.is_empty() {
+                    output.push_str(indent);
+                }
+                output.push_str(line);
+                continue;
+            };
+
+            let line_indent = &content[..content.len() - content.trim_start().len()];
+            let nested_indent = format!("{indent}{line_indent}");
+            match self.include(path) {
+                Ok(included) => {
+                    self.stack.push(path.to_string());
+                    let start = output.len();
+                    self.expand(included, &nested_indent, output);
+                    self.stack.pop();
+                    if output.len() > start && !output.ends_with('\n') {
+                        output.push_str(if newline.is_empty() { "\n" } else { newline });
+                    }
+                }
+                Err(error) => {
+                    output.push_str(&nested_indent);
+                    output.push_str("<!-- include error: ");
+                    output.push_str(&error.to_string().replace("--", "- -"));
+                    output.push_str(" -->");
+                    output.push_str(newline);
+                    self.errors.push(error);
+                }
+            }
+        }
+    }
+
+    fn include(&self, path: &str) -> Result<&'r str, IncludeError> {
+        if self.stack.iter().any(|open| open == path) {
+            return Err(IncludeError::Cycle { path: path.to_string() });
+        }
+        if self.stack.len() >= self.max_depth {
+            return Err(IncludeError::TooDeep { path: path.to_string(), limit: self.max_depth });
+        }
+        (self.resolve)(path).ok_or_else(|| IncludeError::NotFound { path: path.to_string() })
+    }
+}
+
+/// Returns the marker and length of a code fence opening or closing line.
+fn fence_run(line: &str) -> Option<(u8, usize)> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let marker = *trimmed.as_bytes().first()?;
+    if marker != b'`' && marker != b'~' {
+        return None;
+    }
+    let len = trimmed.bytes().take_while(|&b| b == marker).count();
+    (len >= 3).then_some((marker, len))
+}
+
+/// Returns the path of an include directive line.
+fn include_path(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let path = if let Some(inner) = line.strip_prefix("{{<").and_then(|l| l.strip_suffix(">}}")) {
+        unquote(inner.trim().strip_prefix("include")?.trim())?
+    } else {
+        let inner = line.strip_prefix("@include(")?.strip_suffix(')')?.trim();
+        unquote(inner).unwrap_or(inner)
+    };
+    let path = path.strip_prefix("./").unwrap_or(path);
+    (!path.is_empty()).then_some(path)
+}
+
+/// Removes matching single or double quotes.
+fn unquote(text: &str) -> Option<&str> {
+    ['"', '\'']
+        .into_iter()
+        .find_map(|quote| text.strip_prefix(quote).and_then(|t| t.strip_suffix(quote)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn files(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(path, content)| ((*path).to_string(), (*content).to_string()))
+            .collect()
+    }
+
+    fn expand(source: &str, files: &HashMap<String, String>) -> ResolvedIncludes {
+        resolve_includes(
+            source,
+            |path| files.get(path).map(String::as_str),
+            DEFAULT_MAX_INCLUDE_DEPTH,
+        )
+    }
+
+    #[test]
+    fn test_include_forms() {
+        let files = files(&[("a.md", "Alpha"), ("b.md", "Beta\n")]);
+        let resolved =
+            expand("{{< include \"a.md\" >}}\n@include(./b.md)\n@include('a.md')", &files);
+        assert_eq!(resolved.source, "Alpha\nBeta\nAlpha\n");
+        assert!(resolved.errors.is_empty());
+
+        // Not directives: inline mentions and fenced code.
+        let source = "Use @include(a.md) inline.\n```\n@include(a.md)\n```\n";
+        assert_eq!(expand(source, &files).source, source);
+    }
+
+    #[test]
+    fn test_include_nested_and_indented() {
+        let files =
+            files(&[("outer.md", "Outer\n\n@include(inner.md)\n"), ("inner.md", "- one\n- two\n")]);
+        let resolved = expand("- item\n\n  @include(outer.md)\n", &files);
+        assert_eq!(resolved.source, "- item\n\n  Outer\n\n  - one\n  - two\n");
+    }
+
+    #[test]
+    fn test_include_errors() {
+        let files = files(&[("loop.md", "@include(loop.md)"), ("deep.md", "@include(deep2.md)")]);
+        let resolved = expand("@include(missing.md)\n@include(loop.md)\n", &files);
+        assert_eq!(
+            resolved.source,
+            "<!-- include error: include not found: missing.md -->\n<!-- include error: include cycle: loop.md includes itself -->\n"
+        );
+        assert_eq!(
+            resolved.errors,
+            [
+                IncludeError::NotFound { path: "missing.md".to_string() },
+                IncludeError::Cycle { path: "loop.md".to_string() },
+            ]
+        );
+
+        let resolved =
+            resolve_includes("@include(deep.md)", |path| files.get(path).map(String::as_str), 1);
+        assert_eq!(
+            resolved.errors,
+            [IncludeError::TooDeep { path: "deep2.md".to_string(), limit: 1 }]
+        );
+    }
+}
@@ -8,6 +8,10 @@
 //! - Arena-based allocation for zero-copy parsing
 //! - CommonMark compliant with GFM extensions
 //! - Pluggable architecture for custom syntax extensions
+//! - HTML entity and numeric character reference decoding
+//! - Structural lint checks that run without rendering
+//! - Link validation against the known pages of a site
+//! - Include directives resolved before parsing
 //!
 //! # Example
 //!
@@ -21,12 +25,26 @@
 //! let document = parser.parse();
 //! ```
 
+mod clock;
+mod entities;
+mod entity;
 mod error;
+mod features;
+mod include;
 mod lexer;
+mod lint;
 mod parser;
+mod stats;
 
+pub use entity::{decode_entities, decode_entity};
 pub use error::{ParseError, ParseResult};
-pub use parser::{Parser, ParserOptions};
+pub use features::{Feature, FeaturePreset, Features};
+pub use include::{resolve_includes, IncludeError, ResolvedIncludes, DEFAULT_MAX_INCLUDE_DEPTH};
+pub use lint::{
+    lint, validate_links, BrokenLink, LintContext, LintIssue, LintOptions, LintRule, Severity,
+};
+pub use parser::{Parser, ParserOptions, ParserPreset, DEFAULT_MAX_NESTING_DEPTH};
+pub use stats::{count_nodes, task_progress, ParseStats, TaskProgress};
 
 /// Parses Markdown source into an AST.
 ///
@@ -0,0 +1,735 @@
+//! Structural lint checks for Markdown sources.
+//!
+//! Linting parses the source but never renders it, so it is cheap enough to
+//! run over a whole documentation tree in CI.
+
+use std::collections::{HashMap, HashSet};
+
+use ox_content_allocator::Allocator;
+use ox_content_ast::{heading_slug, Heading, Image, Link, Position, SlugStyle, Visit};
+
+use crate::error::ParseResult;
+use crate::parser::{Parser, ParserOptions};
+
+/// A lint rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintRule {
+    /// Two headings produce the same anchor slug.
+    DuplicateHeadingSlug,
+    /// A relative link points to a file that is not in the known file set.
+    BrokenRelativeLink,
+    /// An image has no alt text.
+    ImageMissingAlt,
+    /// A heading is more than one level deeper than the previous heading.
+    HeadingLevelSkip,
+    /// A fenced code block or frontmatter block is never closed.
+    UnclosedContainer,
+    /// A list item is indented so that CommonMark nests it differently
+    /// from how it looks, or its indentation mixes tabs and spaces.
+    AmbiguousListIndent,
+    /// The source could not be parsed.
+    ParseError,
+}
+
+impl LintRule {
+    /// Returns the rule name used in reports.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::DuplicateHeadingSlug => "duplicate-heading-slug",
+            Self::BrokenRelativeLink => "broken-relative-link",
+            Self::ImageMissingAlt => "image-missing-alt",
+            Self::HeadingLevelSkip => "heading-level-skip",
+            Self::UnclosedContainer => "unclosed-container",
+            Self::AmbiguousListIndent => "ambiguous-list-indent",
+            Self::ParseError => "parse-error",
+        }
+    }
+
+    /// Returns the severity issues of this rule are reported with.
+    #[must_use]
+    pub const fn severity(self) -> Severity {
+        match self {
+            Self::BrokenRelativeLink | Self::UnclosedContainer | Self::ParseError => {
+                Severity::Error
+            }
+            Self::DuplicateHeadingSlug
+            | Self::ImageMissingAlt
+            | Self::HeadingLevelSkip
+            | Self::AmbiguousListIndent => Severity::Warning,
+        }
+    }
+}
+
+/// Severity of a lint issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// Likely a mistake, but the output is still usable.
+    Warning,
+    /// The output is broken.
+    Error,
+}
+
+impl Severity {
+    /// Returns the severity name used in reports.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// A single lint finding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    /// The rule that produced the issue.
+    pub rule: LintRule,
+    /// Human-readable description.
+    pub message: String,
+    /// 1-indexed line number.
+    pub line: u32,
+    /// Issue severity.
+    pub severity: Severity,
+}
+
+/// Lint options.
+///
+/// Every rule is enabled by default.
+#[derive(Debug, Clone)]
+pub struct LintOptions {
+    /// Parser options used to build the AST.
+    pub parser: ParserOptions,
+    /// Report headings that produce the same slug.
+    pub duplicate_heading_slug: bool,
+    /// Slug style used by `duplicate_heading_slug`; set it to the style the
+    /// renderer uses so the same headings collide.
+    pub slug_style: SlugStyle,
+    /// Report relative links to files outside [`LintContext::files`].
+    pub broken_relative_link: bool,
+    /// Report images without alt text.
+    pub image_missing_alt: bool,
+    /// Report heading level skips (e.g. h2 followed by h4).
+    pub heading_level_skip: bool,
+    /// Report unclosed fenced code blocks and frontmatter.
+    pub unclosed_container: bool,
+    /// Report list items whose indentation is ambiguous relative to the
+    /// parent item's marker width, or mixes tabs and spaces.
+    pub ambiguous_list_indent: bool,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        Self {
+            parser: ParserOptions::default(),
+            duplicate_heading_slug: true,
+            slug_style: SlugStyle::default(),
+            broken_relative_link: true,
+            image_missing_alt: true,
+            heading_level_skip: true,
+            unclosed_container: true,
+            ambiguous_list_indent: true,
+        }
+    }
+}
+
+/// Information about where the linted source lives.
+#[derive(Debug, Clone, Default)]
+pub struct LintContext {
+    /// Path of the linted file, relative to the content root
+    /// (e.g. `guide/intro.md`).
+    pub file: String,
+    /// Known files relative to the content root. Relative links are only
+    /// checked when this is set.
+    pub files: Option<HashSet<String>>,
+}
+
+/// Lints Markdown source without rendering it.
+///
+/// Issues are returned in source order.
+#[must_use]
+pub fn lint(source: &str, options: &LintOptions, context: &LintContext) -> Vec<LintIssue> {
+    let line = |offset| Position::from_offset(source, offset).line;
+    let mut issues = Vec::new();
+
+    let body = blank_frontmatter(source);
+    if body.is_none() && options.unclosed_container {
+        issues.push(issue(LintRule::UnclosedContainer, "frontmatter is never closed".into(), 1));
+    }
+    let body = body.unwrap_or_else(|| source.to_string());
+
+    if options.unclosed_container {
+        if let Some(line) = find_unclosed_fence(&body) {
+            let message = "fenced code block is never closed".to_string();
+            issues.push(issue(LintRule::UnclosedContainer, message, line));
+        }
+    }
+
+    if options.ambiguous_list_indent {
+        for (line, message) in find_ambiguous_list_indents(&body) {
+            issues.push(issue(LintRule::AmbiguousListIndent, message, line));
+        }
+    }
+
+    let allocator = Allocator::new();
+    let document = match Parser::with_options(&allocator, &body, options.parser.clone()).parse() {
+        Ok(document) => document,
+        Err(err) => {
+            let line = line(err.span().start);
+            issues.push(issue(LintRule::ParseError, err.to_string(), line));
+            return issues;
+        }
+    };
+
+    let mut collector = Collector { slug_style: options.slug_style, ..Collector::default() };
+    collector.visit_document(&document);
+
+    if options.duplicate_heading_slug || options.heading_level_skip {
+        let mut seen: HashMap<String, u32> = HashMap::new();
+        let mut prev_depth = None;
+        for heading in &collector.headings {
+            let line = line(heading.offset);
+            if options.heading_level_skip {
+                if let Some(prev) = prev_depth.filter(|&prev| heading.depth > prev + 1) {
+                    let message = format!("heading level skips from h{prev} to h{}", heading.depth);
+                    issues.push(issue(LintRule::HeadingLevelSkip, message, line));
+                }
+            }
+            prev_depth = Some(heading.depth);
+
+            if options.duplicate_heading_slug {
+                let slug = &heading.slug;
+                if let Some(first) = seen.get(slug) {
+                    let message =
+                        format!("duplicate heading slug \"{slug}\" (first used on line {first})");
+                    issues.push(issue(LintRule::DuplicateHeadingSlug, message, line));
+                } else {
+                    seen.insert(slug.clone(), line);
+                }
+            }
+        }
+    }
+
+    if options.image_missing_alt {
+        for (alt, offset) in &collector.images {
+            if alt.trim().is_empty() {
+                let message = "image has no alt text".to_string();
+                issues.push(issue(LintRule::ImageMissingAlt, message, line(*offset)));
+            }
+        }
+    }
+
+    if options.broken_relative_link {
+        if let Some(files) = &context.files {
+            for (url, offset) in &collector.links {
+                let Some(target) = resolve_link(&context.file, url) else {
+                    continue;
+                };
+                if !link_exists(files, &target) {
+                    let message = format!("link target \"{url}\" does not exist");
+                    issues.push(issue(LintRule::BrokenRelativeLink, message, line(*offset)));
+                }
+            }
+        }
+    }
+
+    issues.sort_by_key(|issue| issue.line);
+    issues
+}
+
+/// A link whose target is not a known page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenLink {
+    /// The link destination as written.
+    pub href: String,
+    /// 1-indexed line number.
+    pub line: u32,
+}
+
+/// Finds the links of `source` that point to a path outside `known_paths`,
+/// in source order.
+///
+/// Relative links are resolved against `base_url`, the URL path of the
+/// page (`/guide/intro` or `/guide/`); links starting with `/` are taken
+/// as they are. Query strings and fragments are ignored, and paths match
+/// with or without a trailing `/`, `index`, `.html` or `.md`. External
+/// URLs, protocol-relative URLs and same-page fragments are not checked.
+///
+/// # Errors
+///
+/// Returns the parse error when `options` make parsing fail.
+pub fn validate_links(
+    source: &str,
+    options: &ParserOptions,
+    known_paths: &[String],
+    base_url: &str,
+) -> ParseResult<Vec<BrokenLink>> {
+    let body = blank_frontmatter(source).unwrap_or_else(|| source.to_string());
+    let allocator = Allocator::new();
+    let document = Parser::with_options(&allocator, &body, options.clone()).parse()?;
+    let mut collector = Collector::default();
+    collector.visit_document(&document);
+
+    let known: HashSet<&str> = known_paths.iter().map(|path| normalize_page_path(path)).collect();
+    Ok(collector
+        .links
+        .into_iter()
+        .filter(|(url, _)| {
+            resolve_link(base_url, url)
+                .is_some_and(|target| !known.contains(normalize_page_path(&target)))
+        })
+        .map(|(href, offset)| BrokenLink { href, line: Position::from_offset(source, offset).line })
+        .collect())
+}
+
+/// Strips the parts of a page path that do not change the page: leading
+/// and trailing `/`, a `.html` or `.md` extension and a last `index`
+/// segment.
+fn normalize_page_path(path: &str) -> &str {
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".html").or_else(|| path.strip_suffix(".md")).unwrap_or(path);
+    if path == "index" {
+        return "";
+    }
+    path.strip_suffix("/index").unwrap_or(path)
+}
+
+fn issue(rule: LintRule, message: String, line: u32) -> LintIssue {
+    LintIssue { rule, message, line, severity: rule.severity() }
+}
+
+/// Heading data collected for linting.
+struct HeadingInfo {
+    depth: u8,
+    /// The heading ID before deduplication, as the renderer computes it.
+    slug: String,
+    offset: u32,
+}
+
+/// Collects the nodes the lint rules need from the AST.
+#[derive(Default)]
+struct Collector {
+    slug_style: SlugStyle,
+    headings: Vec<HeadingInfo>,
+    links: Vec<(String, u32)>,
+    images: Vec<(String, u32)>,
+}
+
+impl<'a> Visit<'a> for Collector {
+    fn visit_heading(&mut self, heading: &Heading<'a>) {
+        self.headings.push(HeadingInfo {
+            depth: heading.depth,
+            slug: heading_slug(heading, self.slug_style),
+            offset: heading.span.start,
+        });
+        ox_content_ast::walk_heading(self, heading);
+    }
+
+    fn visit_link(&mut self, link: &Link<'a>) {
+        self.links.push((link.url.to_string(), link.span.start));
+        ox_content_ast::walk_link(self, link);
+    }
+
+    fn visit_image(&mut self, image: &Image<'a>) {
+        self.images.push((image.alt.to_string(), image.span.start));
+    }
+}
+
+/// Replaces a leading frontmatter block with spaces so byte offsets and line
+/// numbers stay aligned with the original source.
+///
+/// Returns `None` when the frontmatter is opened but never closed.
+fn blank_frontmatter(source: &str) -> Option<String> {
+    if !source.starts_with("---\n") && !source.starts_with("---\r\n") {
+        return Some(source.to_string());
+    }
+    let close = source[3..].find("\n---")? + 3 + 4;
+    let blanked: String =
+        source[..close].chars().map(|c| if c == '\n' { '\n' } else { ' ' }).collect();
+    Some(blanked + &source[close..])
+}
+
+/// Returns the line of a fenced code block that is never closed.
+fn find_unclosed_fence(source: &str) -> Option<u32> {
+    let mut open: Option<(char, usize, u32)> = None;
+    for (i, line) in source.lines().enumerate() {
+        let trimmed = line.trim_start_matches(' ');
+        if line.len() - trimmed.len() > 3 {
+            continue;
+        }
+        let Some(ch) = trimmed.chars().next().filter(|&c| c == '`' || c == '~') else {
+            continue;
+        };
+        let len = trimmed.chars().take_while(|&c| c == ch).count();
+        if len < 3 {
+            continue;
+        }
+        match open {
+            None => open = Some((ch, len, i as u32 + 1)),
+            Some((open_ch, open_len, _))
+                if ch == open_ch && len >= open_len && trimmed[len..].trim().is_empty() =>
+            {
+                open = None;
+            }
+            Some(_) => {}
+        }
+    }
+    open.map(|(_, _, line)| line)
+}
+
+/// A list item that later lines can be nested in.
+struct OpenItem {
+    /// Column of the list marker.
+    marker: usize,
+    /// Column where the item content starts; nested items must reach it.
+    content: usize,
+    /// 1-indexed line of the item.
+    line: u32,
+}
+
+/// Returns list items whose indentation CommonMark reads differently from
+/// how it looks, with a message suggesting the fix.
+///
+/// An item indented past its parent's marker but short of the parent's
+/// content column is not nested (`1. a` needs `   - b`, not `  - b`), and
+/// one indented four or more columns past the content column is code.
+fn find_ambiguous_list_indents(source: &str) -> Vec<(u32, String)> {
+    let mut found = Vec::new();
+    let mut open: Vec<OpenItem> = Vec::new();
+    let mut fence: Option<(char, usize)> = None;
+    let mut prev_blank = true;
+
+    for (i, line) in source.lines().enumerate() {
+        let number = i as u32 + 1;
+        let trimmed = line.trim_start_matches([' ', '\t']);
+        if trimmed.is_empty() {
+            prev_blank = true;
+            continue;
+        }
+        let whitespace = &line[..line.len() - trimmed.len()];
+        let indent = indent_width(whitespace);
+
+        // Skip fenced code, wherever it is indented
+        let fence_char = trimmed.chars().next().filter(|&c| c == '`' || c == '~');
+        let fence_len = fence_char.map_or(0, |c| trimmed.chars().take_while(|&ch| ch == c).count());
+        match (fence, fence_char) {
+            (None, Some(c)) if fence_len >= 3 => {
+                fence = Some((c, fence_len));
+                continue;
+            }
+            (Some((open_char, open_len)), Some(c))
+                if c == open_char
+                    && fence_len >= open_len
+                    && trimmed[fence_len..].trim().is_empty() =>
+            {
+                fence = None;
+                continue;
+            }
+            (Some(_), _) => continue,
+            _ => {}
+        }
+
+        let marker_len = list_marker_len(trimmed);
+        if marker_len.is_none() || (open.is_empty() && indent >= 4) {
+            // A paragraph after a blank line ends the items it does not reach
+            if prev_blank {
+                open.retain(|item| item.content <= indent);
+            }
+            prev_blank = false;
+            continue;
+        }
+        let marker_len = marker_len.unwrap_or_default();
+        prev_blank = false;
+
+        if whitespace.contains('\t') && whitespace.contains(' ') {
+            let message = "list item indentation mixes tabs and spaces; indent with spaces only";
+            found.push((number, message.to_string()));
+        }
+
+        let mut as_code = false;
+        let mut warned = false;
+        while let Some(top) = open.last() {
+            if indent >= top.content {
+                if indent >= top.content + 4 {
+                    as_code = true;
+                    found.push((
+                        number,
+                        format!(
+                            "list item indented {} is read as code in the item on line {}; \
+                             indent it {} to nest it",
+                            columns(indent),
+                            top.line,
+                            columns(top.content)
+                        ),
+                    ));
+                }
+                break;
+            }
+            if indent > top.marker && !warned {
+                warned = true;
+                found.push((
+                    number,
+                    format!(
+                        "list item indented {} is not nested in the item on line {}; \
+                         indent it {} to nest it, or {} to continue that list",
+                        columns(indent),
+                        top.line,
+                        columns(top.content),
+                        columns(top.marker)
+                    ),
+                ));
+            }
+            open.pop();
+        }
+
+        if !as_code {
+            let after = &trimmed[marker_len..];
+            let spaces = after.len() - after.trim_start_matches(' ').len();
+            let padding =
+                if after.trim().is_empty() || !(1..=4).contains(&spaces) { 1 } else { spaces };
+            open.push(OpenItem {
+                marker: indent,
+                content: indent + marker_len + padding,
+                line: number,
+            });
+        }
+    }
+
+    found
+}
+
+/// Returns the width of leading whitespace, with tab stops every 4 columns.
+fn indent_width(whitespace: &str) -> usize {
+    whitespace.chars().fold(0, |width, c| if c == '\t' { width + 4 - width % 4 } else { width + 1 })
+}
+
+/// Returns the length of the list marker (`-`, `*`, `+`, `1.` or `1)`) that
+/// starts `line`, if any. Thematic breaks such as `* * *` are not markers.
+fn list_marker_len(line: &str) -> Option<usize> {
+    let bytes = line.as_bytes();
+    let digits = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+    let len = match bytes.first()? {
+        b'-' | b'*' | b'+' => 1,
+        _ if (1..=9).contains(&digits) && matches!(bytes.get(digits), Some(b'.' | b')')) => {
+            digits + 1
+        }
+        _ => return None,
+    };
+    if !matches!(bytes.get(len), None | Some(b' ' | b'\t')) {
+        return None;
+    }
+
+    let mut symbols = line.chars().filter(|c| !c.is_whitespace());
+    let first = symbols.next()?;
+    let thematic_break = matches!(first, '-' | '*' | '_')
+        && line.chars().filter(|c| !c.is_whitespace()).count() >= 3
+        && symbols.all(|c| c == first);
+    (!thematic_break).then_some(len)
+}
+
+/// Formats a column count as `1 space` or `N spaces`.
+fn columns(n: usize) -> String {
+    if n == 1 {
+        "1 space".to_string()
+    } else {
+        format!("{n} spaces")
+    }
+}
+
+/// Resolves a link URL against `file`, the linted file or the URL path of
+/// the page.
+///
+/// Returns `None` for links that are not checked: external URLs,
+/// protocol-relative URLs and same-page fragments.
+fn resolve_link(file: &str, url: &str) -> Option<String> {
+    let path = url.split(['#', '?']).next().unwrap_or_default();
+    if path.is_empty() || path.starts_with("//") || has_scheme(path) {
+        return None;
+    }
+
+    let mut segments: Vec<&str> = Vec::new();
+    if !path.starts_with('/') {
+        segments.extend(file.split('/'));
+        segments.pop();
+    }
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+    Some(segments.join("/"))
+}
+
+/// Returns true if `path` starts with a URL scheme such as `https:`.
+fn has_scheme(path: &str) -> bool {
+    path.split_once(':').is_some_and(|(scheme, _)| {
+        !scheme.is_empty()
+            && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    })
+}
+
+/// Returns true if a resolved link target matches a known file, allowing
+/// extensionless links to pages and directory links to index pages.
+fn link_exists(files: &HashSet<String>, target: &str) -> bool {
+    let target = target.trim_end_matches('/');
+    files.contains(target)
+        || files.contains(&format!("{target}.md"))
+        || files.contains(&format!("{target}/index.md"))
+        || (target.is_empty() && files.contains("index.md"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(issues: &[LintIssue]) -> Vec<(&'static str, u32)> {
+        issues.iter().map(|issue| (issue.rule.as_str(), issue.line)).collect()
+    }
+
+    #[test]
+    fn test_headings() {
+        let source = "# Intro\n\n## Setup\n\n#### Deep\n\n## Setup\n";
+        let issues = lint(source, &LintOptions::default(), &LintContext::default());
+        assert_eq!(rules(&issues), [("heading-level-skip", 5), ("duplicate-heading-slug", 7)]);
+        assert_eq!(issues[1].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_duplicate_slugs_follow_slug_style() {
+        // Combining accents and NBSP are handled like the renderer does.
+        let source = "# Cafe\u{301}\n\n# Cafe\n\n# Tab\u{a0}x\n\n# Tabx\n\n# 日本\n\n# 中国\n";
+        let issues = lint(source, &LintOptions::default(), &LintContext::default());
+        assert_eq!(rules(&issues), [("duplicate-heading-slug", 7)]);
+
+        // ASCII slugs transliterate accents, but keep the kanji headings
+        // apart by a hash.
+        let source = "# 日本\n\n# 中国\n\n# Café\n\n# Cafe\n";
+        assert!(lint(source, &LintOptions::default(), &LintContext::default()).is_empty());
+        let options = LintOptions { slug_style: SlugStyle::Ascii, ..Default::default() };
+        let issues = lint(source, &options, &LintContext::default());
+        assert_eq!(rules(&issues), [("duplicate-heading-slug", 7)]);
+
+        // An explicit ID collides with a slug.
+        let source = "# Setup\n\n# Install {#setup}\n";
+        let options = LintOptions {
+            parser: ParserOptions { attributes: true, ..ParserOptions::default() },
+            ..Default::default()
+        };
+        assert_eq!(
+            rules(&lint(source, &options, &LintContext::default())),
+            [("duplicate-heading-slug", 3)]
+        );
+    }
+
+    #[test]
+    fn test_image_missing_alt() {
+        let source = "![](a.png)\n\n![Logo](b.png)\n";
+        let issues = lint(source, &LintOptions::default(), &LintContext::default());
+        assert_eq!(rules(&issues), [("image-missing-alt", 1)]);
+    }
+
+    #[test]
+    fn test_broken_relative_links() {
+        let source =
+            "[a](./setup.md) [b](../api/) [c](missing.md#x) [d](https://x.dev) [e](#top)\n";
+        let files = ["guide/setup.md", "api/index.md"].map(String::from);
+        let context =
+            LintContext { file: "guide/intro.md".into(), files: Some(files.into_iter().collect()) };
+        let issues = lint(source, &LintOptions::default(), &context);
+        assert_eq!(rules(&issues), [("broken-relative-link", 1)]);
+        assert!(issues[0].message.contains("missing.md#x"));
+        assert_eq!(issues[0].severity, Severity::Error);
+
+        // Without a file set, links are not checked.
+        assert!(lint(source, &LintOptions::default(), &LintContext::default()).is_empty());
+    }
+
+    #[test]
+    fn test_validate_links() {
+        let source = "---\ntitle: x\n---\n\n[a](setup) [b](../api/#x) [c](missing)\n\n\
+                      - [d](/guide/setup.html) [e](/gone?q=1)\n- [f](https://x.dev) [g](#top) [h](./)\n";
+        let known = ["/guide/setup", "/api/index.html", "/guide/"].map(String::from);
+        let broken =
+            validate_links(source, &ParserOptions::default(), &known, "/guide/intro").unwrap();
+        assert_eq!(
+            broken,
+            [
+                BrokenLink { href: "missing".to_string(), line: 5 },
+                BrokenLink { href: "/gone?q=1".to_string(), line: 7 },
+            ]
+        );
+
+        // A directory base resolves links inside it.
+        let broken = validate_links("[a](setup)", &ParserOptions::default(), &known, "/guide/");
+        assert!(broken.unwrap().is_empty());
+        let broken = validate_links("[a](setup)", &ParserOptions::default(), &known, "/");
+        assert_eq!(broken.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_lines_in_lists_and_tables() {
+        let source = "# A\n\n- item\n- ![](a.png)\n\n| a |\n|---|\n| ![](b.png) |\n";
+        let options = LintOptions { parser: ParserOptions::gfm(), ..Default::default() };
+        let issues = lint(source, &options, &LintContext::default());
+        assert_eq!(rules(&issues), [("image-missing-alt", 4), ("image-missing-alt", 8)]);
+    }
+
+    #[test]
+    fn test_unclosed_containers() {
+        let source = "---\ntitle: x\n---\n\n# A\n\n```rust\nfn main() {}\n";
+        let issues = lint(source, &LintOptions::default(), &LintContext::default());
+        assert_eq!(rules(&issues), [("unclosed-container", 7)]);
+
+        let issues = lint("---\ntitle: x\n", &LintOptions::default(), &LintContext::default());
+        assert_eq!(rules(&issues), [("unclosed-container", 1)]);
+    }
+
+    #[test]
+    fn test_ambiguous_list_indent() {
+        // Indented past the parent marker, but short of its content column.
+        let source = "1. Install\n  - npm\n  - pnpm\n\n10. Build\n   - vite\n";
+        let issues = lint(source, &LintOptions::default(), &LintContext::default());
+        assert_eq!(rules(&issues), [("ambiguous-list-indent", 2), ("ambiguous-list-indent", 6)]);
+        assert_eq!(
+            issues[0].message,
+            "list item indented 2 spaces is not nested in the item on line 1; \
+             indent it 3 spaces to nest it, or 0 spaces to continue that list"
+        );
+        assert!(issues[1].message.contains("indent it 4 spaces to nest it"));
+        assert_eq!(issues[0].severity, Severity::Warning);
+
+        // One-space sibling and too-deep nesting.
+        let source = "- a\n - b\n- c\n      - d\n";
+        let issues = lint(source, &LintOptions::default(), &LintContext::default());
+        assert_eq!(rules(&issues), [("ambiguous-list-indent", 2), ("ambiguous-list-indent", 4)]);
+        assert!(issues[1].message.contains("is read as code in the item on line 3"));
+
+        // Mixed tabs and spaces.
+        let source = "- a\n \t- b\n";
+        let issues = lint(source, &LintOptions::default(), &LintContext::default());
+        assert_eq!(rules(&issues), [("ambiguous-list-indent", 2)]);
+        assert!(issues[0].message.contains("mixes tabs and spaces"));
+    }
+
+    #[test]
+    fn test_unambiguous_lists() {
+        let source = "- a\n  - b\n    - c\n- d\n\n1. one\n   - nested\n\n* * *\n\n```\n - x\n```\n\n    - code\n";
+        assert!(lint(source, &LintOptions::default(), &LintContext::default()).is_empty());
+    }
+
+    #[test]
+    fn test_rules_toggle() {
+        let source = "# A\n\n### B\n\n![](x.png)\n";
+        let options = LintOptions {
+            heading_level_skip: false,
+            image_missing_alt: false,
+            ..Default::default()
+        };
+        assert!(lint(source, &options, &LintContext::default()).is_empty());
+    }
+}
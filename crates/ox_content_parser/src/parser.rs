@@ -1,19 +1,33 @@
 //! Markdown parser implementation.
 
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
 use ox_content_allocator::{Allocator, Vec};
 use ox_content_ast::{
-    AlignKind, Document, Image, Link, List, ListItem, Node, Paragraph, Span, Table, TableCell,
-    TableRow, Text,
+    walk_footnote_definition, Abbreviation, AlignKind, BlockQuote, Break, CodeBlock, Definition,
+    Delete, Document, Emphasis, FootnoteDefinition, FootnoteReference, Heading, Html, Image,
+    InlineCode, InlineFootnote, InlineMath, Link, List, ListItem, Math, Node, Paragraph, Position,
+    Span, Strong, Table, TableCell, TableRow, Text, ThematicBreak, Visit,
 };
 
+use crate::clock::now;
+use crate::entity::{decode_entities, decode_entity};
 use crate::error::{ParseError, ParseResult};
+use crate::features::{Feature, Features};
+use crate::stats::{count_nodes, ParseStats};
+
+/// Default maximum nesting depth for blocks and inline elements.
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 100;
 
 /// Parser options.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct ParserOptions {
     /// Enable GFM (GitHub Flavored Markdown) extensions.
     pub gfm: bool,
-    /// Enable footnotes.
+    /// Enable footnotes: `[^label]` references with their definitions, and
+    /// inline `^[note]` footnotes.
     pub footnotes: bool,
     /// Enable task lists.
     pub task_lists: bool,
@@ -23,8 +37,154 @@ pub struct ParserOptions {
     pub strikethrough: bool,
     /// Enable autolinks.
     pub autolinks: bool,
-    /// Maximum nesting depth for block elements.
+    /// Maximum nesting depth for nested lists and inline elements such as
+    /// links and emphasis.
+    ///
+    /// Input nested deeper than this fails with
+    /// [`ParseError::NestingTooDeep`] instead of overflowing the stack.
     pub max_nesting_depth: usize,
+    /// Maximum input size in bytes. Larger input fails with
+    /// [`ParseError::InputTooLarge`] before any parsing happens.
+    pub max_input_bytes: Option<usize>,
+    /// Time budget for a single parse. Checked before every block and
+    /// inline run; once exceeded, parsing stops with
+    /// [`ParseError::TimeBudgetExceeded`].
+    ///
+    /// Ignored on `wasm32-unknown-unknown`, which has no clock.
+    pub time_budget: Option<Duration>,
+    /// Enable PHP Markdown Extra abbreviations (`*[HTML]: HyperText Markup Language`).
+    ///
+    /// Definitions apply to the whole document, produce no output of their
+    /// own, and match case-sensitively on whole words only.
+    pub abbreviations: bool,
+    /// Parse Markdown inside block-level HTML such as `<details>`.
+    ///
+    /// CommonMark keeps everything up to the next blank line after a
+    /// block-level tag as raw HTML. With this enabled, each line that starts
+    /// with a block-level tag becomes its own HTML node and the lines between
+    /// are parsed as Markdown. `<pre>`, `<script>`, `<style>`, `<textarea>`
+    /// and comments stay raw.
+    pub parse_inside_html_blocks: bool,
+    /// Enable TeX math: `$...$` inline, and `$$` ... `$$` blocks or code
+    /// fences with the language `math`, as on GitHub.
+    ///
+    /// An inline opener must not be followed by whitespace, and the closer
+    /// must not be preceded by whitespace or followed by a digit, so prices
+    /// such as `$5 and $10` stay text. Use `\$` for a literal dollar sign.
+    pub math: bool,
+    /// Text that becomes a line break inside table cells, such as `\n` or
+    /// `;;`.
+    ///
+    /// GFM cells are single lines, so `<br>` (also `<br/>` and `<br />`) is
+    /// always turned into a break in cells; this adds a delimiter that is
+    /// easier to write. Inline Markdown on either side of a break is parsed
+    /// as usual. Breaks inside code spans are kept as text.
+    pub table_cell_break: Option<String>,
+    /// Constructs the parser recognizes. Disabled ones are kept as their
+    /// literal source text, so a restricted set such as
+    /// [`FeaturePreset::Basic`](crate::FeaturePreset::Basic) can render
+    /// user comments without headings, images or HTML.
+    pub features: Features,
+    /// Collapse runs of spaces and tabs in text to a single space and drop
+    /// the spaces around line breaks, for generated content with ragged
+    /// whitespace. Code is left as written.
+    pub normalize_whitespace: bool,
+    /// Fail on input the parser would otherwise recover from silently, for
+    /// CI checks. In strict mode parsing fails with:
+    ///
+    /// - [`ParseError::UndefinedReference`] for a full (`[text][label]`) or
+    ///   collapsed (`[text][]`) link or image reference without a
+    ///   definition; shortcut references (`[text]`) stay text, as brackets
+    ///   are common in prose
+    /// - [`ParseError::UndefinedFootnote`] for a footnote reference without
+    ///   a definition, when footnotes are enabled
+    /// - [`ParseError::UnclosedBlock`] for a fenced code block or math block
+    ///   that is never closed
+    ///
+    /// Nesting, size and time limits fail in either mode. Not to be confused
+    /// with [`ParserPreset::GfmStrict`], which selects extensions.
+    pub strict: bool,
+    /// Read a trailing attribute block on headings, as in
+    /// `## Setup {#install .no-toc}`, as the heading's ID (`#id`) and
+    /// classes (`.name`) instead of text.
+    ///
+    /// The block must follow a space and holds at most one ID and any
+    /// number of classes, separated by spaces. Names may contain letters,
+    /// digits, `-`, `_`, `:` and `.`; a block with anything else stays
+    /// heading text.
+    pub attributes: bool,
+    /// Indentation, in columns past the list marker's own indentation, that
+    /// is enough to continue a list item after a blank line, such as `2`
+    /// for content written with two-space indentation. `0` (the default)
+    /// keeps the CommonMark rule.
+    ///
+    /// CommonMark requires continuation blocks and nested lists to line up
+    /// with the item's content, which is three columns after `1. ` and
+    /// more after `10. `. When set, an item continues from whichever of the
+    /// two columns comes first, and an indented code block inside it needs
+    /// four more columns than that. This diverges from CommonMark: content
+    /// that a CommonMark parser ends the list at is kept in the item.
+    pub list_indent: u8,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self {
+            gfm: false,
+            footnotes: false,
+            task_lists: false,
+            tables: false,
+            strikethrough: false,
+            autolinks: false,
+            max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH,
+            max_input_bytes: None,
+            time_budget: None,
+            abbreviations: false,
+            parse_inside_html_blocks: false,
+            math: false,
+            table_cell_break: None,
+            features: Features::all(),
+            normalize_whitespace: false,
+            strict: false,
+            attributes: false,
+            list_indent: 0,
+        }
+    }
+}
+
+/// Named sets of parser options.
+///
+/// A preset only chooses the starting point; individual fields can be
+/// overridden afterwards, e.g.
+/// `ParserOptions { autolinks: false, ..ParserOptions::gfm() }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParserPreset {
+    /// Plain CommonMark with every extension disabled.
+    CommonMark,
+    /// GitHub Flavored Markdown, including footnotes.
+    Gfm,
+    /// Only the extensions in the GFM spec: footnotes are disabled.
+    GfmStrict,
+}
+
+impl ParserPreset {
+    /// Returns the preset name (`commonmark`, `gfm` or `gfm_strict`).
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::CommonMark => "commonmark",
+            Self::Gfm => "gfm",
+            Self::GfmStrict => "gfm_strict",
+        }
+    }
+
+    /// Looks up a preset by name.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::CommonMark, Self::Gfm, Self::GfmStrict]
+            .into_iter()
+            .find(|preset| preset.as_str().eq_ignore_ascii_case(name))
+    }
 }
 
 impl ParserOptions {
@@ -38,7 +198,34 @@ impl ParserOptions {
             tables: true,
             strikethrough: true,
             autolinks: true,
-            max_nesting_depth: 100,
+            max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH,
+            max_input_bytes: None,
+            time_budget: None,
+            abbreviations: false,
+            parse_inside_html_blocks: false,
+            math: false,
+            table_cell_break: None,
+            features: Features::all(),
+            normalize_whitespace: false,
+            strict: false,
+            attributes: false,
+            list_indent: 0,
+        }
+    }
+
+    /// Creates new parser options with only the extensions from the GFM spec.
+    #[must_use]
+    pub fn gfm_strict() -> Self {
+        Self { footnotes: false, ..Self::gfm() }
+    }
+
+    /// Creates new parser options from a preset.
+    #[must_use]
+    pub fn from_preset(preset: ParserPreset) -> Self {
+        match preset {
+            ParserPreset::CommonMark => Self::default(),
+            ParserPreset::Gfm => Self::gfm(),
+            ParserPreset::GfmStrict => Self::gfm_strict(),
         }
     }
 }
@@ -55,33 +242,105 @@ pub struct Parser<'a> {
     position: usize,
     /// Current nesting depth.
     nesting_depth: usize,
+    /// Abbreviation definitions, longest first.
+    abbreviations: std::vec::Vec<(&'a str, &'a str)>,
+    /// Link reference definitions by normalized label, as URL and title.
+    definitions: HashMap<String, (&'a str, Option<&'a str>)>,
+    /// Instant after which parsing is aborted, if a time budget is set.
+    deadline: Option<Instant>,
 }
 
 impl<'a> Parser<'a> {
     /// Creates a new parser with default options.
     #[must_use]
     pub fn new(allocator: &'a Allocator, source: &'a str) -> Self {
-        Self { allocator, source, options: ParserOptions::default(), position: 0, nesting_depth: 0 }
+        Self::with_options(allocator, source, ParserOptions::default())
     }
 
     /// Creates a new parser with the specified options.
     #[must_use]
     pub fn with_options(allocator: &'a Allocator, source: &'a str, options: ParserOptions) -> Self {
-        Self { allocator, source, options, position: 0, nesting_depth: 0 }
+        Self {
+            allocator,
+            source,
+            options,
+            position: 0,
+            nesting_depth: 0,
+            abbreviations: std::vec::Vec::new(),
+            definitions: HashMap::new(),
+            deadline: None,
+        }
     }
 
     /// Parses the source into a document AST.
-    pub fn parse(mut self) -> ParseResult<Document<'a>> {
+    pub fn parse(self) -> ParseResult<Document<'a>> {
+        match self.parse_partial() {
+            (document, None) => Ok(document),
+            (_, Some(error)) => Err(error),
+        }
+    }
+
+    /// Parses the source like [`parse`](Self::parse), but keeps what was
+    /// parsed when an error stops parsing, for previews of documents that
+    /// are being edited.
+    ///
+    /// Returns the document with every block that was complete before the
+    /// error, and the error. Input over
+    /// [`max_input_bytes`](ParserOptions::max_input_bytes) gives an empty
+    /// document. Strict-mode footnote checks run on the whole document, so
+    /// their errors come with every block.
+    pub fn parse_partial(mut self) -> (Document<'a>, Option<ParseError>) {
         let mut children = self.allocator.new_vec();
+        let mut error = self.parse_blocks(&mut children).err();
+
+        let span = Span::new(0, self.source.len() as u32);
+        let document = Document { children, span };
+        if error.is_none() && self.options.strict && self.options.footnotes {
+            error = check_footnotes(&document, self.source).err();
+        }
+        (document, error)
+    }
+
+    /// Parses blocks into `children` until the end of the source or the
+    /// first error.
+    fn parse_blocks(&mut self, children: &mut Vec<'a, Node<'a>>) -> ParseResult<()> {
+        if let Some(limit) = self.options.max_input_bytes {
+            if self.source.len() > limit {
+                return Err(ParseError::InputTooLarge { size: self.source.len(), limit });
+            }
+        }
+        self.deadline = self.options.time_budget.and_then(|budget| Some(now()? + budget));
 
+        if self.options.abbreviations {
+            self.abbreviations = self.source.lines().filter_map(parse_abbreviation).collect();
+            self.abbreviations.sort_by_key(|(abbr, _)| std::cmp::Reverse(abbr.len()));
+        }
+        self.definitions = collect_link_definitions(self.source);
+        self.parse_remaining_blocks(children)
+    }
+
+    /// Parses blocks from the current position into `children` until the
+    /// end of the source.
+    fn parse_remaining_blocks(&mut self, children: &mut Vec<'a, Node<'a>>) -> ParseResult<()> {
         while !self.is_at_end() {
             if let Some(node) = self.parse_block()? {
                 children.push(node);
             }
         }
+        Ok(())
+    }
 
-        let span = Span::new(0, self.source.len() as u32);
-        Ok(Document { children, span })
+    /// Parses the source and reports node count, parse time and input size.
+    ///
+    /// The node count is taken from a traversal after parsing and is not
+    /// included in the measured time.
+    pub fn parse_with_stats(self) -> ParseResult<(Document<'a>, ParseStats)> {
+        let bytes = self.source.len();
+        let start = now();
+        let document = self.parse()?;
+        let parse_time = start.map_or(Duration::ZERO, |start| start.elapsed());
+        let stats = ParseStats { node_count: count_nodes(&document), parse_time, bytes };
+        Ok((document, stats))
     }
 
     /// Checks if we've reached the end of input.
@@ -117,14 +376,17 @@ impl<'a> Parser<'a> {
         }
     }
 
-    /// Skips blank lines.
+    /// Skips blank lines, including whitespace-only lines ending in `\r\n`.
     fn skip_blank_lines(&mut self) {
         while !self.is_at_end() {
             let start = self.position;
             self.skip_whitespace();
+            if self.peek() == Some('\r') {
+                self.advance();
+            }
             if self.peek() == Some('\n') {
                 self.advance();
-            } else {
+            } else if !self.is_at_end() {
                 self.position = start;
                 break;
             }
@@ -139,17 +401,29 @@ impl<'a> Parser<'a> {
             return Ok(None);
         }
 
-        // Check nesting depth
-        if self.nesting_depth > self.options.max_nesting_depth {
-            return Err(ParseError::NestingTooDeep {
-                span: Span::new(self.position as u32, self.position as u32),
-                max_depth: self.options.max_nesting_depth,
-            });
-        }
+        // Check nesting depth and time budget
+        self.check_nesting(self.nesting_depth, self.position)?;
+        self.check_deadline(self.position)?;
 
         let start = self.position;
 
+        // Abbreviation definitions were collected up front and render nothing
+        if self.try_parse_abbreviation() {
+            self.consume_line();
+            return Ok(None);
+        }
+
         // Try to parse different block types
+        if self.calc_indentation(start) >= CODE_INDENT && self.allows(Feature::CodeBlock) {
+            return Ok(self.parse_indented_code(start, CODE_INDENT));
+        }
+
+        self.parse_block_at(start)
+    }
+
+    /// Parses the block at `start`, the current position, once blank lines,
+    /// abbreviations and indented code have been ruled out.
+    fn parse_block_at(&mut self, start: usize) -> ParseResult<Option<Node<'a>>> {
         if self.try_parse_heading() {
             return self.parse_heading(start);
         }
@@ -162,30 +436,235 @@ impl<'a> Parser<'a> {
             return self.parse_fenced_code(start);
         }
 
-        if self.options.tables && self.try_parse_table() {
+        if self.current_line_is(RAW_OPEN) {
+            return Ok(Some(self.parse_raw_region(start)));
+        }
+
+        if self.try_parse_math_block() {
+            if let Some(math) = self.parse_math_block(start) {
+                return Ok(Some(math));
+            }
+            if self.options.strict {
+                let line = self.remaining().lines().next().unwrap_or_default();
+                let span = Span::new(start as u32, (start + line.len()) as u32);
+                let position = self.position_of(span);
+                return Err(ParseError::UnclosedBlock { span, position, block: "math block" });
+            }
+            // Unclosed: the opening line starts a paragraph
+            self.consume_line();
+            return self.parse_paragraph_lines(start, self.position);
+        }
+
+        if let Some(kind) = self.html_block_kind() {
+            return Ok(Some(self.parse_html_block(start, kind)));
+        }
+
+        if self.try_parse_footnote_definition() {
+            return self.parse_footnote_definition(start);
+        }
+
+        if self.try_parse_table() {
             return self.parse_table(start);
         }
 
+        if self.try_parse_block_quote() {
+            return self.parse_block_quote(start);
+        }
+
         if self.try_parse_list() {
             return self.parse_list(start);
         }
 
+        if let Some(definition) = self.parse_link_definition(start) {
+            return Ok(Some(definition));
+        }
+
         // Default: parse as paragraph
         self.parse_paragraph(start)
     }
 
+    /// Returns whether `feature` is enabled.
+    const fn allows(&self, feature: Feature) -> bool {
+        self.options.features.contains(feature)
+    }
+
+    /// Returns the line and column of the start of `span`.
+    fn position_of(&self, span: Span) -> Position {
+        Position::from_offset(self.source, span.start)
+    }
+
+    /// Fails if `depth` exceeds the configured maximum nesting depth.
+    fn check_nesting(&self, depth: usize, position: usize) -> ParseResult<()> {
+        if depth > self.options.max_nesting_depth {
+            let span = Span::new(position as u32, position as u32);
+            return Err(ParseError::NestingTooDeep {
+                span,
+                position: self.position_of(span),
+                max_depth: self.options.max_nesting_depth,
+            });
+        }
+        Ok(())
+    }
+
+    /// Fails if the time budget has run out.
+    fn check_deadline(&self, position: usize) -> ParseResult<()> {
+        if let (Some(deadline), Some(budget)) = (self.deadline, self.options.time_budget) {
+            if now().is_some_and(|now| now >= deadline) {
+                let span = Span::new(position as u32, position as u32);
+                return Err(ParseError::TimeBudgetExceeded {
+                    span,
+                    position: self.position_of(span),
+                    budget,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks if the current line is an abbreviation definition.
+    fn try_parse_abbreviation(&self) -> bool {
+        self.options.abbreviations
+            && parse_abbreviation(self.remaining().lines().next().unwrap_or("")).is_some()
+    }
+
+    /// Parses a `[label]: url "title"` link reference definition line. The
+    /// definition was already collected up front, so references before it
+    /// resolve too.
+    fn parse_link_definition(&mut self, start: usize) -> Option<Node<'a>> {
+        let line = self.remaining().lines().next().unwrap_or("");
+        let (label, url, title) = link_definition(line)?;
+        self.consume_line();
+        Some(Node::Definition(Definition {
+            identifier: self.allocator.alloc_str(&normalize_label(label)),
+            label: Some(label),
+            url,
+            title: title.map(|title| self.allocator.alloc_str(&decode_entities(title))),
+            span: Span::new(start as u32, (start + line.len()) as u32),
+        }))
+    }
+
+    /// Looks up the reference after link or image text ending at `close`
+    /// (the `]`): full `[text][label]`, collapsed `[text][]` or shortcut
+    /// `[text]`. Returns the definition's URL and title and the offset just
+    /// past the reference.
+    fn resolve_reference(
+        &self,
+        content: &str,
+        text_start: usize,
+        close: usize,
+    ) -> Option<(&'a str, Option<&'a str>, usize)> {
+        if self.definitions.is_empty() {
+            return None;
+        }
+        let text = &content[text_start..close];
+        let (label, end) = match content[close + 1..].strip_prefix('[') {
+            Some(rest) => {
+                let len = rest.find(']')?;
+                let label = if len == 0 { text } else { &rest[..len] };
+                (label, close + len + 3)
+            }
+            None => (text, close + 1),
+        };
+        let &(url, title) = self.definitions.get(&normalize_label(label))?;
+        Some((url, title, end))
+    }
+
+    /// In strict mode, fails when the unresolved link or image text ending
+    /// at `close` is followed by a full or collapsed reference.
+    fn check_reference(
+        &self,
+        content: &str,
+        offset: usize,
+        start: usize,
+        text_start: usize,
+        close: usize,
+    ) -> ParseResult<()> {
+        if !self.options.strict || close >= content.len() {
+            return Ok(());
+        }
+        let Some(rest) = content[close + 1..].strip_prefix('[') else {
+            return Ok(());
+        };
+        let Some(len) = rest.find(']') else {
+            return Ok(());
+        };
+        let label = if len == 0 { &content[text_start..close] } else { &rest[..len] };
+        let span = Span::new((offset + start) as u32, (offset + close + len + 3) as u32);
+        Err(ParseError::UndefinedReference {
+            span,
+            position: self.position_of(span),
+            label: label.to_string(),
+        })
+    }
+
+    /// Checks if the current line starts a footnote definition.
+    fn try_parse_footnote_definition(&self) -> bool {
+        self.options.footnotes
+            && footnote_definition_label(self.remaining().lines().next().unwrap_or("")).is_some()
+    }
+
+    /// Parses a footnote definition: the paragraph after the `[^label]:`
+    /// marker, followed by every block indented by at least four columns,
+    /// such as further paragraphs, lists and indented code.
+    fn parse_footnote_definition(&mut self, start: usize) -> ParseResult<Option<Node<'a>>> {
+        let line = self.remaining().lines().next().unwrap_or("");
+        let Some((label, marker_len)) = footnote_definition_label(line) else {
+            return self.parse_paragraph(start);
+        };
+        self.nesting_depth += 1;
+        self.check_nesting(self.nesting_depth, start)?;
+
+        let mut children = self.allocator.new_vec();
+        let content_start = start + marker_len;
+        self.consume_line();
+        if let Some(paragraph) = self.parse_paragraph_lines(content_start, self.position)? {
+            children.push(paragraph);
+        }
+
+        // Blocks indented to the content column continue the definition
+        let mut end = self.position;
+        loop {
+            let before = self.position;
+            self.skip_blank_lines();
+            if self.is_at_end() || self.calc_indentation(self.position) < CODE_INDENT {
+                self.position = before;
+                break;
+            }
+            let block_start = self.position;
+            let block = self.parse_list_item_block(CODE_INDENT)?;
+            if self.position == block_start {
+                self.position = before;
+                break;
+            }
+            children.extend(block);
+            end = self.position;
+        }
+        self.position = end;
+        self.nesting_depth -= 1;
+
+        Ok(Some(Node::FootnoteDefinition(FootnoteDefinition {
+            identifier: label,
+            label: Some(label),
+            children,
+            span: Span::new(start as u32, end as u32),
+        })))
+    }
+
     /// Checks if the current position starts a list.
     fn try_parse_list(&self) -> bool {
+        if !self.allows(Feature::List) {
+            return false;
+        }
         let remaining = self.remaining();
         let line = remaining.lines().next().unwrap_or("");
         let trimmed = line.trim_start();
 
-        // Unordered list: starts with -, *, or + followed by space
-        if trimmed.starts_with("- ") || trimmed.starts_with("* ") || trimmed.starts_with("+ ") {
+        // Unordered list: starts with -, *, or + followed by a space or tab
+        if trimmed.starts_with(['-', '*', '+']) && trimmed[1..].starts_with([' ', '\t']) {
             return true;
         }
 
-        // Ordered list: starts with digit(s) followed by . or ) and space
+        // Ordered list: starts with digit(s) followed by . or ) and a space or tab
         let mut chars = trimmed.chars().peekable();
         let mut has_digit = false;
         while let Some(ch) = chars.peek() {
@@ -198,7 +677,7 @@ impl<'a> Parser<'a> {
         }
         if has_digit {
             if let Some(ch) = chars.next() {
-                if (ch == '.' || ch == ')') && chars.peek() == Some(&' ') {
+                if (ch == '.' || ch == ')') && matches!(chars.peek(), Some(' ' | '\t')) {
                     return true;
                 }
             }
@@ -207,18 +686,53 @@ impl<'a> Parser<'a> {
         false
     }
 
-    /// Calculates the indentation level (number of spaces) of the current line.
+    /// Calculates the indentation (in columns) of the line starting at `start`.
     fn calc_indentation(&self, start: usize) -> usize {
-        let mut indent = 0;
-        let bytes = self.source.as_bytes();
-        for byte in bytes.iter().skip(start) {
-            match byte {
-                b' ' => indent += 1,
-                b'\t' => indent += 4, // Assume tab is 4 spaces
-                _ => break,
+        line_indent(&self.source[start..])
+    }
+
+    /// Parses an indented code block whose lines are indented by at least
+    /// `indent` columns, removing that much indentation from each line.
+    ///
+    /// Tabs are expanded to 4-column tab stops only where they fall inside
+    /// the removed indentation; the rest of the line is kept verbatim.
+    /// Returns `None`, with the lines skipped, when they are all blank.
+    fn parse_indented_code(&mut self, start: usize, indent: usize) -> Option<Node<'a>> {
+        let mut value = String::new();
+        let mut blank_lines = 0;
+        let mut end = self.position;
+
+        while !self.is_at_end() {
+            let line_start = self.position;
+            let line = self.consume_line().trim_end_matches('\r');
+            if line.trim().is_empty() {
+                blank_lines += 1;
+                continue;
+            }
+            if line_indent(line) < indent {
+                self.position = line_start;
+                break;
             }
+            // Blank lines are only part of the block when more code follows
+            for _ in 0..blank_lines {
+                value.push('\n');
+            }
+            blank_lines = 0;
+            value.push_str(&strip_indent(line, indent));
+            value.push('\n');
+            end = self.position;
+        }
+        if value.is_empty() {
+            return None;
         }
-        indent
+        self.position = end;
+
+        Some(Node::CodeBlock(CodeBlock {
+            lang: None,
+            meta: None,
+            value: self.allocator.alloc_str(&value),
+            span: Span::new(start as u32, end as u32),
+        }))
     }
 
     /// Parses a list (ordered or unordered).
@@ -246,6 +760,8 @@ impl<'a> Parser<'a> {
         };
 
         let mut children: Vec<'a, ListItem<'a>> = self.allocator.new_vec();
+        // Column where the content of the last item starts
+        let mut content_column = 0;
 
         loop {
             if self.is_at_end() {
@@ -255,6 +771,24 @@ impl<'a> Parser<'a> {
             let line_start = self.position;
             self.skip_whitespace();
             if self.peek() == Some('\n') || self.is_at_end() {
+                // Content indented to the last item's content column after a
+                // blank line continues that item
+                self.skip_blank_lines();
+                if !children.is_empty()
+                    && !self.is_at_end()
+                    && self.calc_indentation(self.position) >= content_column
+                {
+                    let block_start = self.position;
+                    let block = self.parse_list_item_block(content_column)?;
+                    if self.position > block_start {
+                        if let Some(last_item) = children.last_mut() {
+                            last_item.spread = true;
+                            last_item.span = Span::new(last_item.span.start, self.position as u32);
+                            last_item.children.extend(block);
+                        }
+                        continue;
+                    }
+                }
                 self.position = line_start; // Backtrack to handle end of block
                 break;
             }
@@ -274,7 +808,11 @@ impl<'a> Parser<'a> {
                 self.position = line_start; // Reset position to check marker properly
                 if self.try_parse_list() {
                     // Parse nested list
-                    if let Some(Node::List(nested_list)) = self.parse_list(line_start)? {
+                    self.nesting_depth += 1;
+                    self.check_nesting(self.nesting_depth, line_start)?;
+                    let nested = self.parse_list(line_start)?;
+                    self.nesting_depth -= 1;
+                    if let Some(Node::List(nested_list)) = nested {
                         // Add to the LAST item's children
                         if let Some(last_item) = children.last_mut() {
                             last_item.children.push(Node::List(nested_list));
@@ -303,9 +841,8 @@ impl<'a> Parser<'a> {
             let trimmed = line.trim_start();
 
             // Check marker
-            let (is_list_item, content, checked) = if trimmed.starts_with("- ")
-                || trimmed.starts_with("* ")
-                || trimmed.starts_with("+ ")
+            let (is_list_item, content, checked) = if trimmed.starts_with(['-', '*', '+'])
+                && trimmed[1..].starts_with([' ', '\t'])
             {
                 let mut content = &trimmed[2..];
                 let mut checked = None;
@@ -344,8 +881,9 @@ impl<'a> Parser<'a> {
                     chars.next().map_or_else(
                         || (false, String::new(), None),
                         |ch| {
-                            if (ch == '.' || ch == ')') && chars.peek() == Some(&' ') {
-                                chars.next(); // skip space
+                            if (ch == '.' || ch == ')') && matches!(chars.peek(), Some(' ' | '\t'))
+                            {
+                                chars.next(); // skip space or tab
                                 let content: String = chars.collect();
                                 (true, content, None)
                             } else {
@@ -365,6 +903,22 @@ impl<'a> Parser<'a> {
                 break;
             }
 
+            // Content starts after the marker and up to four following columns
+            // of spaces or tabs
+            let marker_len = trimmed.find([' ', '\t']).unwrap_or(trimmed.len());
+            let marker_end = line_indent(line) + marker_len;
+            let gap = whitespace_end_column(&trimmed[marker_len..], marker_end) - marker_end;
+            content_column = marker_end + if (1..=4).contains(&gap) { gap } else { 1 };
+            if self.options.list_indent > 0 {
+                content_column =
+                    content_column.min(line_indent(line) + usize::from(self.options.list_indent));
+            }
+
+            // Item content is always a suffix of the line
+            let item_start = self.position;
+            let content_start = item_start + line.len() - content.len();
+            let content_end = item_start + line.len();
+
             // Consume line
             while let Some(ch) = self.peek() {
                 self.advance();
@@ -375,14 +929,18 @@ impl<'a> Parser<'a> {
 
             // Create list item
             let content_str = self.allocator.alloc_str(&content);
-            let item_children_inline = self.parse_inline(content_str, 0)?;
+            let item_children_inline =
+                self.parse_inline(content_str, content_start, self.nesting_depth)?;
 
             // Wrap in Paragraph
             let mut para_children = self.allocator.new_vec();
             for child in item_children_inline {
                 para_children.push(child);
             }
-            let para = Paragraph { children: para_children, span: Span::new(0, 0) };
+            let para = Paragraph {
+                children: para_children,
+                span: Span::new(content_start as u32, content_end as u32),
+            };
 
             let mut list_item_children = self.allocator.new_vec();
             list_item_children.push(Node::Paragraph(para));
@@ -391,7 +949,7 @@ impl<'a> Parser<'a> {
                 checked,
                 spread: false,
                 children: list_item_children,
-                span: Span::new(0, 0),
+                span: Span::new(item_start as u32, content_end as u32),
             };
             children.push(list_item);
         }
@@ -400,8 +958,114 @@ impl<'a> Parser<'a> {
         Ok(Some(Node::List(List { ordered, start: list_start, spread: false, children, span })))
     }
 
+    /// Parses a block that continues a list item whose content starts at
+    /// `content_column`.
+    ///
+    /// Four more columns start an indented code block. Anything else is
+    /// parsed from its first non-blank character like a top-level block, so
+    /// headings, fences and block quotes keep their meaning inside the item.
+    fn parse_list_item_block(&mut self, content_column: usize) -> ParseResult<Option<Node<'a>>> {
+        let start = self.position;
+        if self.calc_indentation(start) >= content_column + CODE_INDENT {
+            return Ok(self.parse_indented_code(start, content_column + CODE_INDENT));
+        }
+        // Nested lists measure their indentation from the start of the line
+        if self.try_parse_list() {
+            self.nesting_depth += 1;
+            self.check_nesting(self.nesting_depth, start)?;
+            let nested = self.parse_list(start);
+            self.nesting_depth -= 1;
+            return nested;
+        }
+        self.skip_whitespace();
+        self.parse_block_at(self.position)
+    }
+
+    /// Checks if the current line starts a block quote.
+    fn try_parse_block_quote(&self) -> bool {
+        let line = self.remaining().lines().next().unwrap_or("");
+        self.allows(Feature::BlockQuote)
+            && line_indent(line) < CODE_INDENT
+            && line.trim_start_matches([' ', '\t']).starts_with('>')
+    }
+
+    /// Parses a block quote: consecutive lines starting with `>`, along with
+    /// lazy continuation lines that carry on its text.
+    ///
+    /// The `>` and one column of the space or tab after it are removed from
+    /// each line, and the rest is parsed as blocks of its own.
+    fn parse_block_quote(&mut self, start: usize) -> ParseResult<Option<Node<'a>>> {
+        self.nesting_depth += 1;
+        self.check_nesting(self.nesting_depth, start)?;
+
+        // The quoted text, and for each of its lines the offset where it
+        // starts, the source offset it was taken from and the columns of a
+        // tab that were written out as spaces
+        let mut text = String::new();
+        let mut lines = std::vec::Vec::new();
+        let mut lazy = false;
+        while !self.is_at_end() {
+            let line_start = self.position;
+            let line = self.remaining().split_inclusive('\n').next().unwrap_or("");
+            let marker_column = line_indent(line);
+            let trimmed = line.trim_start_matches([' ', '\t']);
+            if let Some(rest) = trimmed.strip_prefix('>').filter(|_| marker_column < CODE_INDENT) {
+                let content = rest.trim_start_matches([' ', '\t']);
+                let padding = whitespace_end_column(rest, marker_column + 1)
+                    .saturating_sub(marker_column + 2);
+                lines.push((text.len(), line_start + line.len() - content.len(), padding));
+                text.push_str(&" ".repeat(padding));
+                text.push_str(content);
+                lazy = !content.trim().is_empty();
+            } else if lazy && !line.trim().is_empty() && !self.interrupts_paragraph() {
+                lines.push((text.len(), line_start, 0));
+                text.push_str(line);
+            } else {
+                break;
+            }
+            self.position = line_start + line.len();
+        }
+
+        // A span that ends where a line starts ends with the line before it
+        let offset = |offset: u32, end: bool| {
+            let offset = offset as usize;
+            let line = lines
+                .partition_point(|&(at, _, _)| at < offset || !end && at == offset)
+                .saturating_sub(1);
+            let (at, source, padding) = lines[line];
+            (source + (offset - at).saturating_sub(padding)) as u32
+        };
+        let map = |span: Span| Span::new(offset(span.start, false), offset(span.end, true));
+        let mut quote = Parser {
+            allocator: self.allocator,
+            source: self.allocator.alloc_str(&text),
+            options: self.options.clone(),
+            position: 0,
+            nesting_depth: self.nesting_depth,
+            abbreviations: std::mem::take(&mut self.abbreviations),
+            definitions: std::mem::take(&mut self.definitions),
+            deadline: self.deadline,
+        };
+        let mut children = self.allocator.new_vec();
+        let result = quote.parse_remaining_blocks(&mut children);
+        self.abbreviations = quote.abbreviations;
+        self.definitions = quote.definitions;
+        if let Err(error) = result {
+            let span = map(error.span());
+            return Err(error.relocated(span, self.position_of(span)));
+        }
+        remap_spans(&mut children, &map);
+        self.nesting_depth -= 1;
+
+        let span = Span::new(start as u32, self.position as u32);
+        Ok(Some(Node::BlockQuote(BlockQuote { children, span })))
+    }
+
     /// Checks if the current position starts a heading.
     fn try_parse_heading(&self) -> bool {
+        if !self.allows(Feature::Heading) {
+            return false;
+        }
         let remaining = self.remaining();
         let mut chars = remaining.chars().peekable();
         let mut hash_count = 0;
@@ -419,6 +1083,9 @@ impl<'a> Parser<'a> {
 
     /// Checks if the current position starts a thematic break.
     fn try_parse_thematic_break(&self) -> bool {
+        if !self.allows(Feature::ThematicBreak) {
+            return false;
+        }
         let remaining = self.remaining();
         let line = remaining.lines().next().unwrap_or("");
         let trimmed = line.trim();
@@ -439,11 +1106,15 @@ impl<'a> Parser<'a> {
     /// Checks if the current position starts a fenced code block.
     fn try_parse_fenced_code(&self) -> bool {
         let remaining = self.remaining();
-        remaining.starts_with("```") || remaining.starts_with("~~~")
+        self.allows(Feature::CodeBlock) && remaining.starts_with("```")
+            || remaining.starts_with("~~~")
     }
 
     /// Checks if the current position starts a table.
     fn try_parse_table(&self) -> bool {
+        if !self.options.tables || !self.allows(Feature::Table) {
+            return false;
+        }
         let remaining = self.remaining();
         let lines: std::vec::Vec<&str> = remaining.lines().take(2).collect();
 
@@ -501,6 +1172,12 @@ impl<'a> Parser<'a> {
         // Skip trailing hashes and whitespace
         let content = self.source[content_start..content_end].trim_end();
         let content = content.trim_end_matches('#').trim_end();
+        let mut classes = self.allocator.new_vec();
+        let (content, id) = if self.options.attributes {
+            split_heading_attributes(content, &mut classes)
+        } else {
+            (content, None)
+        };
 
         // Consume newline
         if self.peek() == Some('\n') {
@@ -511,12 +1188,12 @@ impl<'a> Parser<'a> {
 
         // Parse inline content
         let children = if !content.is_empty() {
-            self.parse_inline(content, content_start)?
+            self.parse_inline(content, content_start, self.nesting_depth)?
         } else {
             self.allocator.new_vec()
         };
 
-        Ok(Some(Node::Heading(ox_content_ast::Heading { depth, children, span })))
+        Ok(Some(Node::Heading(ox_content_ast::Heading { depth, children, id, classes, span })))
     }
 
     /// Parses a thematic break.
@@ -534,7 +1211,13 @@ impl<'a> Parser<'a> {
     }
 
     /// Parses a fenced code block.
+    ///
+    /// An indented fence, such as one inside a list item, has its own
+    /// indentation removed from each line of code, and its closing fence may
+    /// be indented by up to three more columns.
     fn parse_fenced_code(&mut self, start: usize) -> ParseResult<Option<Node<'a>>> {
+        let fence_line = self.source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let indent = line_indent(&self.source[fence_line..]);
         let fence_char = self.peek().unwrap();
         let mut fence_len = 0;
 
@@ -575,12 +1258,20 @@ impl<'a> Parser<'a> {
 
         loop {
             if self.is_at_end() {
+                if self.options.strict {
+                    let span = Span::new(start as u32, info_start as u32);
+                    let position = self.position_of(span);
+                    return Err(ParseError::UnclosedBlock { span, position, block: "code fence" });
+                }
                 break;
             }
 
             let line_start = self.position;
 
             // Check for closing fence
+            if line_indent(self.remaining()) < indent + CODE_INDENT {
+                self.skip_whitespace();
+            }
             let mut closing_fence_len = 0;
             while self.peek() == Some(fence_char) {
                 closing_fence_len += 1;
@@ -611,12 +1302,128 @@ impl<'a> Parser<'a> {
             content_end = self.position;
         }
 
-        let value = self.allocator.alloc_str(&self.source[content_start..content_end]);
+        let code = &self.source[content_start..content_end];
+        let value = if indent == 0 {
+            self.allocator.alloc_str(code)
+        } else {
+            let lines = code.split_inclusive('\n').map(|line| strip_indent(line, indent));
+            self.allocator.alloc_str(&lines.collect::<String>())
+        };
         let span = Span::new(start as u32, self.position as u32);
 
+        // GitHub renders ```math fences as display math
+        if self.options.math && lang == Some("math") {
+            return Ok(Some(Node::Math(Math { value: value.trim(), span })));
+        }
+
         Ok(Some(Node::CodeBlock(ox_content_ast::CodeBlock { lang, meta, value, span })))
     }
 
+    /// Returns the kind of HTML block starting on the current line, if any.
+    fn html_block_kind(&self) -> Option<HtmlBlockKind> {
+        if !self.allows(Feature::Html) {
+            return None;
+        }
+        html_block_kind(self.remaining().lines().next().unwrap_or("").trim_start())
+    }
+
+    /// Returns whether the current line, ignoring surrounding whitespace, is `marker`.
+    fn current_line_is(&self, marker: &str) -> bool {
+        self.remaining().lines().next().is_some_and(|line| line.trim() == marker)
+    }
+
+    /// Parses a `<!-- raw -->` ... `<!-- /raw -->` region into a code block
+    /// with the `raw` meta, so its content is rendered verbatim. An
+    /// unterminated region runs to the end of the document.
+    fn parse_raw_region(&mut self, start: usize) -> Node<'a> {
+        self.consume_line();
+        let content_start = self.position;
+        let mut content_end = self.source.len();
+        while !self.is_at_end() {
+            let line_start = self.position;
+            if self.current_line_is(RAW_CLOSE) {
+                content_end = line_start;
+                self.consume_line();
+                break;
+            }
+            self.consume_line();
+        }
+
+        Node::CodeBlock(CodeBlock {
+            lang: None,
+            meta: Some("raw"),
+            value: &self.source[content_start..content_end],
+            span: Span::new(start as u32, self.position as u32),
+        })
+    }
+
+    /// Checks if the current line opens a `$$` math block.
+    fn try_parse_math_block(&self) -> bool {
+        self.options.math
+            && self
+                .remaining()
+                .lines()
+                .next()
+                .is_some_and(|line| line.trim_start().starts_with("$$"))
+    }
+
+    /// Parses a `$$` math block, either on one line (`$$x^2$$`) or running
+    /// to the next line ending in `$$`. Returns `None` without consuming
+    /// anything when the block is never closed, so it reads as a paragraph.
+    fn parse_math_block(&mut self, start: usize) -> Option<Node<'a>> {
+        let line = self.consume_line().trim_end_matches('\r');
+        let opened = &line.trim_start()[2..];
+        let content_start = self.offset_of(opened);
+
+        let content_end = if opened.trim_end().len() >= 2 && opened.trim_end().ends_with("$$") {
+            content_start + opened.trim_end().len() - 2
+        } else {
+            loop {
+                if self.is_at_end() {
+                    self.position = start;
+                    return None;
+                }
+                let line = self.consume_line().trim_end();
+                if let Some(body) = line.strip_suffix("$$") {
+                    break self.offset_of(body) + body.len();
+                }
+            }
+        };
+
+        let end = self.position;
+        Some(Node::Math(Math {
+            value: self.source[content_start..content_end].trim(),
+            span: Span::new(start as u32, end as u32),
+        }))
+    }
+
+    /// Parses an HTML block (CommonMark HTML block kinds 1, 2 and 6).
+    fn parse_html_block(&mut self, start: usize, kind: HtmlBlockKind) -> Node<'a> {
+        let mut end = self.position;
+        loop {
+            let line = self.consume_line();
+            let content_end = end + line.trim_end_matches('\r').len();
+            let done = match kind {
+                HtmlBlockKind::Raw(tag) => contains_closing_tag(line, tag),
+                HtmlBlockKind::Comment => line.contains("-->"),
+                HtmlBlockKind::Block => {
+                    self.options.parse_inside_html_blocks
+                        || self.remaining().lines().next().is_none_or(|next| next.trim().is_empty())
+                }
+            };
+            end = content_end;
+            if done || self.is_at_end() {
+                break;
+            }
+            end = self.position;
+        }
+
+        Node::Html(Html {
+            value: &self.source[start..end],
+            span: Span::new(start as u32, end as u32),
+        })
+    }
+
     /// Parses a table.
     fn parse_table(&mut self, start: usize) -> ParseResult<Option<Node<'a>>> {
         let mut rows: std::vec::Vec<std::vec::Vec<&str>> = std::vec::Vec::new();
@@ -671,17 +1478,31 @@ impl<'a> Parser<'a> {
             rows.push(row_cells);
         }
 
-        // Build the table AST
+        // Build the table AST. As in GFM, body rows are cut or padded with
+        // empty cells to the width of the header row.
         let mut children: Vec<'a, TableRow<'a>> = self.allocator.new_vec();
+        let width = rows[0].len();
 
-        for row_cells in rows {
+        for mut row_cells in rows {
+            row_cells.truncate(width);
             let mut cells: Vec<'a, TableCell<'a>> = self.allocator.new_vec();
+            let mut row_span: Option<Span> = None;
             for cell_content in row_cells {
-                let cell_children = self.parse_inline(cell_content, 0)?;
-                let cell = TableCell { children: cell_children, span: Span::new(0, 0) };
+                let cell_start = self.offset_of(cell_content);
+                let cell_children = self.parse_table_cell(cell_content, cell_start)?;
+                let span = Span::new(cell_start as u32, (cell_start + cell_content.len()) as u32);
+                row_span = Some(row_span.map_or(span, |row| row.merge(span)));
+                let cell = TableCell { children: cell_children, span };
                 cells.push(cell);
             }
-            let row = TableRow { children: cells, span: Span::new(0, 0) };
+            let end = row_span.map_or(0, |row| row.end);
+            while cells.len() < width {
+                cells.push(TableCell {
+                    children: self.allocator.new_vec(),
+                    span: Span::new(end, end),
+                });
+            }
+            let row = TableRow { children: cells, span: row_span.unwrap_or_default() };
             children.push(row);
         }
 
@@ -689,6 +1510,33 @@ impl<'a> Parser<'a> {
         Ok(Some(Node::Table(Table { align, children, span })))
     }
 
+    /// Parses the inline content of a table cell, turning `<br>` tags and
+    /// the configured cell break delimiter into line breaks.
+    fn parse_table_cell(&self, content: &'a str, offset: usize) -> ParseResult<Vec<'a, Node<'a>>> {
+        let delimiter = self.options.table_cell_break.as_deref().filter(|d| !d.is_empty());
+        let mut children = self.allocator.new_vec();
+        let mut rest = 0;
+        while let Some((start, len)) = next_cell_break(&content[rest..], delimiter) {
+            let start = rest + start;
+            children.extend(self.parse_inline(
+                &content[rest..start],
+                offset + rest,
+                self.nesting_depth,
+            )?);
+            children.push(Node::Break(Break {
+                span: Span::new((offset + start) as u32, (offset + start + len) as u32),
+            }));
+            rest = start + len;
+        }
+        children.extend(self.parse_inline(&content[rest..], offset + rest, self.nesting_depth)?);
+        Ok(children)
+    }
+
+    /// Returns the byte offset of a slice of the source text.
+    fn offset_of(&self, slice: &str) -> usize {
+        slice.as_ptr() as usize - self.source.as_ptr() as usize
+    }
+
     /// Consumes a line and returns it.
     fn consume_line(&mut self) -> &'a str {
         let start = self.position;
@@ -709,10 +1557,33 @@ impl<'a> Parser<'a> {
         trimmed.split('|').map(str::trim).collect()
     }
 
+    /// Checks if the current line starts a block that ends a paragraph.
+    fn interrupts_paragraph(&self) -> bool {
+        self.try_parse_heading()
+            || self.try_parse_thematic_break()
+            || self.try_parse_fenced_code()
+            || self.try_parse_math_block()
+            || self.html_block_kind().is_some()
+            || self.try_parse_table()
+            || self.try_parse_block_quote()
+            || self.try_parse_list()
+            || self.try_parse_abbreviation()
+            || self.try_parse_footnote_definition()
+    }
+
     /// Parses a paragraph.
     fn parse_paragraph(&mut self, start: usize) -> ParseResult<Option<Node<'a>>> {
-        let mut content_end = start;
+        self.parse_paragraph_lines(start, start)
+    }
 
+    /// Parses a paragraph starting at `start` whose text up to `content_end`
+    /// has already been consumed, continuing with the lines at the current
+    /// position.
+    fn parse_paragraph_lines(
+        &mut self,
+        start: usize,
+        mut content_end: usize,
+    ) -> ParseResult<Option<Node<'a>>> {
         loop {
             if self.is_at_end() {
                 break;
@@ -728,13 +1599,7 @@ impl<'a> Parser<'a> {
 
             self.position = line_start;
 
-            // Check for block-level element that would end paragraph
-            if self.try_parse_heading()
-                || self.try_parse_thematic_break()
-                || self.try_parse_fenced_code()
-                || (self.options.tables && self.try_parse_table())
-                || self.try_parse_list()
-            {
+            if self.interrupts_paragraph() {
                 break;
             }
 
@@ -756,14 +1621,84 @@ impl<'a> Parser<'a> {
         let span = Span::new(start as u32, content_end as u32);
 
         // Parse inline content
-        let children = self.parse_inline(content, start)?;
+        let children = self.parse_inline(content, start, self.nesting_depth)?;
 
         Ok(Some(Node::Paragraph(Paragraph { children, span })))
     }
 
+    /// Pushes `content[start..end]` as text, wrapping whole-word
+    /// abbreviation occurrences in abbreviation nodes.
+    fn push_text(
+        &self,
+        children: &mut Vec<'a, Node<'a>>,
+        content: &'a str,
+        start: usize,
+        end: usize,
+        offset: usize,
+    ) {
+        let span = |from: usize, to: usize| Span::new((offset + from) as u32, (offset + to) as u32);
+        let mut text_start = start;
+        let mut pos = start;
+        while pos < end && !self.abbreviations.is_empty() {
+            let Some((value, title)) = self.abbreviation_at(content, pos, end) else {
+                pos += content[pos..].chars().next().map_or(1, char::len_utf8);
+                continue;
+            };
+            if pos > text_start {
+                let value = self.text_value(&content[text_start..pos]);
+                children.push(Node::Text(Text { value, span: span(text_start, pos) }));
+            }
+            let abbr_end = pos + value.len();
+            let abbreviation = Abbreviation { value, title, span: span(pos, abbr_end) };
+            children.push(Node::Abbreviation(abbreviation));
+            pos = abbr_end;
+            text_start = pos;
+        }
+        if end > text_start {
+            let value = self.text_value(&content[text_start..end]);
+            children.push(Node::Text(Text { value, span: span(text_start, end) }));
+        }
+    }
+
+    /// Allocates the value of a text node, normalizing its whitespace if
+    /// enabled.
+    fn text_value(&self, text: &str) -> &'a str {
+        if self.options.normalize_whitespace {
+            self.allocator.alloc_str(&collapse_whitespace(text))
+        } else {
+            self.allocator.alloc_str(text)
+        }
+    }
+
+    /// Returns the abbreviation that occurs as a whole word at `pos`.
+    fn abbreviation_at(&self, content: &str, pos: usize, end: usize) -> Option<(&'a str, &'a str)> {
+        if content[..pos].chars().next_back().is_some_and(is_word_char) {
+            return None;
+        }
+        self.abbreviations.iter().copied().find(|(abbr, _)| {
+            let abbr_end = pos + abbr.len();
+            abbr_end <= end
+                && content[pos..].starts_with(abbr)
+                && !content[abbr_end..].chars().next().is_some_and(is_word_char)
+        })
+    }
+
     /// Parses inline content.
-    fn parse_inline(&self, content: &'a str, offset: usize) -> ParseResult<Vec<'a, Node<'a>>> {
+    ///
+    /// `depth` is the current nesting depth; link text is parsed one level
+    /// deeper, and each emphasis around a node adds a level.
+    fn parse_inline(
+        &self,
+        content: &'a str,
+        offset: usize,
+        depth: usize,
+    ) -> ParseResult<Vec<'a, Node<'a>>> {
+        self.check_nesting(depth, offset)?;
+        self.check_deadline(offset)?;
         let mut children = self.allocator.new_vec();
+        let mut delimiters = std::vec::Vec::new();
+        // Closing `]` of each `[`, found once for the whole content
+        let mut brackets = None;
         let mut pos = 0;
         let bytes = content.as_bytes();
 
@@ -773,7 +1708,10 @@ impl<'a> Parser<'a> {
             // Look for special characters
             while pos < content.len() {
                 let ch = bytes[pos];
-                if matches!(ch, b'*' | b'_' | b'`' | b'[' | b'!' | b'~' | b'\\') {
+                if matches!(ch, b'*' | b'_' | b'`' | b'[' | b'!' | b'~' | b'\\' | b'&')
+                    || (ch == b'$' && self.options.math)
+                    || (ch == b'^' && self.options.footnotes)
+                {
                     break;
                 }
                 pos += 1;
@@ -781,12 +1719,7 @@ impl<'a> Parser<'a> {
 
             // Emit text before special character
             if pos > start {
-                let text_content = &content[start..pos];
-                let text = Text {
-                    value: self.allocator.alloc_str(text_content),
-                    span: Span::new((offset + start) as u32, (offset + pos) as u32),
-                };
-                children.push(Node::Text(text));
+                self.push_text(&mut children, content, start, pos, offset);
             }
 
             if pos >= content.len() {
@@ -794,6 +1727,8 @@ impl<'a> Parser<'a> {
             }
 
             // Handle special characters
+            let node_start = pos;
+            let node_index = children.len();
             let ch = bytes[pos];
             match ch {
                 b'\\' if pos + 1 < content.len() => {
@@ -807,68 +1742,43 @@ impl<'a> Parser<'a> {
                     children.push(Node::Text(text));
                     pos += 1;
                 }
+                b'&' => {
+                    // Entity or numeric character reference
+                    let (value, len) = match decode_entity(&content[pos..]) {
+                        Some((decoded, len)) => (self.allocator.alloc_str(&decoded), len),
+                        None => (self.allocator.alloc_str("&"), 1),
+                    };
+                    let text = Text {
+                        value,
+                        span: Span::new((offset + pos) as u32, (offset + pos + len) as u32),
+                    };
+                    children.push(Node::Text(text));
+                    pos += len;
+                }
                 b'*' | b'_' => {
-                    // Emphasis or Strong
-                    let marker = ch;
-                    let mut count = 1;
-                    while pos + count < content.len() && bytes[pos + count] == marker {
-                        count += 1;
-                    }
-
-                    // Simple logic: find next matching sequence of same length
-                    let inner_start = pos + count;
-                    let mut inner_end = inner_start;
-                    let mut found = false;
-
-                    while inner_end < content.len() {
-                        if bytes[inner_end] == marker {
-                            let mut end_count = 1;
-                            while inner_end + end_count < content.len()
-                                && bytes[inner_end + end_count] == marker
-                            {
-                                end_count += 1;
-                            }
-
-                            if end_count >= count {
-                                found = true;
-                                break;
-                            }
-                            inner_end += end_count;
-                        } else {
-                            inner_end += 1;
-                        }
-                    }
-
-                    if found {
-                        let inner_content = &content[inner_start..inner_end];
-                        // Recursively parse inner content
-                        let inner_children =
-                            self.parse_inline(inner_content, offset + inner_start)?;
-
-                        let span =
-                            Span::new((offset + pos) as u32, (offset + inner_end + count) as u32);
-
-                        if count >= 2 {
-                            // Strong
-                            let strong = ox_content_ast::Strong { children: inner_children, span };
-                            children.push(Node::Strong(strong));
-                            pos = inner_end + count;
-                        } else {
-                            // Emphasis
-                            let emphasis =
-                                ox_content_ast::Emphasis { children: inner_children, span };
-                            children.push(Node::Emphasis(emphasis));
-                            pos = inner_end + count;
-                        }
-                    } else {
-                        // Treat as text
-                        let text = Text {
-                            value: self.allocator.alloc_str(&content[pos..pos + count]),
-                            span: Span::new((offset + pos) as u32, (offset + pos + count) as u32),
-                        };
-                        children.push(Node::Text(text));
-                        pos += count;
+                    // Emphasis delimiter run, paired once the whole content
+                    // has been read
+                    let count = delimiter_run_len(bytes, pos, ch);
+                    let (can_open, can_close) = delimiter_flanking(content, pos, pos + count);
+                    if can_open || can_close {
+                        delimiters.push(Delimiter {
+                            item: children.len(),
+                            marker: ch,
+                            length: count,
+                            start: pos,
+                            count,
+                            can_open,
+                            can_close,
+                            opens: std::vec::Vec::new(),
+                            closes: 0,
+                        });
                     }
+                    let text = Text {
+                        value: self.allocator.alloc_str(&content[pos..pos + count]),
+                        span: Span::new((offset + pos) as u32, (offset + pos + count) as u32),
+                    };
+                    children.push(Node::Text(text));
+                    pos += count;
                 }
                 b'`' => {
                     // Inline code
@@ -900,6 +1810,38 @@ impl<'a> Parser<'a> {
                         children.push(Node::Text(text));
                     }
                 }
+                b'[' if self.options.footnotes
+                    && footnote_reference_label(&content[pos..]).is_some() =>
+                {
+                    // Footnote reference: [^label]
+                    let label = footnote_reference_label(&content[pos..]).unwrap_or_default();
+                    let len = label.len() + 3;
+                    children.push(Node::FootnoteReference(FootnoteReference {
+                        identifier: label,
+                        label: Some(label),
+                        span: Span::new((offset + pos) as u32, (offset + pos + len) as u32),
+                    }));
+                    pos += len;
+                }
+                b'^' if bytes.get(pos + 1) == Some(&b'[') => {
+                    // Inline footnote: ^[note]
+                    let close = closing_bracket(&mut brackets, bytes, pos + 1);
+                    if close < content.len() && close > pos + 2 {
+                        let note_children = self.parse_inline(
+                            &content[pos + 2..close],
+                            offset + pos + 2,
+                            depth + 1,
+                        )?;
+                        children.push(Node::InlineFootnote(InlineFootnote {
+                            children: note_children,
+                            span: Span::new((offset + pos) as u32, (offset + close + 1) as u32),
+                        }));
+                        pos = close + 1;
+                    } else {
+                        self.push_text(&mut children, content, pos, pos + 1, offset);
+                        pos += 1;
+                    }
+                }
                 b'[' => {
                     // Link: [text](url)
                     let link_start = pos;
@@ -907,17 +1849,7 @@ impl<'a> Parser<'a> {
                     let text_start = pos;
 
                     // Find closing ]
-                    let mut bracket_depth = 1;
-                    while pos < content.len() && bracket_depth > 0 {
-                        match bytes[pos] {
-                            b'[' => bracket_depth += 1,
-                            b']' => bracket_depth -= 1,
-                            _ => {} // Ignore other characters
-                        }
-                        if bracket_depth > 0 {
-                            pos += 1;
-                        }
-                    }
+                    pos = closing_bracket(&mut brackets, bytes, link_start);
 
                     if pos < content.len()
                         && bytes[pos] == b']'
@@ -942,16 +1874,17 @@ impl<'a> Parser<'a> {
                         }
 
                         if pos < content.len() && bytes[pos] == b')' {
-                            let url = &content[url_start..pos];
+                            let (url, title) = split_link_title(&content[url_start..pos]);
                             pos += 1; // skip )
 
                             // Parse link text as inline content
                             let link_children =
-                                self.parse_inline(link_text, offset + text_start)?;
+                                self.parse_inline(link_text, offset + text_start, depth + 1)?;
 
                             let link = Link {
                                 url: self.allocator.alloc_str(url),
-                                title: None,
+                                title: title
+                                    .map(|title| self.allocator.alloc_str(&decode_entities(title))),
                                 children: link_children,
                                 span: Span::new(
                                     (offset + link_start) as u32,
@@ -970,7 +1903,26 @@ impl<'a> Parser<'a> {
                             };
                             children.push(Node::Text(text));
                         }
+                    } else if let Some((url, title, end)) = (pos < content.len())
+                        .then(|| self.resolve_reference(content, text_start, pos))
+                        .flatten()
+                    {
+                        // Reference link: [text][label], [text][] or [text]
+                        let link_children = self.parse_inline(
+                            &content[text_start..pos],
+                            offset + text_start,
+                            depth + 1,
+                        )?;
+                        children.push(Node::Link(Link {
+                            url,
+                            title: title
+                                .map(|title| self.allocator.alloc_str(&decode_entities(title))),
+                            children: link_children,
+                            span: Span::new((offset + link_start) as u32, (offset + end) as u32),
+                        }));
+                        pos = end;
                     } else {
+                        self.check_reference(content, offset, link_start, text_start, pos)?;
                         // Not a link, just a [
                         let text = Text {
                             value: self.allocator.alloc_str("["),
@@ -991,17 +1943,7 @@ impl<'a> Parser<'a> {
                         let alt_start = pos;
 
                         // Find closing ]
-                        let mut bracket_depth = 1;
-                        while pos < content.len() && bracket_depth > 0 {
-                            match bytes[pos] {
-                                b'[' => bracket_depth += 1,
-                                b']' => bracket_depth -= 1,
-                                _ => {}
-                            }
-                            if bracket_depth > 0 {
-                                pos += 1;
-                            }
-                        }
+                        pos = closing_bracket(&mut brackets, bytes, image_start + 1);
 
                         if pos < content.len()
                             && bytes[pos] == b']'
@@ -1026,13 +1968,15 @@ impl<'a> Parser<'a> {
                             }
 
                             if pos < content.len() && bytes[pos] == b')' {
-                                let url = &content[url_start..pos];
+                                let (url, title) = split_link_title(&content[url_start..pos]);
                                 pos += 1; // skip )
 
                                 let image = Image {
                                     url: self.allocator.alloc_str(url),
-                                    alt: self.allocator.alloc_str(alt_text),
-                                    title: None,
+                                    alt: self.allocator.alloc_str(&decode_entities(alt_text)),
+                                    title: title.map(|title| {
+                                        self.allocator.alloc_str(&decode_entities(title))
+                                    }),
                                     span: Span::new(
                                         (offset + image_start) as u32,
                                         (offset + pos) as u32,
@@ -1050,7 +1994,26 @@ impl<'a> Parser<'a> {
                                 };
                                 children.push(Node::Text(text));
                             }
+                        } else if let Some((url, title, end)) = (pos < content.len())
+                            .then(|| self.resolve_reference(content, alt_start, pos))
+                            .flatten()
+                        {
+                            // Reference image: ![alt][label], ![alt][] or ![alt]
+                            children.push(Node::Image(Image {
+                                url,
+                                alt: self
+                                    .allocator
+                                    .alloc_str(&decode_entities(&content[alt_start..pos])),
+                                title: title
+                                    .map(|title| self.allocator.alloc_str(&decode_entities(title))),
+                                span: Span::new(
+                                    (offset + image_start) as u32,
+                                    (offset + end) as u32,
+                                ),
+                            }));
+                            pos = end;
                         } else {
+                            self.check_reference(content, offset, image_start, alt_start, pos)?;
                             // Not an image, just ![
                             let text = Text {
                                 value: self.allocator.alloc_str("!["),
@@ -1072,6 +2035,64 @@ impl<'a> Parser<'a> {
                         pos += 1;
                     }
                 }
+                b'~' => {
+                    // Strikethrough: GFM requires exactly two tildes on each
+                    // side. Other runs stay literal, so `~` is free for subscript.
+                    let count = delimiter_run_len(bytes, pos, b'~');
+                    let close = if self.options.strikethrough && count == 2 {
+                        find_delimiter_run(bytes, pos + 2, b'~', 2).filter(|&end| end > pos + 2)
+                    } else {
+                        None
+                    };
+
+                    if let Some(inner_end) = close {
+                        let inner_children = self.parse_inline(
+                            &content[pos + 2..inner_end],
+                            offset + pos + 2,
+                            depth + 1,
+                        )?;
+                        let span =
+                            Span::new((offset + pos) as u32, (offset + inner_end + 2) as u32);
+                        children.push(Node::Delete(Delete { children: inner_children, span }));
+                        pos = inner_end + 2;
+                    } else {
+                        let text = Text {
+                            value: self.allocator.alloc_str(&content[pos..pos + count]),
+                            span: Span::new((offset + pos) as u32, (offset + pos + count) as u32),
+                        };
+                        children.push(Node::Text(text));
+                        pos += count;
+                    }
+                }
+                b'$' => {
+                    // Inline math: `$x$` or `$$x$$`
+                    let count = delimiter_run_len(bytes, pos, b'$');
+                    let close = if count <= 2 {
+                        find_delimiter_run(bytes, pos + count, b'$', count)
+                            .filter(|&end| inline_math_bounds(bytes, pos, end, count))
+                    } else {
+                        None
+                    };
+
+                    if let Some(inner_end) = close {
+                        let math = InlineMath {
+                            value: self.allocator.alloc_str(&content[pos + count..inner_end]),
+                            span: Span::new(
+                                (offset + pos) as u32,
+                                (offset + inner_end + count) as u32,
+                            ),
+                        };
+                        children.push(Node::InlineMath(math));
+                        pos = inner_end + count;
+                    } else {
+                        let text = Text {
+                            value: self.allocator.alloc_str(&content[pos..pos + count]),
+                            span: Span::new((offset + pos) as u32, (offset + pos + count) as u32),
+                        };
+                        children.push(Node::Text(text));
+                        pos += count;
+                    }
+                }
                 _ => {
                     // Other special characters
                     let text = Text {
@@ -1082,15 +2103,747 @@ impl<'a> Parser<'a> {
                     pos += 1;
                 }
             }
+
+            // Disabled constructs stay as their source text
+            if children[node_index..].iter().any(|node| !self.options.features.allows(node)) {
+                children.truncate(node_index);
+                let text = Text {
+                    value: self.allocator.alloc_str(&content[node_start..pos]),
+                    span: Span::new((offset + node_start) as u32, (offset + pos) as u32),
+                };
+                children.push(Node::Text(text));
+            }
         }
 
-        Ok(children)
+        if delimiters.is_empty() {
+            return Ok(children);
+        }
+        match_delimiters(&mut delimiters);
+        self.build_emphasis(children, &delimiters, content, offset, depth)
+    }
+
+    /// Replaces the delimiter runs in `children` with the emphasis and
+    /// strong nodes paired by [`match_delimiters`]. Unpaired delimiters stay
+    /// as text, and so do the delimiters of a disabled construct.
+    fn build_emphasis(
+        &self,
+        children: Vec<'a, Node<'a>>,
+        delimiters: &[Delimiter],
+        content: &'a str,
+        offset: usize,
+        depth: usize,
+    ) -> ParseResult<Vec<'a, Node<'a>>> {
+        let span = |from: usize, to: usize| Span::new((offset + from) as u32, (offset + to) as u32);
+        let text = |from: usize, to: usize| {
+            Node::Text(Text {
+                value: self.allocator.alloc_str(&content[from..to]),
+                span: span(from, to),
+            })
+        };
+        // Nodes of the innermost open emphasis, and of the ones around it
+        let mut nodes = self.allocator.new_vec();
+        let mut open: std::vec::Vec<(Vec<'a, Node<'a>>, DelimiterPair)> = std::vec::Vec::new();
+        let mut delimiters = delimiters.iter().peekable();
+
+        for (index, node) in children.into_iter().enumerate() {
+            let Some(delimiter) = delimiters.next_if(|delimiter| delimiter.item == index) else {
+                nodes.push(node);
+                continue;
+            };
+            for _ in 0..delimiter.closes {
+                let Some((parent, pair)) = open.pop() else {
+                    unreachable!("every closed pair was opened");
+                };
+                let inner = std::mem::replace(&mut nodes, parent);
+                let span = span(pair.start, pair.end);
+                let node = if pair.strong {
+                    Node::Strong(ox_content_ast::Strong { children: inner, span })
+                } else {
+                    Node::Emphasis(ox_content_ast::Emphasis { children: inner, span })
+                };
+                if self.options.features.allows(&node) {
+                    nodes.push(node);
+                } else if let Node::Strong(ox_content_ast::Strong { children: inner, .. })
+                | Node::Emphasis(ox_content_ast::Emphasis { children: inner, .. }) = node
+                {
+                    let marks = if pair.strong { 2 } else { 1 };
+                    nodes.push(text(pair.start, pair.start + marks));
+                    nodes.extend(inner);
+                    nodes.push(text(pair.end - marks, pair.end));
+                }
+            }
+            if delimiter.count > 0 {
+                nodes.push(text(delimiter.start, delimiter.start + delimiter.count));
+            }
+            for pair in delimiter.opens.iter().rev() {
+                self.check_nesting(depth + open.len() + 1, offset + pair.start)?;
+                open.push((std::mem::replace(&mut nodes, self.allocator.new_vec()), *pair));
+            }
+        }
+
+        debug_assert!(open.is_empty(), "every opened pair is closed");
+        Ok(nodes)
+    }
+}
+
+/// How an HTML block ends, by CommonMark HTML block start condition.
+#[derive(Debug, Clone, Copy)]
+enum HtmlBlockKind {
+    /// `<pre>`, `<script>`, `<style>` or `<textarea>`: ends at the closing tag.
+    Raw(&'static str),
+    /// `<!--`: ends at `-->`.
+    Comment,
+    /// A block-level tag such as `<div>` or `<details>`: ends at a blank line.
+    Block,
+}
+
+/// Line that opens a region rendered verbatim.
+const RAW_OPEN: &str = "<!-- raw -->";
+
+/// Line that closes a region opened by [`RAW_OPEN`].
+const RAW_CLOSE: &str = "<!-- /raw -->";
+
+/// Tags whose content is never parsed as Markdown.
+const RAW_HTML_TAGS: &[&str] = &["pre", "script", "style", "textarea"];
+
+/// Block-level tags that start an HTML block (CommonMark HTML block kind 6).
+const BLOCK_HTML_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "base",
+    "basefont",
+    "blockquote",
+    "body",
+    "caption",
+    "center",
+    "col",
+    "colgroup",
+    "dd",
+    "details",
+    "dialog",
+    "dir",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "frame",
+    "frameset",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hr",
+    "html",
+    "iframe",
+    "legend",
+    "li",
+    "link",
+    "main",
+    "menu",
+    "menuitem",
+    "nav",
+    "noframes",
+    "ol",
+    "optgroup",
+    "option",
+    "p",
+    "param",
+    "search",
+    "section",
+    "summary",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "title",
+    "tr",
+    "track",
+    "ul",
+];
+
+/// Returns the kind of HTML block that `line` (without indentation) starts.
+fn html_block_kind(line: &str) -> Option<HtmlBlockKind> {
+    let rest = line.strip_prefix('<')?;
+    if rest.starts_with("!--") {
+        return Some(HtmlBlockKind::Comment);
+    }
+    let (closing, rest) = rest.strip_prefix('/').map_or((false, rest), |rest| (true, rest));
+    let name_len = rest.bytes().take_while(u8::is_ascii_alphanumeric).count();
+    let (name, after) = rest.split_at(name_len);
+    let name = name.to_ascii_lowercase();
+
+    let ends_name = after.is_empty() || after.starts_with([' ', '\t', '>']);
+    if !closing && ends_name {
+        if let Some(tag) = RAW_HTML_TAGS.iter().find(|tag| **tag == name) {
+            return Some(HtmlBlockKind::Raw(tag));
+        }
+    }
+    let is_block_tag = BLOCK_HTML_TAGS.contains(&name.as_str());
+    (is_block_tag && (ends_name || after.starts_with("/>"))).then_some(HtmlBlockKind::Block)
+}
+
+/// Checks whether `line` contains `</tag>`, ignoring ASCII case.
+fn contains_closing_tag(line: &str, tag: &str) -> bool {
+    let closing = format!("</{tag}>");
+    line.to_ascii_lowercase().contains(&closing)
+}
+
+/// Indentation (in columns) that starts an indented code block.
+const CODE_INDENT: usize = 4;
+
+/// Returns the column reached by a tab at `column`, using 4-column tab stops.
+const fn next_tab_stop(column: usize) -> usize {
+    column + 4 - column % 4
+}
+
+/// Returns the indentation of `line` in columns, expanding tabs to tab stops.
+fn line_indent(line: &str) -> usize {
+    whitespace_end_column(line, 0)
+}
+
+/// Returns the column reached after the spaces and tabs that start `text`,
+/// when `text` starts at `column`.
+fn whitespace_end_column(text: &str, mut column: usize) -> usize {
+    for byte in text.bytes() {
+        match byte {
+            b' ' => column += 1,
+            b'\t' => column = next_tab_stop(column),
+            _ => break,
+        }
+    }
+    column
+}
+
+/// Removes up to `columns` columns of indentation from `line`.
+///
+/// A tab that straddles the boundary is split, and its remaining columns
+/// are kept as spaces so the content stays aligned.
+fn strip_indent(line: &str, columns: usize) -> Cow<'_, str> {
+    let mut column = 0;
+    for (i, byte) in line.bytes().enumerate() {
+        if column >= columns {
+            return Cow::Borrowed(&line[i..]);
+        }
+        match byte {
+            b' ' => column += 1,
+            b'\t' => {
+                let next = next_tab_stop(column);
+                if next > columns {
+                    let padding = " ".repeat(next - columns);
+                    return Cow::Owned(padding + &line[i + 1..]);
+                }
+                column = next;
+            }
+            _ => return Cow::Borrowed(&line[i..]),
+        }
+    }
+    Cow::Borrowed("")
+}
+
+/// Maps the spans of `nodes` and everything inside them through `map`.
+fn remap_spans(nodes: &mut [Node<'_>], map: &impl Fn(Span) -> Span) {
+    let remap = |span: &mut Span| *span = map(*span);
+    for node in nodes {
+        match node {
+            Node::Paragraph(Paragraph { children, span })
+            | Node::Heading(Heading { children, span, .. })
+            | Node::BlockQuote(BlockQuote { children, span })
+            | Node::ListItem(ListItem { children, span, .. })
+            | Node::Emphasis(Emphasis { children, span })
+            | Node::Strong(Strong { children, span })
+            | Node::Link(Link { children, span, .. })
+            | Node::Delete(Delete { children, span })
+            | Node::InlineFootnote(InlineFootnote { children, span })
+            | Node::FootnoteDefinition(FootnoteDefinition { children, span, .. }) => {
+                remap(span);
+                remap_spans(children, map);
+            }
+            Node::List(list) => {
+                remap(&mut list.span);
+                for item in &mut list.children {
+                    remap(&mut item.span);
+                    remap_spans(&mut item.children, map);
+                }
+            }
+            Node::Table(table) => {
+                remap(&mut table.span);
+                for row in &mut table.children {
+                    remap(&mut row.span);
+                    for cell in &mut row.children {
+                        remap(&mut cell.span);
+                        remap_spans(&mut cell.children, map);
+                    }
+                }
+            }
+            Node::ThematicBreak(ThematicBreak { span })
+            | Node::CodeBlock(CodeBlock { span, .. })
+            | Node::Html(Html { span, .. })
+            | Node::Math(Math { span, .. })
+            | Node::Text(Text { span, .. })
+            | Node::InlineCode(InlineCode { span, .. })
+            | Node::Break(Break { span })
+            | Node::Image(Image { span, .. })
+            | Node::FootnoteReference(FootnoteReference { span, .. })
+            | Node::Abbreviation(Abbreviation { span, .. })
+            | Node::InlineMath(InlineMath { span, .. })
+            | Node::Definition(Definition { span, .. }) => remap(span),
+        }
+    }
+}
+
+/// Splits a link destination into the URL and an optional title, written as
+/// `url "title"`, `url 'title'` or `url (title)`. Angle brackets around the
+/// URL are removed.
+fn split_link_title(destination: &str) -> (&str, Option<&str>) {
+    let destination = destination.trim();
+    let mut url = destination;
+    let mut title = None;
+    for (open, close) in [('"', '"'), ('\'', '\''), ('(', ')')] {
+        let Some(body) = destination.strip_suffix(close) else { continue };
+        let Some(start) = body.rfind(open) else { continue };
+        let before = &body[..start];
+        if before.ends_with([' ', '\t', '\n']) && !before.trim().is_empty() {
+            url = before.trim_end();
+            title = Some(&body[start + 1..]);
+            break;
+        }
+    }
+    let url = url.strip_prefix('<').and_then(|url| url.strip_suffix('>')).unwrap_or(url);
+    (url, title)
+}
+
+/// Parses a `[label]: url "title"` link reference definition line, indented
+/// by at most three spaces. Footnote labels (`[^label]`) are not definitions.
+fn link_definition(line: &str) -> Option<(&str, &str, Option<&str>)> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() >= CODE_INDENT {
+        return None;
+    }
+    let rest = trimmed.strip_prefix('[')?;
+    let (label, destination) = rest.split_once("]:")?;
+    if label.trim().is_empty() || label.starts_with('^') || label.contains(['[', ']']) {
+        return None;
+    }
+    let (url, title) = split_link_title(destination);
+    if url.is_empty() || url.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((label, url, title))
+}
+
+/// Collects the link reference definitions of `source`, skipping fenced
+/// code. The first definition of a label wins.
+fn collect_link_definitions(source: &str) -> HashMap<String, (&str, Option<&str>)> {
+    let mut definitions = HashMap::new();
+    let mut fence: Option<&str> = None;
+    for line in source.lines() {
+        let trimmed = line.trim_start();
+        let marker_len = trimmed.bytes().take_while(|&b| b == b'`' || b == b'~').count();
+        let marker = &trimmed[..marker_len];
+        match fence {
+            Some(open) if marker.starts_with(open) && trimmed[marker_len..].trim().is_empty() => {
+                fence = None;
+            }
+            Some(_) => {}
+            None if marker_len >= 3 && marker.bytes().all(|b| b == marker.as_bytes()[0]) => {
+                fence = Some(marker);
+            }
+            None => {
+                if let Some((label, url, title)) = link_definition(line) {
+                    definitions.entry(normalize_label(label)).or_insert((url, title));
+                }
+            }
+        }
+    }
+    definitions
+}
+
+/// Splits a trailing `{#id .class}` attribute block from heading text,
+/// adding its classes to `classes`.
+fn split_heading_attributes<'s>(
+    content: &'s str,
+    classes: &mut Vec<'_, &'s str>,
+) -> (&'s str, Option<&'s str>) {
+    let Some(open) = content.strip_suffix('}').and_then(|rest| rest.rfind('{')) else {
+        return (content, None);
+    };
+    let text = &content[..open];
+    if !(text.is_empty() || text.ends_with([' ', '\t'])) {
+        return (content, None);
+    }
+    let is_name = |name: &str| {
+        !name.is_empty()
+            && name.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'))
+    };
+    let mut id = None;
+    let mut names = std::vec::Vec::new();
+    for token in content[open + 1..content.len() - 1].split_whitespace() {
+        match (token.strip_prefix('#'), token.strip_prefix('.')) {
+            (Some(name), _) if id.is_none() && is_name(name) => id = Some(name),
+            (_, Some(name)) if is_name(name) => names.push(name),
+            _ => return (content, None),
+        }
+    }
+    if id.is_none() && names.is_empty() {
+        return (content, None);
+    }
+    classes.extend(names);
+    (text.trim_end(), id)
+}
+
+/// Fails on the first footnote reference in `document` without a
+/// definition.
+fn check_footnotes(document: &Document<'_>, source: &str) -> ParseResult<()> {
+    let mut footnotes = FootnoteLabels::default();
+    footnotes.visit_document(document);
+    match footnotes.references.into_iter().find(|(label, _)| !footnotes.definitions.contains(label))
+    {
+        Some((label, span)) => {
+            let position = Position::from_offset(source, span.start);
+            Err(ParseError::UndefinedFootnote { span, position, label: label.to_string() })
+        }
+        None => Ok(()),
+    }
+}
+
+/// Footnote labels referenced and defined in a document.
+#[derive(Default)]
+struct FootnoteLabels<'a> {
+    references: std::vec::Vec<(&'a str, Span)>,
+    definitions: std::vec::Vec<&'a str>,
+}
+
+impl<'a> Visit<'a> for FootnoteLabels<'a> {
+    fn visit_footnote_reference(&mut self, footnote_ref: &FootnoteReference<'a>) {
+        self.references.push((footnote_ref.identifier, footnote_ref.span));
+    }
+
+    fn visit_footnote_definition(&mut self, footnote_def: &FootnoteDefinition<'a>) {
+        self.definitions.push(footnote_def.identifier);
+        walk_footnote_definition(self, footnote_def);
+    }
+}
+
+/// Normalizes a reference label for matching: case-folded, with runs of
+/// whitespace collapsed to one space.
+fn normalize_label(label: &str) -> String {
+    label.split_whitespace().collect::<std::vec::Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Finds the next line break in table cell text: a `<br>`, `<br/>` or
+/// `<br />` tag in any case, or `delimiter`. Code spans are skipped.
+/// Returns the start and length of the break.
+fn next_cell_break(text: &str, delimiter: Option<&str>) -> Option<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        if bytes[pos] == b'`' {
+            // Skip a code span, or just the backticks when it is unclosed
+            let ticks = delimiter_run_len(bytes, pos, b'`');
+            pos += ticks;
+            if let Some(close) = find_delimiter_run(bytes, pos, b'`', ticks) {
+                pos = close + ticks;
+            }
+            continue;
+        }
+        let rest = &text[pos..];
+        if let Some(delimiter) = delimiter.filter(|delimiter| rest.starts_with(delimiter)) {
+            return Some((pos, delimiter.len()));
+        }
+        if let Some(len) = ["<br>", "<br/>", "<br />"].iter().find_map(|tag| {
+            rest.get(..tag.len()).filter(|head| head.eq_ignore_ascii_case(tag)).map(|_| tag.len())
+        }) {
+            return Some((pos, len));
+        }
+        pos += rest.chars().next().map_or(1, char::len_utf8);
+    }
+    None
+}
+
+/// Parses a `*[ABBR]: definition` line.
+fn parse_abbreviation(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_prefix("*[")?;
+    let (abbr, definition) = rest.split_once("]:")?;
+    if abbr.trim().is_empty() || abbr.contains(']') {
+        return None;
+    }
+    Some((abbr, definition.trim()))
+}
+
+/// Returns the label of a `[^label]` footnote reference at the start of
+/// `text`.
+fn footnote_reference_label(text: &str) -> Option<&str> {
+    let rest = text.strip_prefix("[^")?;
+    let end = rest.find(|c: char| c.is_whitespace() || c == '[' || c == ']')?;
+    (end > 0 && rest[end..].starts_with(']')).then(|| &rest[..end])
+}
+
+/// Returns the position of the `]` closing the `[` at `open`, or the end
+/// of `bytes` when it is never closed. Brackets nest, and every bracket of
+/// `bytes` is paired in one pass the first time this is called, so that
+/// text full of unclosed `[` is not scanned again for each of them.
+fn closing_bracket(
+    brackets: &mut Option<HashMap<usize, usize>>,
+    bytes: &[u8],
+    open: usize,
+) -> usize {
+    let brackets = brackets.get_or_insert_with(|| {
+        let mut pairs = HashMap::new();
+        let mut opened = std::vec::Vec::new();
+        for (pos, &byte) in bytes.iter().enumerate() {
+            match byte {
+                b'[' => opened.push(pos),
+                b']' => {
+                    if let Some(open) = opened.pop() {
+                        pairs.insert(open, pos);
+                    }
+                }
+                _ => {}
+            }
+        }
+        pairs
+    });
+    brackets.get(&open).copied().unwrap_or(bytes.len())
+}
+
+/// Returns the label of a `[^label]:` footnote definition line and the
+/// length of the marker, including indentation and the spaces after it.
+fn footnote_definition_label(line: &str) -> Option<(&str, usize)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent >= CODE_INDENT {
+        return None;
+    }
+    let label = footnote_reference_label(&line[indent..])?;
+    let after = line[indent + label.len() + 3..].strip_prefix(':')?;
+    let spaces = after.len() - after.trim_start_matches([' ', '\t']).len();
+    Some((label, indent + label.len() + 4 + spaces))
+}
+
+/// A run of `*` or `_` that can open or close emphasis.
+struct Delimiter {
+    /// Index of the text node holding the run.
+    item: usize,
+    marker: u8,
+    /// Length of the whole run, for the multiple of three rule.
+    length: usize,
+    /// Start and length of the part of the run not yet paired. Closing
+    /// pairs take delimiters from the left and opening pairs from the right.
+    start: usize,
+    count: usize,
+    can_open: bool,
+    can_close: bool,
+    /// Pairs opened by this run, innermost first.
+    opens: std::vec::Vec<DelimiterPair>,
+    /// Number of pairs closed by this run.
+    closes: usize,
+}
+
+/// Emphasis (one delimiter on each side) or strong emphasis (two) paired by
+/// [`match_delimiters`], spanning `start..end` including its delimiters.
+#[derive(Clone, Copy)]
+struct DelimiterPair {
+    strong: bool,
+    start: usize,
+    end: usize,
+}
+
+/// Pairs up the delimiter runs of one inline content, following the
+/// CommonMark "process emphasis" procedure: each closer, from left to
+/// right, pairs with the nearest opener of the same marker, two delimiters
+/// at a time when both runs have two left, and the runs between them
+/// become text.
+///
+/// Openers wait on a stack that pairing only shortens, and each closer
+/// remembers how far down the stack a search already failed, so this runs
+/// in linear time however the runs are arranged.
+fn match_delimiters(delimiters: &mut [Delimiter]) {
+    // Indices of the runs that may still open emphasis, in text order
+    let mut stack: std::vec::Vec<usize> = std::vec::Vec::new();
+    // Stack height below which no opener is left for a closer, by marker,
+    // whether the closer can open, and closer length modulo 3
+    let mut openers_bottom = [[[0; 3]; 2]; 2];
+
+    for closer in 0..delimiters.len() {
+        let (before, rest) = delimiters.split_at_mut(closer);
+        let current = &mut rest[0];
+        while current.can_close && current.count > 0 {
+            let bottom = &mut openers_bottom[usize::from(current.marker == b'_')]
+                [usize::from(current.can_open)][current.length % 3];
+            *bottom = (*bottom).min(stack.len());
+            let found = stack[*bottom..].iter().rposition(|&index| {
+                let opener = &before[index];
+                opener.marker == current.marker
+                    && delimiters_pair(
+                        opener.length,
+                        opener.can_close,
+                        current.length,
+                        current.can_open,
+                    )
+            });
+            let Some(found) = found.map(|found| *bottom + found) else {
+                *bottom = stack.len();
+                break;
+            };
+
+            // The runs between the opener and the closer become text
+            stack.truncate(found + 1);
+            let open = &mut before[stack[found]];
+            let used = if open.count >= 2 && current.count >= 2 { 2 } else { 1 };
+            open.count -= used;
+            open.opens.push(DelimiterPair {
+                strong: used == 2,
+                start: open.start + open.count,
+                end: current.start + used,
+            });
+            if open.count == 0 {
+                stack.pop();
+            }
+            current.start += used;
+            current.count -= used;
+            current.closes += 1;
+        }
+        if current.can_open && current.count > 0 {
+            stack.push(closer);
+        }
+    }
+}
+
+/// Returns the length of the run of `marker` bytes starting at `start`.
+fn delimiter_run_len(bytes: &[u8], start: usize, marker: u8) -> usize {
+    bytes[start..].iter().take_while(|&&b| b == marker).count()
+}
+
+/// Returns whether the `*` or `_` delimiter run at `start..end` of `text`
+/// can open and can close emphasis, following the CommonMark flanking
+/// rules. The start and end of `text` count as whitespace.
+///
+/// A run is left-flanking (can open) when it is not followed by whitespace,
+/// and not followed by punctuation unless preceded by whitespace or
+/// punctuation; right-flanking (can close) is the mirror image. An `_` run
+/// between two letters is both, and may do neither, so `snake_case_word`
+/// stays plain text while `a*b*c` is emphasized.
+fn delimiter_flanking(text: &str, start: usize, end: usize) -> (bool, bool) {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    let is_space = |c: Option<char>| c.is_none_or(char::is_whitespace);
+    let is_punct = |c: Option<char>| c.is_some_and(is_punctuation);
+
+    let left = !is_space(after) && (!is_punct(after) || is_space(before) || is_punct(before));
+    let right = !is_space(before) && (!is_punct(before) || is_space(after) || is_punct(after));
+    if text.as_bytes()[start] == b'_' {
+        (left && (!right || is_punct(before)), right && (!left || is_punct(after)))
+    } else {
+        (left, right)
+    }
+}
+
+/// Returns whether an opening run of `open_len` delimiters can pair with a
+/// closing run of `close_len`. When either run can both open and close,
+/// the lengths must not add up to a multiple of three unless both are
+/// multiples of three, so `*foo**bar*` keeps the inner `**` literal.
+const fn delimiters_pair(
+    open_len: usize,
+    opener_closes: bool,
+    close_len: usize,
+    closer_opens: bool,
+) -> bool {
+    !((opener_closes || closer_opens)
+        && (open_len + close_len) % 3 == 0
+        && !(open_len % 3 == 0 && close_len % 3 == 0))
+}
+
+/// Returns whether `c` is punctuation for the flanking rules: ASCII
+/// punctuation, or a non-ASCII character that is neither alphanumeric nor
+/// whitespace (Unicode punctuation and symbols).
+fn is_punctuation(c: char) -> bool {
+    if c.is_ascii() {
+        c.is_ascii_punctuation()
+    } else {
+        !c.is_alphanumeric() && !c.is_whitespace()
+    }
+}
+
+/// Finds the next run of exactly `len` `marker` bytes at or after `from`.
+fn find_delimiter_run(bytes: &[u8], from: usize, marker: u8, len: usize) -> Option<usize> {
+    let mut pos = from;
+    while pos < bytes.len() {
+        if bytes[pos] == marker {
+            let count = delimiter_run_len(bytes, pos, marker);
+            if count == len {
+                return Some(pos);
+            }
+            pos += count;
+        } else {
+            pos += 1;
+        }
     }
+    None
+}
+
+/// Checks the pandoc rules for inline math between `open` and `close`: the
+/// content is non-empty and, for single dollars, neither starts nor ends
+/// with whitespace and the closer is not followed by a digit.
+fn inline_math_bounds(bytes: &[u8], open: usize, close: usize, len: usize) -> bool {
+    let inner = &bytes[open + len..close];
+    let (Some(first), Some(last)) = (inner.first(), inner.last()) else {
+        return false;
+    };
+    len == 2
+        || (!first.is_ascii_whitespace()
+            && !last.is_ascii_whitespace()
+            && bytes.get(close + 1).is_none_or(|next| !next.is_ascii_digit()))
+}
+
+/// Collapses runs of spaces and tabs to one space and removes the spaces
+/// and tabs next to line breaks.
+///
+/// A space at either end of the text is kept, as it separates the text
+/// from the inline nodes next to it.
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut blank = false;
+    for ch in text.chars() {
+        match ch {
+            ' ' | '\t' => blank = true,
+            '\n' => {
+                blank = false;
+                collapsed.push('\n');
+            }
+            _ => {
+                if blank && !collapsed.ends_with('\n') {
+                    collapsed.push(' ');
+                }
+                blank = false;
+                collapsed.push(ch);
+            }
+        }
+    }
+    if blank && !collapsed.ends_with('\n') {
+        collapsed.push(' ');
+    }
+    collapsed
+}
+
+/// Returns true for characters that are part of a word.
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::FeaturePreset;
 
     #[test]
     fn test_parse_image() {
@@ -1126,6 +2879,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_heading_id() {
+        let allocator = Allocator::new();
+        let source = "## Setup {#install}\n# Title {#a.b:c} ##\n## {#empty}\n\
+                      ## Set {#bad id}\n## Obj{#x}\n## Braces {#}\n\
+                      ## Old {.no-toc .muted #old}\n## Two {#a #b}\n## Empty {}";
+        let options = ParserOptions { attributes: true, ..ParserOptions::default() };
+        let doc = Parser::with_options(&allocator, source, options).parse().unwrap();
+        let headings: std::vec::Vec<_> = doc
+            .children
+            .iter()
+            .map(|node| match node {
+                Node::Heading(h) => {
+                    let text = match h.children.first() {
+                        Some(Node::Text(t)) => t.value,
+                        _ => "",
+                    };
+                    (text, h.id, h.classes.to_vec())
+                }
+                _ => panic!("expected heading"),
+            })
+            .collect();
+        assert_eq!(
+            headings,
+            [
+                ("Setup", Some("install"), vec![]),
+                ("Title", Some("a.b:c"), vec![]),
+                ("", Some("empty"), vec![]),
+                ("Set {#bad id}", None, vec![]),
+                ("Obj{#x}", None, vec![]),
+                ("Braces {#}", None, vec![]),
+                ("Old", Some("old"), vec!["no-toc", "muted"]),
+                ("Two {#a #b}", None, vec![]),
+                ("Empty {}", None, vec![]),
+            ]
+        );
+
+        let doc = Parser::new(&allocator, "## Setup {#install}").parse().unwrap();
+        assert!(matches!(&doc.children[0], Node::Heading(h) if h.id.is_none()));
+    }
+
     #[test]
     fn test_parse_paragraph() {
         let allocator = Allocator::new();
@@ -1134,6 +2928,500 @@ mod tests {
         assert!(matches!(&doc.children[0], Node::Paragraph(_)));
     }
 
+    #[test]
+    fn test_max_nesting_depth() {
+        let allocator = Allocator::new();
+
+        // Used to overflow the stack.
+        let links = format!("{}x{}", "[".repeat(10_000), "](u)".repeat(10_000));
+        let err = Parser::new(&allocator, &links).parse().unwrap_err();
+        assert!(matches!(err, ParseError::NestingTooDeep { max_depth: 100, .. }));
+        assert!(err.to_string().contains("max nesting depth exceeded"));
+
+        let lists: std::string::String =
+            (0..10_000).flat_map(|i| [" ".repeat(i), "- item\n".to_string()]).collect();
+        let err = Parser::new(&allocator, &lists).parse().unwrap_err();
+        assert!(matches!(err, ParseError::NestingTooDeep { .. }));
+
+        // Within the limit, nesting parses normally.
+        let options = ParserOptions { max_nesting_depth: 3, ..ParserOptions::default() };
+        let source = "[[[x](a)](b)](c)";
+        assert!(Parser::with_options(&allocator, source, options.clone()).parse().is_ok());
+        let source = "[[[[x](a)](b)](c)](d)";
+        assert!(Parser::with_options(&allocator, source, options).parse().is_err());
+    }
+
+    #[test]
+    fn test_presets() {
+        let commonmark = ParserOptions::from_preset(ParserPreset::CommonMark);
+        assert!(!commonmark.gfm && !commonmark.tables && !commonmark.autolinks);
+
+        let strict = ParserOptions::from_preset(ParserPreset::from_name("gfm_strict").unwrap());
+        assert!(strict.gfm && strict.tables && strict.autolinks && !strict.footnotes);
+
+        let options =
+            ParserOptions { autolinks: false, ..ParserOptions::from_preset(ParserPreset::Gfm) };
+        assert!(options.footnotes && !options.autolinks);
+
+        assert_eq!(ParserPreset::from_name("GFM"), Some(ParserPreset::Gfm));
+        assert_eq!(ParserPreset::from_name("markdown"), None);
+    }
+
+    #[test]
+    fn test_parse_restricted_features() {
+        let allocator = Allocator::new();
+        let source =
+            "# Title\n\n![img](a.png) [link](/u) **bold** `code`\n\n<div>x</div>\n\n- item\n\n---";
+        let options = ParserOptions {
+            features: Features::from_preset(FeaturePreset::Basic),
+            ..ParserOptions::gfm()
+        };
+        let doc = Parser::with_options(&allocator, source, options).parse().unwrap();
+        let kinds: std::vec::Vec<_> = doc
+            .children
+            .iter()
+            .map(|node| match node {
+                Node::Paragraph(_) => "paragraph",
+                Node::List(_) => "list",
+                _ => "other",
+            })
+            .collect();
+        assert_eq!(kinds, ["paragraph", "paragraph", "paragraph", "list", "paragraph"]);
+
+        let Node::Paragraph(heading) = &doc.children[0] else { unreachable!() };
+        assert!(matches!(&heading.children[..], [Node::Text(text)] if text.value == "# Title"));
+        let Node::Paragraph(inline) = &doc.children[1] else { unreachable!() };
+        assert!(matches!(&inline.children[0], Node::Text(text) if text.value == "![img](a.png)"));
+        assert!(matches!(inline.children[2], Node::Link(_)));
+        assert!(matches!(inline.children[4], Node::Strong(_)));
+        assert!(matches!(inline.children[6], Node::InlineCode(_)));
+
+        let options = ParserOptions {
+            features: Features::from_preset(FeaturePreset::InlineOnly),
+            ..ParserOptions::gfm()
+        };
+        let doc =
+            Parser::with_options(&allocator, "- item\n\n```\ncode\n```", options).parse().unwrap();
+        assert!(doc.children.iter().all(|node| matches!(node, Node::Paragraph(_))));
+    }
+
+    #[test]
+    fn test_parse_emphasis_nesting() {
+        let allocator = Allocator::new();
+        let doc = Parser::new(&allocator, "***a** b*").parse().unwrap();
+        let Node::Paragraph(paragraph) = &doc.children[0] else { unreachable!() };
+        let [Node::Emphasis(emphasis)] = &paragraph.children[..] else { unreachable!() };
+        assert_eq!(emphasis.span, Span::new(0, 9));
+        let [Node::Strong(strong), Node::Text(text)] = &emphasis.children[..] else {
+            unreachable!()
+        };
+        assert_eq!(strong.span, Span::new(1, 6));
+        assert!(matches!(&strong.children[..], [Node::Text(a)] if a.value == "a"));
+        assert_eq!(text.value, " b");
+
+        // Delimiters of a disabled construct stay as text around its content
+        let options = ParserOptions {
+            features: Features::all().without(Feature::Strong),
+            ..ParserOptions::default()
+        };
+        let doc = Parser::with_options(&allocator, "**a *b***", options).parse().unwrap();
+        let Node::Paragraph(paragraph) = &doc.children[0] else { unreachable!() };
+        let values: std::vec::Vec<_> = paragraph
+            .children
+            .iter()
+            .map(|node| match node {
+                Node::Text(text) => text.value,
+                Node::Emphasis(_) => "<em>",
+                _ => "other",
+            })
+            .collect();
+        assert_eq!(values, ["**", "a ", "<em>", "**"]);
+
+        // Deeply nested emphasis is bounded like other inline nesting
+        let source = format!("{}x{}", "*a ".repeat(200), " b*".repeat(200));
+        let err = Parser::new(&allocator, &source).parse().unwrap_err();
+        assert!(matches!(err, ParseError::NestingTooDeep { .. }));
+    }
+
+    #[test]
+    fn test_parse_long_line() {
+        // Unclosed brackets, references and emphasis on a single line used
+        // to be rescanned to the end of the line, taking minutes at 2 MB
+        let unit = "[see ![figure &amp [^note *bold _mixed a* ";
+        let source = unit.repeat(500_000 / unit.len());
+        let allocator = Allocator::new();
+        let (doc, stats) = Parser::new(&allocator, &source).parse_with_stats().unwrap();
+        assert_eq!(doc.children.len(), 1);
+        assert!(stats.parse_time < Duration::from_secs(5), "{:?}", stats.parse_time);
+    }
+
+    #[test]
+    fn test_max_input_bytes() {
+        let allocator = Allocator::new();
+        let options = ParserOptions { max_input_bytes: Some(8), ..ParserOptions::default() };
+        assert!(Parser::with_options(&allocator, "# Title", options.clone()).parse().is_ok());
+        let err = Parser::with_options(&allocator, "# Longer title", options).parse().unwrap_err();
+        assert!(matches!(err, ParseError::InputTooLarge { size: 14, limit: 8 }));
+    }
+
+    #[test]
+    fn test_time_budget() {
+        let allocator = Allocator::new();
+        let source = "paragraph\n\n".repeat(1000);
+
+        let options =
+            ParserOptions { time_budget: Some(Duration::ZERO), ..ParserOptions::default() };
+        let err = Parser::with_options(&allocator, &source, options).parse().unwrap_err();
+        assert!(matches!(err, ParseError::TimeBudgetExceeded { .. }));
+
+        let options = ParserOptions {
+            time_budget: Some(Duration::from_secs(60)),
+            ..ParserOptions::default()
+        };
+        assert!(Parser::with_options(&allocator, &source, options).parse().is_ok());
+    }
+
+    #[test]
+    fn test_parse_abbreviations() {
+        let allocator = Allocator::new();
+        let source = "The HTML and HTML5 specs, not html or XHTML.\n*[HTML]: HyperText Markup Language\n*[HTML5]: HTML version 5\n";
+        let options = ParserOptions { abbreviations: true, ..ParserOptions::default() };
+        let doc = Parser::with_options(&allocator, source, options).parse().unwrap();
+        assert_eq!(doc.children.len(), 1);
+
+        let Node::Paragraph(para) = &doc.children[0] else { panic!("expected paragraph") };
+        let abbrs: std::vec::Vec<_> = para
+            .children
+            .iter()
+            .filter_map(|node| match node {
+                Node::Abbreviation(a) => Some((a.value, a.title, a.span.source_text(source))),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            abbrs,
+            [("HTML", "HyperText Markup Language", "HTML"), ("HTML5", "HTML version 5", "HTML5")]
+        );
+
+        // Disabled by default: definitions stay as text.
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let Node::Paragraph(para) = &doc.children[0] else { panic!("expected paragraph") };
+        assert!(para.children.iter().all(|node| !matches!(node, Node::Abbreviation(_))));
+    }
+
+    #[test]
+    fn test_parse_inline_footnote() {
+        let allocator = Allocator::new();
+        let source = "Fact^[See [the docs](/docs) and *more*.] and x^2 and ^[] and ^[open";
+        let options = ParserOptions { footnotes: true, ..ParserOptions::default() };
+        let doc = Parser::with_options(&allocator, source, options).parse().unwrap();
+        let Node::Paragraph(para) = &doc.children[0] else { panic!("expected paragraph") };
+        let Node::InlineFootnote(note) = &para.children[1] else {
+            panic!("expected inline footnote")
+        };
+        assert_eq!(
+            &source[note.span.start as usize..note.span.end as usize],
+            "^[See [the docs](/docs) and *more*.]"
+        );
+        assert!(matches!(note.children[1], Node::Link(_)));
+        assert!(matches!(note.children[3], Node::Emphasis(_)));
+        let notes = para.children.iter().filter(|node| matches!(node, Node::InlineFootnote(_)));
+        assert_eq!(notes.count(), 1);
+
+        // Disabled with footnotes: the note stays text.
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let Node::Paragraph(para) = &doc.children[0] else { panic!("expected paragraph") };
+        assert!(para.children.iter().all(|node| !matches!(node, Node::InlineFootnote(_))));
+    }
+
+    #[test]
+    fn test_parse_footnote_block_content() {
+        let allocator = Allocator::new();
+        let source = "Claim.[^cite]\n\n[^cite]: First paragraph\ncontinues lazily.\n\n    Second paragraph.\n\n    - one\n    - two\n\nAfter.";
+        let options = ParserOptions { footnotes: true, ..ParserOptions::default() };
+        let doc = Parser::with_options(&allocator, source, options).parse().unwrap();
+        assert_eq!(doc.children.len(), 3);
+
+        let Node::Paragraph(para) = &doc.children[0] else { panic!("expected paragraph") };
+        let Node::FootnoteReference(reference) = &para.children[1] else {
+            panic!("expected footnote reference")
+        };
+        assert_eq!(reference.identifier, "cite");
+
+        let Node::FootnoteDefinition(definition) = &doc.children[1] else {
+            panic!("expected footnote definition")
+        };
+        assert_eq!(definition.identifier, "cite");
+        assert_eq!(definition.children.len(), 3);
+        let Node::Paragraph(first) = &definition.children[0] else { panic!("expected paragraph") };
+        let Node::Text(text) = &first.children[0] else { panic!("expected text") };
+        assert_eq!(text.value, "First paragraph\ncontinues lazily.");
+        assert!(matches!(definition.children[1], Node::Paragraph(_)));
+        let Node::List(list) = &definition.children[2] else { panic!("expected list") };
+        assert_eq!(list.children.len(), 2);
+
+        assert!(matches!(doc.children[2], Node::Paragraph(_)));
+
+        // Disabled: both stay text.
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        assert!(doc.children.iter().all(|node| !matches!(node, Node::FootnoteDefinition(_))));
+    }
+
+    #[test]
+    fn test_parse_math() {
+        let allocator = Allocator::new();
+        let source =
+            "Euler: $e^{i\\pi} + 1 = 0$, costs $5 and $10.\n$$\n\\int_0^1 x\\,dx\n$$\n\n$$a^2$$";
+        let options = ParserOptions { math: true, ..ParserOptions::default() };
+        let doc = Parser::with_options(&allocator, source, options.clone()).parse().unwrap();
+        assert_eq!(doc.children.len(), 3);
+
+        let Node::Paragraph(para) = &doc.children[0] else { panic!("expected paragraph") };
+        let inline: std::vec::Vec<_> = para
+            .children
+            .iter()
+            .filter_map(|node| match node {
+                Node::InlineMath(m) => Some(m.value),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(inline, ["e^{i\\pi} + 1 = 0"]);
+
+        let Node::Math(block) = &doc.children[1] else { panic!("expected math block") };
+        assert_eq!(block.value, "\\int_0^1 x\\,dx");
+        let Node::Math(single) = &doc.children[2] else { panic!("expected math block") };
+        assert_eq!(single.value, "a^2");
+
+        // A math fence is the same block.
+        let fenced = "```math\n\\int_0^1 x\\,dx\n```";
+        let doc = Parser::with_options(&allocator, fenced, options.clone()).parse().unwrap();
+        let Node::Math(block) = &doc.children[0] else { panic!("expected math block") };
+        assert_eq!(block.value, "\\int_0^1 x\\,dx");
+        let doc = Parser::new(&allocator, fenced).parse().unwrap();
+        assert!(matches!(doc.children[0], Node::CodeBlock(_)));
+
+        // Unclosed blocks and disabled math stay text.
+        let doc = Parser::with_options(&allocator, "$$\nx", options).parse().unwrap();
+        assert!(matches!(doc.children[0], Node::Paragraph(_)));
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        assert!(doc.children.iter().all(|node| !matches!(node, Node::Math(_))));
+    }
+
+    #[test]
+    fn test_parse_entities() {
+        let allocator = Allocator::new();
+        let doc = Parser::new(&allocator, "&copy; &#x263A; &bogus; `&amp;`").parse().unwrap();
+        let Node::Paragraph(para) = &doc.children[0] else { panic!("expected paragraph") };
+        let text: std::string::String = para
+            .children
+            .iter()
+            .map(|node| match node {
+                Node::Text(t) => t.value,
+                Node::InlineCode(c) => c.value,
+                _ => "",
+            })
+            .collect();
+        assert_eq!(text, "\u{a9} \u{263a} &bogus; &amp;");
+    }
+
+    #[test]
+    fn test_parse_strikethrough_requires_double_tilde() {
+        let allocator = Allocator::new();
+        let parse = |source| Parser::with_options(&allocator, source, ParserOptions::gfm()).parse();
+
+        let doc = parse("~one~").unwrap();
+        let Node::Paragraph(para) = &doc.children[0] else { panic!("expected paragraph") };
+        assert!(para.children.iter().all(|node| matches!(node, Node::Text(_))));
+
+        let doc = parse("~~two~~").unwrap();
+        let Node::Paragraph(para) = &doc.children[0] else { panic!("expected paragraph") };
+        assert_eq!(para.children.len(), 1);
+        let Node::Delete(delete) = &para.children[0] else { panic!("expected delete") };
+        assert!(matches!(&delete.children[0], Node::Text(t) if t.value == "two"));
+        assert_eq!((delete.span.start, delete.span.end), (0, 7));
+
+        // A leading `~~~` would open a code fence.
+        let doc = parse("a ~~~three~~~").unwrap();
+        let Node::Paragraph(para) = &doc.children[0] else { panic!("expected paragraph") };
+        assert!(para.children.iter().all(|node| matches!(node, Node::Text(_))));
+
+        // Disabled without the strikethrough option.
+        let doc = Parser::new(&allocator, "~~two~~").parse().unwrap();
+        let Node::Paragraph(para) = &doc.children[0] else { panic!("expected paragraph") };
+        assert!(para.children.iter().all(|node| matches!(node, Node::Text(_))));
+    }
+
+    #[test]
+    fn test_parse_html_blocks() {
+        let allocator = Allocator::new();
+        let source =
+            "<details>\n<summary>Hi</summary>\n**bold**\n</details>\n\n<!-- a\n\nb -->\ntext";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        assert_eq!(doc.children.len(), 3);
+        let Node::Html(html) = &doc.children[0] else { panic!("expected html") };
+        assert_eq!(html.value, "<details>\n<summary>Hi</summary>\n**bold**\n</details>");
+        let Node::Html(comment) = &doc.children[1] else { panic!("expected html") };
+        assert_eq!(comment.value, "<!-- a\n\nb -->");
+        assert!(matches!(&doc.children[2], Node::Paragraph(_)));
+
+        // Block-level HTML interrupts a paragraph; inline tags do not.
+        let doc = Parser::new(&allocator, "text\n<div>\n\n<span>x</span>").parse().unwrap();
+        assert!(matches!(&doc.children[1], Node::Html(_)));
+        assert!(matches!(&doc.children[2], Node::Paragraph(_)));
+    }
+
+    #[test]
+    fn test_parse_inside_html_blocks() {
+        let allocator = Allocator::new();
+        let options = ParserOptions { parse_inside_html_blocks: true, ..ParserOptions::default() };
+        let source =
+            "<details>\n<summary>Hi</summary>\n**bold**\n</details>\n<pre>\n**raw**\n</pre>";
+        let doc = Parser::with_options(&allocator, source, options).parse().unwrap();
+        let kinds: std::vec::Vec<_> = doc
+            .children
+            .iter()
+            .map(|node| match node {
+                Node::Html(html) => html.value,
+                Node::Paragraph(_) => "paragraph",
+                _ => "other",
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "<details>",
+                "<summary>Hi</summary>",
+                "paragraph",
+                "</details>",
+                "<pre>\n**raw**\n</pre>"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_link_titles() {
+        let allocator = Allocator::new();
+        let source =
+            "[a](/x \"Title &amp; more\") [b](</y z> 'single') ![c](i.png (Paren)) [d](u \"v\")";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let Node::Paragraph(para) = &doc.children[0] else { panic!("expected paragraph") };
+        let targets: std::vec::Vec<_> = para
+            .children
+            .iter()
+            .filter_map(|node| match node {
+                Node::Link(link) => Some((link.url, link.title)),
+                Node::Image(image) => Some((image.url, image.title)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            targets,
+            [
+                ("/x", Some("Title & more")),
+                ("/y z", Some("single")),
+                ("i.png", Some("Paren")),
+                ("u", Some("v")),
+            ]
+        );
+    }
+
+    /// Returns the `(url, title, text)` of each link or image in the first
+    /// paragraph, with `text` the image alt or the link's text children.
+    fn reference_targets(source: &str) -> std::vec::Vec<(String, Option<String>, String)> {
+        let allocator = Allocator::new();
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let Some(Node::Paragraph(para)) =
+            doc.children.iter().find(|node| matches!(node, Node::Paragraph(_)))
+        else {
+            panic!("expected paragraph")
+        };
+        para.children
+            .iter()
+            .filter_map(|node| match node {
+                Node::Link(link) => {
+                    let text = link
+                        .children
+                        .iter()
+                        .map(|child| match child {
+                            Node::Text(text) => text.value,
+                            _ => "",
+                        })
+                        .collect();
+                    Some((link.url.to_string(), link.title.map(str::to_string), text))
+                }
+                Node::Image(image) => Some((
+                    image.url.to_string(),
+                    image.title.map(str::to_string),
+                    image.alt.to_string(),
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_reference_links() {
+        let defs = "\n\n[id]: /page \"Page &amp; more\"\n[Other  Label]: </other>";
+        let target = |title: Option<&str>, url: &str, text: &str| {
+            (url.to_string(), title.map(str::to_string), text.to_string())
+        };
+        let page = Some("Page & more");
+        assert_eq!(
+            reference_targets(&format!("[full][id]{defs}")),
+            [target(page, "/page", "full")]
+        );
+        assert_eq!(reference_targets(&format!("[ID][]{defs}")), [target(page, "/page", "ID")]);
+        assert_eq!(reference_targets(&format!("[id]{defs}")), [target(page, "/page", "id")]);
+        assert_eq!(
+            reference_targets(&format!("[x][other\nlabel]{defs}")),
+            [target(None, "/other", "x")]
+        );
+        // Undefined references stay literal text
+        assert!(reference_targets(&format!("[x][missing] [missing]{defs}")).is_empty());
+    }
+
+    #[test]
+    fn test_parse_reference_images() {
+        let defs = "\n\n[id]: /img.png \"Img &amp; more\"\n[Other  Label]: </other.png>";
+        let target = |title: Option<&str>, url: &str, alt: &str| {
+            (url.to_string(), title.map(str::to_string), alt.to_string())
+        };
+        let img = Some("Img & more");
+        assert_eq!(
+            reference_targets(&format!("![full][id]{defs}")),
+            [target(img, "/img.png", "full")]
+        );
+        assert_eq!(reference_targets(&format!("![ID][]{defs}")), [target(img, "/img.png", "ID")]);
+        assert_eq!(reference_targets(&format!("![id]{defs}")), [target(img, "/img.png", "id")]);
+        assert_eq!(
+            reference_targets(&format!("![x][other\nlabel]{defs}")),
+            [target(None, "/other.png", "x")]
+        );
+        // Undefined references stay literal text
+        assert!(reference_targets(&format!("![x][missing] ![missing]{defs}")).is_empty());
+    }
+
+    #[test]
+    fn test_parse_link_definitions() {
+        let allocator = Allocator::new();
+        let source = "[a]: /a 'A'\n[a]: /second\n[^note]: footnote\n\n```\n[b]: /b\n```\n\n[b]";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let definitions: std::vec::Vec<_> = doc
+            .children
+            .iter()
+            .filter_map(|node| match node {
+                Node::Definition(def) => Some((def.identifier, def.url, def.title)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(definitions, [("a", "/a", Some("A")), ("a", "/second", None)]);
+        // Lines inside fenced code are not definitions
+        let Some(Node::Paragraph(para)) = doc.children.last() else { panic!("expected paragraph") };
+        assert!(para.children.iter().all(|node| matches!(node, Node::Text(_))));
+        // The first definition of a label wins
+        assert_eq!(reference_targets("[a]\n\n[a]: /a\n[A]: /b")[0].0, "/a");
+    }
+
     #[test]
     fn test_parse_thematic_break() {
         let allocator = Allocator::new();
@@ -1161,6 +3449,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_fenced_code_meta() {
+        let allocator = Allocator::new();
+        let source = "```toml title=\"My Config.toml\" copy lines='1-3' theme=dark\nx = 1\n```";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let Node::CodeBlock(cb) = &doc.children[0] else { panic!("expected code block") };
+        assert_eq!(cb.lang, Some("toml"));
+        assert_eq!(cb.title(), Some("My Config.toml"));
+        let attrs: std::vec::Vec<_> = cb.meta_attributes().collect();
+        assert_eq!(attrs, [("title", "My Config.toml"), ("lines", "1-3"), ("theme", "dark")]);
+        assert_eq!(cb.meta_value("missing"), None);
+    }
+
     #[test]
     fn test_parse_inline_code() {
         let allocator = Allocator::new();
@@ -1189,6 +3490,172 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_table_row_widths() {
+        let allocator = Allocator::new();
+        let source = "| a | b | c |\n|---|---|---|\n| 1 |\n| 1 | 2 | 3 | 4 | 5 |\n| 1 | 2 | 3 |";
+        let doc = Parser::with_options(&allocator, source, ParserOptions::gfm()).parse().unwrap();
+        let Node::Table(table) = &doc.children[0] else { panic!("expected table") };
+        let widths: std::vec::Vec<_> =
+            table.children.iter().map(|row| row.children.len()).collect();
+        assert_eq!(widths, [3, 3, 3, 3]);
+
+        // Padded cells are empty and sit at the end of the row
+        let short = &table.children[1];
+        assert!(short.children[1].children.is_empty() && short.children[2].children.is_empty());
+        assert_eq!(short.children[2].span, Span::new(31, 31));
+        // Extra cells are dropped
+        let long = &table.children[2];
+        let Node::Text(last) = &long.children[2].children[0] else { panic!("expected text") };
+        assert_eq!(last.value, "3");
+        assert_eq!(long.span.end, 45);
+    }
+
+    /// Returns the kinds of the inline nodes in the first body cell.
+    fn first_cell_kinds(source: &str, options: ParserOptions) -> std::vec::Vec<&'static str> {
+        let allocator = Allocator::new();
+        let doc = Parser::with_options(&allocator, source, options).parse().unwrap();
+        let Node::Table(table) = &doc.children[0] else {
+            panic!("expected table, got {:?}", &doc.children[0]);
+        };
+        table.children[1].children[0]
+            .children
+            .iter()
+            .map(|node| match node {
+                Node::Text(_) => "text",
+                Node::Strong(_) => "strong",
+                Node::Emphasis(_) => "emphasis",
+                Node::Link(_) => "link",
+                Node::InlineCode(_) => "code",
+                Node::Break(_) => "break",
+                _ => "other",
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_table_cell_breaks() {
+        let source = "| a |\n|---|\n| **bold**<br>[link](/u)<BR />*em* |";
+        assert_eq!(
+            first_cell_kinds(source, ParserOptions::gfm()),
+            ["strong", "break", "link", "break", "emphasis"]
+        );
+        // Tags in code spans stay code
+        let source = "| a |\n|---|\n| `x<br>y` |";
+        assert_eq!(first_cell_kinds(source, ParserOptions::gfm()), ["code"]);
+    }
+
+    #[test]
+    fn test_parse_table_cell_break_delimiter() {
+        let source = "| a |\n|---|\n| one;;**two**;;`;;` |";
+        assert_eq!(
+            first_cell_kinds(source, ParserOptions::gfm()),
+            ["text", "strong", "text", "code"]
+        );
+        let options =
+            ParserOptions { table_cell_break: Some(";;".to_string()), ..ParserOptions::gfm() };
+        assert_eq!(first_cell_kinds(source, options), ["text", "break", "strong", "break", "code"]);
+    }
+
+    #[test]
+    fn test_parse_runs_of_blank_lines() {
+        let allocator = Allocator::new();
+        let source = "\n\n\n# Title\n\n\n\n   \n\t\nFirst\n\n\n\n\nSecond\n  \n\n\n```\ncode\n\n\n```\n\n\n\n";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        assert_eq!(doc.children.len(), 4);
+        assert!(matches!(doc.children[0], Node::Heading(_)));
+        assert!(matches!(doc.children[3], Node::CodeBlock(_)));
+        for node in &doc.children[1..3] {
+            let Node::Paragraph(paragraph) = node else { panic!("expected paragraph") };
+            assert!(!paragraph.children.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_parse_normalize_whitespace() {
+        let allocator = Allocator::new();
+        let source = "Some   ragged\t text   \n   wrapped  *here*  and `a   b`";
+        let text = |options: ParserOptions| {
+            let doc = Parser::with_options(&allocator, source, options).parse().unwrap();
+            let Node::Paragraph(paragraph) = &doc.children[0] else { panic!("expected paragraph") };
+            paragraph
+                .children
+                .iter()
+                .map(|node| match node {
+                    Node::Text(text) => text.value.to_string(),
+                    Node::InlineCode(code) => format!("`{}`", code.value),
+                    _ => "<node>".to_string(),
+                })
+                .collect::<std::string::String>()
+        };
+        assert_eq!(
+            text(ParserOptions::default()),
+            "Some   ragged\t text   \n   wrapped  <node>  and `a   b`"
+        );
+        let options = ParserOptions { normalize_whitespace: true, ..ParserOptions::default() };
+        assert_eq!(text(options), "Some ragged text\nwrapped <node> and `a   b`");
+    }
+
+    #[test]
+    fn test_parse_strict() {
+        let allocator = Allocator::new();
+        let strict = ParserOptions { strict: true, math: true, ..ParserOptions::gfm() };
+        let parse = |source, options: &ParserOptions| {
+            Parser::with_options(&allocator, source, options.clone()).parse()
+        };
+
+        let ok =
+            "[Docs][docs] and ![logo][] and [x] and [^1]\n\n[docs]: /docs\n[logo]: /logo.png\n\n\
+                  [^1]: Note.\n\n```js\ncode\n```\n\n$$\nx\n$$";
+        assert!(parse(ok, &strict).is_ok());
+
+        let failing = [
+            (
+                "See [the docs][missing].",
+                "undefined link reference [missing] at line 1, column 5",
+                4,
+            ),
+            ("See ![Logo][].", "undefined link reference [Logo] at line 1, column 5", 4),
+            ("Text[^nope].", "undefined footnote [^nope] at line 1, column 5", 4),
+            ("Intro\n\n```rust\nfn main() {}", "unclosed code fence at line 3, column 1", 7),
+            ("$$\nx = 1", "unclosed math block at line 1, column 1", 0),
+            ("日本語 [x][y]", "undefined link reference [y] at line 1, column 5", 10),
+        ];
+        for (source, message, start) in failing {
+            assert!(parse(source, &ParserOptions { strict: false, ..strict.clone() }).is_ok());
+            let err = parse(source, &strict).unwrap_err();
+            assert_eq!(err.to_string(), message, "{source}");
+            assert_eq!(err.span().start, start, "{source}");
+        }
+
+        let err = parse("Text[^nope].", &strict).unwrap_err().with_line_offset(3);
+        assert_eq!(err.to_string(), "undefined footnote [^nope] at line 4, column 5");
+    }
+
+    #[test]
+    fn test_parse_partial() {
+        let allocator = Allocator::new();
+        let strict = ParserOptions { strict: true, ..ParserOptions::gfm() };
+        let source = "# Title\n\nIntro.\n\n```rust\nfn main() {";
+        let (doc, error) = Parser::with_options(&allocator, source, strict.clone()).parse_partial();
+        assert_eq!(error.unwrap().to_string(), "unclosed code fence at line 5, column 1");
+        assert_eq!(doc.children.len(), 2);
+
+        let (doc, error) =
+            Parser::with_options(&allocator, "Text[^nope].\n\nMore", strict).parse_partial();
+        assert!(matches!(error, Some(ParseError::UndefinedFootnote { .. })));
+        assert_eq!(doc.children.len(), 2);
+
+        let options = ParserOptions { max_input_bytes: Some(4), ..ParserOptions::default() };
+        let (doc, error) = Parser::with_options(&allocator, source, options).parse_partial();
+        assert!(matches!(error, Some(ParseError::InputTooLarge { .. })));
+        assert!(doc.children.is_empty());
+
+        let (doc, error) = Parser::new(&allocator, source).parse_partial();
+        assert!(error.is_none());
+        assert_eq!(doc.children.len(), 3);
+    }
+
     #[test]
     fn test_parse_unordered_list() {
         let allocator = Allocator::new();
@@ -1220,4 +3687,171 @@ mod tests {
             _ => panic!("expected list, got {:?}", &doc.children[0]),
         }
     }
+
+    fn code_value<'a>(node: &'a Node<'_>) -> &'a str {
+        match node {
+            Node::CodeBlock(code) => code.value,
+            other => panic!("expected code block, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_indented_code_tabs() {
+        let allocator = Allocator::new();
+        let doc =
+            Parser::new(&allocator, "\tfoo\tbaz\t\tbim\n  \tbar\n\n    baz  \n").parse().unwrap();
+        assert_eq!(doc.children.len(), 1);
+        // Tabs inside the indentation are consumed; tabs in content are kept.
+        assert_eq!(code_value(&doc.children[0]), "foo\tbaz\t\tbim\nbar\n\nbaz  \n");
+
+        // A tab reaching past column 4 leaves the remaining columns as spaces.
+        let doc = Parser::new(&allocator, " \t\tfoo").parse().unwrap();
+        assert_eq!(code_value(&doc.children[0]), "\tfoo\n");
+        let doc = Parser::new(&allocator, "  \t  x\n      y").parse().unwrap();
+        assert_eq!(code_value(&doc.children[0]), "  x\n  y\n");
+
+        // Whitespace-only trailing lines are not code.
+        let doc = Parser::new(&allocator, "para\n     ").parse().unwrap();
+        assert_eq!(doc.children.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_crlf_whitespace_lines() {
+        let allocator = Allocator::new();
+        let doc = Parser::new(&allocator, "# T\r\n    \r\n").parse().unwrap();
+        assert_eq!(doc.children.len(), 1);
+        assert!(matches!(doc.children[0], Node::Heading(_)));
+
+        for source in ["\t\r\n", "    \r\n", "    \r", " \t \r\n\r\n"] {
+            let doc = Parser::new(&allocator, source).parse().unwrap();
+            assert!(doc.children.is_empty(), "{source:?} produced {:?}", doc.children);
+        }
+
+        let doc = Parser::new(&allocator, "    \r\nx").parse().unwrap();
+        assert_eq!(doc.children.len(), 1);
+        assert!(matches!(doc.children[0], Node::Paragraph(_)));
+
+        let doc = Parser::new(&allocator, "    a\r\n    \r\n    b\r\n").parse().unwrap();
+        assert_eq!(doc.children.len(), 1);
+        assert_eq!(code_value(&doc.children[0]), "a\n\nb\n");
+    }
+
+    #[test]
+    fn test_parse_list_item_code_tabs() {
+        let allocator = Allocator::new();
+        let doc = Parser::new(&allocator, "- foo\n\n\t\tbar").parse().unwrap();
+        assert_eq!(doc.children.len(), 1);
+        let Node::List(list) = &doc.children[0] else { panic!("expected list") };
+        let item = &list.children[0];
+        assert_eq!(item.children.len(), 2);
+        // Content column 2 plus 4: the second tab stops at column 8.
+        assert_eq!(code_value(&item.children[1]), "  bar\n");
+
+        let doc = Parser::new(&allocator, "1.  foo\n\n        code\n\n    more").parse().unwrap();
+        let Node::List(list) = &doc.children[0] else { panic!("expected list") };
+        let item = &list.children[0];
+        assert_eq!(code_value(&item.children[1]), "code\n");
+        assert!(matches!(&item.children[2], Node::Paragraph(_)));
+        assert!(item.spread);
+
+        // A tab-indented nested list continues the item.
+        let doc = Parser::new(&allocator, "- a\n\n\t- b").parse().unwrap();
+        let Node::List(list) = &doc.children[0] else { panic!("expected list") };
+        assert!(matches!(&list.children[0].children[1], Node::List(_)));
+    }
+
+    #[test]
+    fn test_parse_tab_after_marker() {
+        let allocator = Allocator::new();
+        // The tab reaches column 4, which is where the item content starts.
+        let doc = Parser::new(&allocator, "-\tfoo\n\n\tbar").parse().unwrap();
+        assert_eq!(doc.children.len(), 1);
+        let Node::List(list) = &doc.children[0] else { panic!("expected list") };
+        assert_eq!(list.children[0].children.len(), 2);
+        assert!(matches!(&list.children[0].children[1], Node::Paragraph(_)));
+
+        let doc = Parser::new(&allocator, "1.\tx").parse().unwrap();
+        let Node::List(list) = &doc.children[0] else { panic!("expected list") };
+        assert!(list.ordered);
+
+        let doc = Parser::new(&allocator, ">\tfoo").parse().unwrap();
+        let Node::BlockQuote(quote) = &doc.children[0] else { panic!("expected block quote") };
+        let Node::Paragraph(paragraph) = &quote.children[0] else { panic!("expected paragraph") };
+        let Node::Text(text) = &paragraph.children[0] else { panic!("expected text") };
+        assert_eq!(text.value, "foo");
+
+        // One column of the tab belongs to the marker, leaving six.
+        let doc = Parser::new(&allocator, ">\t\tfoo").parse().unwrap();
+        let Node::BlockQuote(quote) = &doc.children[0] else { panic!("expected block quote") };
+        assert_eq!(code_value(&quote.children[0]), "  foo\n");
+    }
+
+    #[test]
+    fn test_parse_block_quote() {
+        let allocator = Allocator::new();
+        let source = "> a\nlazy\n> > nested\n\n> - item\n";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        assert_eq!(doc.children.len(), 2);
+        let Node::BlockQuote(quote) = &doc.children[0] else { panic!("expected block quote") };
+        assert_eq!(quote.children.len(), 2);
+        assert_eq!(quote.children[0].span(), Span::new(2, 9));
+        let Node::BlockQuote(nested) = &quote.children[1] else { panic!("expected block quote") };
+        assert_eq!(nested.children[0].span(), Span::new(13, 20));
+        let Node::BlockQuote(quote) = &doc.children[1] else { panic!("expected block quote") };
+        assert!(matches!(&quote.children[0], Node::List(_)));
+
+        // Quotes interrupt paragraphs and can be turned off.
+        let doc = Parser::new(&allocator, "para\n> quote").parse().unwrap();
+        assert!(matches!(&doc.children[1], Node::BlockQuote(_)));
+        let features = Features::all().without(Feature::BlockQuote);
+        let options = ParserOptions { features, ..ParserOptions::default() };
+        let doc = Parser::with_options(&allocator, "> quote", options).parse().unwrap();
+        assert!(matches!(&doc.children[0], Node::Paragraph(_)));
+    }
+
+    #[test]
+    fn test_parse_list_item_blocks() {
+        let allocator = Allocator::new();
+        let doc = Parser::new(&allocator, "- a\n\n  > q").parse().unwrap();
+        let Node::List(list) = &doc.children[0] else { panic!("expected list") };
+        assert!(matches!(&list.children[0].children[1], Node::BlockQuote(_)));
+
+        let doc = Parser::new(&allocator, "- a\n\n  # h").parse().unwrap();
+        let Node::List(list) = &doc.children[0] else { panic!("expected list") };
+        assert!(matches!(&list.children[0].children[1], Node::Heading(_)));
+
+        let doc =
+            Parser::new(&allocator, "- a\n\n  ```js\n  let x;\n    y\n  ```\n- b").parse().unwrap();
+        let Node::List(list) = &doc.children[0] else { panic!("expected list") };
+        assert_eq!(list.children.len(), 2);
+        let Node::CodeBlock(code) = &list.children[0].children[1] else { panic!("expected code") };
+        assert_eq!((code.lang, code.value), (Some("js"), "let x;\n  y\n"));
+    }
+
+    #[test]
+    fn test_parse_list_indent() {
+        let allocator = Allocator::new();
+        let source = "1. one\n\n  - nested\n\n  more\n\n      code\n";
+
+        // CommonMark: two columns is short of `1. `, so the list ends.
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        assert!(doc.children.len() > 1);
+
+        let options = ParserOptions { list_indent: 2, ..ParserOptions::default() };
+        let doc = Parser::with_options(&allocator, source, options.clone()).parse().unwrap();
+        assert_eq!(doc.children.len(), 1);
+        let Node::List(list) = &doc.children[0] else { panic!("expected list") };
+        let item = &list.children[0];
+        assert_eq!(item.children.len(), 4);
+        assert!(matches!(&item.children[1], Node::List(_)));
+        assert!(matches!(&item.children[2], Node::Paragraph(_)));
+        assert_eq!(code_value(&item.children[3]), "code\n");
+
+        // Nesting two levels deep at two columns each.
+        let source = "- a\n\n  - b\n\n    - c\n";
+        let doc = Parser::with_options(&allocator, source, options).parse().unwrap();
+        let Node::List(list) = &doc.children[0] else { panic!("expected list") };
+        let Node::List(inner) = &list.children[0].children[1] else { panic!("expected list") };
+        assert!(matches!(&inner.children[0].children[1], Node::List(_)));
+    }
 }
@@ -0,0 +1,160 @@
+//! Parse statistics for performance profiling.
+
+use std::time::Duration;
+
+use ox_content_ast::{
+    walk_list_item, walk_node, walk_table_cell, walk_table_row, Document, ListItem, Node,
+    TableCell, TableRow, Visit,
+};
+
+/// Statistics collected while parsing a document.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseStats {
+    /// Number of AST nodes, including the document root, list items,
+    /// table rows and table cells.
+    pub node_count: usize,
+    /// Wall-clock parse time. Always zero on `wasm32-unknown-unknown`,
+    /// which has no clock.
+    pub parse_time: Duration,
+    /// Size of the source in bytes.
+    pub bytes: usize,
+}
+
+impl ParseStats {
+    /// Parse time in whole microseconds.
+    #[must_use]
+    pub fn parse_micros(&self) -> u64 {
+        u64::try_from(self.parse_time.as_micros()).unwrap_or(u64::MAX)
+    }
+
+    /// Parse throughput in bytes per second, or `0.0` when no time was measured.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn bytes_per_second(&self) -> f64 {
+        let seconds = self.parse_time.as_secs_f64();
+        if seconds > 0.0 {
+            self.bytes as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Task list checkboxes in a document.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaskProgress {
+    /// Number of task list items.
+    pub total: usize,
+    /// Number of task list items checked with `[x]`.
+    pub completed: usize,
+}
+
+/// Counts the task list items in a document, including nested lists.
+///
+/// Task items are only recognized when the document was parsed with
+/// [`ParserOptions::task_lists`](crate::ParserOptions::task_lists).
+#[must_use]
+pub fn task_progress(document: &Document<'_>) -> TaskProgress {
+    let mut counter = TaskCounter::default();
+    counter.visit_document(document);
+    counter.progress
+}
+
+#[derive(Default)]
+struct TaskCounter {
+    progress: TaskProgress,
+}
+
+impl<'a> Visit<'a> for TaskCounter {
+    fn visit_list_item(&mut self, list_item: &ListItem<'a>) {
+        if let Some(checked) = list_item.checked {
+            self.progress.total += 1;
+            self.progress.completed += usize::from(checked);
+        }
+        walk_list_item(self, list_item);
+    }
+}
+
+/// Counts every node in a document, including the root.
+#[must_use]
+pub fn count_nodes(document: &Document<'_>) -> usize {
+    let mut counter = NodeCounter { count: 1 };
+    counter.visit_document(document);
+    counter.count
+}
+
+struct NodeCounter {
+    count: usize,
+}
+
+impl<'a> Visit<'a> for NodeCounter {
+    fn visit_node(&mut self, node: &Node<'a>) {
+        self.count += 1;
+        walk_node(self, node);
+    }
+
+    fn visit_list_item(&mut self, list_item: &ListItem<'a>) {
+        self.count += 1;
+        walk_list_item(self, list_item);
+    }
+
+    fn visit_table_row(&mut self, table_row: &TableRow<'a>) {
+        self.count += 1;
+        walk_table_row(self, table_row);
+    }
+
+    fn visit_table_cell(&mut self, table_cell: &TableCell<'a>) {
+        self.count += 1;
+        walk_table_cell(self, table_cell);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, ParserOptions};
+    use ox_content_allocator::Allocator;
+
+    #[test]
+    fn test_count_nodes() {
+        let allocator = Allocator::new();
+        // root, heading, text, paragraph, text, strong, text, list, 2 items,
+        // 2 paragraphs, 2 texts
+        let source = "# Title\n\nSome **bold**\n\n- a\n- b\n";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        assert_eq!(count_nodes(&doc), 14);
+
+        // root, table, 2 rows, 4 cells, 4 texts
+        let doc = Parser::with_options(
+            &allocator,
+            "| a | b |\n|---|---|\n| c | d |",
+            ParserOptions::gfm(),
+        )
+        .parse()
+        .unwrap();
+        assert_eq!(count_nodes(&doc), 12);
+    }
+
+    #[test]
+    fn test_task_progress() {
+        let allocator = Allocator::new();
+        let source = "- [x] one\n- [ ] two\n  - [X] nested\n  - plain\n- [ ] three\n";
+        let options = ParserOptions { task_lists: true, ..ParserOptions::default() };
+        let doc = Parser::with_options(&allocator, source, options).parse().unwrap();
+        assert_eq!(task_progress(&doc), TaskProgress { total: 4, completed: 2 });
+
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        assert_eq!(task_progress(&doc), TaskProgress::default());
+    }
+
+    #[test]
+    fn test_parse_with_stats() {
+        let allocator = Allocator::new();
+        let source = "Hello *world*";
+        let (doc, stats) = Parser::new(&allocator, source).parse_with_stats().unwrap();
+        assert_eq!(doc.children.len(), 1);
+        assert_eq!(stats.node_count, 5);
+        assert_eq!(stats.bytes, source.len());
+        assert!(stats.parse_micros() < 1_000_000);
+    }
+}
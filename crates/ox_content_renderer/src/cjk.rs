@@ -0,0 +1,112 @@
+//! Soft line breaks in Chinese and Japanese text.
+//!
+//! Browsers render a soft break as a space, which is right between Latin
+//! words but leaves a visible gap between CJK characters, which are
+//! written without spaces.
+
+/// Returns whether `c` is written without spaces between words: Han
+/// ideographs, kana, Bopomofo, CJK punctuation and fullwidth forms.
+///
+/// Hangul is not included, as Korean separates words with spaces.
+pub const fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{2E80}'..='\u{2FDF}'     // CJK radicals, Kangxi radicals
+        | '\u{3000}'..='\u{303F}'   // CJK symbols and punctuation
+        | '\u{3040}'..='\u{30FF}'   // Hiragana, Katakana
+        | '\u{3100}'..='\u{312F}'   // Bopomofo
+        | '\u{31A0}'..='\u{31FF}'   // Bopomofo extended, Katakana extensions
+        | '\u{3400}'..='\u{4DBF}'   // CJK extension A
+        | '\u{4E00}'..='\u{9FFF}'   // CJK unified ideographs
+        | '\u{F900}'..='\u{FAFF}'   // CJK compatibility ideographs
+        | '\u{FE30}'..='\u{FE4F}'   // CJK compatibility forms
+        | '\u{FF00}'..='\u{FF60}'   // Fullwidth forms
+        | '\u{FF61}'..='\u{FF9F}'   // Halfwidth Katakana
+        | '\u{20000}'..='\u{3134F}' // Extensions B to G
+    )
+}
+
+/// Removes the soft line breaks of rendered inline HTML that sit between
+/// two CJK characters, looking through the inline tags around them.
+///
+/// Breaks in code spans and next to `<br>` or `<img>` are kept.
+pub fn remove_cjk_soft_breaks(html: &str) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut in_code = false;
+    for (i, c) in html.char_indices() {
+        match c {
+            '<' if html[i..].starts_with("<code") => in_code = true,
+            '<' if html[i..].starts_with("</code>") => in_code = false,
+            '\n' if !in_code
+                && visible_char_before(&html[..i]).is_some_and(is_cjk)
+                && visible_char_after(&html[i + 1..]).is_some_and(is_cjk) =>
+            {
+                continue;
+            }
+            _ => {}
+        }
+        output.push(c);
+    }
+    output
+}
+
+/// Returns the last text character of `html`, skipping closing and opening
+/// tags, or `None` if a `<br>` or `<img>` comes first.
+fn visible_char_before(mut html: &str) -> Option<char> {
+    loop {
+        let c = html.chars().next_back()?;
+        if c != '>' {
+            return Some(c);
+        }
+        // Text is escaped, so every `<` and `>` belongs to a tag
+        let tag_start = html.rfind('<')?;
+        if is_replaced_tag(&html[tag_start..]) {
+            return None;
+        }
+        html = &html[..tag_start];
+    }
+}
+
+/// Returns the first text character of `html`, skipping tags, or `None`
+/// if a `<br>` or `<img>` comes first.
+fn visible_char_after(mut html: &str) -> Option<char> {
+    loop {
+        let c = html.chars().next()?;
+        if c != '<' {
+            return Some(c);
+        }
+        if is_replaced_tag(html) {
+            return None;
+        }
+        html = &html[html.find('>')? + 1..];
+    }
+}
+
+/// Returns whether `tag` starts with a `<br>` or `<img>` tag, which break
+/// the text around them.
+fn is_replaced_tag(tag: &str) -> bool {
+    ["<br", "<img"].iter().any(|name| {
+        tag.strip_prefix(name).is_some_and(|rest| !rest.starts_with(|c: char| c.is_alphanumeric()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_cjk_soft_breaks() {
+        let cases = [
+            ("中文\n句子", "中文句子"),
+            ("日本語の\nテキスト。", "日本語のテキスト。"),
+            ("English\nwords", "English\nwords"),
+            ("中文\nEnglish\n中文", "中文\nEnglish\n中文"),
+            ("<strong>中文</strong>\n<em>句子</em>", "<strong>中文</strong><em>句子</em>"),
+            ("中文<br>\n句子", "中文<br>\n句子"),
+            ("<code>中\n文</code>", "<code>中\n文</code>"),
+            ("한국어\n문장", "한국어\n문장"),
+        ];
+        for (html, expected) in cases {
+            assert_eq!(remove_cjk_soft_breaks(html), expected, "{html}");
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! HTML templates for custom node types.
+
+/// Fills `template` with the fields of a custom node: `{name}` is replaced
+/// by the HTML-escaped value of `field("name")`, or nothing if the node has
+/// no such field.
+///
+/// Braces around anything other than a name of letters, digits and `_` are
+/// kept as written, so templates can contain CSS or JSON.
+pub fn fill_template(template: &str, field: impl Fn(&str) -> Option<String>) -> String {
+    let mut html = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        html.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let name_len = after.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'));
+        match name_len {
+            Some(len) if len > 0 && after[len..].starts_with('}') => {
+                if let Some(value) = field(&after[..len]) {
+                    push_escaped(&mut html, &value);
+                }
+                rest = &after[len + 1..];
+            }
+            _ => {
+                html.push('{');
+                rest = after;
+            }
+        }
+    }
+    html.push_str(rest);
+    html
+}
+
+/// Returns the comment written for a node type without a template.
+///
+/// Only letters, digits and single `-`, `_`, `.` or `:` between them are
+/// kept from the type, so it cannot end the comment early.
+pub fn unknown_node_comment(node_type: &str) -> String {
+    let mut name = String::with_capacity(node_type.len());
+    let separators = ['-', '_', '.', ':'];
+    for c in node_type.chars() {
+        let after_name = !name.is_empty() && !name.ends_with(separators);
+        if c.is_alphanumeric() || (separators.contains(&c) && after_name) {
+            name.push(c);
+        }
+    }
+    let name = name.trim_end_matches(separators);
+    format!("<!-- unknown node: {name} -->")
+}
+
+fn push_escaped(html: &mut String, value: &str) {
+    for ch in value.chars() {
+        match ch {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            _ => html.push(ch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_template() {
+        let field = |name: &str| match name {
+            "id" => Some("w1".to_string()),
+            "title" => Some("<Tom & \"Jerry\">".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            fill_template("<div data-id=\"{id}\" title=\"{title}\">{missing}</div>", field),
+            "<div data-id=\"w1\" title=\"&lt;Tom &amp; &quot;Jerry&quot;&gt;\"></div>"
+        );
+        assert_eq!(
+            fill_template("<style>a { color: red }</style>{", field),
+            "<style>a { color: red }</style>{"
+        );
+        assert_eq!(unknown_node_comment("my-widget"), "<!-- unknown node: my-widget -->");
+        assert_eq!(unknown_node_comment("x--><script>"), "<!-- unknown node: x-script -->");
+    }
+}
@@ -0,0 +1,332 @@
+//! Heuristic language detection for code blocks without a language.
+
+/// Minimum score for a guess to be used.
+const MIN_SCORE: u32 = 4;
+/// Minimum lead of the best guess over the runner-up.
+const MIN_MARGIN: u32 = 2;
+
+/// A clue that code is written in a language, with its weight.
+///
+/// Patterns match anywhere in the code, except that a leading `^` anchors
+/// them to the start of a line (after indentation) and a trailing `$` to
+/// the end of a line. Each clue counts once, however often it matches.
+type Clue = (&'static str, u32);
+
+/// Clues per language, using the names of [`DEFAULT_LANGUAGE_ALIASES`].
+///
+/// [`DEFAULT_LANGUAGE_ALIASES`]: crate::DEFAULT_LANGUAGE_ALIASES
+const CLUES: &[(&str, &[Clue])] = &[
+    (
+        "rust",
+        &[
+            ("^fn ", 2),
+            ("^pub fn ", 3),
+            ("^use std::", 3),
+            ("^use ", 1),
+            ("?;", 2),
+            ("^impl ", 2),
+            ("^mod ", 2),
+            ("let mut ", 3),
+            ("&self", 3),
+            ("&str", 3),
+            ("#[derive(", 3),
+            ("println!(", 3),
+            ("Some(", 1),
+            ("Vec<", 2),
+            ("::new(", 1),
+            (".unwrap()", 2),
+            (") -> ", 1),
+        ],
+    ),
+    (
+        "go",
+        &[
+            ("^package ", 3),
+            ("^func ", 3),
+            ("^import (", 3),
+            (" := ", 3),
+            ("fmt.", 3),
+            ("err != nil", 4),
+            ("go func", 3),
+            ("^defer ", 3),
+            ("chan ", 1),
+        ],
+    ),
+    (
+        "python",
+        &[
+            ("^def ", 3),
+            ("^from ", 1),
+            (" import ", 1),
+            ("^elif ", 3),
+            ("__init__", 3),
+            ("self.", 1),
+            ("print(", 1),
+            (" None", 1),
+            ("lambda ", 2),
+            (" not in ", 2),
+            (" in range(", 3),
+            ("^if __name__", 4),
+            ("^class ", 1),
+            (":$", 2),
+        ],
+    ),
+    (
+        "javascript",
+        &[
+            ("^const ", 1),
+            ("^let ", 1),
+            ("^function ", 2),
+            (" => ", 1),
+            ("===", 2),
+            ("console.log(", 3),
+            ("require(", 3),
+            ("module.exports", 3),
+            ("^export ", 1),
+            ("^import ", 1),
+            ("document.", 2),
+            ("await ", 1),
+            (";$", 1),
+        ],
+    ),
+    (
+        "bash",
+        &[
+            ("^#!/bin/", 4),
+            ("^#!/usr/bin/env bash", 4),
+            ("^$ ", 4),
+            ("^npm ", 4),
+            ("^npx ", 4),
+            ("^pnpm ", 4),
+            ("^yarn ", 4),
+            ("^cargo ", 4),
+            ("^git ", 4),
+            ("^curl ", 4),
+            ("^sudo ", 4),
+            ("^cd ", 2),
+            ("^echo ", 2),
+            ("^mkdir ", 2),
+            ("^brew ", 4),
+            ("apt-get ", 3),
+            ("then$", 2),
+            ("^fi$", 2),
+            ("^done$", 2),
+        ],
+    ),
+    (
+        "html",
+        &[
+            ("^<!DOCTYPE", 4),
+            ("^<!doctype", 4),
+            ("^<html", 3),
+            ("<div", 2),
+            ("</div>", 2),
+            ("<script", 2),
+            ("href=\"", 2),
+            ("^</", 1),
+            ("<p>", 1),
+        ],
+    ),
+    (
+        "css",
+        &[
+            ("^@media ", 3),
+            ("^@import ", 2),
+            ("^:root {", 3),
+            ("!important", 3),
+            ("px;$", 2),
+            ("^color: ", 2),
+            ("^margin: ", 2),
+            ("^padding: ", 2),
+            ("^display: ", 2),
+            ("^font-", 2),
+            ("^}$", 1),
+        ],
+    ),
+    (
+        "sql",
+        &[
+            ("SELECT ", 3),
+            (" FROM ", 2),
+            ("WHERE ", 2),
+            ("INSERT INTO ", 4),
+            ("CREATE TABLE ", 4),
+            ("GROUP BY ", 3),
+            ("ORDER BY ", 3),
+            (" JOIN ", 2),
+            ("^UPDATE ", 2),
+        ],
+    ),
+];
+
+/// Clues that code using JavaScript syntax is TypeScript.
+const TYPESCRIPT_CLUES: &[Clue] = &[
+    (": string", 3),
+    (": number", 3),
+    (": boolean", 3),
+    ("^interface ", 3),
+    ("^export interface ", 3),
+    ("^type ", 2),
+    ("^export type ", 3),
+    ("^import type ", 3),
+    (" as const", 2),
+    ("): void", 3),
+];
+
+/// Guesses the language of a code block from keywords and symbols.
+///
+/// Recognizes Rust, Go, Python, JavaScript, TypeScript, Bash, HTML, CSS,
+/// SQL, JSON, YAML and TOML. Returns `None` unless one language is a clear
+/// winner, so short or ambiguous snippets stay untagged.
+#[must_use]
+pub fn detect_language(code: &str) -> Option<&'static str> {
+    let code = code.trim();
+    if code.is_empty() {
+        return None;
+    }
+    if let Some(language) = detect_data_format(code) {
+        return Some(language);
+    }
+
+    // SQL keywords are matched in any case
+    let upper = code.to_ascii_uppercase();
+    let mut scores: Vec<(&'static str, u32)> = CLUES
+        .iter()
+        .map(|&(language, clues)| {
+            let text = if language == "sql" { upper.as_str() } else { code };
+            (language, score(text, clues))
+        })
+        .collect();
+    // TypeScript is JavaScript with types, so it needs the JavaScript clues
+    // and at least one of its own.
+    let javascript = scores.iter().find(|(language, _)| *language == "javascript").map(|s| s.1);
+    let typescript = score(code, TYPESCRIPT_CLUES);
+    if typescript > 0 {
+        scores.push(("typescript", javascript.unwrap_or(0) + typescript));
+    }
+
+    scores.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    let (language, best) = scores[0];
+    let runner_up = scores.get(1).map_or(0, |s| s.1);
+    // A TypeScript guess wins over JavaScript on its type clues alone.
+    let runner_up = if language == "typescript" && scores[1].0 == "javascript" {
+        scores.get(2).map_or(0, |s| s.1)
+    } else {
+        runner_up
+    };
+    (best >= MIN_SCORE && best >= runner_up + MIN_MARGIN).then_some(language)
+}
+
+/// Sums the weights of the clues found in `code`.
+fn score(code: &str, clues: &[Clue]) -> u32 {
+    clues.iter().filter(|(pattern, _)| has_clue(code, pattern)).map(|(_, weight)| weight).sum()
+}
+
+/// Returns whether `pattern`, with optional `^` and `$` anchors, occurs in
+/// `code`.
+fn has_clue(code: &str, pattern: &str) -> bool {
+    let (start, pattern) =
+        pattern.strip_prefix('^').map_or((false, pattern), |pattern| (true, pattern));
+    let (end, pattern) =
+        pattern.strip_suffix('$').map_or((false, pattern), |pattern| (true, pattern));
+    if !start && !end {
+        return code.contains(pattern);
+    }
+    code.lines().map(str::trim).any(|line| match (start, end) {
+        (true, true) => line == pattern,
+        (true, false) => line.starts_with(pattern),
+        _ => line.ends_with(pattern),
+    })
+}
+
+/// Recognizes JSON, YAML and TOML from their overall structure.
+fn detect_data_format(code: &str) -> Option<&'static str> {
+    let first = code.chars().next()?;
+    let last = code.chars().last()?;
+    if matches!((first, last), ('{', '}') | ('[', ']'))
+        && code.contains("\":")
+        && !code.contains(';')
+        && !code.contains(" = ")
+    {
+        return Some("json");
+    }
+
+    let lines: Vec<&str> = code
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+    if lines.len() < 2 {
+        return None;
+    }
+    let share = |is_match: fn(&str) -> bool| {
+        lines.iter().filter(|line| is_match(line)).count() * 5 >= lines.len() * 4
+    };
+    if share(|line| {
+        (line.starts_with('[') && line.ends_with(']'))
+            || line.split_once(" = ").is_some_and(|(key, _)| is_key(key.trim_matches('"')))
+    }) && lines.iter().any(|line| line.contains(" = "))
+    {
+        return Some("toml");
+    }
+    if share(|line| {
+        line.starts_with("- ")
+            || line == "-"
+            || line.split_once(':').is_some_and(|(key, value)| {
+                is_key(key.trim_start_matches("- ")) && (value.is_empty() || value.starts_with(' '))
+            })
+    }) && !code.contains(['{', ';', '('])
+        && (lines.len() >= 3 || code.lines().any(|line| line.starts_with([' ', '-'])))
+    {
+        return Some("yaml");
+    }
+    None
+}
+
+/// Returns whether `key` looks like a configuration key.
+fn is_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        let cases = [
+            ("fn main() {\n    let mut v = Vec::new();\n    println!(\"{}\", v.len());\n}", "rust"),
+            ("package main\n\nfunc main() {\n\tx := 1\n\tfmt.Println(x)\n}", "go"),
+            ("def greet(name):\n    if name is None:\n        return\n    print(name)", "python"),
+            ("const x = require('x');\nconsole.log(x === 1);", "javascript"),
+            ("interface User {\n  name: string;\n}\nexport const id = (u: User): string => u.name;", "typescript"),
+            ("$ npm install\n$ cargo build --release", "bash"),
+            ("npm install ox-content", "bash"),
+            ("use ox_content_parser::Parser;\nlet doc = Parser::new(&a, src).parse()?;", "rust"),
+            ("<!DOCTYPE html>\n<html>\n<div class=\"a\"></div>\n</html>", "html"),
+            (":root {\n  color: red;\n}\n@media (max-width: 600px) {\n  margin: 0 !important;\n}", "css"),
+            ("SELECT id, name\nFROM users\nWHERE active = 1\nORDER BY name;", "sql"),
+            ("select * from posts\ninner join users on users.id = posts.author", "sql"),
+            ("{\n  \"name\": \"ox\",\n  \"private\": true\n}", "json"),
+            ("name: docs\non:\n  push:\n    branches:\n      - main", "yaml"),
+            ("[package]\nname = \"ox\"\nversion = \"0.1.0\"", "toml"),
+        ];
+        for (code, expected) in cases {
+            assert_eq!(detect_language(code), Some(expected), "{code}");
+        }
+    }
+
+    #[test]
+    fn test_low_confidence_is_untagged() {
+        for code in [
+            "hello world",
+            "x = 1",
+            "foo();",
+            "Some text\nthat is prose.",
+            "a: b",
+            "Note: one\nTip: two",
+        ] {
+            assert_eq!(detect_language(code), None, "{code}");
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Custom emoji shortcodes such as `:custom-logo:`.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// What a custom emoji shortcode expands to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Emoji {
+    /// Text written in place of the shortcode, usually a Unicode emoji.
+    Text(String),
+    /// An image URL, rendered as `<img class="emoji custom-emoji">`.
+    Image(String),
+}
+
+impl Emoji {
+    /// Creates an emoji from a shortcode map value: URLs starting with
+    /// `http://`, `https://`, `data:image/`, `/`, `./` or `../` are images,
+    /// anything else is text.
+    #[must_use]
+    pub fn from_value(value: &str) -> Self {
+        let is_url = ["http://", "https://", "data:image/", "/", "./", "../"]
+            .iter()
+            .any(|prefix| value.starts_with(prefix));
+        if is_url {
+            Self::Image(value.to_string())
+        } else {
+            Self::Text(value.to_string())
+        }
+    }
+}
+
+/// Finds the shortcodes in `text` that are in `emoji`, keyed by name
+/// without colons, with their byte ranges.
+///
+/// Shortcode names are made of letters, digits, `_`, `+` and `-`. Unknown
+/// shortcodes are skipped, so they stay literal text.
+pub fn find_shortcodes<'e>(
+    text: &str,
+    emoji: &'e HashMap<String, Emoji>,
+) -> Vec<(Range<usize>, &'e Emoji)> {
+    let mut found = Vec::new();
+    let mut pos = 0;
+    while let Some(offset) = text[pos..].find(':') {
+        let start = pos + offset;
+        let name_len = text[start + 1..]
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '+' | '-')))
+            .unwrap_or(text.len() - start - 1);
+        let end = start + 1 + name_len;
+        if name_len > 0 && text[end..].starts_with(':') {
+            if let Some(value) = emoji.get(&text[start + 1..end]) {
+                found.push((start..end + 1, value));
+                pos = end + 1;
+                continue;
+            }
+        }
+        // The closing colon of an unknown name may open the next shortcode
+        pos = start + 1;
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_shortcodes() {
+        let emoji = HashMap::from([
+            ("ship".to_string(), Emoji::from_value("🚢")),
+            ("custom-logo".to_string(), Emoji::from_value("/img/logo.png")),
+        ]);
+        let text = "At 10:30 :ship: :unknown:custom-logo: :ship";
+        let found: Vec<_> =
+            find_shortcodes(text, &emoji).into_iter().map(|(range, e)| (&text[range], e)).collect();
+        assert_eq!(
+            found,
+            [
+                (":ship:", &Emoji::Text("🚢".to_string())),
+                (":custom-logo:", &Emoji::Image("/img/logo.png".to_string())),
+            ]
+        );
+        assert_eq!(Emoji::from_value("¯\\_(ツ)_/¯"), Emoji::Text("¯\\_(ツ)_/¯".to_string()));
+    }
+}
@@ -0,0 +1,165 @@
+//! Heading level normalization.
+
+use ox_content_ast::{Document, Heading, Node};
+
+/// How heading levels are adjusted before rendering.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HeadingNormalization {
+    /// Leave heading levels as written.
+    #[default]
+    None,
+    /// Keep the first h1 and demote every later h1 to h2. Headings after
+    /// the second h1 move down one level with it (capped at h6), so each
+    /// demoted section keeps its structure.
+    SingleH1Demote,
+    /// Promote the first heading to h1 when the document has no h1, for
+    /// pages that start at h2. Other headings are unchanged.
+    PromoteFirst,
+}
+
+impl HeadingNormalization {
+    /// Returns the mode name (`none`, `single_h1_demote` or `promote_first`).
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::SingleH1Demote => "single_h1_demote",
+            Self::PromoteFirst => "promote_first",
+        }
+    }
+
+    /// Looks up a mode by name, ignoring ASCII case.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::None, Self::SingleH1Demote, Self::PromoteFirst]
+            .into_iter()
+            .find(|mode| mode.as_str().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Adjusts the heading levels of `document` in place.
+///
+/// Headings nested in block quotes, list items and footnote definitions are
+/// included, in document order. Run this before rendering and before
+/// building a table of contents so both use the same levels.
+pub fn normalize_headings(document: &mut Document<'_>, mode: HeadingNormalization) {
+    let mut headings = Vec::new();
+    collect_headings(&mut document.children, &mut headings);
+
+    match mode {
+        HeadingNormalization::None => {}
+        HeadingNormalization::SingleH1Demote => {
+            let mut seen_h1 = false;
+            let mut shift = 0;
+            for heading in headings {
+                if heading.depth == 1 {
+                    if seen_h1 {
+                        shift = 1;
+                    }
+                    seen_h1 = true;
+                }
+                heading.depth = (heading.depth + shift).min(6);
+            }
+        }
+        HeadingNormalization::PromoteFirst => {
+            if headings.iter().all(|heading| heading.depth != 1) {
+                if let Some(first) = headings.into_iter().next() {
+                    first.depth = 1;
+                }
+            }
+        }
+    }
+}
+
+/// Removes the first top-level h1 of `document`, for pages whose layout
+/// shows the title from frontmatter. Returns whether a heading was removed.
+///
+/// Run this before [`normalize_headings`], so that only an h1 written in
+/// the source counts as the title.
+pub fn remove_title_heading(document: &mut Document<'_>) -> bool {
+    let title = document
+        .children
+        .iter()
+        .position(|node| matches!(node, Node::Heading(heading) if heading.depth == 1));
+    if let Some(index) = title {
+        document.children.remove(index);
+    }
+    title.is_some()
+}
+
+/// Collects mutable references to all headings under `nodes`.
+fn collect_headings<'n, 'a>(nodes: &'n mut [Node<'a>], out: &mut Vec<&'n mut Heading<'a>>) {
+    for node in nodes {
+        match node {
+            Node::Heading(heading) => out.push(heading),
+            Node::BlockQuote(quote) => collect_headings(&mut quote.children, out),
+            Node::List(list) => {
+                for item in &mut list.children {
+                    collect_headings(&mut item.children, out);
+                }
+            }
+            Node::FootnoteDefinition(definition) => {
+                collect_headings(&mut definition.children, out);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ox_content_allocator::Allocator;
+    use ox_content_parser::Parser;
+
+    fn depths(source: &str, mode: HeadingNormalization) -> Vec<u8> {
+        let allocator = Allocator::new();
+        let mut document = Parser::new(&allocator, source).parse().unwrap();
+        normalize_headings(&mut document, mode);
+        let mut headings = Vec::new();
+        collect_headings(&mut document.children, &mut headings);
+        headings.iter().map(|heading| heading.depth).collect()
+    }
+
+    #[test]
+    fn test_single_h1_demote() {
+        let source = "# A\n## A.1\n# B\n## B.1\n###### deep";
+        assert_eq!(depths(source, HeadingNormalization::SingleH1Demote), [1, 2, 2, 3, 6]);
+        assert_eq!(depths(source, HeadingNormalization::None), [1, 2, 1, 2, 6]);
+    }
+
+    #[test]
+    fn test_promote_first() {
+        assert_eq!(depths("## Title\n## A\n### B", HeadingNormalization::PromoteFirst), [1, 2, 3]);
+        assert_eq!(depths("## Intro\n# Title", HeadingNormalization::PromoteFirst), [2, 1]);
+    }
+
+    #[test]
+    fn test_remove_title_heading() {
+        let allocator = Allocator::new();
+        let mut document =
+            Parser::new(&allocator, "Intro\n\n# Title\n\n## A\n\n# Second").parse().unwrap();
+        assert!(remove_title_heading(&mut document));
+        let mut headings = Vec::new();
+        collect_headings(&mut document.children, &mut headings);
+        assert_eq!(headings.iter().map(|heading| heading.depth).collect::<Vec<_>>(), [2, 1]);
+        assert_eq!(document.children.len(), 3);
+
+        let mut document = Parser::new(&allocator, "## Only h2").parse().unwrap();
+        assert!(!remove_title_heading(&mut document));
+        assert_eq!(document.children.len(), 1);
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(
+            HeadingNormalization::from_name("single_h1_demote"),
+            Some(HeadingNormalization::SingleH1Demote)
+        );
+        assert_eq!(
+            HeadingNormalization::from_name("PROMOTE_FIRST"),
+            Some(HeadingNormalization::PromoteFirst)
+        );
+        assert_eq!(HeadingNormalization::from_name("demote"), None);
+    }
+}
@@ -1,17 +1,79 @@
 //! HTML renderer implementation.
 
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::ops::Range;
+
 use ox_content_ast::{
-    BlockQuote, Break, CodeBlock, Definition, Delete, Document, Emphasis, FootnoteDefinition,
-    FootnoteReference, Heading, Html, Image, InlineCode, Link, List, ListItem, Paragraph, Strong,
-    Table, TableCell, TableRow, Text, ThematicBreak, Visit,
+    heading_slug, heading_text, Abbreviation, BlockQuote, Break, CodeBlock, Definition, Delete,
+    Document, Emphasis, FootnoteDefinition, FootnoteReference, Heading, HeadingAnchor, Html, Image,
+    InlineCode, InlineFootnote, InlineMath, Link, List, ListItem, Math, Node, Paragraph, SlugStyle,
+    Span, Strong, Table, TableCell, TableRow, Text, ThematicBreak, UniqueSlugs, Visit,
 };
 
+use crate::cjk::remove_cjk_soft_breaks;
+use crate::custom::{fill_template, unknown_node_comment};
+use crate::detect::detect_language;
+use crate::emoji::{find_shortcodes, Emoji};
+use crate::languages::canonical_language;
+use crate::math::{latex_to_mathml, MathOutput};
+use crate::numbering::HeadingNumbering;
+use crate::references::{Reference, ReferenceLinks};
 use crate::render::{RenderResult, Renderer};
+use crate::sections::section_range;
+
+/// Line ending style for rendered HTML.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// Unix-style `\n`.
+    #[default]
+    Lf,
+    /// Windows-style `\r\n`.
+    Crlf,
+}
+
+impl LineEnding {
+    /// Returns the line ending characters.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::Crlf => "\r\n",
+        }
+    }
+}
+
+/// Code fence languages mapped to their canonical names by default.
+pub const DEFAULT_LANGUAGE_ALIASES: &[(&str, &str)] = &[
+    ("js", "javascript"),
+    ("mjs", "javascript"),
+    ("cjs", "javascript"),
+    ("ts", "typescript"),
+    ("mts", "typescript"),
+    ("cts", "typescript"),
+    ("sh", "bash"),
+    ("shell", "bash"),
+    ("zsh", "bash"),
+    ("py", "python"),
+    ("rb", "ruby"),
+    ("rs", "rust"),
+    ("yml", "yaml"),
+    ("md", "markdown"),
+    ("htm", "html"),
+    ("kt", "kotlin"),
+    ("ps1", "powershell"),
+    ("dockerfile", "docker"),
+];
 
 /// HTML renderer options.
 #[derive(Debug, Clone, Default)]
 pub struct HtmlRendererOptions {
-    /// Use XHTML-style self-closing tags (e.g., `<br />`).
+    /// Write XHTML for feeds and other XML contexts: void elements are
+    /// self-closed (`<br />`, `<img … />`, `<hr />`, `<input … />`) and
+    /// boolean attributes get values (`disabled="disabled"`). A `<br>` in
+    /// [`hard_break`](Self::hard_break) is self-closed too; raw HTML is
+    /// left as written.
     pub xhtml: bool,
     /// Add soft breaks between inline elements.
     pub soft_break: String,
@@ -19,12 +81,127 @@ pub struct HtmlRendererOptions {
     pub hard_break: String,
     /// Enable syntax highlighting for code blocks.
     pub highlight: bool,
+    /// Code fence languages to rename before they are used in the
+    /// `language-*` class, keyed by lowercase alias (`js` → `javascript`).
+    ///
+    /// Languages are looked up case-insensitively; unmapped ones are kept
+    /// as written. [`new`](Self::new) starts from
+    /// [`DEFAULT_LANGUAGE_ALIASES`].
+    pub language_aliases: HashMap<String, String>,
     /// Sanitize HTML output.
     pub sanitize: bool,
     /// Convert `.md` links to `.html` links for SSG output.
     pub convert_md_links: bool,
     /// Base URL for absolute link conversion (e.g., "/" or "/docs/").
     pub base_url: String,
+    /// Line ending used for every newline in the output.
+    pub line_ending: LineEnding,
+    /// Ensure the output ends with exactly one line ending.
+    ///
+    /// When disabled, the output is left as rendered.
+    pub trailing_newline: bool,
+    /// Prefix headings with hierarchical section numbers (`1.`, `1.1.`, ...).
+    pub number_headings: bool,
+    /// Heading level that gets top-level numbers (default: 2).
+    ///
+    /// Higher-level headings are not numbered and restart the numbering.
+    pub number_headings_start: u8,
+    /// Add an `id` attribute to every heading, derived from its text.
+    /// Repeated IDs get `-1`, `-2`, ... appended, see [`UniqueSlugs`].
+    pub heading_ids: bool,
+    /// How heading IDs are derived from heading text.
+    pub slug_style: SlugStyle,
+    /// Append a `<a class="heading-anchor" href="#id">#</a>` permalink to
+    /// every heading. Requires `heading_ids`.
+    pub heading_anchors: bool,
+    /// Emit semantic, ARIA-friendly markup. This changes exactly:
+    ///
+    /// - A paragraph holding only an image with a title becomes
+    ///   `<figure><img …><figcaption>title</figcaption></figure>`.
+    /// - Heading anchors get `aria-label="Permalink to <heading>"` and the
+    ///   `#` symbol is wrapped in `<span aria-hidden="true">`.
+    /// - Footnote references get `role="doc-noteref"` and
+    ///   `aria-label="Footnote <id>"`.
+    /// - Footnote definitions get `role="doc-footnote"`.
+    /// - Footnote back links get `role="doc-backlink"` and
+    ///   `aria-label="Back to reference <id>"`; the `↩` symbol is wrapped
+    ///   in `<span aria-hidden="true">`.
+    pub accessible: bool,
+    /// Character sequences to encode as numeric entities inside code
+    /// blocks and inline code, e.g. `{{` and `}}`.
+    ///
+    /// This keeps template engines that post-process the HTML from
+    /// interpreting code samples; browsers decode the entities, so the code
+    /// displays unchanged. Empty by default.
+    pub code_escapes: Vec<String>,
+    /// How math parsed with the `math` parser option is rendered.
+    pub math_output: MathOutput,
+    /// Wrap every table in `<div class="table-wrapper">` so wide tables can
+    /// scroll horizontally instead of overflowing the page.
+    pub table_wrapper: bool,
+    /// Give ordered lists `class="nested-numbers"` so a stylesheet can
+    /// number nested items `1.`, `1.1.`, `1.1.1.` with the `nested-list`
+    /// CSS counter, as the SSG stylesheet does.
+    ///
+    /// Lists starting at another number than 1 also get
+    /// `style="counter-reset: nested-list <start - 1>"`. Bullet lists are
+    /// left as they are, so an ordered list inside one continues the
+    /// numbering of the nearest ordered list around it.
+    pub nested_list_numbers: bool,
+    /// Guess the language of code blocks that have none with
+    /// [`detect_language`](crate::detect_language), adding the guess as a
+    /// `language-*` class with `data-detected="true"`. Blocks without a
+    /// confident guess stay untagged.
+    pub detect_code_language: bool,
+    /// Keep the line ending of the last code line inside `<code>`, as
+    /// CommonMark does (default: true).
+    ///
+    /// When false, the content ends with the text of the last line, so
+    /// `foo` renders as `<pre><code>foo</code></pre>`. A blank line before
+    /// the closing fence is code and keeps its own line break.
+    pub code_final_newline: bool,
+    /// Apply the GFM tagfilter to raw HTML: the `<` of `<title>`,
+    /// `<textarea>`, `<style>`, `<xmp>`, `<iframe>`, `<noembed>`,
+    /// `<noframes>`, `<script>` and `<plaintext>` tags, opening or closing,
+    /// is written as `&lt;`. Other HTML is kept as written.
+    ///
+    /// This matches GitHub's output and is much narrower than `sanitize`,
+    /// which takes precedence. Enabled by [`HtmlRendererOptions::gfm`].
+    pub gfm_tagfilter: bool,
+    /// Link issue references such as `#123`, commit SHAs and `@name`
+    /// mentions in text to the configured URLs. Disabled when `None`.
+    pub reference_links: Option<ReferenceLinks>,
+    /// Add a `data-line` attribute with the 1-based source line to block
+    /// elements: paragraphs, headings, thematic breaks, block quotes, lists,
+    /// list items, code blocks and tables. Inline elements, raw HTML and
+    /// display math are left alone.
+    ///
+    /// Lines are only known when rendering with
+    /// [`HtmlRenderer::render_with_source`].
+    pub source_line_attrs: bool,
+    /// Number of lines before the rendered source in its file, added to
+    /// every `data-line`, e.g. the lines of a stripped frontmatter block.
+    pub source_line_offset: u32,
+    /// Drop soft line breaks between two Chinese or Japanese characters.
+    ///
+    /// Browsers show a soft break as a space, which splits CJK text that
+    /// was wrapped in the source. Breaks next to Latin text, in code spans
+    /// and after hard breaks are kept.
+    pub cjk_friendly_breaks: bool,
+    /// Custom emoji shortcodes, keyed by name without colons: `:name:` in
+    /// text is replaced by the emoji. Unknown shortcodes stay literal, and
+    /// code is never changed. Empty by default.
+    pub emoji: HashMap<String, Emoji>,
+    /// HTML templates for custom node types, keyed by node `type`, used by
+    /// front ends that build documents from a serialized AST. See
+    /// [`custom_node_html`](Self::custom_node_html).
+    pub custom_nodes: HashMap<String, String>,
+    /// Markers such as `[[toc]]` that stand for the table of contents: a
+    /// paragraph containing only a marker is replaced by
+    /// [`toc_html`](Self::toc_html). Empty by default.
+    pub toc_markers: Vec<String>,
+    /// HTML written in place of a TOC marker paragraph.
+    pub toc_html: String,
 }
 
 impl HtmlRendererOptions {
@@ -36,44 +213,357 @@ impl HtmlRendererOptions {
             soft_break: "\n".to_string(),
             hard_break: "<br>\n".to_string(),
             highlight: false,
+            language_aliases: DEFAULT_LANGUAGE_ALIASES
+                .iter()
+                .map(|&(alias, language)| (alias.to_string(), language.to_string()))
+                .collect(),
             sanitize: false,
             convert_md_links: false,
             base_url: "/".to_string(),
+            line_ending: LineEnding::Lf,
+            trailing_newline: false,
+            number_headings: false,
+            number_headings_start: 2,
+            heading_ids: false,
+            slug_style: SlugStyle::GitHub,
+            heading_anchors: false,
+            accessible: false,
+            code_escapes: Vec::new(),
+            math_output: MathOutput::Passthrough,
+            table_wrapper: false,
+            nested_list_numbers: false,
+            detect_code_language: false,
+            code_final_newline: true,
+            gfm_tagfilter: false,
+            reference_links: None,
+            source_line_attrs: false,
+            source_line_offset: 0,
+            cjk_friendly_breaks: false,
+            emoji: HashMap::new(),
+            custom_nodes: HashMap::new(),
+            toc_markers: Vec::new(),
+            toc_html: String::new(),
         }
     }
+
+    /// Creates options for GFM output: defaults with the tagfilter enabled.
+    #[must_use]
+    pub fn gfm() -> Self {
+        Self { gfm_tagfilter: true, ..Self::new() }
+    }
+
+    /// Returns the HTML for a node of a custom type, given a lookup of its
+    /// fields as text.
+    ///
+    /// `{name}` in the type's template is replaced by the HTML-escaped
+    /// field value, or nothing if the field is missing. Types without a
+    /// template become an `<!-- unknown node: type -->` comment.
+    #[must_use]
+    pub fn custom_node_html(
+        &self,
+        node_type: &str,
+        field: impl Fn(&str) -> Option<String>,
+    ) -> String {
+        self.custom_nodes.get(node_type).map_or_else(
+            || unknown_node_comment(node_type),
+            |template| fill_template(template, field),
+        )
+    }
 }
 
 /// HTML renderer.
 pub struct HtmlRenderer {
     options: HtmlRendererOptions,
     output: String,
+    numbering: HeadingNumbering,
+    slugs: UniqueSlugs,
+    anchors: Vec<HeadingAnchor>,
+    /// Number of links being rendered around the current node.
+    link_depth: usize,
+    /// Byte offsets of the source line starts, for `data-line` attributes.
+    line_starts: Vec<u32>,
+    /// Footnote numbers by reference label, in order of first reference.
+    footnote_numbers: HashMap<String, usize>,
+    /// Number of footnotes numbered so far, inline footnotes included.
+    footnote_count: usize,
+    /// Rendered inline footnotes with their numbers, written after the
+    /// content with the footnote definitions.
+    inline_footnotes: Vec<(usize, String)>,
+    /// Output offsets where each top-level block starts, followed by the
+    /// offset where the footnotes start.
+    block_offsets: Vec<usize>,
 }
 
 impl HtmlRenderer {
     /// Creates a new HTML renderer with default options.
     #[must_use]
     pub fn new() -> Self {
-        Self { options: HtmlRendererOptions::new(), output: String::new() }
+        Self::with_options(HtmlRendererOptions::new())
     }
 
     /// Creates a new HTML renderer with the specified options.
     #[must_use]
     pub fn with_options(options: HtmlRendererOptions) -> Self {
-        Self { options, output: String::new() }
+        let numbering = HeadingNumbering::new(options.number_headings_start);
+        Self {
+            options,
+            output: String::new(),
+            numbering,
+            slugs: UniqueSlugs::new(),
+            anchors: Vec::new(),
+            link_depth: 0,
+            line_starts: Vec::new(),
+            footnote_numbers: HashMap::new(),
+            footnote_count: 0,
+            inline_footnotes: Vec::new(),
+            block_offsets: Vec::new(),
+        }
+    }
+
+    /// Returns the renderer's options.
+    #[must_use]
+    pub const fn options(&self) -> &HtmlRendererOptions {
+        &self.options
     }
 
     /// Renders a document to HTML string.
     #[must_use]
     pub fn render(&mut self, document: &Document<'_>) -> String {
-        self.output.clear();
+        self.render_to_buffer(document);
+        std::mem::take(&mut self.output)
+    }
+
+    /// Renders a document into the renderer's internal buffer and returns it.
+    ///
+    /// Unlike [`render`](Self::render), the buffer keeps its capacity, so
+    /// rendering many documents with one renderer avoids reallocating it.
+    pub fn render_to_buffer(&mut self, document: &Document<'_>) -> &str {
+        self.reset();
         self.visit_document(document);
+        self.apply_line_endings();
+        &self.output
+    }
+
+    /// Renders a document parsed from `source` to HTML.
+    ///
+    /// The source is used to turn node spans into the line numbers of
+    /// `source_line_attrs`; otherwise this is the same as
+    /// [`render`](Self::render).
+    #[must_use]
+    pub fn render_with_source(&mut self, document: &Document<'_>, source: &str) -> String {
+        self.index_lines(source);
+        self.render_to_buffer(document);
+        self.line_starts.clear();
         std::mem::take(&mut self.output)
     }
 
+    /// Renders only the section under the top-level heading with ID `slug`,
+    /// as found by [`section_range`](crate::section_range): the heading and everything up to the
+    /// next heading of the same or a higher level.
+    ///
+    /// The whole document is rendered and the section cut out, so heading
+    /// IDs and numbers match the full page. Footnote definitions are left
+    /// out. Returns `None` if no top-level heading has the ID.
+    #[must_use]
+    pub fn render_section(&mut self, document: &Document<'_>, slug: &str) -> Option<String> {
+        let range = section_range(document, slug, self.options.slug_style)?;
+        self.reset();
+        self.visit_document(document);
+        let start = self.block_offsets[range.start];
+        let end = self.block_offsets[range.end];
+        self.output.truncate(end);
+        self.output.drain(..start);
+        self.apply_line_endings();
+        Some(std::mem::take(&mut self.output))
+    }
+
+    /// Renders a section like [`render_section`](Self::render_section),
+    /// using `source` for the line numbers of `source_line_attrs`.
+    #[must_use]
+    pub fn render_section_with_source(
+        &mut self,
+        document: &Document<'_>,
+        source: &str,
+        slug: &str,
+    ) -> Option<String> {
+        self.index_lines(source);
+        let html = self.render_section(document, slug);
+        self.line_starts.clear();
+        html
+    }
+
+    /// Clears the state of the previous render.
+    fn reset(&mut self) {
+        self.output.clear();
+        self.numbering = HeadingNumbering::new(self.options.number_headings_start);
+        self.slugs = UniqueSlugs::new();
+        self.anchors.clear();
+        self.footnote_numbers.clear();
+        self.footnote_count = 0;
+        self.inline_footnotes.clear();
+        self.block_offsets.clear();
+    }
+
+    /// Records the line starts of `source` for `source_line_attrs`.
+    fn index_lines(&mut self, source: &str) {
+        self.line_starts.clear();
+        if self.options.source_line_attrs {
+            self.line_starts.push(0);
+            self.line_starts.extend(
+                source.match_indices('\n').map(|(i, _)| u32::try_from(i + 1).unwrap_or(u32::MAX)),
+            );
+        }
+    }
+
+    /// Returns the heading IDs assigned by the last render, in document
+    /// order.
+    ///
+    /// Every heading with an `id` is listed, including headings nested in
+    /// other blocks, so the slugs match the HTML exactly. Empty unless
+    /// `heading_ids` is enabled.
+    #[must_use]
+    pub fn anchors(&self) -> &[HeadingAnchor] {
+        &self.anchors
+    }
+
+    /// Applies the configured line ending and trailing newline to the output.
+    fn apply_line_endings(&mut self) {
+        if self.options.trailing_newline {
+            let trimmed = self.output.trim_end_matches(['\r', '\n']).len();
+            self.output.truncate(trimmed);
+            self.output.push('\n');
+        }
+        if self.options.line_ending == LineEnding::Crlf {
+            self.output = self.output.replace("\r\n", "\n").replace('\n', "\r\n");
+        }
+    }
+
     fn write(&mut self, s: &str) {
         self.output.push_str(s);
     }
 
+    /// Returns whether a paragraph contains only a TOC marker. The parser
+    /// may split text at brackets, so the text nodes are joined first.
+    fn is_toc_marker(&self, paragraph: &Paragraph<'_>) -> bool {
+        let mut text = String::new();
+        for child in &paragraph.children {
+            let Node::Text(t) = child else {
+                return false;
+            };
+            text.push_str(t.value);
+        }
+        self.options.toc_markers.iter().any(|marker| marker == text.trim())
+    }
+
+    /// Writes math as MathML or, for passthrough and math outside the
+    /// supported subset, as escaped TeX in a `math` element.
+    fn write_math(&mut self, latex: &str, display: bool) {
+        let mathml = match self.options.math_output {
+            MathOutput::Passthrough => None,
+            MathOutput::MathMl => latex_to_mathml(latex, display),
+        };
+        if let Some(mathml) = mathml {
+            self.write(&mathml);
+            return;
+        }
+
+        let (tag, class) = if display { ("div", "math-display") } else { ("span", "math-inline") };
+        let _ = write!(self.output, "<{tag} class=\"math {class}\"");
+        if self.options.math_output == MathOutput::MathMl {
+            self.write(" data-latex=\"");
+            self.write_escaped(latex);
+            self.write("\"");
+        }
+        self.write(">");
+        self.write_escaped(latex);
+        let _ = write!(self.output, "</{tag}>");
+    }
+
+    /// Writes the ` data-line` attribute of a block starting at `span`, if
+    /// source lines are known.
+    fn write_line_attr(&mut self, span: Span) {
+        if self.line_starts.is_empty() {
+            return;
+        }
+        let index = self.line_starts.partition_point(|&start| start <= span.start);
+        let line = u32::try_from(index).unwrap_or(u32::MAX);
+        let _ = write!(
+            self.output,
+            " data-line=\"{}\"",
+            line.saturating_add(self.options.source_line_offset)
+        );
+    }
+
+    /// Writes text, linking issue references, commit SHAs and mentions if
+    /// enabled.
+    fn write_text(&mut self, text: &str) {
+        // Links cannot nest, so text inside a link stays text
+        match &self.options.reference_links {
+            Some(links) if self.link_depth == 0 => {
+                let references = links
+                    .find(text)
+                    .into_iter()
+                    .filter_map(|(range, reference)| {
+                        Some((range, reference, links.url(reference)?))
+                    })
+                    .collect();
+                self.write_with_references(text, references);
+            }
+            _ => self.write_escaped(text),
+        }
+    }
+
+    /// Writes the custom emoji for `shortcode`.
+    fn write_emoji(&mut self, shortcode: &str, emoji: &Emoji) {
+        match emoji {
+            Emoji::Text(text) => self.write_escaped(text),
+            Emoji::Image(url) => {
+                self.write("<img class=\"emoji custom-emoji\" src=\"");
+                self.write_url_escaped(url);
+                self.write("\" alt=\"");
+                self.write_escaped(shortcode);
+                self.write(if self.options.xhtml { "\" />" } else { "\">" });
+            }
+        }
+    }
+
+    /// Writes text with the given issue references, commit SHAs and mentions
+    /// linked.
+    fn write_with_references(
+        &mut self,
+        text: &str,
+        references: Vec<(Range<usize>, Reference<'_>, String)>,
+    ) {
+        let mut rest = 0;
+        for (range, reference, url) in references {
+            self.write_escaped(&text[rest..range.start]);
+            self.write("<a href=\"");
+            self.write_url_escaped(&url);
+            self.write("\"");
+            if url.starts_with("http://") || url.starts_with("https://") {
+                self.write(" target=\"_blank\" rel=\"noopener noreferrer\"");
+            }
+            match reference {
+                Reference::Issue(_) => {
+                    self.write(" class=\"issue-link\">");
+                    self.write_escaped(&text[range.clone()]);
+                }
+                Reference::Mention(_) => {
+                    self.write(" class=\"mention\">");
+                    self.write_escaped(&text[range.clone()]);
+                }
+                Reference::Commit(sha) => {
+                    self.write(" class=\"commit-link\"><code>");
+                    self.write_escaped(&sha[..7]);
+                    self.write("</code>");
+                }
+            }
+            self.write("</a>");
+            rest = range.end;
+        }
+        self.write_escaped(&text[rest..]);
+    }
+
     fn write_escaped(&mut self, s: &str) {
         for ch in s.chars() {
             match ch {
@@ -87,6 +577,33 @@ impl HtmlRenderer {
         }
     }
 
+    /// Writes code content, encoding the configured `code_escapes`
+    /// sequences as numeric entities.
+    fn write_code_escaped(&mut self, s: &str) {
+        if self.options.code_escapes.is_empty() {
+            self.write_escaped(s);
+            return;
+        }
+        let mut rest = s;
+        while let Some(ch) = rest.chars().next() {
+            let escape = self
+                .options
+                .code_escapes
+                .iter()
+                .find(|seq| !seq.is_empty() && rest.starts_with(seq.as_str()));
+            if let Some(seq) = escape {
+                for ch in seq.chars() {
+                    let _ = write!(self.output, "&#{};", u32::from(ch));
+                }
+                rest = &rest[seq.len()..];
+            } else {
+                let mut buf = [0; 4];
+                self.write_escaped(ch.encode_utf8(&mut buf));
+                rest = &rest[ch.len_utf8()..];
+            }
+        }
+    }
+
     fn write_url_escaped(&mut self, s: &str) {
         for ch in s.chars() {
             match ch {
@@ -190,11 +707,50 @@ impl Renderer for HtmlRenderer {
 }
 
 impl<'a> Visit<'a> for HtmlRenderer {
+    fn visit_document(&mut self, document: &Document<'a>) {
+        let mut definitions = Vec::new();
+        for child in &document.children {
+            self.block_offsets.push(self.output.len());
+            match child {
+                Node::FootnoteDefinition(definition) => definitions.push(definition),
+                _ => self.visit_node(child),
+            }
+        }
+        self.block_offsets.push(self.output.len());
+        self.write_footnotes(&definitions);
+    }
+
     fn visit_paragraph(&mut self, paragraph: &Paragraph<'a>) {
-        self.write("<p>");
+        if self.options.accessible {
+            if let [Node::Image(image)] = paragraph.children.as_slice() {
+                if let Some(title) = image.title {
+                    self.write("<figure");
+                    self.write_line_attr(paragraph.span);
+                    self.write(">\n");
+                    self.visit_image(image);
+                    self.write("\n<figcaption>");
+                    self.write_escaped(title);
+                    self.write("</figcaption>\n</figure>\n");
+                    return;
+                }
+            }
+        }
+        if !self.options.toc_markers.is_empty() && self.is_toc_marker(paragraph) {
+            self.output.push_str(&self.options.toc_html);
+            return;
+        }
+        self.write("<p");
+        self.write_line_attr(paragraph.span);
+        self.write(">");
+        let start = self.output.len();
         for child in &paragraph.children {
             self.visit_node(child);
         }
+        if self.options.cjk_friendly_breaks {
+            let content = remove_cjk_soft_breaks(&self.output[start..]);
+            self.output.truncate(start);
+            self.output.push_str(&content);
+        }
         self.write("</p>\n");
     }
 
@@ -209,25 +765,61 @@ impl<'a> Visit<'a> for HtmlRenderer {
         };
         self.write("<");
         self.write(tag);
+        self.write_line_attr(heading.span);
+        let mut anchor = None;
+        if self.options.heading_ids {
+            let text = heading_text(heading);
+            let slug = self.slugs.unique(heading_slug(heading, self.options.slug_style));
+            self.write(" id=\"");
+            self.write_escaped(&slug);
+            self.write("\"");
+            self.anchors.push(HeadingAnchor {
+                depth: heading.depth,
+                text: text.clone(),
+                slug: slug.clone(),
+            });
+            if self.options.heading_anchors {
+                anchor = Some((slug, text));
+            }
+        }
+        if !heading.classes.is_empty() {
+            self.write(" class=\"");
+            self.write_escaped(&heading.classes.join(" "));
+            self.write("\"");
+        }
         self.write(">");
+        if self.options.number_headings {
+            if let Some(number) = self.numbering.next(heading.depth) {
+                self.write("<span class=\"heading-number\">");
+                self.write(&number);
+                self.write("</span> ");
+            }
+        }
         for child in &heading.children {
             self.visit_node(child);
         }
+        if let Some((slug, text)) = anchor {
+            self.write_heading_anchor(&slug, &text);
+        }
         self.write("</");
         self.write(tag);
         self.write(">\n");
     }
 
-    fn visit_thematic_break(&mut self, _thematic_break: &ThematicBreak) {
+    fn visit_thematic_break(&mut self, thematic_break: &ThematicBreak) {
+        self.write("<hr");
+        self.write_line_attr(thematic_break.span);
         if self.options.xhtml {
-            self.write("<hr />\n");
+            self.write(" />\n");
         } else {
-            self.write("<hr>\n");
+            self.write(">\n");
         }
     }
 
     fn visit_block_quote(&mut self, block_quote: &BlockQuote<'a>) {
-        self.write("<blockquote>\n");
+        self.write("<blockquote");
+        self.write_line_attr(block_quote.span);
+        self.write(">\n");
         for child in &block_quote.children {
             self.visit_node(child);
         }
@@ -236,20 +828,22 @@ impl<'a> Visit<'a> for HtmlRenderer {
 
     fn visit_list(&mut self, list: &List<'a>) {
         if list.ordered {
-            if let Some(start) = list.start {
-                if start != 1 {
-                    self.write("<ol start=\"");
-                    self.write(&start.to_string());
-                    self.write("\">\n");
-                } else {
-                    self.write("<ol>\n");
+            self.write("<ol");
+            if self.options.nested_list_numbers {
+                self.write(" class=\"nested-numbers\"");
+            }
+            if let Some(start) = list.start.filter(|&start| start != 1) {
+                let _ = write!(self.output, " start=\"{start}\"");
+                if self.options.nested_list_numbers {
+                    let reset = i64::from(start) - 1;
+                    let _ = write!(self.output, " style=\"counter-reset: nested-list {reset}\"");
                 }
-            } else {
-                self.write("<ol>\n");
             }
         } else {
-            self.write("<ul>\n");
+            self.write("<ul");
         }
+        self.write_line_attr(list.span);
+        self.write(">\n");
 
         for child in &list.children {
             self.visit_list_item(child);
@@ -263,14 +857,19 @@ impl<'a> Visit<'a> for HtmlRenderer {
     }
 
     fn visit_list_item(&mut self, list_item: &ListItem<'a>) {
-        self.write("<li>");
+        self.write("<li");
+        self.write_line_attr(list_item.span);
+        self.write(">");
 
         if let Some(checked) = list_item.checked {
-            if checked {
-                self.write("<input type=\"checkbox\" checked disabled> ");
-            } else {
-                self.write("<input type=\"checkbox\" disabled> ");
-            }
+            self.write(match (checked, self.options.xhtml) {
+                (true, false) => "<input type=\"checkbox\" checked disabled> ",
+                (false, false) => "<input type=\"checkbox\" disabled> ",
+                (true, true) => {
+                    "<input type=\"checkbox\" checked=\"checked\" disabled=\"disabled\" /> "
+                }
+                (false, true) => "<input type=\"checkbox\" disabled=\"disabled\" /> ",
+            });
         }
 
         for child in &list_item.children {
@@ -281,28 +880,80 @@ impl<'a> Visit<'a> for HtmlRenderer {
     }
 
     fn visit_code_block(&mut self, code_block: &CodeBlock<'a>) {
-        self.write("<pre><code");
+        if code_block.is_raw() {
+            self.write("<pre class=\"raw\"");
+            self.write_line_attr(code_block.span);
+            self.write(">");
+            self.write_code_escaped(code_block.value);
+            self.write("</pre>\n");
+            return;
+        }
+        let title = code_block.title();
+        if let Some(title) = title {
+            self.write("<div class=\"code-block\"");
+            self.write_line_attr(code_block.span);
+            self.write("><div class=\"code-title\">");
+            self.write_escaped(title);
+            self.write("</div>");
+        }
+        self.write("<pre");
+        if title.is_none() {
+            self.write_line_attr(code_block.span);
+        }
+        self.write("><code");
         if let Some(lang) = code_block.lang {
+            let lang = canonical_language(&self.options.language_aliases, lang).to_string();
             self.write(" class=\"language-");
-            self.write_escaped(lang);
+            self.write_escaped(&lang);
             self.write("\"");
+        } else if let Some(lang) =
+            self.options.detect_code_language.then(|| detect_language(code_block.value)).flatten()
+        {
+            self.write(" class=\"language-");
+            self.write(lang);
+            self.write("\" data-detected=\"true\"");
         }
         self.write(">");
-        self.write_escaped(code_block.value);
-        self.write("</code></pre>\n");
+        let code = if self.options.code_final_newline {
+            code_block.value
+        } else {
+            let code = code_block.value.strip_suffix('\n').unwrap_or(code_block.value);
+            code.strip_suffix('\r').unwrap_or(code)
+        };
+        self.write_code_escaped(code);
+        self.write("</code></pre>");
+        if title.is_some() {
+            self.write("</div>");
+        }
+        self.write("\n");
     }
 
     fn visit_html(&mut self, html: &Html<'a>) {
         if self.options.sanitize {
             self.write_escaped(html.value);
+        } else if self.options.gfm_tagfilter {
+            self.write(&filter_disallowed_tags(html.value));
         } else {
             self.write(html.value);
         }
         self.write("\n");
     }
 
+    fn visit_math(&mut self, math: &Math<'a>) {
+        self.write_math(math.value, true);
+        self.write("\n");
+    }
+
     fn visit_table(&mut self, table: &Table<'a>) {
-        self.write("<table>\n");
+        if self.options.table_wrapper {
+            self.write("<div class=\"table-wrapper\"");
+            self.write_line_attr(table.span);
+            self.write(">\n<table>\n");
+        } else {
+            self.write("<table");
+            self.write_line_attr(table.span);
+            self.write(">\n");
+        }
         for (i, row) in table.children.iter().enumerate() {
             if i == 0 {
                 self.write("<thead>\n");
@@ -318,10 +969,28 @@ impl<'a> Visit<'a> for HtmlRenderer {
             self.write("</tbody>\n");
         }
         self.write("</table>\n");
+        if self.options.table_wrapper {
+            self.write("</div>\n");
+        }
     }
 
     fn visit_text(&mut self, text: &Text<'a>) {
-        self.write_escaped(text.value);
+        if self.options.emoji.is_empty() {
+            self.write_text(text.value);
+            return;
+        }
+        let shortcodes: Vec<(Range<usize>, Emoji)> =
+            find_shortcodes(text.value, &self.options.emoji)
+                .into_iter()
+                .map(|(range, emoji)| (range, emoji.clone()))
+                .collect();
+        let mut rest = 0;
+        for (range, emoji) in shortcodes {
+            self.write_text(&text.value[rest..range.start]);
+            self.write_emoji(&text.value[range.clone()], &emoji);
+            rest = range.end;
+        }
+        self.write_text(&text.value[rest..]);
     }
 
     fn visit_emphasis(&mut self, emphasis: &Emphasis<'a>) {
@@ -342,12 +1011,20 @@ impl<'a> Visit<'a> for HtmlRenderer {
 
     fn visit_inline_code(&mut self, inline_code: &InlineCode<'a>) {
         self.write("<code>");
-        self.write_escaped(inline_code.value);
+        self.write_code_escaped(inline_code.value);
         self.write("</code>");
     }
 
+    fn visit_inline_math(&mut self, inline_math: &InlineMath<'a>) {
+        self.write_math(inline_math.value, false);
+    }
+
     fn visit_break(&mut self, _break_node: &Break) {
-        self.write(&self.options.hard_break.clone());
+        if self.options.xhtml {
+            self.write(&self.options.hard_break.replace("<br>", "<br />"));
+        } else {
+            self.write(&self.options.hard_break.clone());
+        }
     }
 
     fn visit_link(&mut self, link: &Link<'a>) {
@@ -365,9 +1042,11 @@ impl<'a> Visit<'a> for HtmlRenderer {
             self.write("\"");
         }
         self.write(">");
+        self.link_depth += 1;
         for child in &link.children {
             self.visit_node(child);
         }
+        self.link_depth -= 1;
         self.write("</a>");
     }
 
@@ -398,13 +1077,38 @@ impl<'a> Visit<'a> for HtmlRenderer {
     }
 
     fn visit_footnote_reference(&mut self, footnote_ref: &FootnoteReference<'a>) {
-        self.write("<sup><a href=\"#fn-");
-        self.write_escaped(footnote_ref.identifier);
-        self.write("\" id=\"fnref-");
-        self.write_escaped(footnote_ref.identifier);
+        let next = self.footnote_count + 1;
+        let number =
+            *self.footnote_numbers.entry(footnote_ref.identifier.to_string()).or_insert(next);
+        self.footnote_count = self.footnote_count.max(number);
+        self.write_footnote_reference(footnote_ref.identifier, number);
+    }
+
+    fn visit_inline_footnote(&mut self, footnote: &InlineFootnote<'a>) {
+        self.footnote_count += 1;
+        let number = self.footnote_count;
+        let id = format!("inline-{number}");
+        self.write_footnote_reference(&id, number);
+
+        // The note is rendered aside and written with the definitions
+        let content = std::mem::take(&mut self.output);
+        self.write_footnote_start(&id);
+        self.write("<p>");
+        for child in &footnote.children {
+            self.visit_node(child);
+        }
+        self.write("</p>\n");
+        self.write_footnote_end(&id, &number.to_string());
+        let note = std::mem::replace(&mut self.output, content);
+        self.inline_footnotes.push((number, note));
+    }
+
+    fn visit_abbreviation(&mut self, abbreviation: &Abbreviation<'a>) {
+        self.write("<abbr title=\"");
+        self.write_escaped(abbreviation.title);
         self.write("\">");
-        self.write_escaped(footnote_ref.identifier);
-        self.write("</a></sup>");
+        self.write_escaped(abbreviation.value);
+        self.write("</abbr>");
     }
 
     fn visit_definition(&mut self, _definition: &Definition<'a>) {
@@ -412,19 +1116,94 @@ impl<'a> Visit<'a> for HtmlRenderer {
     }
 
     fn visit_footnote_definition(&mut self, footnote_def: &FootnoteDefinition<'a>) {
-        self.write("<div id=\"fn-");
-        self.write_escaped(footnote_def.identifier);
-        self.write("\" class=\"footnote\">\n");
+        self.write_footnote_start(footnote_def.identifier);
         for child in &footnote_def.children {
             self.visit_node(child);
         }
-        self.write("<a href=\"#fnref-");
-        self.write_escaped(footnote_def.identifier);
-        self.write("\">↩</a>\n</div>\n");
+        let label = self
+            .footnote_numbers
+            .get(footnote_def.identifier)
+            .map_or_else(|| footnote_def.identifier.to_string(), ToString::to_string);
+        self.write_footnote_end(footnote_def.identifier, &label);
     }
 }
 
 impl HtmlRenderer {
+    /// Writes the footnotes after the content: definitions and inline
+    /// footnotes in the order they are first referenced, then definitions
+    /// that are never referenced.
+    fn write_footnotes(&mut self, definitions: &[&FootnoteDefinition<'_>]) {
+        let mut definitions: Vec<_> = definitions
+            .iter()
+            .map(|definition| {
+                let number = self.footnote_numbers.get(definition.identifier).copied();
+                (number.unwrap_or(usize::MAX), *definition)
+            })
+            .collect();
+        definitions.sort_by_key(|(number, _)| *number);
+        let mut inline = std::mem::take(&mut self.inline_footnotes).into_iter().peekable();
+        for (number, definition) in definitions {
+            while let Some((_, note)) = inline.next_if(|(inline, _)| *inline < number) {
+                self.output.push_str(&note);
+            }
+            self.visit_footnote_definition(definition);
+        }
+        // Inline footnotes in definitions are rendered with them
+        for (_, note) in inline.chain(std::mem::take(&mut self.inline_footnotes)) {
+            self.output.push_str(&note);
+        }
+    }
+
+    /// Writes the superscript link of footnote `id`, showing its `number`.
+    fn write_footnote_reference(&mut self, id: &str, number: usize) {
+        self.write("<sup><a href=\"#fn-");
+        self.write_escaped(id);
+        self.write("\" id=\"fnref-");
+        self.write_escaped(id);
+        if self.options.accessible {
+            let _ = write!(self.output, "\" role=\"doc-noteref\" aria-label=\"Footnote {number}");
+        }
+        let _ = write!(self.output, "\">{number}</a></sup>");
+    }
+
+    /// Opens the `<div>` of footnote `id`.
+    fn write_footnote_start(&mut self, id: &str) {
+        self.write("<div id=\"fn-");
+        self.write_escaped(id);
+        self.write("\" class=\"footnote\"");
+        if self.options.accessible {
+            self.write(" role=\"doc-footnote\"");
+        }
+        self.write(">\n");
+    }
+
+    /// Writes the back link of footnote `id`, labelled with its number, and
+    /// closes its `<div>`.
+    fn write_footnote_end(&mut self, id: &str, number: &str) {
+        self.write("<a href=\"#fnref-");
+        self.write_escaped(id);
+        if self.options.accessible {
+            self.write("\" role=\"doc-backlink\" aria-label=\"Back to reference ");
+            self.write_escaped(number);
+            self.write("\"><span aria-hidden=\"true\">↩</span></a>\n</div>\n");
+        } else {
+            self.write("\">↩</a>\n</div>\n");
+        }
+    }
+
+    /// Writes the permalink anchor closing a heading.
+    fn write_heading_anchor(&mut self, slug: &str, text: &str) {
+        self.write(" <a class=\"heading-anchor\" href=\"#");
+        self.write_escaped(slug);
+        if self.options.accessible {
+            self.write("\" aria-label=\"Permalink to ");
+            self.write_escaped(text);
+            self.write("\"><span aria-hidden=\"true\">#</span></a>");
+        } else {
+            self.write("\">#</a>");
+        }
+    }
+
     fn visit_table_row_with_header(
         &mut self,
         row: &TableRow<'_>,
@@ -452,11 +1231,49 @@ impl HtmlRenderer {
     }
 }
 
+/// Tags whose `<` the GFM tagfilter escapes.
+const GFM_DISALLOWED_TAGS: &[&str] =
+    &["title", "textarea", "style", "xmp", "iframe", "noembed", "noframes", "script", "plaintext"];
+
+/// Replaces the `<` of every opening or closing GFM disallowed tag with
+/// `&lt;`. A tag name matches, ignoring ASCII case, when it is followed by
+/// whitespace, `>` or `/>`.
+fn filter_disallowed_tags(html: &str) -> Cow<'_, str> {
+    let is_disallowed = |at: usize| {
+        let rest = &html[at + 1..];
+        let rest = rest.strip_prefix('/').unwrap_or(rest);
+        GFM_DISALLOWED_TAGS.iter().any(|tag| {
+            let Some(after) = rest
+                .get(..tag.len())
+                .filter(|name| name.eq_ignore_ascii_case(tag))
+                .map(|_| &rest[tag.len()..])
+            else {
+                return false;
+            };
+            after.starts_with(|c: char| c.is_ascii_whitespace() || c == '>')
+                || after.starts_with("/>")
+        })
+    };
+    let mut out = String::new();
+    let mut last = 0;
+    for (at, _) in html.match_indices('<').filter(|&(at, _)| is_disallowed(at)) {
+        out.push_str(&html[last..at]);
+        out.push_str("&lt;");
+        last = at + 1;
+    }
+    if last == 0 {
+        return Cow::Borrowed(html);
+    }
+    out.push_str(&html[last..]);
+    Cow::Owned(out)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use ox_content_allocator::Allocator;
-    use ox_content_parser::Parser;
+    use ox_content_ast::Span;
+    use ox_content_parser::{Parser, ParserOptions};
 
     #[test]
     fn test_render_paragraph() {
@@ -485,6 +1302,547 @@ mod tests {
         assert!(html.contains("<pre><code class=\"language-rust\">"));
     }
 
+    #[test]
+    fn test_render_detected_code_language() {
+        let allocator = Allocator::new();
+        let source = "```\nfn main() {\n    let mut v = Vec::new();\n}\n```\n\n```\nhello\n```\n\n```text\nlet mut x: Vec<u8>;\n```";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let html = HtmlRenderer::new().render(&doc);
+        assert!(!html.contains("data-detected"));
+
+        let options =
+            HtmlRendererOptions { detect_code_language: true, ..HtmlRendererOptions::new() };
+        let html = HtmlRenderer::with_options(options).render(&doc);
+        assert!(
+            html.starts_with("<pre><code class=\"language-rust\" data-detected=\"true\">fn main()")
+        );
+        assert!(html.contains("<pre><code>hello\n</code></pre>"));
+        assert!(html.contains("<pre><code class=\"language-text\">let mut"));
+    }
+
+    #[test]
+    fn test_render_code_final_newline() {
+        let allocator = Allocator::new();
+        let source = "```\nfoo\nbar\n```\n\n```\nfoo\n\n```\n\n```\n```";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let html = HtmlRenderer::new().render(&doc);
+        assert_eq!(
+            html,
+            "<pre><code>foo\nbar\n</code></pre>\n<pre><code>foo\n\n</code></pre>\n<pre><code></code></pre>\n"
+        );
+
+        let options =
+            HtmlRendererOptions { code_final_newline: false, ..HtmlRendererOptions::new() };
+        let html = HtmlRenderer::with_options(options).render(&doc);
+        assert_eq!(
+            html,
+            "<pre><code>foo\nbar</code></pre>\n<pre><code>foo\n</code></pre>\n<pre><code></code></pre>\n"
+        );
+    }
+
+    #[test]
+    fn test_render_gfm_tagfilter() {
+        let allocator = Allocator::new();
+        // The HTML block of the tagfilter example in the GFM spec
+        let source =
+            "<blockquote>\n  <xmp> is disallowed.  <XMP> is also disallowed.\n</blockquote>";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let options = HtmlRendererOptions::gfm();
+        let html = HtmlRenderer::with_options(options).render(&doc);
+        assert_eq!(
+            html,
+            "<blockquote>\n  &lt;xmp> is disallowed.  &lt;XMP> is also disallowed.\n</blockquote>\n"
+        );
+
+        let source = "<div>\n<script src=\"a.js\"></script><textarea/><iframe/>\n<noembed></noembed><noframes><plaintext>\n<scripts> <titles> <title-x>\n</div>";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let html = HtmlRenderer::with_options(HtmlRendererOptions::gfm()).render(&doc);
+        assert_eq!(
+            html,
+            "<div>\n&lt;script src=\"a.js\">&lt;/script>&lt;textarea/>&lt;iframe/>\n&lt;noembed>&lt;/noembed>&lt;noframes>&lt;plaintext>\n<scripts> <titles> <title-x>\n</div>\n"
+        );
+
+        // Off by default
+        let html = HtmlRenderer::new().render(&doc);
+        assert!(html.contains("<script src=\"a.js\"></script>"));
+    }
+
+    #[test]
+    fn test_render_emphasis_flanking() {
+        // Cases from the CommonMark spec, section 6.2
+        let cases = [
+            ("*foo bar*", "<em>foo bar</em>"),
+            ("a * foo bar*", "a * foo bar*"),
+            ("foo*bar*", "foo<em>bar</em>"),
+            ("5*6*78", "5<em>6</em>78"),
+            ("_foo bar_", "<em>foo bar</em>"),
+            ("_ foo bar_", "_ foo bar_"),
+            ("foo_bar_", "foo_bar_"),
+            ("5_6_78", "5_6_78"),
+            ("пристаням_стремятся_", "пристаням_стремятся_"),
+            ("aa_\"bb\"_cc", "aa_&quot;bb&quot;_cc"),
+            ("foo-_(bar)_", "foo-<em>(bar)</em>"),
+            ("*foo*bar", "<em>foo</em>bar"),
+            ("_foo_bar", "_foo_bar"),
+            ("_foo_bar_baz_", "<em>foo_bar_baz</em>"),
+            ("_(bar)_.", "<em>(bar)</em>."),
+            ("**foo bar**", "<strong>foo bar</strong>"),
+            ("** foo bar**", "** foo bar**"),
+            ("foo**bar**", "foo<strong>bar</strong>"),
+            ("__foo bar__", "<strong>foo bar</strong>"),
+            ("foo__bar__", "foo__bar__"),
+            ("__foo, __bar__, baz__", "<strong>foo, <strong>bar</strong>, baz</strong>"),
+            ("foo**bar**baz", "foo<strong>bar</strong>baz"),
+            ("a*b*c", "a<em>b</em>c"),
+            ("snake_case_word", "snake_case_word"),
+            ("*foo**bar**baz*", "<em>foo<strong>bar</strong>baz</em>"),
+            ("*foo**bar*", "<em>foo**bar</em>"),
+        ];
+        let allocator = Allocator::new();
+        for (source, expected) in cases {
+            let doc = Parser::new(&allocator, source).parse().unwrap();
+            let html = HtmlRenderer::new().render(&doc);
+            assert_eq!(html, format!("<p>{expected}</p>\n"), "{source}");
+        }
+    }
+
+    #[test]
+    fn test_render_emphasis_nesting() {
+        // Triple and mixed delimiter cases from the CommonMark spec, section 6.2
+        let cases = [
+            ("***strong emph***", "<em><strong>strong emph</strong></em>"),
+            ("***strong** in emph*", "<em><strong>strong</strong> in emph</em>"),
+            ("***emph* in strong**", "<strong><em>emph</em> in strong</strong>"),
+            ("**in strong *emph***", "<strong>in strong <em>emph</em></strong>"),
+            ("*in emph **strong***", "<em>in emph <strong>strong</strong></em>"),
+            ("**_mixed_**", "<strong><em>mixed</em></strong>"),
+            ("_**x**_", "<em><strong>x</strong></em>"),
+            ("***foo** bar*", "<em><strong>foo</strong> bar</em>"),
+            ("*foo **bar***", "<em>foo <strong>bar</strong></em>"),
+            ("foo***bar***baz", "foo<em><strong>bar</strong></em>baz"),
+            (
+                "foo******bar*********baz",
+                "foo<strong><strong><strong>bar</strong></strong></strong>***baz",
+            ),
+            (
+                "*foo **bar *baz* bim** bop*",
+                "<em>foo <strong>bar <em>baz</em> bim</strong> bop</em>",
+            ),
+            ("**foo*bar*baz**", "<strong>foo<em>bar</em>baz</strong>"),
+            ("****foo****", "<strong><strong>foo</strong></strong>"),
+            ("______foo______", "<strong><strong><strong>foo</strong></strong></strong>"),
+            ("_____foo_____", "<em><strong><strong>foo</strong></strong></em>"),
+            ("**foo*", "*<em>foo</em>"),
+            ("*foo**", "<em>foo</em>*"),
+            ("***foo**", "*<strong>foo</strong>"),
+            ("**foo***", "<strong>foo</strong>*"),
+            ("*foo****", "<em>foo</em>***"),
+            ("****foo*", "***<em>foo</em>"),
+            ("*(**foo**)*", "<em>(<strong>foo</strong>)</em>"),
+            ("_foo __bar__ baz_", "<em>foo <strong>bar</strong> baz</em>"),
+            ("__foo_ bar_", "<em><em>foo</em> bar</em>"),
+            ("*foo *bar**", "<em>foo <em>bar</em></em>"),
+            ("foo***", "foo***"),
+            ("*foo**bar***", "<em>foo<strong>bar</strong></em>"),
+            ("**foo**bar", "<strong>foo</strong>bar"),
+            ("*foo *bar baz*", "*foo <em>bar baz</em>"),
+            ("**foo bar **baz**", "**foo bar <strong>baz</strong>"),
+            ("*foo _bar* baz_", "<em>foo _bar</em> baz_"),
+            ("**a _b_ c**", "<strong>a <em>b</em> c</strong>"),
+            ("_a **b** c_", "<em>a <strong>b</strong> c</em>"),
+            ("**foo** **bar**", "<strong>foo</strong> <strong>bar</strong>"),
+            ("*foo**bar", "*foo**bar"),
+        ];
+        let allocator = Allocator::new();
+        for (source, expected) in cases {
+            let doc = Parser::new(&allocator, source).parse().unwrap();
+            let html = HtmlRenderer::new().render(&doc);
+            assert_eq!(html, format!("<p>{expected}</p>\n"), "{source}");
+        }
+    }
+
+    #[test]
+    fn test_render_table_wrapper() {
+        let allocator = Allocator::new();
+        let options = ox_content_parser::ParserOptions::gfm();
+        let doc = Parser::with_options(&allocator, "| a |\n|---|\n| 1 |", options).parse().unwrap();
+        let html = HtmlRenderer::new().render(&doc);
+        assert!(html.starts_with("<table>\n"));
+
+        let options = HtmlRendererOptions { table_wrapper: true, ..HtmlRendererOptions::new() };
+        let html = HtmlRenderer::with_options(options).render(&doc);
+        assert!(html.starts_with("<div class=\"table-wrapper\">\n<table>\n"));
+        assert!(html.ends_with("</table>\n</div>\n"));
+    }
+
+    #[test]
+    fn test_render_heading_id_attribute() {
+        let allocator = Allocator::new();
+        let source = "## Getting started {#install}\n\n## Install\n\n## Again {#install}";
+        let options = ox_content_parser::ParserOptions { attributes: true, ..Default::default() };
+        let doc = Parser::with_options(&allocator, source, options).parse().unwrap();
+        let options = HtmlRendererOptions { heading_ids: true, ..HtmlRendererOptions::new() };
+        let mut renderer = HtmlRenderer::with_options(options);
+        let html = renderer.render(&doc);
+        assert_eq!(
+            html,
+            "<h2 id=\"install\">Getting started</h2>\n<h2 id=\"install-1\">Install</h2>\n\
+             <h2 id=\"install-2\">Again</h2>\n"
+        );
+        assert_eq!(renderer.anchors()[0].text, "Getting started");
+        assert!(renderer
+            .render_section(&doc, "install-1")
+            .unwrap()
+            .starts_with("<h2 id=\"install-1\">Install"));
+    }
+
+    #[test]
+    fn test_render_section() {
+        let allocator = Allocator::new();
+        let source = "# Guide\n\n## Install\n\nRun it[^1].\n\n### Linux\n\nUse apt.\n\n\
+                      ## Install\n\nAgain.\n\n[^1]: Note.";
+        let options = ox_content_parser::ParserOptions::gfm();
+        let doc = Parser::with_options(&allocator, source, options).parse().unwrap();
+        let options = HtmlRendererOptions {
+            heading_ids: true,
+            number_headings: true,
+            line_ending: LineEnding::Crlf,
+            ..HtmlRendererOptions::new()
+        };
+        let mut renderer = HtmlRenderer::with_options(options);
+        assert_eq!(
+            renderer.render_section(&doc, "linux").unwrap(),
+            "<h3 id=\"linux\"><span class=\"heading-number\">1.1.</span> Linux</h3>\r\n\
+             <p>Use apt.</p>\r\n"
+        );
+        assert_eq!(
+            renderer.render_section(&doc, "install-1").unwrap(),
+            "<h2 id=\"install-1\"><span class=\"heading-number\">2.</span> Install</h2>\r\n\
+             <p>Again.</p>\r\n"
+        );
+        let html = renderer.render_section(&doc, "install").unwrap();
+        assert!(html.starts_with("<h2 id=\"install\">"));
+        assert!(html.ends_with("<p>Use apt.</p>\r\n"));
+        assert!(renderer.render_section(&doc, "missing").is_none());
+    }
+
+    #[test]
+    fn test_render_nested_list_numbers() {
+        let allocator = Allocator::new();
+        let source = "1. a\n   1. b\n   - c\n     1. d\n\n3. e";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let html = HtmlRenderer::new().render(&doc);
+        assert!(!html.contains("nested-numbers"));
+
+        let options =
+            HtmlRendererOptions { nested_list_numbers: true, ..HtmlRendererOptions::new() };
+        let html = HtmlRenderer::with_options(options).render(&doc);
+        assert_eq!(html.matches("<ol class=\"nested-numbers\">").count(), 3);
+        assert!(!html.contains("<ul class"));
+
+        let doc = Parser::new(&allocator, "3. c\n4. d").parse().unwrap();
+        let options =
+            HtmlRendererOptions { nested_list_numbers: true, ..HtmlRendererOptions::new() };
+        let html = HtmlRenderer::with_options(options).render(&doc);
+        assert!(html.starts_with(
+            "<ol class=\"nested-numbers\" start=\"3\" style=\"counter-reset: nested-list 2\">"
+        ));
+    }
+
+    #[test]
+    fn test_render_language_aliases() {
+        let allocator = Allocator::new();
+        let source = "```js\n```\n\n```Shell\n```\n\n```zig\n```\n\n```vue\n```";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let mut options = HtmlRendererOptions::new();
+        options.language_aliases.insert("vue".to_string(), "html".to_string());
+        let html = HtmlRenderer::with_options(options).render(&doc);
+        let classes: Vec<&str> =
+            html.split("class=\"language-").skip(1).filter_map(|s| s.split('"').next()).collect();
+        assert_eq!(classes, ["javascript", "bash", "zig", "html"]);
+    }
+
+    #[test]
+    fn test_render_to_buffer_reuse() {
+        let allocator = Allocator::new();
+        let source = "Some text.\n\n".repeat(50);
+        let long = Parser::new(&allocator, &source).parse().unwrap();
+        let short = Parser::new(&allocator, "# Hello").parse().unwrap();
+        let mut renderer = HtmlRenderer::new();
+        renderer.render_to_buffer(&long);
+        assert_eq!(renderer.render_to_buffer(&short), "<h1>Hello</h1>\n");
+        assert_eq!(renderer.render(&short), "<h1>Hello</h1>\n");
+    }
+
+    #[test]
+    fn test_render_markdown_inside_details() {
+        let allocator = Allocator::new();
+        let options = ox_content_parser::ParserOptions {
+            parse_inside_html_blocks: true,
+            ..ox_content_parser::ParserOptions::default()
+        };
+        let source = "<details>\n<summary>More</summary>\n\nSome **bold** text\n</details>";
+        let doc = Parser::with_options(&allocator, source, options).parse().unwrap();
+        let html = HtmlRenderer::new().render(&doc);
+        assert_eq!(
+            html,
+            "<details>\n<summary>More</summary>\n<p>Some <strong>bold</strong> text</p>\n</details>\n"
+        );
+    }
+
+    #[test]
+    fn test_render_heading_ids() {
+        let allocator = Allocator::new();
+        let doc = Parser::new(&allocator, "# Hello *World*\n\n## 日本語\n\n## 🎉").parse().unwrap();
+        let options = HtmlRendererOptions { heading_ids: true, ..HtmlRendererOptions::new() };
+        let html = HtmlRenderer::with_options(options).render(&doc);
+        assert!(html.contains("<h1 id=\"hello-world\">Hello <em>World</em></h1>"));
+        assert!(html.contains("<h2 id=\"日本語\">"));
+
+        let options = HtmlRendererOptions {
+            heading_ids: true,
+            slug_style: SlugStyle::Ascii,
+            ..HtmlRendererOptions::new()
+        };
+        let html = HtmlRenderer::with_options(options).render(&doc);
+        let ids: Vec<&str> =
+            html.split("id=\"").skip(1).filter_map(|s| s.split('"').next()).collect();
+        assert_eq!(ids[0], "hello-world");
+        assert!(ids[1].starts_with("section-") && ids[2].starts_with("section-"));
+        assert_ne!(ids[1], ids[2]);
+    }
+
+    #[test]
+    fn test_render_duplicate_heading_ids() {
+        let allocator = Allocator::new();
+        let doc = Parser::new(&allocator, "## Overview\n## Overview\n## Overview").parse().unwrap();
+        let options = HtmlRendererOptions { heading_ids: true, ..HtmlRendererOptions::new() };
+        let mut renderer = HtmlRenderer::with_options(options);
+        let expected = "<h2 id=\"overview\">Overview</h2>\n<h2 id=\"overview-1\">Overview</h2>\n<h2 id=\"overview-2\">Overview</h2>\n";
+        assert_eq!(renderer.render(&doc), expected);
+        let slugs: Vec<_> = renderer.anchors().iter().map(|anchor| anchor.slug.as_str()).collect();
+        assert_eq!(slugs, ["overview", "overview-1", "overview-2"]);
+        // Slugs start over for every document.
+        assert_eq!(renderer.render(&doc), expected);
+    }
+
+    #[test]
+    fn test_render_raw_regions() {
+        let allocator = Allocator::new();
+        let source = "<!-- raw -->\n**not bold** :smile: https://x.y\n  <b>kept</b>\n<!-- /raw -->\n\n```text raw\n# not a heading\n```\n\n*after*";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let html = HtmlRenderer::new().render(&doc);
+        assert_eq!(
+            html,
+            "<pre class=\"raw\">**not bold** :smile: https://x.y\n  &lt;b&gt;kept&lt;/b&gt;\n</pre>\n<pre class=\"raw\"># not a heading\n</pre>\n<p><em>after</em></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_render_footnote_block_content() {
+        let allocator = Allocator::new();
+        let source = "Claim.[^1]\n\n[^1]: First.\n\n    Second.\n\n    - one\n    - two\n";
+        let options = ParserOptions { footnotes: true, ..ParserOptions::default() };
+        let doc = Parser::with_options(&allocator, source, options).parse().unwrap();
+        let html = HtmlRenderer::new().render(&doc);
+        assert_eq!(
+            html,
+            "<p>Claim.<sup><a href=\"#fn-1\" id=\"fnref-1\">1</a></sup></p>\n<div id=\"fn-1\" class=\"footnote\">\n<p>First.</p>\n<p>Second.</p>\n<ul>\n<li><p>one</p>\n</li>\n<li><p>two</p>\n</li>\n</ul>\n<a href=\"#fnref-1\">↩</a>\n</div>\n"
+        );
+    }
+
+    #[test]
+    fn test_render_inline_footnotes() {
+        let allocator = Allocator::new();
+        let source =
+            "A[^b] B^[Inline *one*.] C[^a] D^[Two.] E[^b]\n\n[^a]: Alpha.\n\n[^b]: Beta.\n\n\
+                      [^unused]: Never cited.\n\nAfter.";
+        let options = ParserOptions { footnotes: true, ..ParserOptions::default() };
+        let doc = Parser::with_options(&allocator, source, options).parse().unwrap();
+        let html = HtmlRenderer::new().render(&doc);
+        assert_eq!(
+            html,
+            "<p>A<sup><a href=\"#fn-b\" id=\"fnref-b\">1</a></sup> \
+             B<sup><a href=\"#fn-inline-2\" id=\"fnref-inline-2\">2</a></sup> \
+             C<sup><a href=\"#fn-a\" id=\"fnref-a\">3</a></sup> \
+             D<sup><a href=\"#fn-inline-4\" id=\"fnref-inline-4\">4</a></sup> \
+             E<sup><a href=\"#fn-b\" id=\"fnref-b\">1</a></sup></p>\n\
+             <p>After.</p>\n\
+             <div id=\"fn-b\" class=\"footnote\">\n<p>Beta.</p>\n<a href=\"#fnref-b\">↩</a>\n</div>\n\
+             <div id=\"fn-inline-2\" class=\"footnote\">\n<p>Inline <em>one</em>.</p>\n<a href=\"#fnref-inline-2\">↩</a>\n</div>\n\
+             <div id=\"fn-a\" class=\"footnote\">\n<p>Alpha.</p>\n<a href=\"#fnref-a\">↩</a>\n</div>\n\
+             <div id=\"fn-inline-4\" class=\"footnote\">\n<p>Two.</p>\n<a href=\"#fnref-inline-4\">↩</a>\n</div>\n\
+             <div id=\"fn-unused\" class=\"footnote\">\n<p>Never cited.</p>\n<a href=\"#fnref-unused\">↩</a>\n</div>\n"
+        );
+
+        let options = HtmlRendererOptions { accessible: true, ..HtmlRendererOptions::new() };
+        let html = HtmlRenderer::with_options(options).render(&doc);
+        assert!(html.contains("aria-label=\"Footnote 2\">2</a>"));
+        assert!(html.contains(
+            "href=\"#fnref-a\" role=\"doc-backlink\" aria-label=\"Back to reference 3\""
+        ));
+    }
+
+    #[test]
+    fn test_render_math() {
+        let allocator = Allocator::new();
+        let source = "Area $\\pi r^2$.\n\n$$\n\\begin{matrix} a \\end{matrix}\n$$";
+        let options = ParserOptions { math: true, ..ParserOptions::default() };
+        let doc = Parser::with_options(&allocator, source, options).parse().unwrap();
+
+        let html = HtmlRenderer::new().render(&doc);
+        assert_eq!(
+            html,
+            "<p>Area <span class=\"math math-inline\">\\pi r^2</span>.</p>\n<div class=\"math math-display\">\\begin{matrix} a \\end{matrix}</div>\n"
+        );
+
+        let options =
+            HtmlRendererOptions { math_output: MathOutput::MathMl, ..HtmlRendererOptions::new() };
+        let html = HtmlRenderer::with_options(options).render(&doc);
+        assert_eq!(
+            html,
+            "<p>Area <math><semantics><mrow><mi>π</mi><msup><mi>r</mi><mn>2</mn></msup></mrow><annotation encoding=\"application/x-tex\">\\pi r^2</annotation></semantics></math>.</p>\n<div class=\"math math-display\" data-latex=\"\\begin{matrix} a \\end{matrix}\">\\begin{matrix} a \\end{matrix}</div>\n"
+        );
+    }
+
+    #[test]
+    fn test_render_math_fence() {
+        let allocator = Allocator::new();
+        let fenced = "```math\nx^2 + y^2\n```";
+        let options = ParserOptions { math: true, ..ParserOptions::default() };
+        let doc = Parser::with_options(&allocator, fenced, options.clone()).parse().unwrap();
+        let html = HtmlRenderer::new().render(&doc);
+        let doc = Parser::with_options(&allocator, "$$\nx^2 + y^2\n$$", options).parse().unwrap();
+        assert_eq!(html, HtmlRenderer::new().render(&doc));
+        assert_eq!(html, "<div class=\"math math-display\">x^2 + y^2</div>\n");
+
+        // Without math the fence stays a code block.
+        let doc = Parser::new(&allocator, fenced).parse().unwrap();
+        assert_eq!(
+            HtmlRenderer::new().render(&doc),
+            "<pre><code class=\"language-math\">x^2 + y^2\n</code></pre>\n"
+        );
+    }
+
+    #[test]
+    fn test_render_code_escapes() {
+        let allocator = Allocator::new();
+        let source =
+            "Use `{{ name }}` here {{ kept }}.\n\n```jinja\n{% if x %}{{ x }}{% endif %}\n```";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let options = HtmlRendererOptions {
+            code_escapes: ["{{", "}}", "{%", "%}"].map(String::from).to_vec(),
+            ..HtmlRendererOptions::new()
+        };
+        let html = HtmlRenderer::with_options(options).render(&doc);
+        assert_eq!(
+            html,
+            "<p>Use <code>&#123;&#123; name &#125;&#125;</code> here {{ kept }}.</p>\n<pre><code class=\"language-jinja\">&#123;&#37; if x &#37;&#125;&#123;&#123; x &#125;&#125;&#123;&#37; endif &#37;&#125;\n</code></pre>\n"
+        );
+    }
+
+    #[test]
+    fn test_render_heading_anchors() {
+        let allocator = Allocator::new();
+        let doc = Parser::new(&allocator, "## Setup & Use").parse().unwrap();
+        let options = HtmlRendererOptions {
+            heading_ids: true,
+            heading_anchors: true,
+            ..HtmlRendererOptions::new()
+        };
+        let html = HtmlRenderer::with_options(options.clone()).render(&doc);
+        assert_eq!(
+            html,
+            "<h2 id=\"setup--use\">Setup &amp; Use <a class=\"heading-anchor\" href=\"#setup--use\">#</a></h2>\n"
+        );
+
+        let options = HtmlRendererOptions { accessible: true, ..options };
+        let html = HtmlRenderer::with_options(options).render(&doc);
+        assert!(html.contains(
+            "<a class=\"heading-anchor\" href=\"#setup--use\" aria-label=\"Permalink to Setup &amp; Use\"><span aria-hidden=\"true\">#</span></a>"
+        ));
+    }
+
+    #[test]
+    fn test_render_accessible() {
+        let allocator = Allocator::new();
+        let source = "![Chart](c.png \"Sales by month\")\n\n![Logo](l.png)";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+
+        let html = HtmlRenderer::new().render(&doc);
+        assert!(!html.contains("<figure>"));
+
+        let options = HtmlRendererOptions { accessible: true, ..HtmlRendererOptions::new() };
+        let mut renderer = HtmlRenderer::with_options(options);
+        let html = renderer.render(&doc);
+        assert!(html.contains(
+            "<figure>\n<img src=\"c.png\" alt=\"Chart\" title=\"Sales by month\">\n<figcaption>Sales by month</figcaption>\n</figure>\n"
+        ));
+        assert!(html.contains("<p><img src=\"l.png\" alt=\"Logo\"></p>"));
+
+        let reference = FootnoteReference { identifier: "1", label: None, span: Span::empty() };
+        let definition = FootnoteDefinition {
+            identifier: "1",
+            label: None,
+            children: allocator.new_vec(),
+            span: Span::empty(),
+        };
+        renderer.visit_footnote_reference(&reference);
+        renderer.visit_footnote_definition(&definition);
+        let html = std::mem::take(&mut renderer.output);
+        assert!(html.contains("role=\"doc-noteref\" aria-label=\"Footnote 1\""));
+        assert!(html.contains("class=\"footnote\" role=\"doc-footnote\">"));
+        assert!(html.contains(
+            "role=\"doc-backlink\" aria-label=\"Back to reference 1\"><span aria-hidden=\"true\">↩</span></a>"
+        ));
+    }
+
+    #[test]
+    fn test_render_numbered_headings() {
+        let allocator = Allocator::new();
+        let doc =
+            Parser::new(&allocator, "# Spec\n\n## Scope\n\n### Terms\n\n## Usage").parse().unwrap();
+        let options = HtmlRendererOptions { number_headings: true, ..HtmlRendererOptions::new() };
+        let mut renderer = HtmlRenderer::with_options(options);
+        let html = renderer.render(&doc);
+        assert!(html.contains("<h1>Spec</h1>"));
+        assert!(html.contains("<h2><span class=\"heading-number\">1.</span> Scope</h2>"));
+        assert!(html.contains("<h3><span class=\"heading-number\">1.1.</span> Terms</h3>"));
+        assert!(html.contains("<h2><span class=\"heading-number\">2.</span> Usage</h2>"));
+
+        // Numbering restarts on every render.
+        assert_eq!(renderer.render(&doc), html);
+    }
+
+    #[test]
+    fn test_render_abbreviation() {
+        let allocator = Allocator::new();
+        let options = ox_content_parser::ParserOptions {
+            abbreviations: true,
+            ..ox_content_parser::ParserOptions::default()
+        };
+        let source = "Use W3C specs.\n\n*[W3C]: World Wide Web \"Consortium\"";
+        let doc = Parser::with_options(&allocator, source, options).parse().unwrap();
+        let html = HtmlRenderer::new().render(&doc);
+        assert_eq!(
+            html,
+            "<p>Use <abbr title=\"World Wide Web &quot;Consortium&quot;\">W3C</abbr> specs.</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_render_code_block_title() {
+        let allocator = Allocator::new();
+        let source = "```toml title=\"Cargo.toml\" copy\n[package]\n```";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let html = HtmlRenderer::new().render(&doc);
+        assert_eq!(
+            html,
+            "<div class=\"code-block\"><div class=\"code-title\">Cargo.toml</div>\
+             <pre><code class=\"language-toml\">[package]\n</code></pre></div>\n"
+        );
+    }
+
     #[test]
     fn test_render_nested_list() {
         let allocator = Allocator::new();
@@ -530,6 +1888,134 @@ mod tests {
         assert!(html.contains("<td>body</td>"));
     }
 
+    #[test]
+    fn test_render_table_row_widths() {
+        let allocator = Allocator::new();
+        let source = "| a | b |\n|---|---|\n| 1 |\n| 1 | 2 | 3 |";
+        let doc = Parser::with_options(&allocator, source, ParserOptions::gfm()).parse().unwrap();
+        let html = HtmlRenderer::new().render(&doc);
+        assert!(html
+            .contains("<tr>\n<td>1</td>\n<td></td>\n</tr>\n<tr>\n<td>1</td>\n<td>2</td>\n</tr>"));
+    }
+
+    #[test]
+    fn test_render_table_cell_breaks() {
+        let allocator = Allocator::new();
+        let parser_options = ox_content_parser::ParserOptions {
+            table_cell_break: Some("\\n".to_string()),
+            ..ox_content_parser::ParserOptions::gfm()
+        };
+        let source = "| head |\n| --- |\n| **a**\\n[b](/b)<br/>c |";
+        let doc = Parser::with_options(&allocator, source, parser_options).parse().unwrap();
+        let html = HtmlRenderer::new().render(&doc);
+        assert!(
+            html.contains("<td><strong>a</strong><br>\n<a href=\"/b\">b</a><br>\nc</td>"),
+            "{html}"
+        );
+    }
+
+    #[test]
+    fn test_render_reference_links() {
+        let allocator = Allocator::new();
+        let source = "Fixes #12 in a1b2c3d4e5, not \\#13, `#14` or [#15](/x).\n\n```\n#16\n```\n\n\
+                      Thanks @octocat, not \\@ghost, `@code` or a@b.";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let options = HtmlRendererOptions {
+            reference_links: Some(ReferenceLinks {
+                issue_url: Some("https://example.com/issues/{n}".to_string()),
+                commit_url: Some("/commit/{sha}".to_string()),
+                mention_url: Some("/u/{name}".to_string()),
+                ..ReferenceLinks::new()
+            }),
+            ..HtmlRendererOptions::new()
+        };
+        let html = HtmlRenderer::with_options(options).render(&doc);
+        assert_eq!(
+            html,
+            "<p>Fixes <a href=\"https://example.com/issues/12\" target=\"_blank\" rel=\"noopener noreferrer\" class=\"issue-link\">#12</a> \
+             in <a href=\"/commit/a1b2c3d4e5\" class=\"commit-link\"><code>a1b2c3d</code></a>, \
+             not #13, <code>#14</code> or <a href=\"/x\">#15</a>.</p>\n<pre><code>#16\n</code></pre>\n\
+             <p>Thanks <a href=\"/u/octocat\" class=\"mention\">@octocat</a>, not @ghost, \
+             <code>@code</code> or a@b.</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_render_source_line_attrs() {
+        let allocator = Allocator::new();
+        let source = "# Title\n\nSome *text*\nmore.\n\n- one\n- two\n\n```\ncode\n```\n\n---";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let options = HtmlRendererOptions {
+            source_line_attrs: true,
+            source_line_offset: 2,
+            ..HtmlRendererOptions::new()
+        };
+        let mut renderer = HtmlRenderer::with_options(options);
+        assert_eq!(
+            renderer.render_with_source(&doc, source),
+            "<h1 data-line=\"3\">Title</h1>\n<p data-line=\"5\">Some <em>text</em>\nmore.</p>\n\
+             <ul data-line=\"8\">\n<li data-line=\"8\"><p data-line=\"8\">one</p>\n</li>\n\
+             <li data-line=\"9\"><p data-line=\"9\">two</p>\n</li>\n</ul>\n\
+             <pre data-line=\"11\"><code>code\n</code></pre>\n<hr data-line=\"15\">\n"
+        );
+        // Without the source there are no lines to annotate
+        assert!(!renderer.render(&doc).contains("data-line"));
+    }
+
+    #[test]
+    fn test_render_cjk_friendly_breaks() {
+        let allocator = Allocator::new();
+        let source = "中文的句子\n继续写。\nMixed with\nEnglish 词语\n和**中文**\n文字。";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        assert_eq!(
+            HtmlRenderer::new().render(&doc),
+            "<p>中文的句子\n继续写。\nMixed with\nEnglish 词语\n和<strong>中文</strong>\n文字。</p>\n"
+        );
+        let options =
+            HtmlRendererOptions { cjk_friendly_breaks: true, ..HtmlRendererOptions::new() };
+        assert_eq!(
+            HtmlRenderer::with_options(options).render(&doc),
+            "<p>中文的句子继续写。\nMixed with\nEnglish 词语和<strong>中文</strong>文字。</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_render_custom_emoji() {
+        let allocator = Allocator::new();
+        let source =
+            "Ship it :ship: :custom-logo: :nope: `:ship:`\n\n<!-- raw -->\n:ship:\n<!-- /raw -->";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let options = HtmlRendererOptions {
+            emoji: HashMap::from([
+                ("ship".to_string(), Emoji::Text("🚢".to_string())),
+                ("custom-logo".to_string(), Emoji::Image("/img/logo.png".to_string())),
+            ]),
+            ..HtmlRendererOptions::new()
+        };
+        assert_eq!(
+            HtmlRenderer::with_options(options).render(&doc),
+            "<p>Ship it 🚢 <img class=\"emoji custom-emoji\" src=\"/img/logo.png\" alt=\":custom-logo:\"> \
+             :nope: <code>:ship:</code></p>\n<pre class=\"raw\">:ship:\n</pre>\n"
+        );
+    }
+
+    #[test]
+    fn test_render_toc_marker() {
+        let allocator = Allocator::new();
+        let source = "# Title\n\n[[toc]]\n\n[TOC]\n\nSee [[toc]] here\n\n`[[toc]]`";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let options = HtmlRendererOptions {
+            toc_markers: vec!["[[toc]]".to_string()],
+            toc_html: "<nav>TOC</nav>\n".to_string(),
+            ..HtmlRendererOptions::new()
+        };
+        assert_eq!(
+            HtmlRenderer::with_options(options).render(&doc),
+            "<h1>Title</h1>\n<nav>TOC</nav>\n<p>[TOC]</p>\n<p>See [[toc]] here</p>\n\
+             <p><code>[[toc]]</code></p>\n"
+        );
+    }
+
     #[test]
     fn test_render_table_no_gfm() {
         let allocator = Allocator::new();
@@ -541,6 +2027,23 @@ mod tests {
         assert!(html.contains("| head |"));
     }
 
+    #[test]
+    fn test_render_line_endings() {
+        let allocator = Allocator::new();
+        let doc = Parser::new(&allocator, "# Title\n\n```\na\r\nb\n```").parse().unwrap();
+
+        let mut options = HtmlRendererOptions::new();
+        options.line_ending = LineEnding::Crlf;
+        let html = HtmlRenderer::with_options(options.clone()).render(&doc);
+        assert!(html.starts_with("<h1>Title</h1>\r\n"));
+        assert!(html.ends_with("</code></pre>\r\n"));
+        assert!(!html.replace("\r\n", "").contains('\n'));
+
+        options.trailing_newline = true;
+        let doc = Parser::new(&allocator, "").parse().unwrap();
+        assert_eq!(HtmlRenderer::with_options(options).render(&doc), "\r\n");
+    }
+
     #[test]
     fn test_render_heading_with_link() {
         let allocator = Allocator::new();
@@ -581,6 +2084,19 @@ mod tests {
         assert!(html.contains("<img src=\"/path/to/image.png\" alt=\"Alt text\">"));
     }
 
+    #[test]
+    fn test_render_reference_image() {
+        let allocator = Allocator::new();
+        let source = "![Logo][id] ![missing][nope]\n\n[id]: /img.png \"The title\"";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let mut renderer = HtmlRenderer::new();
+        let html = renderer.render(&doc);
+        assert_eq!(
+            html,
+            "<p><img src=\"/img.png\" alt=\"Logo\" title=\"The title\"> ![missing][nope]</p>\n"
+        );
+    }
+
     #[test]
     fn test_render_image_xhtml() {
         let allocator = Allocator::new();
@@ -590,4 +2106,29 @@ mod tests {
         let html = renderer.render(&doc);
         assert!(html.contains("<img src=\"/logo.svg\" alt=\"Logo\" />"));
     }
+
+    #[test]
+    fn test_render_void_elements() {
+        let allocator = Allocator::new();
+        let source =
+            "![Logo](/logo.svg)\n\n| a |\n|---|\n| x<br>y |\n\n---\n\n- [x] done\n- [ ] todo";
+        let doc = Parser::with_options(&allocator, source, ParserOptions::gfm()).parse().unwrap();
+
+        let html = HtmlRenderer::new().render(&doc);
+        assert!(html.contains("<img src=\"/logo.svg\" alt=\"Logo\">"));
+        assert!(html.contains("<td>x<br>\ny</td>"));
+        assert!(html.contains("<hr>\n"));
+        assert!(html.contains("<input type=\"checkbox\" checked disabled> "));
+        assert!(html.contains("<input type=\"checkbox\" disabled> "));
+
+        let options = HtmlRendererOptions { xhtml: true, ..HtmlRendererOptions::new() };
+        let html = HtmlRenderer::with_options(options).render(&doc);
+        assert!(html.contains("<img src=\"/logo.svg\" alt=\"Logo\" />"));
+        assert!(html.contains("<td>x<br />\ny</td>"));
+        assert!(html.contains("<hr />\n"));
+        assert!(
+            html.contains("<input type=\"checkbox\" checked=\"checked\" disabled=\"disabled\" /> ")
+        );
+        assert!(html.contains("<input type=\"checkbox\" disabled=\"disabled\" /> "));
+    }
 }
@@ -0,0 +1,75 @@
+//! The code languages a document uses, for loading highlighter grammars.
+
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use ox_content_ast::{CodeBlock, Document, Visit};
+
+/// Returns the distinct languages of the fenced code blocks in a document,
+/// in the order they are first used.
+///
+/// Languages are mapped through `aliases` like the `language-*` class
+/// [`HtmlRenderer`](crate::HtmlRenderer) writes, so pass the renderer's
+/// [`language_aliases`](crate::HtmlRendererOptions::language_aliases).
+/// Fences without a language and `raw` fences, which are not highlighted,
+/// are left out.
+#[must_use]
+pub fn code_languages<S: BuildHasher>(
+    document: &Document<'_>,
+    aliases: &HashMap<String, String, S>,
+) -> Vec<String> {
+    let mut collector = LanguageCollector { aliases, languages: Vec::new() };
+    collector.visit_document(document);
+    collector.languages
+}
+
+/// Returns the canonical name of a code fence language: its entry in
+/// `aliases`, matched as written and then lowercased, or the name itself.
+pub fn canonical_language<'l, S: BuildHasher>(
+    aliases: &'l HashMap<String, String, S>,
+    lang: &'l str,
+) -> &'l str {
+    aliases
+        .get(lang)
+        .or_else(|| aliases.get(&lang.to_ascii_lowercase()))
+        .map_or(lang, String::as_str)
+}
+
+struct LanguageCollector<'o, S> {
+    aliases: &'o HashMap<String, String, S>,
+    languages: Vec<String>,
+}
+
+impl<'a, S: BuildHasher> Visit<'a> for LanguageCollector<'_, S> {
+    fn visit_code_block(&mut self, code_block: &CodeBlock<'a>) {
+        let Some(lang) = code_block.lang.filter(|lang| !lang.is_empty()) else {
+            return;
+        };
+        if code_block.is_raw() {
+            return;
+        }
+        let lang = canonical_language(self.aliases, lang);
+        if !self.languages.iter().any(|known| known == lang) {
+            self.languages.push(lang.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HtmlRendererOptions;
+    use ox_content_allocator::Allocator;
+    use ox_content_parser::Parser;
+
+    #[test]
+    fn test_code_languages() {
+        let allocator = Allocator::new();
+        let source = "```ts\nlet a = 1;\n```\n\n```\nplain\n```\n\n```Shell\nls\n```\n\n\
+                      ```typescript\nlet b = 2;\n```\n\n```html raw\n<b></b>\n```\n\n```vue\n<a />\n```";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        let aliases = HtmlRendererOptions::new().language_aliases;
+        assert_eq!(code_languages(&doc, &aliases), ["typescript", "bash", "vue"]);
+        assert!(code_languages(&doc, &HashMap::new()).contains(&"Shell".to_string()));
+    }
+}
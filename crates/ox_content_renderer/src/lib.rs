@@ -1,7 +1,7 @@
 //! Markdown renderer for Ox Content.
 //!
 //! This crate provides a renderer that converts Markdown AST to HTML
-//! and other output formats.
+//! and other output formats, including Markdown itself for formatting.
 //!
 //! # Example
 //!
@@ -19,8 +19,33 @@
 //! let html = renderer.render(&document);
 //! ```
 
+mod cjk;
+mod custom;
+mod detect;
+mod emoji;
+mod headings;
 mod html;
+mod languages;
+mod markdown;
+mod math;
+mod numbering;
+mod preview;
+mod references;
 mod render;
+mod sections;
 
-pub use html::{HtmlRenderer, HtmlRendererOptions};
+pub use detect::detect_language;
+pub use emoji::Emoji;
+pub use headings::{normalize_headings, remove_title_heading, HeadingNormalization};
+pub use html::{HtmlRenderer, HtmlRendererOptions, LineEnding, DEFAULT_LANGUAGE_ALIASES};
+pub use languages::code_languages;
+pub use markdown::{HeadingStyle, MarkdownRenderer, MarkdownStyle};
+pub use math::{latex_to_mathml, MathOutput};
+pub use numbering::HeadingNumbering;
+pub use ox_content_ast::{
+    heading_slug, heading_text, slugify, HeadingAnchor, SlugStyle, UniqueSlugs,
+};
+pub use preview::{link_preview, LinkPreview};
+pub use references::{Reference, ReferenceLinks};
 pub use render::{RenderError, RenderResult, Renderer};
+pub use sections::{section_range, section_stats, SectionStats};
@@ -0,0 +1,585 @@
+//! Markdown renderer for formatting source files.
+//!
+//! Writes a document back to Markdown in one canonical style, so sources
+//! that mix `*`, `-` and `+` bullets or `*` and `_` emphasis can be
+//! normalized or checked in CI.
+
+use std::fmt::Write;
+
+use ox_content_ast::{
+    AlignKind, CodeBlock, Document, Heading, Image, InlineCode, List, ListItem, Node, Table,
+};
+
+use crate::render::{RenderResult, Renderer};
+
+/// Heading syntax written by the [`MarkdownRenderer`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HeadingStyle {
+    /// `# Title` for every level.
+    #[default]
+    Atx,
+    /// `# Title #`, with the opening hashes repeated after the text.
+    AtxClosed,
+}
+
+impl HeadingStyle {
+    /// Returns the style name (`atx` or `atx_closed`).
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Atx => "atx",
+            Self::AtxClosed => "atx_closed",
+        }
+    }
+
+    /// Looks up a style by name, ignoring ASCII case.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::Atx, Self::AtxClosed]
+            .into_iter()
+            .find(|style| style.as_str().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Markup choices of the [`MarkdownRenderer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownStyle {
+    /// Bullet list marker: `-`, `*` or `+`.
+    pub bullet: char,
+    /// Delimiter after ordered list numbers: `.` or `)`.
+    pub ordered_delimiter: char,
+    /// Emphasis marker: `*` or `_`. Emphasis inside a word always uses
+    /// `*`, as `_` cannot open or close there.
+    pub emphasis: char,
+    /// Strong emphasis marker, written twice: `*` or `_`.
+    pub strong: char,
+    /// Heading syntax.
+    pub heading: HeadingStyle,
+}
+
+impl MarkdownStyle {
+    /// Creates the default style: `-` bullets, `1.` numbers, `*emphasis*`,
+    /// `**strong**` and ATX headings.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            bullet: '-',
+            ordered_delimiter: '.',
+            emphasis: '*',
+            strong: '*',
+            heading: HeadingStyle::Atx,
+        }
+    }
+}
+
+impl Default for MarkdownStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Renders a document back to Markdown.
+///
+/// Blocks are separated by one blank line, code blocks are always fenced
+/// and text is escaped only where it would otherwise be read as markup.
+/// Reference definitions and abbreviation definitions are kept, the latter
+/// at the end of the document.
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownRenderer {
+    style: MarkdownStyle,
+    abbreviations: Vec<(String, String)>,
+}
+
+impl MarkdownRenderer {
+    /// Creates a renderer with the default style.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a renderer with the given style.
+    #[must_use]
+    pub fn with_style(style: MarkdownStyle) -> Self {
+        Self { style, abbreviations: Vec::new() }
+    }
+
+    /// Renders a document to Markdown, ending with a newline unless the
+    /// document is empty.
+    pub fn render(&mut self, document: &Document<'_>) -> String {
+        self.abbreviations.clear();
+        let mut markdown = self.blocks(&document.children, true);
+        if !self.abbreviations.is_empty() {
+            if !markdown.is_empty() {
+                markdown.push_str("\n\n");
+            }
+            let definitions: Vec<String> = self
+                .abbreviations
+                .iter()
+                .map(|(value, title)| format!("*[{value}]: {title}"))
+                .collect();
+            markdown.push_str(&definitions.join("\n"));
+        }
+        if !markdown.is_empty() {
+            markdown.push('\n');
+        }
+        markdown
+    }
+
+    /// Renders block nodes, separated by a blank line when `loose` and by
+    /// a line break otherwise.
+    fn blocks(&mut self, nodes: &[Node<'_>], loose: bool) -> String {
+        let separator = if loose { "\n\n" } else { "\n" };
+        let mut markdown = String::new();
+        for node in nodes {
+            let block = self.block(node);
+            if !markdown.is_empty() {
+                markdown.push_str(separator);
+            }
+            markdown.push_str(&block);
+        }
+        markdown
+    }
+
+    fn block(&mut self, node: &Node<'_>) -> String {
+        match node {
+            Node::Paragraph(paragraph) => self.inlines(&paragraph.children),
+            Node::Heading(heading) => self.heading(heading),
+            Node::ThematicBreak(_) => "---".to_string(),
+            Node::BlockQuote(quote) => {
+                let content = self.blocks(&quote.children, true);
+                prefix_lines(&content, "> ", "> ", ">")
+            }
+            Node::List(list) => self.list(list),
+            Node::ListItem(item) => self.list_item(item, &format!("{} ", self.style.bullet), false),
+            Node::CodeBlock(code) => code_block(code),
+            Node::Html(html) => html.value.trim_end_matches('\n').to_string(),
+            Node::Table(table) => self.table(table),
+            Node::Math(math) => format!("$$\n{}\n$$", math.value.trim_matches('\n')),
+            Node::Definition(definition) => {
+                let label = definition.label.unwrap_or(definition.identifier);
+                format!("[{label}]: {}", destination(definition.url, definition.title))
+            }
+            Node::FootnoteDefinition(definition) => {
+                let label = definition.label.unwrap_or(definition.identifier);
+                let content = self.blocks(&definition.children, true);
+                prefix_lines(&content, &format!("[^{label}]: "), "    ", "")
+            }
+            _ => {
+                let mut markdown = String::new();
+                self.inline(node, &mut markdown);
+                markdown
+            }
+        }
+    }
+
+    fn heading(&mut self, heading: &Heading<'_>) -> String {
+        let text = self.inlines(&heading.children);
+        let mut text = text.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+        // A trailing `#` would be read as a closing sequence
+        if text.ends_with('#') {
+            text.insert(text.len() - 1, '\\');
+        }
+        let attributes: Vec<String> = heading
+            .id
+            .map(|id| format!("#{id}"))
+            .into_iter()
+            .chain(heading.classes.iter().map(|class| format!(".{class}")))
+            .collect();
+        if !attributes.is_empty() {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            let _ = write!(text, "{{{}}}", attributes.join(" "));
+        }
+        let hashes = "#".repeat(usize::from(heading.depth.clamp(1, 6)));
+        match (text.is_empty(), self.style.heading) {
+            (true, _) => hashes,
+            (false, HeadingStyle::Atx) => format!("{hashes} {text}"),
+            (false, HeadingStyle::AtxClosed) => format!("{hashes} {text} {hashes}"),
+        }
+    }
+
+    fn list(&mut self, list: &List<'_>) -> String {
+        let mut number = list.start.unwrap_or(1);
+        let mut items = Vec::with_capacity(list.children.len());
+        for item in &list.children {
+            let marker = if list.ordered {
+                let marker = format!("{number}{} ", self.style.ordered_delimiter);
+                number = number.saturating_add(1);
+                marker
+            } else {
+                format!("{} ", self.style.bullet)
+            };
+            items.push(self.list_item(item, &marker, list.spread));
+        }
+        items.join(if list.spread { "\n\n" } else { "\n" })
+    }
+
+    fn list_item(&mut self, item: &ListItem<'_>, marker: &str, list_spread: bool) -> String {
+        let mut content = self.blocks(&item.children, item.spread || list_spread);
+        if let Some(checked) = item.checked {
+            let checkbox = if checked { "[x]" } else { "[ ]" };
+            content = if content.is_empty() {
+                checkbox.to_string()
+            } else {
+                format!("{checkbox} {content}")
+            };
+        }
+        if content.is_empty() {
+            return marker.trim_end().to_string();
+        }
+        prefix_lines(&content, marker, &" ".repeat(marker.len()), "")
+    }
+
+    fn table(&mut self, table: &Table<'_>) -> String {
+        let rows: Vec<Vec<String>> = table
+            .children
+            .iter()
+            .map(|row| {
+                row.children
+                    .iter()
+                    .map(|cell| {
+                        let lines: Vec<String> = cell
+                            .children
+                            .split(|node| matches!(node, Node::Break(_)))
+                            .map(|nodes| self.inlines(nodes))
+                            .collect();
+                        lines.join("<br>").replace('|', "\\|")
+                    })
+                    .collect()
+            })
+            .collect();
+        let columns = rows.iter().map(Vec::len).chain([table.align.len()]).max().unwrap_or(0);
+        let mut widths = vec![3; columns];
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let format_row = |cells: &[String]| {
+            let cells: Vec<String> = widths
+                .iter()
+                .enumerate()
+                .map(|(column, width)| {
+                    let cell = cells.get(column).map_or("", String::as_str);
+                    format!("{cell}{}", " ".repeat(width - cell.chars().count()))
+                })
+                .collect();
+            format!("| {} |", cells.join(" | "))
+        };
+        let delimiters: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(column, width)| {
+                let align = table.align.get(column).copied().unwrap_or(AlignKind::None);
+                let (left, right) = match align {
+                    AlignKind::None => ("-", "-"),
+                    AlignKind::Left => (":", "-"),
+                    AlignKind::Center => (":", ":"),
+                    AlignKind::Right => ("-", ":"),
+                };
+                format!("{left}{}{right}", "-".repeat(width - 2))
+            })
+            .collect();
+
+        let mut lines = Vec::with_capacity(rows.len() + 1);
+        let mut rows = rows.iter();
+        lines.push(format_row(rows.next().map_or(&[][..], Vec::as_slice)));
+        lines.push(format!("| {} |", delimiters.join(" | ")));
+        lines.extend(rows.map(|row| format_row(row)));
+        lines.join("\n")
+    }
+
+    fn inlines(&mut self, nodes: &[Node<'_>]) -> String {
+        let mut markdown = String::new();
+        // Adjacent text nodes are escaped together, so that `snake_case`
+        // split at its delimiter is still seen as one word
+        let mut text = String::new();
+        for node in nodes {
+            if let Node::Text(node) = node {
+                text.push_str(node.value);
+                continue;
+            }
+            escape_text(&std::mem::take(&mut text), &mut markdown);
+            self.inline(node, &mut markdown);
+        }
+        escape_text(&text, &mut markdown);
+        markdown
+    }
+
+    /// Appends an inline node to `markdown`, which holds the inline content
+    /// before it in the same block.
+    fn inline(&mut self, node: &Node<'_>, markdown: &mut String) {
+        match node {
+            Node::Text(text) => escape_text(text.value, markdown),
+            Node::Emphasis(emphasis) => {
+                let content = self.inlines(&emphasis.children);
+                let marker = emphasis_marker(self.style.emphasis, markdown);
+                let _ = write!(markdown, "{marker}{content}{marker}");
+            }
+            Node::Strong(strong) => {
+                let content = self.inlines(&strong.children);
+                let marker = emphasis_marker(self.style.strong, markdown);
+                let _ = write!(markdown, "{marker}{marker}{content}{marker}{marker}");
+            }
+            Node::Delete(delete) => {
+                let content = self.inlines(&delete.children);
+                let _ = write!(markdown, "~~{content}~~");
+            }
+            Node::InlineCode(code) => markdown.push_str(&inline_code(code)),
+            Node::InlineMath(math) => {
+                let _ = write!(markdown, "${}$", math.value);
+            }
+            Node::Break(_) => markdown.push_str("\\\n"),
+            Node::Link(link) => {
+                let content = self.inlines(&link.children);
+                let _ = write!(markdown, "[{content}]({})", destination(link.url, link.title));
+            }
+            Node::Image(image) => markdown.push_str(&image_markdown(image)),
+            Node::FootnoteReference(reference) => {
+                let label = reference.label.unwrap_or(reference.identifier);
+                let _ = write!(markdown, "[^{label}]");
+            }
+            Node::InlineFootnote(footnote) => {
+                let content = self.inlines(&footnote.children);
+                let _ = write!(markdown, "^[{content}]");
+            }
+            Node::Abbreviation(abbreviation) => {
+                let definition = (abbreviation.value.to_string(), abbreviation.title.to_string());
+                if !self.abbreviations.contains(&definition) {
+                    self.abbreviations.push(definition);
+                }
+                markdown.push_str(abbreviation.value);
+            }
+            _ => {
+                let block = self.block(node);
+                markdown.push_str(&block);
+            }
+        }
+    }
+}
+
+impl Renderer for MarkdownRenderer {
+    type Output = String;
+
+    fn render(&mut self, document: &Document<'_>) -> RenderResult<Self::Output> {
+        Ok(Self::render(self, document))
+    }
+}
+
+/// Returns `preferred`, or `*` when `_` would follow a word character
+/// and so could not open emphasis.
+fn emphasis_marker(preferred: char, before: &str) -> char {
+    if preferred == '_' && before.chars().next_back().is_some_and(char::is_alphanumeric) {
+        '*'
+    } else {
+        preferred
+    }
+}
+
+/// Prefixes the first line of `content` with `first`, later lines with
+/// `rest` and blank lines with `blank`.
+fn prefix_lines(content: &str, first: &str, rest: &str, blank: &str) -> String {
+    let mut output = String::with_capacity(content.len() + first.len());
+    for (index, line) in content.split('\n').enumerate() {
+        if index > 0 {
+            output.push('\n');
+        }
+        if index == 0 {
+            output.push_str(if line.is_empty() { first.trim_end() } else { first });
+        } else if line.is_empty() {
+            output.push_str(blank);
+        } else {
+            output.push_str(rest);
+        }
+        output.push_str(line);
+    }
+    output
+}
+
+/// Appends text to `markdown`, escaping the characters that would be read
+/// as markup.
+fn escape_text(text: &str, markdown: &mut String) {
+    let mut previous = markdown.chars().next_back();
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let next = chars.peek().map(|&(_, next)| next);
+        let line_start = markdown.is_empty() || markdown.ends_with('\n');
+        let rest_of_line = text[index..].split('\n').next().unwrap_or_default();
+        let escape = match c {
+            '\\' | '*' | '`' | '[' | ']' | '~' => true,
+            // `_` inside a word cannot open or close emphasis
+            '_' => {
+                !(previous.is_some_and(char::is_alphanumeric)
+                    && next.is_some_and(char::is_alphanumeric))
+            }
+            '&' => next.is_some_and(|next| next.is_ascii_alphanumeric() || next == '#'),
+            // `1.` or `1)` after the digits that start a line begins a list
+            '.' | ')' => {
+                let line = &markdown[markdown.rfind('\n').map_or(0, |i| i + 1)..];
+                (1..=9).contains(&line.len())
+                    && line.bytes().all(|b| b.is_ascii_digit())
+                    && next.is_none_or(char::is_whitespace)
+            }
+            _ if !line_start => false,
+            '#' => {
+                let hashes = rest_of_line.len() - rest_of_line.trim_start_matches('#').len();
+                hashes <= 6 && rest_of_line[hashes..].chars().next().is_none_or(char::is_whitespace)
+            }
+            '<' => next.is_some_and(|next| next.is_ascii_alphabetic() || "/!?".contains(next)),
+            // `- ` and `+ ` begin a list item and a line of `-` is a break
+            '+' | '-' => {
+                next.is_none_or(char::is_whitespace)
+                    || (c == '-' && rest_of_line.trim_end().chars().all(|other| other == c))
+            }
+            _ => false,
+        };
+        if escape {
+            markdown.push('\\');
+        }
+        markdown.push(c);
+        previous = Some(c);
+    }
+}
+
+fn code_block(code: &CodeBlock<'_>) -> String {
+    let info = [code.lang, code.meta].into_iter().flatten().collect::<Vec<_>>().join(" ");
+    // Backticks cannot appear in the info string of a backtick fence
+    let fence_char = if info.contains('`') { '~' } else { '`' };
+    let longest = longest_run(code.value, fence_char);
+    let fence = fence_char.to_string().repeat(longest.max(2) + 1);
+    let value = code.value.strip_suffix('\n').unwrap_or(code.value);
+    if value.is_empty() {
+        format!("{fence}{info}\n{fence}")
+    } else {
+        format!("{fence}{info}\n{value}\n{fence}")
+    }
+}
+
+fn inline_code(code: &InlineCode<'_>) -> String {
+    let ticks = "`".repeat(longest_run(code.value, '`') + 1);
+    if code.value.starts_with('`') || code.value.ends_with('`') {
+        format!("{ticks} {} {ticks}", code.value)
+    } else {
+        format!("{ticks}{}{ticks}", code.value)
+    }
+}
+
+fn image_markdown(image: &Image<'_>) -> String {
+    let mut alt = String::new();
+    escape_text(image.alt, &mut alt);
+    format!("![{alt}]({})", destination(image.url, image.title))
+}
+
+/// Formats a link destination and optional title.
+fn destination(url: &str, title: Option<&str>) -> String {
+    let mut destination = if url.is_empty() || url.contains([' ', '(', ')', '<', '>']) {
+        format!("<{}>", url.replace('<', "%3C").replace('>', "%3E"))
+    } else {
+        url.to_string()
+    };
+    if let Some(title) = title {
+        let _ = write!(destination, " \"{}\"", title.replace('"', "\\\""));
+    }
+    destination
+}
+
+/// Returns the length of the longest run of `c` in `text`.
+fn longest_run(text: &str, c: char) -> usize {
+    text.split(|other| other != c).map(str::len).max().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ox_content_allocator::Allocator;
+    use ox_content_parser::{Parser, ParserOptions};
+
+    fn format(source: &str, style: MarkdownStyle) -> String {
+        let allocator = Allocator::new();
+        let document =
+            Parser::with_options(&allocator, source, ParserOptions::gfm()).parse().unwrap();
+        MarkdownRenderer::with_style(style).render(&document)
+    }
+
+    #[test]
+    fn test_format_markdown() {
+        let source = "# Title #\n\n* one\n+ two\n    * nested\n\n1) first\n2) second\n\n\
+                      Some _emphasis_ and __strong__ text.\n";
+        let expected = "# Title\n\n- one\n- two\n  - nested\n\n1. first\n2. second\n\n\
+                        Some *emphasis* and **strong** text.\n";
+        assert_eq!(format(source, MarkdownStyle::new()), expected);
+        assert_eq!(format(expected, MarkdownStyle::new()), expected);
+
+        let style = MarkdownStyle {
+            bullet: '*',
+            ordered_delimiter: ')',
+            emphasis: '_',
+            strong: '_',
+            heading: HeadingStyle::AtxClosed,
+        };
+        assert_eq!(
+            format(source, style),
+            "# Title #\n\n* one\n* two\n  * nested\n\n1) first\n2) second\n\n\
+             Some _emphasis_ and __strong__ text.\n"
+        );
+    }
+
+    #[test]
+    fn test_format_heading_id() {
+        let allocator = Allocator::new();
+        let options = ParserOptions { attributes: true, ..ParserOptions::gfm() };
+        let source = "## Getting *started* {#install .no-toc} ##\n";
+        let document = Parser::with_options(&allocator, source, options).parse().unwrap();
+        assert_eq!(
+            MarkdownRenderer::with_style(MarkdownStyle::new()).render(&document),
+            "## Getting *started* {#install .no-toc}\n"
+        );
+        let style = MarkdownStyle { heading: HeadingStyle::AtxClosed, ..MarkdownStyle::new() };
+        assert_eq!(
+            MarkdownRenderer::with_style(style).render(&document),
+            "## Getting *started* {#install .no-toc} ##\n"
+        );
+    }
+
+    #[test]
+    fn test_format_markdown_blocks() {
+        let source = "- [x] done\n- [ ] todo\n\n```rust title=\"a.rs\"\nfn main() {}\n```\n\n\
+                      | a | b |\n|:--|--:|\n| long cell | x |\n\n\
+                      [link](https://example.com \"Home\") and ![alt](/a.png) `co de`\n\n***\n";
+        let expected = "- [x] done\n- [ ] todo\n\n```rust title=\"a.rs\"\nfn main() {}\n```\n\n\
+                        | a         | b   |\n| :-------- | --: |\n| long cell | x   |\n\n\
+                        [link](https://example.com \"Home\") and ![alt](/a.png) `co de`\n\n---\n";
+        let formatted = format(source, MarkdownStyle::new());
+        assert_eq!(formatted, expected);
+        assert_eq!(format(&formatted, MarkdownStyle::new()), formatted);
+    }
+
+    #[test]
+    fn test_format_markdown_escapes() {
+        let cases = [
+            ("1\\. not a list", "1\\. not a list\n"),
+            ("1.5 times", "1.5 times\n"),
+            ("\\# not a heading", "\\# not a heading\n"),
+            ("#hashtag", "#hashtag\n"),
+            ("snake_case and \\*stars\\*", "snake_case and \\*stars\\*\n"),
+            ("&amp;copy; and \\[brackets\\]", "\\&copy; and \\[brackets\\]\n"),
+            ("intra*word*emphasis", "intra*word*emphasis\n"),
+            ("- a\n\n\n* b", "- a\n\n- b\n"),
+            ("See^[a _note_] and x^2", "See^[a *note*] and x^2\n"),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(format(source, MarkdownStyle::new()), expected, "{source}");
+        }
+        let style = MarkdownStyle { emphasis: '_', ..MarkdownStyle::new() };
+        assert_eq!(format("intra*word*emphasis", style), "intra*word*emphasis\n");
+    }
+
+    #[test]
+    fn test_heading_style_from_name() {
+        assert_eq!(HeadingStyle::from_name("ATX_CLOSED"), Some(HeadingStyle::AtxClosed));
+        assert_eq!(HeadingStyle::from_name("atx"), Some(HeadingStyle::Atx));
+        assert_eq!(HeadingStyle::from_name("setext"), None);
+    }
+}
@@ -0,0 +1,559 @@
+//! Server-side rendering of TeX math to MathML.
+
+use std::fmt::Write;
+
+/// How math nodes are rendered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MathOutput {
+    /// Keep the TeX source, escaped, in `<span class="math math-inline">`
+    /// and `<div class="math math-display">` for a client-side renderer
+    /// such as KaTeX.
+    #[default]
+    Passthrough,
+    /// Convert a subset of TeX to MathML with [`latex_to_mathml`]. Math
+    /// outside the subset is rendered as passthrough with the source in a
+    /// `data-latex` attribute.
+    MathMl,
+}
+
+impl MathOutput {
+    /// Returns the output name (`passthrough` or `mathml`).
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Passthrough => "passthrough",
+            Self::MathMl => "mathml",
+        }
+    }
+
+    /// Looks up an output by name, ignoring ASCII case.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::Passthrough, Self::MathMl]
+            .into_iter()
+            .find(|output| output.as_str().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Converts TeX math to a MathML `<math>` element, with the source kept in
+/// an `application/x-tex` annotation.
+///
+/// The supported subset covers identifiers, numbers, operators, `{}`
+/// groups, `^` and `_` scripts, `\frac`, `\sqrt`, `\left`/`\right`,
+/// `\text`, font commands such as `\mathbf`, spacing commands, Greek
+/// letters and common symbols and function names. Anything else, such as
+/// environments (`\begin`), alignment (`&`, `\\`) or unknown commands,
+/// returns `None`.
+#[must_use]
+pub fn latex_to_mathml(latex: &str, display: bool) -> Option<String> {
+    let mut parser = MathParser { source: latex, pos: 0 };
+    let row = parser.row(Until::End)?;
+
+    let mut out = String::from(if display { "<math display=\"block\">" } else { "<math>" });
+    let _ = write!(out, "<semantics><mrow>{row}</mrow><annotation encoding=\"application/x-tex\">");
+    escape(latex, &mut out);
+    out.push_str("</annotation></semantics></math>");
+    Some(out)
+}
+
+/// Identifiers written as commands.
+const IDENTIFIERS: &[(&str, char)] = &[
+    ("alpha", 'α'),
+    ("beta", 'β'),
+    ("gamma", 'γ'),
+    ("delta", 'δ'),
+    ("epsilon", 'ϵ'),
+    ("varepsilon", 'ε'),
+    ("zeta", 'ζ'),
+    ("eta", 'η'),
+    ("theta", 'θ'),
+    ("vartheta", 'ϑ'),
+    ("iota", 'ι'),
+    ("kappa", 'κ'),
+    ("lambda", 'λ'),
+    ("mu", 'μ'),
+    ("nu", 'ν'),
+    ("xi", 'ξ'),
+    ("pi", 'π'),
+    ("rho", 'ρ'),
+    ("sigma", 'σ'),
+    ("tau", 'τ'),
+    ("upsilon", 'υ'),
+    ("phi", 'ϕ'),
+    ("varphi", 'φ'),
+    ("chi", 'χ'),
+    ("psi", 'ψ'),
+    ("omega", 'ω'),
+    ("Gamma", 'Γ'),
+    ("Delta", 'Δ'),
+    ("Theta", 'Θ'),
+    ("Lambda", 'Λ'),
+    ("Xi", 'Ξ'),
+    ("Pi", 'Π'),
+    ("Sigma", 'Σ'),
+    ("Upsilon", 'Υ'),
+    ("Phi", 'Φ'),
+    ("Psi", 'Ψ'),
+    ("Omega", 'Ω'),
+    ("infty", '∞'),
+    ("partial", '∂'),
+    ("nabla", '∇'),
+    ("emptyset", '∅'),
+    ("ell", 'ℓ'),
+    ("hbar", 'ℏ'),
+    ("aleph", 'ℵ'),
+];
+
+/// Operators and relations written as commands.
+const OPERATORS: &[(&str, char)] = &[
+    ("times", '×'),
+    ("cdot", '⋅'),
+    ("pm", '±'),
+    ("mp", '∓'),
+    ("div", '÷'),
+    ("ast", '∗'),
+    ("star", '⋆'),
+    ("circ", '∘'),
+    ("oplus", '⊕'),
+    ("otimes", '⊗'),
+    ("leq", '≤'),
+    ("le", '≤'),
+    ("geq", '≥'),
+    ("ge", '≥'),
+    ("neq", '≠'),
+    ("ne", '≠'),
+    ("ll", '≪'),
+    ("gg", '≫'),
+    ("approx", '≈'),
+    ("equiv", '≡'),
+    ("cong", '≅'),
+    ("sim", '∼'),
+    ("propto", '∝'),
+    ("perp", '⊥'),
+    ("parallel", '∥'),
+    ("mid", '∣'),
+    ("in", '∈'),
+    ("notin", '∉'),
+    ("subset", '⊂'),
+    ("subseteq", '⊆'),
+    ("supset", '⊃'),
+    ("supseteq", '⊇'),
+    ("cup", '∪'),
+    ("cap", '∩'),
+    ("setminus", '∖'),
+    ("forall", '∀'),
+    ("exists", '∃'),
+    ("neg", '¬'),
+    ("land", '∧'),
+    ("wedge", '∧'),
+    ("lor", '∨'),
+    ("vee", '∨'),
+    ("to", '→'),
+    ("rightarrow", '→'),
+    ("leftarrow", '←'),
+    ("uparrow", '↑'),
+    ("downarrow", '↓'),
+    ("mapsto", '↦'),
+    ("Rightarrow", '⇒'),
+    ("Leftarrow", '⇐'),
+    ("Leftrightarrow", '⇔'),
+    ("implies", '⟹'),
+    ("iff", '⟺'),
+    ("sum", '∑'),
+    ("prod", '∏'),
+    ("int", '∫'),
+    ("oint", '∮'),
+    ("ldots", '…'),
+    ("dots", '…'),
+    ("cdots", '⋯'),
+    ("vdots", '⋮'),
+    ("ddots", '⋱'),
+    ("prime", '′'),
+    ("langle", '⟨'),
+    ("rangle", '⟩'),
+    ("lfloor", '⌊'),
+    ("rfloor", '⌋'),
+    ("lceil", '⌈'),
+    ("rceil", '⌉'),
+    ("vert", '|'),
+    ("lvert", '|'),
+    ("rvert", '|'),
+    ("Vert", '‖'),
+];
+
+/// Function names rendered upright.
+const FUNCTIONS: &[&str] = &[
+    "sin", "cos", "tan", "cot", "sec", "csc", "arcsin", "arccos", "arctan", "sinh", "cosh", "tanh",
+    "log", "ln", "lg", "exp", "lim", "max", "min", "sup", "inf", "det", "gcd", "deg", "dim", "ker",
+    "arg", "Pr",
+];
+
+/// Font commands and the `mathvariant` they select.
+const VARIANTS: &[(&str, &str)] = &[
+    ("mathrm", "normal"),
+    ("operatorname", "normal"),
+    ("mathit", "italic"),
+    ("mathbf", "bold"),
+    ("mathbb", "double-struck"),
+    ("mathcal", "script"),
+    ("mathsf", "sans-serif"),
+    ("mathtt", "monospace"),
+];
+
+/// What ends a row of atoms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Until {
+    /// The end of the input.
+    End,
+    /// A closing `}`, which is consumed.
+    Brace,
+    /// A closing `]`, which is consumed.
+    Bracket,
+    /// A `\right`, which is left for the caller.
+    Right,
+}
+
+/// Recursive-descent converter over the TeX source.
+struct MathParser<'s> {
+    source: &'s str,
+    pos: usize,
+}
+
+impl MathParser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.source[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(ch) = self.peek().filter(|ch| ch.is_whitespace()) {
+            self.pos += ch.len_utf8();
+        }
+    }
+
+    /// Returns whether the input continues with the command `\name`.
+    fn at_command(&self, name: &str) -> bool {
+        let rest = &self.source[self.pos..];
+        rest.strip_prefix('\\')
+            .and_then(|rest| rest.strip_prefix(name))
+            .is_some_and(|after| !after.starts_with(|ch: char| ch.is_ascii_alphabetic()))
+    }
+
+    /// Converts atoms and their scripts up to `until`.
+    fn row(&mut self, until: Until) -> Option<String> {
+        let mut out = String::new();
+        loop {
+            self.skip_whitespace();
+            match (self.peek(), until) {
+                (None, Until::End) => return Some(out),
+                (Some('}'), Until::Brace) | (Some(']'), Until::Bracket) => {
+                    self.bump();
+                    return Some(out);
+                }
+                (None | Some('}'), _) => return None,
+                _ if until == Until::Right && self.at_command("right") => return Some(out),
+                _ => {}
+            }
+            let base = self.atom()?;
+            self.scripts(base, &mut out)?;
+        }
+    }
+
+    /// Appends `base` with any `_` and `^` scripts that follow it.
+    fn scripts(&mut self, base: String, out: &mut String) -> Option<()> {
+        let (mut sub, mut sup) = (None, None);
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('_') if sub.is_none() => {
+                    self.bump();
+                    sub = Some(self.arg()?);
+                }
+                Some('^') if sup.is_none() => {
+                    self.bump();
+                    sup = Some(self.arg()?);
+                }
+                _ => break,
+            }
+        }
+        let _ = match (sub, sup) {
+            (None, None) => write!(out, "{base}"),
+            (Some(sub), None) => write!(out, "<msub>{base}{sub}</msub>"),
+            (None, Some(sup)) => write!(out, "<msup>{base}{sup}</msup>"),
+            (Some(sub), Some(sup)) => write!(out, "<msubsup>{base}{sub}{sup}</msubsup>"),
+        };
+        Some(())
+    }
+
+    /// Converts a command or script argument: a group, a single digit or a
+    /// single atom, as in `x^2`, `x^{10}` or `\frac12`.
+    fn arg(&mut self) -> Option<String> {
+        self.skip_whitespace();
+        match self.peek()? {
+            digit if digit.is_ascii_digit() => {
+                self.bump();
+                Some(format!("<mn>{digit}</mn>"))
+            }
+            _ => self.atom(),
+        }
+    }
+
+    /// Reads a `{...}` argument as raw text.
+    fn raw_arg(&mut self) -> Option<&str> {
+        self.skip_whitespace();
+        if self.bump()? != '{' {
+            return None;
+        }
+        let start = self.pos;
+        let mut depth = 0usize;
+        loop {
+            match self.bump()? {
+                '{' => depth += 1,
+                '}' if depth == 0 => return Some(&self.source[start..self.pos - 1]),
+                '}' => depth -= 1,
+                _ => {}
+            }
+        }
+    }
+
+    /// Converts a single atom without scripts.
+    fn atom(&mut self) -> Option<String> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let ch = self.bump()?;
+        let atom = match ch {
+            '{' => format!("<mrow>{}</mrow>", self.row(Until::Brace)?),
+            '\\' => return self.command(),
+            '0'..='9' => {
+                while self.peek().is_some_and(|ch| ch.is_ascii_digit())
+                    || (self.peek() == Some('.')
+                        && self.source[self.pos + 1..].starts_with(|ch: char| ch.is_ascii_digit()))
+                {
+                    self.pos += 1;
+                }
+                format!("<mn>{}</mn>", &self.source[start..self.pos])
+            }
+            '~' => "<mspace width=\"0.333em\"/>".to_string(),
+            '-' => "<mo>\u{2212}</mo>".to_string(),
+            '\'' => "<mo>\u{2032}</mo>".to_string(),
+            '+' | '=' | '<' | '>' | '(' | ')' | '[' | ']' | ',' | ';' | ':' | '.' | '!' | '?'
+            | '|' | '/' | '*' => tag("mo", &ch.to_string()),
+            letter if letter.is_alphabetic() => tag("mi", &letter.to_string()),
+            _ => return None,
+        };
+        Some(atom)
+    }
+
+    /// Converts the command after a `\`.
+    fn command(&mut self) -> Option<String> {
+        let rest = &self.source[self.pos..];
+        let name_len = rest.bytes().take_while(u8::is_ascii_alphabetic).count();
+        if name_len == 0 {
+            // Control symbol such as `\,` or `\{`
+            return match self.bump()? {
+                ',' => Some(space("0.1667em")),
+                ':' | '>' => Some(space("0.2222em")),
+                ';' => Some(space("0.2778em")),
+                '!' => Some(space("-0.1667em")),
+                ' ' => Some(space("0.333em")),
+                ch @ ('{' | '}' | '|' | '%' | '$' | '#' | '_' | '&') => {
+                    Some(tag("mo", &ch.to_string()))
+                }
+                _ => None,
+            };
+        }
+        let name = &rest[..name_len];
+        self.pos += name_len;
+
+        let converted = match name {
+            "frac" | "dfrac" | "tfrac" => {
+                let numerator = self.arg()?;
+                let denominator = self.arg()?;
+                format!("<mfrac>{numerator}{denominator}</mfrac>")
+            }
+            "sqrt" => {
+                self.skip_whitespace();
+                if self.peek() == Some('[') {
+                    self.bump();
+                    let index = self.row(Until::Bracket)?;
+                    let radicand = self.arg()?;
+                    format!("<mroot>{radicand}<mrow>{index}</mrow></mroot>")
+                } else {
+                    format!("<msqrt>{}</msqrt>", self.arg()?)
+                }
+            }
+            "left" => {
+                let open = self.delimiter()?;
+                let body = self.row(Until::Right)?;
+                self.pos += "\\right".len();
+                let close = self.delimiter()?;
+                format!("<mrow>{open}{body}{close}</mrow>")
+            }
+            "text" | "textrm" => tag("mtext", self.raw_arg()?),
+            "quad" => space("1em"),
+            "qquad" => space("2em"),
+            _ => {
+                if let Some(&(_, variant)) = VARIANTS.iter().find(|(command, _)| *command == name) {
+                    let text = self.raw_arg()?;
+                    if !text.chars().all(|ch| ch.is_alphanumeric() || ch == ' ') {
+                        return None;
+                    }
+                    let mut out = format!("<mi mathvariant=\"{variant}\">");
+                    escape(text.trim(), &mut out);
+                    out.push_str("</mi>");
+                    return Some(out);
+                }
+                if let Some(&(_, ch)) = IDENTIFIERS.iter().find(|(command, _)| *command == name) {
+                    tag("mi", &ch.to_string())
+                } else if let Some(&(_, ch)) =
+                    OPERATORS.iter().find(|(command, _)| *command == name)
+                {
+                    tag("mo", &ch.to_string())
+                } else if FUNCTIONS.contains(&name) {
+                    tag("mi", name)
+                } else {
+                    return None;
+                }
+            }
+        };
+        Some(converted)
+    }
+
+    /// Converts the delimiter after `\left` or `\right`; `.` is empty.
+    fn delimiter(&mut self) -> Option<String> {
+        self.skip_whitespace();
+        let symbol = match self.bump()? {
+            '.' => return Some(String::new()),
+            ch @ ('(' | ')' | '[' | ']' | '|' | '/') => ch,
+            '\\' => {
+                let rest = &self.source[self.pos..];
+                let name_len = rest.bytes().take_while(u8::is_ascii_alphabetic).count();
+                if name_len == 0 {
+                    match self.bump()? {
+                        ch @ ('{' | '}') => ch,
+                        '|' => '‖',
+                        _ => return None,
+                    }
+                } else {
+                    let name = &rest[..name_len];
+                    self.pos += name_len;
+                    OPERATORS
+                        .iter()
+                        .find(|(command, _)| *command == name)
+                        .map(|&(_, ch)| ch)
+                        .filter(|ch| "⟨⟩⌊⌋⌈⌉|‖".contains(*ch))?
+                }
+            }
+            _ => return None,
+        };
+        let mut out = String::from("<mo fence=\"true\">");
+        escape(&symbol.to_string(), &mut out);
+        out.push_str("</mo>");
+        Some(out)
+    }
+}
+
+/// Returns `<name>text</name>` with `text` escaped.
+fn tag(name: &str, text: &str) -> String {
+    let mut out = format!("<{name}>");
+    escape(text, &mut out);
+    let _ = write!(out, "</{name}>");
+    out
+}
+
+/// Returns an `<mspace>` of the given width.
+fn space(width: &str) -> String {
+    format!("<mspace width=\"{width}\"/>")
+}
+
+/// Appends `text` to `out` with HTML special characters escaped.
+fn escape(text: &str, out: &mut String) {
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(ch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Converts inline math and strips the `<math>` wrapper and annotation.
+    fn body(latex: &str) -> Option<String> {
+        let mathml = latex_to_mathml(latex, false)?;
+        let start = mathml.find("<semantics><mrow>")? + "<semantics><mrow>".len();
+        let end = mathml.find("</mrow><annotation")?;
+        Some(mathml[start..end].to_string())
+    }
+
+    #[test]
+    fn test_basic_atoms() {
+        assert_eq!(body("x + 12.5").unwrap(), "<mi>x</mi><mo>+</mo><mn>12.5</mn>");
+        assert_eq!(body("a < b").unwrap(), "<mi>a</mi><mo>&lt;</mo><mi>b</mi>");
+        assert_eq!(body(r"\alpha \leq \infty").unwrap(), "<mi>α</mi><mo>≤</mo><mi>∞</mi>");
+        assert_eq!(body(r"\sin x").unwrap(), "<mi>sin</mi><mi>x</mi>");
+    }
+
+    #[test]
+    fn test_scripts_and_fractions() {
+        assert_eq!(body("x^2").unwrap(), "<msup><mi>x</mi><mn>2</mn></msup>");
+        assert_eq!(body("x^23").unwrap(), "<msup><mi>x</mi><mn>2</mn></msup><mn>3</mn>");
+        assert_eq!(
+            body("a_i^{n}").unwrap(),
+            "<msubsup><mi>a</mi><mi>i</mi><mrow><mi>n</mi></mrow></msubsup>"
+        );
+        assert_eq!(body(r"\frac12").unwrap(), "<mfrac><mn>1</mn><mn>2</mn></mfrac>");
+        assert_eq!(
+            body(r"\sqrt[3]{x}").unwrap(),
+            "<mroot><mrow><mi>x</mi></mrow><mrow><mn>3</mn></mrow></mroot>"
+        );
+        assert_eq!(
+            body(r"\left( x \right]").unwrap(),
+            "<mrow><mo fence=\"true\">(</mo><mi>x</mi><mo fence=\"true\">]</mo></mrow>"
+        );
+        assert_eq!(body(r"\text{if } x").unwrap(), "<mtext>if </mtext><mi>x</mi>");
+        assert_eq!(body(r"\mathbb{R}").unwrap(), "<mi mathvariant=\"double-struck\">R</mi>");
+    }
+
+    #[test]
+    fn test_wrapper() {
+        assert_eq!(
+            latex_to_mathml("x", true).unwrap(),
+            "<math display=\"block\"><semantics><mrow><mi>x</mi></mrow><annotation encoding=\"application/x-tex\">x</annotation></semantics></math>"
+        );
+    }
+
+    #[test]
+    fn test_unsupported() {
+        for latex in [
+            r"\begin{matrix} a \end{matrix}",
+            r"a \\ b",
+            "a & b",
+            r"\unknown",
+            "{x",
+            "x}",
+            "x^",
+            "x_1_2",
+            r"\left( x",
+        ] {
+            assert_eq!(latex_to_mathml(latex, false), None, "{latex}");
+        }
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(MathOutput::from_name("MathML"), Some(MathOutput::MathMl));
+        assert_eq!(MathOutput::from_name("passthrough"), Some(MathOutput::Passthrough));
+        assert_eq!(MathOutput::from_name("katex"), None);
+    }
+}
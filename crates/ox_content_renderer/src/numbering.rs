@@ -0,0 +1,83 @@
+//! Hierarchical heading numbering.
+
+/// Computes section numbers (`1.`, `1.1.`, `1.2.`, ...) for headings in
+/// document order.
+///
+/// Headings above the start level are not numbered and reset all counters,
+/// so with the default start level of 2 every h1 begins a new numbering.
+/// Skipped levels are numbered `0` (an h4 directly under the first h2 is
+/// `1.0.1.`).
+#[derive(Debug, Clone)]
+pub struct HeadingNumbering {
+    start_level: u8,
+    counters: [u32; 6],
+}
+
+impl HeadingNumbering {
+    /// Creates a numbering that starts at the given heading level (1-6).
+    #[must_use]
+    pub fn new(start_level: u8) -> Self {
+        Self { start_level: start_level.clamp(1, 6), counters: [0; 6] }
+    }
+
+    /// Returns the number for the next heading of the given depth, or `None`
+    /// if headings at that depth are not numbered.
+    pub fn next(&mut self, depth: u8) -> Option<String> {
+        if depth < self.start_level {
+            self.counters = [0; 6];
+            return None;
+        }
+        let level = usize::from(depth.min(6) - self.start_level);
+        self.counters[level] += 1;
+        self.counters[level + 1..].fill(0);
+
+        let mut number = String::new();
+        for counter in &self.counters[..=level] {
+            number.push_str(&counter.to_string());
+            number.push('.');
+        }
+        Some(number)
+    }
+}
+
+impl Default for HeadingNumbering {
+    fn default() -> Self {
+        Self::new(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(start: u8, depths: &[u8]) -> Vec<Option<String>> {
+        let mut numbering = HeadingNumbering::new(start);
+        depths.iter().map(|&depth| numbering.next(depth)).collect()
+    }
+
+    #[test]
+    fn test_hierarchy() {
+        let result = numbers(2, &[1, 2, 3, 3, 2, 3]);
+        let expected = [None, Some("1."), Some("1.1."), Some("1.2."), Some("2."), Some("2.1.")];
+        assert_eq!(result, expected.map(|n| n.map(String::from)));
+    }
+
+    #[test]
+    fn test_resets_at_higher_level() {
+        let result = numbers(2, &[2, 2, 1, 2]);
+        assert_eq!(result[3].as_deref(), Some("1."));
+    }
+
+    #[test]
+    fn test_level_skip() {
+        let result = numbers(2, &[2, 4, 3]);
+        assert_eq!(result[1].as_deref(), Some("1.0.1."));
+        assert_eq!(result[2].as_deref(), Some("1.1."));
+    }
+
+    #[test]
+    fn test_start_level_one() {
+        let result = numbers(1, &[1, 2, 1]);
+        assert_eq!(result[2].as_deref(), Some("2."));
+    }
+}
@@ -0,0 +1,90 @@
+//! Link preview data: a page's title, summary, image and length.
+
+use ox_content_ast::{collect_text, heading_text, Document, Image, Node, Visit};
+
+use crate::sections::document_words;
+
+/// What a link preview shows about a document, taken from its body.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinkPreview {
+    /// Plain text of the first h1.
+    pub title: Option<String>,
+    /// Plain text of the first top-level paragraph with text, with line
+    /// breaks and runs of whitespace collapsed to single spaces.
+    pub description: Option<String>,
+    /// URL of the first image, as written.
+    pub image: Option<String>,
+    /// Number of words, counted like
+    /// [`section_stats`](crate::section_stats) but over the whole document,
+    /// headings included.
+    pub words: usize,
+}
+
+/// Collects the link preview data of a document.
+#[must_use]
+pub fn link_preview(document: &Document<'_>) -> LinkPreview {
+    let mut preview = LinkPreview { words: document_words(document), ..LinkPreview::default() };
+    for node in &document.children {
+        match node {
+            Node::Heading(heading) if heading.depth == 1 && preview.title.is_none() => {
+                preview.title = Some(heading_text(heading));
+            }
+            Node::Paragraph(paragraph) if preview.description.is_none() => {
+                let mut text = String::new();
+                for child in &paragraph.children {
+                    collect_text(child, &mut text);
+                }
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                if !text.is_empty() {
+                    preview.description = Some(text);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut finder = ImageFinder::default();
+    finder.visit_document(document);
+    preview.image = finder.url;
+    preview
+}
+
+#[derive(Default)]
+struct ImageFinder {
+    url: Option<String>,
+}
+
+impl<'a> Visit<'a> for ImageFinder {
+    fn visit_image(&mut self, image: &Image<'a>) {
+        if self.url.is_none() {
+            self.url = Some(image.url.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ox_content_allocator::Allocator;
+    use ox_content_parser::Parser;
+
+    #[test]
+    fn test_link_preview() {
+        let allocator = Allocator::new();
+        let source =
+            "![logo](/logo.png)\n\n# Getting *Started*\n\nInstall the\npackage  first.\n\n\
+                      ## Usage\n\nRun it.\n\n![shot](/shot.png)\n\n# Second\n";
+        let doc = Parser::new(&allocator, source).parse().unwrap();
+        assert_eq!(
+            link_preview(&doc),
+            LinkPreview {
+                title: Some("Getting Started".to_string()),
+                description: Some("Install the package first.".to_string()),
+                image: Some("/logo.png".to_string()),
+                words: 10,
+            }
+        );
+
+        let doc = Parser::new(&allocator, "```\ncode\n```").parse().unwrap();
+        assert_eq!(link_preview(&doc), LinkPreview::default());
+    }
+}
@@ -0,0 +1,193 @@
+//! Autolinks for issue references, commit SHAs and mentions in text.
+
+/// URL templates for linking issue references such as `#123`, commit SHAs
+/// and `@name` mentions, as on GitHub.
+///
+/// References are only found in plain text: code, link text and escaped
+/// prefixes such as `\#123` and `\@name` are left alone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceLinks {
+    /// Text that starts an issue reference (default: `#`).
+    pub issue_prefix: String,
+    /// Issue URL, with `{n}` replaced by the issue number. Issue references
+    /// are not linked when unset.
+    pub issue_url: Option<String>,
+    /// Commit URL, with `{sha}` replaced by the full SHA. Commit SHAs are
+    /// not linked when unset.
+    pub commit_url: Option<String>,
+    /// Profile URL, with `{name}` replaced by the mentioned name. Mentions
+    /// are not linked when unset.
+    pub mention_url: Option<String>,
+}
+
+impl Default for ReferenceLinks {
+    fn default() -> Self {
+        Self { issue_prefix: "#".to_string(), issue_url: None, commit_url: None, mention_url: None }
+    }
+}
+
+/// A reference found in text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reference<'t> {
+    /// An issue reference, holding the issue number.
+    Issue(&'t str),
+    /// A commit SHA of 7 to 40 hex digits.
+    Commit(&'t str),
+    /// A mention such as `@octocat`, holding the name.
+    Mention(&'t str),
+}
+
+impl ReferenceLinks {
+    /// Creates reference links with the default `#` issue prefix.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the URL of a reference.
+    #[must_use]
+    pub fn url(&self, reference: Reference<'_>) -> Option<String> {
+        match reference {
+            Reference::Issue(number) => {
+                self.issue_url.as_ref().map(|url| url.replace("{n}", number))
+            }
+            Reference::Commit(hash) => {
+                self.commit_url.as_ref().map(|url| url.replace("{sha}", hash))
+            }
+            Reference::Mention(user) => {
+                self.mention_url.as_ref().map(|url| url.replace("{name}", user))
+            }
+        }
+    }
+
+    /// Finds the linkable references in `text`, with their byte ranges.
+    ///
+    /// A reference must be a whole word: `#123` in `a#123` or `#123a` is not
+    /// a reference, nor is `@b` in the email-like `a@b`. SHAs are lowercase
+    /// and need both a digit and a letter, so numbers and words such as
+    /// `deadbeef` stay text. Mentioned names start with a letter or digit
+    /// and go on with letters, digits, `_` and inner `-`.
+    #[must_use]
+    pub fn find<'t>(&self, text: &'t str) -> Vec<(std::ops::Range<usize>, Reference<'t>)> {
+        let mut found = Vec::new();
+        let prefix = self.issue_prefix.as_str();
+        let mut pos = 0;
+        while pos < text.len() {
+            let word_start = !text[..pos].chars().next_back().is_some_and(is_word_char);
+            if word_start {
+                if let Some(reference) = self.reference_at(&text[pos..], prefix) {
+                    let len = match reference {
+                        Reference::Issue(number) => prefix.len() + number.len(),
+                        Reference::Commit(sha) => sha.len(),
+                        Reference::Mention(name) => name.len() + 1,
+                    };
+                    found.push((pos..pos + len, reference));
+                    pos += len;
+                    continue;
+                }
+            }
+            pos += text[pos..].chars().next().map_or(1, char::len_utf8);
+        }
+        found
+    }
+
+    /// Returns the reference that starts `rest`, if it ends at a word end.
+    fn reference_at<'t>(&self, rest: &'t str, prefix: &str) -> Option<Reference<'t>> {
+        if self.mention_url.is_some() {
+            if let Some(after) = rest.strip_prefix('@') {
+                let len =
+                    after.find(|c: char| !(is_word_char(c) || c == '-')).unwrap_or(after.len());
+                let name = after[..len].trim_end_matches('-');
+                if name.starts_with(char::is_alphanumeric) {
+                    return Some(Reference::Mention(name));
+                }
+            }
+        }
+        if self.issue_url.is_some() && !prefix.is_empty() {
+            if let Some(after) = rest.strip_prefix(prefix) {
+                let number = &after[..after.bytes().take_while(u8::is_ascii_digit).count()];
+                if !number.is_empty() && !after[number.len()..].starts_with(is_word_char) {
+                    return Some(Reference::Issue(number));
+                }
+            }
+        }
+        if self.commit_url.is_some() {
+            let len = rest.bytes().take_while(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')).count();
+            let sha = &rest[..len];
+            if (7..=40).contains(&len)
+                && !rest[len..].starts_with(is_word_char)
+                && sha.bytes().any(|b| b.is_ascii_digit())
+                && sha.bytes().any(|b| b.is_ascii_alphabetic())
+            {
+                return Some(Reference::Commit(sha));
+            }
+        }
+        None
+    }
+}
+
+/// Returns whether `c` continues a word.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links() -> ReferenceLinks {
+        ReferenceLinks {
+            issue_url: Some("https://example.com/issues/{n}".to_string()),
+            commit_url: Some("https://example.com/commit/{sha}".to_string()),
+            mention_url: Some("https://example.com/u/{name}".to_string()),
+            ..ReferenceLinks::new()
+        }
+    }
+
+    #[test]
+    fn test_find_references() {
+        let text = "Fixes #12 (see a1b2c3d and #7).";
+        let found = links().find(text);
+        let references: Vec<_> =
+            found.iter().map(|(range, r)| (&text[range.clone()], *r)).collect();
+        assert_eq!(
+            references,
+            [
+                ("#12", Reference::Issue("12")),
+                ("a1b2c3d", Reference::Commit("a1b2c3d")),
+                ("#7", Reference::Issue("7")),
+            ]
+        );
+        assert_eq!(links().url(Reference::Issue("12")).unwrap(), "https://example.com/issues/12");
+    }
+
+    #[test]
+    fn test_find_mentions() {
+        let text = "Thanks @octo-cat, @a_b and (@x)! Mail me@example.com or @-x, @y-.";
+        let names: Vec<_> = links()
+            .find(text)
+            .into_iter()
+            .filter_map(|(range, r)| match r {
+                Reference::Mention(name) => Some((&text[range], name)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(names, [("@octo-cat", "octo-cat"), ("@a_b", "a_b"), ("@x", "x"), ("@y", "y")]);
+        assert_eq!(links().url(Reference::Mention("x")).unwrap(), "https://example.com/u/x");
+        let no_mentions = ReferenceLinks { mention_url: None, ..links() };
+        assert_eq!(no_mentions.find("@octocat"), []);
+    }
+
+    #[test]
+    fn test_not_references() {
+        for text in
+            ["a#12", "#12a", "#", "# 12", "deadbeef", "1234567", "a1b2c3", "a1b2c3dz", "A1B2C3D"]
+        {
+            assert_eq!(links().find(text), [], "{text}");
+        }
+        let issues_only = ReferenceLinks { commit_url: None, ..links() };
+        assert_eq!(issues_only.find("a1b2c3d").len(), 0);
+        let custom = ReferenceLinks { issue_prefix: "GH-".to_string(), ..links() };
+        assert_eq!(custom.find("GH-5 #5").len(), 1);
+    }
+}
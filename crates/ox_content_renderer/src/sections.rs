@@ -0,0 +1,248 @@
+//! Word and character counts per document section, and section lookup.
+
+use std::ops::Range;
+
+use ox_content_ast::{
+    collect_text, heading_slug, heading_text, Document, Heading, Node, SlugStyle, UniqueSlugs,
+    Visit,
+};
+
+use crate::cjk::is_cjk;
+
+/// Text counts for one section of a document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SectionStats {
+    /// Plain text of the h2 starting the section, or empty for the text
+    /// before the first h2.
+    pub heading: String,
+    /// The heading ID, deduplicated like the renderer's, or empty for the
+    /// text before the first h2.
+    pub slug: String,
+    /// Number of words. Each Chinese or Japanese character counts as a
+    /// word, as those languages are written without spaces.
+    pub words: usize,
+    /// Number of characters, not counting whitespace.
+    pub chars: usize,
+}
+
+/// Splits a document at its top-level h2 headings and counts the text in
+/// each section.
+///
+/// A section runs until the next h2 and includes its deeper headings; h1
+/// headings are page titles and are not counted. Text before the first h2
+/// is returned as a section with an empty heading, if there is any. Code
+/// blocks, HTML blocks and math are not counted; inline code is.
+#[must_use]
+pub fn section_stats(document: &Document<'_>, slug_style: SlugStyle) -> Vec<SectionStats> {
+    let mut sections = Vec::new();
+    // The text before the first h2 until one is found
+    let mut section = SectionStats::default();
+    let mut intro = true;
+    let mut slugs = UniqueSlugs::new();
+    let mut text = String::new();
+
+    for node in &document.children {
+        if let Node::Heading(heading) = node {
+            let heading_text = heading_text(heading);
+            let slug = slugs.unique(heading_slug(heading, slug_style));
+            match heading.depth {
+                1 => continue,
+                2 => {
+                    section.count(&std::mem::take(&mut text));
+                    let next =
+                        SectionStats { heading: heading_text, slug, ..SectionStats::default() };
+                    let done = std::mem::replace(&mut section, next);
+                    if !intro || done.chars > 0 {
+                        sections.push(done);
+                    }
+                    intro = false;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        block_text(node, &mut text);
+        text.push('\n');
+    }
+    section.count(&text);
+    if !intro || section.chars > 0 {
+        sections.push(section);
+    }
+    sections
+}
+
+/// Finds the section under the top-level heading with ID `slug`: the
+/// heading and the blocks after it, up to the next heading of the same or
+/// a higher level.
+///
+/// Returns the range of `document.children` it covers. IDs are assigned
+/// like the renderer's, so repeated headings are found by their suffixed
+/// IDs. Headings nested in other blocks take up IDs but cannot start a
+/// section.
+#[must_use]
+pub fn section_range(
+    document: &Document<'_>,
+    slug: &str,
+    slug_style: SlugStyle,
+) -> Option<Range<usize>> {
+    let mut finder = HeadingSlugs { slugs: UniqueSlugs::new(), style: slug_style };
+    let mut section: Option<(usize, u8)> = None;
+    for (i, node) in document.children.iter().enumerate() {
+        match (node, section) {
+            (Node::Heading(heading), Some((start, depth))) if heading.depth <= depth => {
+                return Some(start..i);
+            }
+            (Node::Heading(heading), None) => {
+                let id = finder.slugs.unique(heading_slug(heading, slug_style));
+                if id == slug {
+                    section = Some((i, heading.depth));
+                }
+            }
+            // The renderer writes footnote definitions last, after every
+            // other heading has its ID
+            (Node::FootnoteDefinition(_), _) | (_, Some(_)) => {}
+            _ => finder.visit_node(node),
+        }
+    }
+    section.map(|(start, _)| start..document.children.len())
+}
+
+/// Takes up the IDs of headings nested in other blocks.
+struct HeadingSlugs {
+    slugs: UniqueSlugs,
+    style: SlugStyle,
+}
+
+impl<'a> Visit<'a> for HeadingSlugs {
+    fn visit_heading(&mut self, heading: &Heading<'a>) {
+        self.slugs.unique(heading_slug(heading, self.style));
+    }
+}
+
+/// Counts the words of a whole document, headings of every level
+/// included, with the same rules as [`section_stats`].
+pub fn document_words(document: &Document<'_>) -> usize {
+    let mut text = String::new();
+    for node in &document.children {
+        block_text(node, &mut text);
+        text.push('\n');
+    }
+    let mut stats = SectionStats::default();
+    stats.count(&text);
+    stats.words
+}
+
+/// Appends the countable text of a block to `text`, one line per block.
+fn block_text(node: &Node<'_>, text: &mut String) {
+    let mut blocks = |children: &[Node<'_>]| {
+        for child in children {
+            block_text(child, text);
+            text.push('\n');
+        }
+    };
+    match node {
+        Node::Paragraph(paragraph) => inline_text(&paragraph.children, text),
+        Node::Heading(heading) => inline_text(&heading.children, text),
+        Node::BlockQuote(quote) => blocks(&quote.children),
+        Node::List(list) => list.children.iter().for_each(|item| blocks(&item.children)),
+        Node::ListItem(item) => blocks(&item.children),
+        Node::FootnoteDefinition(definition) => blocks(&definition.children),
+        Node::Table(table) => {
+            for cell in table.children.iter().flat_map(|row| &row.children) {
+                inline_text(&cell.children, text);
+                text.push('\n');
+            }
+        }
+        Node::CodeBlock(_)
+        | Node::Html(_)
+        | Node::Math(_)
+        | Node::ThematicBreak(_)
+        | Node::Definition(_) => {}
+        _ => collect_text(node, text),
+    }
+}
+
+fn inline_text(children: &[Node<'_>], text: &mut String) {
+    for child in children {
+        collect_text(child, text);
+    }
+}
+
+impl SectionStats {
+    /// Adds the words and non-whitespace characters of `text`.
+    ///
+    /// A word is a run of characters between whitespace that contains a
+    /// letter or digit, so punctuation on its own is not a word.
+    fn count(&mut self, text: &str) {
+        let mut in_word = false;
+        for c in text.chars() {
+            if c.is_whitespace() {
+                in_word = false;
+                continue;
+            }
+            self.chars += 1;
+            if is_cjk(c) {
+                self.words += usize::from(c.is_alphanumeric());
+                in_word = false;
+            } else if c.is_alphanumeric() && !in_word {
+                self.words += 1;
+                in_word = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ox_content_allocator::Allocator;
+    use ox_content_parser::{Parser, ParserOptions};
+
+    fn stats(source: &str) -> Vec<(String, String, usize, usize)> {
+        let allocator = Allocator::new();
+        let document =
+            Parser::with_options(&allocator, source, ParserOptions::gfm()).parse().unwrap();
+        section_stats(&document, SlugStyle::default())
+            .into_iter()
+            .map(|s| (s.heading, s.slug, s.words, s.chars))
+            .collect()
+    }
+
+    #[test]
+    fn test_section_stats() {
+        let source = "# Title\n\nIntro text.\n\n## Setup\n\nRun `npm install` now.\n\n\
+                      ### Details\n\n- one\n- two\n\n```sh\nnot counted\n```\n\n\
+                      ## Setup\n\n## 日本語\n\nこれはテストです。Rust!\n";
+        assert_eq!(
+            stats(source),
+            [
+                (String::new(), String::new(), 2, 10),
+                ("Setup".to_string(), "setup".to_string(), 7, 30),
+                ("Setup".to_string(), "setup-1".to_string(), 0, 0),
+                ("日本語".to_string(), "日本語".to_string(), 9, 14),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_section_range() {
+        let allocator = Allocator::new();
+        let source = "# Guide\n\n## Setup\n\nText\n\n### Details\n\nMore\n\n## Setup\n\n\
+                      - item\n\n# Next\n\n## Usage";
+        let document = Parser::new(&allocator, source).parse().unwrap();
+        let range = |slug| section_range(&document, slug, SlugStyle::default());
+        assert_eq!(range("guide"), Some(0..7));
+        assert_eq!(range("setup"), Some(1..5));
+        assert_eq!(range("details"), Some(3..5));
+        assert_eq!(range("setup-1"), Some(5..7));
+        assert_eq!(range("usage"), Some(8..9));
+        assert_eq!(range("missing"), None);
+    }
+
+    #[test]
+    fn test_section_stats_without_intro() {
+        let counts = stats("# Title\n\n## A\n\nOne -- two don't.");
+        assert_eq!(counts, [("A".to_string(), "a".to_string(), 3, 14)]);
+        assert!(stats("").is_empty());
+    }
+}
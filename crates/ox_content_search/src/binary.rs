@@ -0,0 +1,445 @@
+//! Compact binary serialization for the search index.
+//!
+//! The JSON form of a [`SearchIndex`] repeats every field name for every
+//! posting and spells numbers out in decimal, which makes it both large and
+//! slow to parse. This format stores the same data with:
+//!
+//! - a fixed `OXSI` magic header and a format version byte,
+//! - LEB128 varints for all lengths and counts,
+//! - delta-encoded document indices inside each posting list,
+//! - a single byte per posting for the field tag,
+//! - document frequencies derived from posting list lengths instead of
+//!   being stored a second time.
+//!
+//! Terms are written in sorted order, so the output is deterministic for a
+//! given index. The result is smaller than the JSON index because no field
+//! names, quotes or decimal digits are stored, and it decodes faster because
+//! reading it involves no JSON tokenizing or number parsing.
+
+use std::collections::{BTreeMap, HashMap};
+
+use thiserror::Error;
+
+use crate::index::{Field, Posting, SearchDocument, SearchIndex};
+
+/// Magic bytes at the start of every binary index.
+const MAGIC: &[u8; 4] = b"OXSI";
+
+/// Current binary format version. Version 2 added per-document boosts and
+/// version 3 document tags and facets; older indexes are still read, with a
+/// boost of 1.0 and without tags or facets.
+const VERSION: u8 = 3;
+
+/// Result type for binary decoding.
+pub type BinaryResult<T> = Result<T, BinaryError>;
+
+/// Errors that can occur while decoding a binary index.
+#[derive(Debug, Error)]
+pub enum BinaryError {
+    /// The input does not start with the expected magic bytes.
+    #[error("invalid magic bytes: not an ox-content search index")]
+    InvalidMagic,
+
+    /// The input was written by an unsupported format version.
+    #[error("unsupported binary index version {0}")]
+    UnsupportedVersion(u8),
+
+    /// The input ended before a complete value could be read.
+    #[error("unexpected end of input at byte {0}")]
+    UnexpectedEof(usize),
+
+    /// A string was not valid UTF-8.
+    #[error("invalid UTF-8 string at byte {0}")]
+    InvalidUtf8(usize),
+
+    /// A varint was longer than 64 bits.
+    #[error("varint overflow at byte {0}")]
+    VarintOverflow(usize),
+
+    /// An unknown field tag was encountered.
+    #[error("invalid field tag {tag} at byte {offset}")]
+    InvalidField {
+        /// The tag value.
+        tag: u8,
+        /// Byte offset of the tag.
+        offset: usize,
+    },
+
+    /// A posting referenced a document that does not exist.
+    #[error("posting references document {0} which is out of range")]
+    DocumentOutOfRange(usize),
+}
+
+impl Field {
+    /// Returns the tag byte used by the binary format.
+    const fn tag(self) -> u8 {
+        match self {
+            Self::Title => 0,
+            Self::Heading => 1,
+            Self::Body => 2,
+            Self::Code => 3,
+        }
+    }
+
+    /// Parses a tag byte written by [`Field::tag`].
+    const fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(Self::Title),
+            1 => Some(Self::Heading),
+            2 => Some(Self::Body),
+            3 => Some(Self::Code),
+            _ => None,
+        }
+    }
+}
+
+impl SearchIndex {
+    /// Serializes the index to the compact binary format.
+    ///
+    /// Use this for production builds; [`SearchIndex::to_json`] remains
+    /// available when a human-readable index is more useful.
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::default();
+        writer.bytes(MAGIC);
+        writer.byte(VERSION);
+
+        writer.f64(self.avg_dl);
+        writer.usize(self.documents.len());
+        for doc in &self.documents {
+            writer.str(&doc.id);
+            writer.str(&doc.title);
+            writer.str(&doc.url);
+            writer.str(&doc.body);
+            writer.strs(&doc.headings);
+            writer.strs(&doc.code);
+            writer.f64(doc.boost);
+            writer.strs(&doc.tags);
+            writer.usize(doc.facets.len());
+            for (name, values) in &doc.facets {
+                writer.str(name);
+                writer.strs(values);
+            }
+        }
+
+        let mut terms: Vec<&String> = self.index.keys().collect();
+        terms.sort_unstable();
+        writer.usize(terms.len());
+        for term in terms {
+            let mut postings: Vec<&Posting> = self.index[term].iter().collect();
+            postings.sort_by_key(|p| p.doc_idx);
+
+            writer.str(term);
+            writer.usize(postings.len());
+            let mut prev = 0;
+            for posting in postings {
+                writer.usize(posting.doc_idx - prev);
+                writer.varint(u64::from(posting.tf));
+                writer.byte(posting.field.tag());
+                prev = posting.doc_idx;
+            }
+        }
+
+        writer.buf
+    }
+
+    /// Deserializes an index from the compact binary format.
+    pub fn from_bytes(bytes: &[u8]) -> BinaryResult<Self> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(BinaryError::InvalidMagic);
+        }
+        let version = reader.byte()?;
+        if !(1..=VERSION).contains(&version) {
+            return Err(BinaryError::UnsupportedVersion(version));
+        }
+
+        let avg_dl = reader.f64()?;
+        let doc_count = reader.usize()?;
+        let mut documents = Vec::with_capacity(doc_count.min(bytes.len()));
+        for _ in 0..doc_count {
+            let mut doc = SearchDocument {
+                id: reader.string()?,
+                title: reader.string()?,
+                url: reader.string()?,
+                body: reader.string()?,
+                headings: reader.strings()?,
+                code: reader.strings()?,
+                boost: 1.0,
+                tags: Vec::new(),
+                facets: BTreeMap::new(),
+            };
+            if version >= 2 {
+                doc.boost = reader.f64()?;
+            }
+            if version >= 3 {
+                doc.tags = reader.strings()?;
+                for _ in 0..reader.usize()? {
+                    let name = reader.string()?;
+                    doc.facets.insert(name, reader.strings()?);
+                }
+            }
+            documents.push(doc);
+        }
+
+        let term_count = reader.usize()?;
+        let mut index = HashMap::with_capacity(term_count.min(bytes.len()));
+        let mut df = HashMap::with_capacity(term_count.min(bytes.len()));
+        for _ in 0..term_count {
+            let term = reader.string()?;
+            let posting_count = reader.usize()?;
+            let mut postings = Vec::with_capacity(posting_count.min(bytes.len()));
+            let mut doc_idx = 0usize;
+            for _ in 0..posting_count {
+                doc_idx = doc_idx.saturating_add(reader.usize()?);
+                if doc_idx >= doc_count {
+                    return Err(BinaryError::DocumentOutOfRange(doc_idx));
+                }
+                let tf = u32::try_from(reader.varint()?).unwrap_or(u32::MAX);
+                let offset = reader.pos;
+                let tag = reader.byte()?;
+                let field =
+                    Field::from_tag(tag).ok_or(BinaryError::InvalidField { tag, offset })?;
+                postings.push(Posting { doc_idx, tf, field });
+            }
+            df.insert(term.clone(), postings.len());
+            index.insert(term, postings);
+        }
+
+        Ok(Self { documents, index, df, avg_dl, doc_count })
+    }
+}
+
+/// Append-only byte writer.
+#[derive(Default)]
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn byte(&mut self, b: u8) {
+        self.buf.push(b);
+    }
+
+    fn bytes(&mut self, b: &[u8]) {
+        self.buf.extend_from_slice(b);
+    }
+
+    fn varint(&mut self, mut value: u64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.buf.push(byte);
+                break;
+            }
+            self.buf.push(byte | 0x80);
+        }
+    }
+
+    fn usize(&mut self, value: usize) {
+        self.varint(value as u64);
+    }
+
+    fn f64(&mut self, value: f64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn str(&mut self, s: &str) {
+        self.usize(s.len());
+        self.bytes(s.as_bytes());
+    }
+
+    fn strs(&mut self, strings: &[String]) {
+        self.usize(strings.len());
+        for s in strings {
+            self.str(s);
+        }
+    }
+}
+
+/// Cursor over an input buffer.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> BinaryResult<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.bytes.len());
+        let Some(end) = end else {
+            return Err(BinaryError::UnexpectedEof(self.pos));
+        };
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn byte(&mut self) -> BinaryResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> BinaryResult<u64> {
+        let start = self.pos;
+        let mut value = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            if shift >= 64 {
+                return Err(BinaryError::VarintOverflow(start));
+            }
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    fn usize(&mut self) -> BinaryResult<usize> {
+        let start = self.pos;
+        usize::try_from(self.varint()?).map_err(|_| BinaryError::VarintOverflow(start))
+    }
+
+    fn f64(&mut self) -> BinaryResult<f64> {
+        let bytes = self.take(8)?;
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(bytes);
+        Ok(f64::from_le_bytes(buf))
+    }
+
+    fn string(&mut self) -> BinaryResult<String> {
+        let len = self.usize()?;
+        let start = self.pos;
+        let bytes = self.take(len)?;
+        std::str::from_utf8(bytes).map(str::to_string).map_err(|_| BinaryError::InvalidUtf8(start))
+    }
+
+    fn strings(&mut self) -> BinaryResult<Vec<String>> {
+        let count = self.usize()?;
+        let mut strings = Vec::with_capacity(count.min(self.bytes.len()));
+        for _ in 0..count {
+            strings.push(self.string()?);
+        }
+        Ok(strings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::SearchIndexBuilder;
+    use crate::query::SearchOptions;
+
+    fn sample_index() -> SearchIndex {
+        let mut builder = SearchIndexBuilder::new();
+        builder.add_document(SearchDocument {
+            id: "1".to_string(),
+            title: "Getting Started".to_string(),
+            url: "/getting-started".to_string(),
+            body: "Welcome to the documentation. このガイドは日本語です。".to_string(),
+            headings: vec!["Installation".to_string()],
+            code: vec!["npm install ox-content".to_string()],
+            boost: 2.0,
+            tags: vec!["guide".to_string()],
+            facets: BTreeMap::from([("version".to_string(), vec!["v2".to_string()])]),
+        });
+        builder.add_simple("2", "API Reference", "/api", "Complete API documentation.");
+        builder.build()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let index = sample_index();
+        let restored = SearchIndex::from_bytes(&index.to_bytes()).unwrap();
+
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored.doc_count, index.doc_count);
+        assert!((restored.avg_dl - index.avg_dl).abs() < f64::EPSILON);
+        assert_eq!(restored.documents[0].headings, vec!["Installation"]);
+        assert!((restored.documents[0].boost - 2.0).abs() < f64::EPSILON);
+        assert_eq!(restored.documents[0].tags, ["guide"]);
+        assert_eq!(restored.documents[0].facets["version"], ["v2"]);
+        assert!(restored.documents[1].tags.is_empty());
+        assert_eq!(restored.df, index.df);
+
+        let options = SearchOptions::default();
+        let before = index.search("documentation", &options);
+        let after = restored.search("documentation", &options);
+        assert_eq!(before.len(), after.len());
+        assert_eq!(before[0].id, after[0].id);
+    }
+
+    /// Writes a one-document index in the layout of `version`, which must
+    /// be older than [`VERSION`].
+    fn old_index(version: u8) -> Vec<u8> {
+        let mut writer = Writer::default();
+        writer.bytes(MAGIC);
+        writer.byte(version);
+        writer.f64(3.0);
+        writer.usize(1);
+        for field in ["1", "Old", "/old", "old index body"] {
+            writer.str(field);
+        }
+        writer.strs(&["Intro".to_string()]);
+        writer.strs(&[]);
+        if version >= 2 {
+            writer.f64(2.5);
+        }
+        writer.usize(1);
+        writer.str("old");
+        writer.usize(1);
+        writer.usize(0);
+        writer.varint(1);
+        writer.byte(Field::Title.tag());
+        writer.buf
+    }
+
+    #[test]
+    fn test_read_version_1() {
+        let index = SearchIndex::from_bytes(&old_index(1)).unwrap();
+        let doc = &index.documents[0];
+        assert_eq!(doc.title, "Old");
+        assert_eq!(doc.headings, ["Intro"]);
+        assert!((doc.boost - 1.0).abs() < f64::EPSILON);
+        assert!(doc.tags.is_empty() && doc.facets.is_empty());
+        assert_eq!(index.df["old"], 1);
+        assert_eq!(index.search("old", &SearchOptions::default())[0].id, "1");
+    }
+
+    #[test]
+    fn test_read_version_2() {
+        let index = SearchIndex::from_bytes(&old_index(2)).unwrap();
+        let doc = &index.documents[0];
+        assert!((doc.boost - 2.5).abs() < f64::EPSILON);
+        assert!(doc.tags.is_empty() && doc.facets.is_empty());
+        assert_eq!(index.index["old"][0].field, Field::Title);
+    }
+
+    #[test]
+    fn test_smaller_than_json() {
+        let index = sample_index();
+        assert!(index.to_bytes().len() < index.to_json().len());
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let index = sample_index();
+        assert_eq!(index.to_bytes(), index.to_bytes());
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(matches!(SearchIndex::from_bytes(b"nope"), Err(BinaryError::InvalidMagic)));
+        assert!(matches!(
+            SearchIndex::from_bytes(b"OXSI\x09"),
+            Err(BinaryError::UnsupportedVersion(9))
+        ));
+
+        let bytes = sample_index().to_bytes();
+        assert!(matches!(
+            SearchIndex::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(BinaryError::UnexpectedEof(_))
+        ));
+    }
+}
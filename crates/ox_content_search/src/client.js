@@ -0,0 +1,195 @@
+// Ox Content search client. Generated by ox_content_search; scoring constants
+// are filled in from the Rust implementation so results match `SearchIndex::search`.
+const K1 = {{k1}};
+const B = {{b}};
+const FIELD_BOOST = {{field_boosts}};
+const CJK = /[\u4E00-\u9FFF\u3400-\u4DBF\u3040-\u309F\u30A0-\u30FF\uAC00-\uD7AF]/;
+const WORD = /[\p{L}\p{N}_]/u;
+const encoder = new TextEncoder();
+const segmenter = typeof Intl !== 'undefined' && Intl.Segmenter ? new Intl.Segmenter() : null;
+
+// Splits text into grapheme clusters, like the Rust snippets, falling back
+// to code points where `Intl.Segmenter` is unavailable.
+function graphemes(text) {
+  return segmenter ? Array.from(segmenter.segment(text), (s) => s.segment) : Array.from(text);
+}
+
+export function tokenize(text) {
+  const tokens = [];
+  let current = '';
+  for (const ch of text) {
+    if (CJK.test(ch)) {
+      if (current) tokens.push(current.toLowerCase());
+      current = '';
+      tokens.push(ch);
+    } else if (WORD.test(ch)) {
+      current += ch;
+    } else if (current) {
+      tokens.push(current.toLowerCase());
+      current = '';
+    }
+  }
+  if (current) tokens.push(current.toLowerCase());
+  return tokens;
+}
+
+function snippetSource(doc, field, term) {
+  const find = (texts) => (texts || []).find((t) => t.toLowerCase().includes(term));
+  if (field === 'Heading') return find(doc.headings) ?? doc.body;
+  if (field === 'Code') return find(doc.code) ?? doc.body;
+  return doc.body;
+}
+
+function matchesFilters(doc, tags, facets) {
+  const has = (values, wanted) => (values || []).some((v) => v.toLowerCase() === wanted.toLowerCase());
+  return (
+    tags.every((tag) => has(doc.tags, tag)) &&
+    Object.entries(facets).every(([name, wanted]) => wanted.some((v) => has(doc.facets && Object.hasOwn(doc.facets, name) ? doc.facets[name] : [], v)))
+  );
+}
+
+function sectionHeading(doc, field, term) {
+  const matched = field === 'Heading' ? doc.headings.find((h) => h.toLowerCase().includes(term)) : undefined;
+  return matched ?? doc.headings[0] ?? doc.title;
+}
+
+function groupByPage(results) {
+  const groups = [];
+  const positions = new Map();
+  for (const { heading, ...result } of results) {
+    const subMatch = { id: result.id, url: result.url, heading, snippet: result.snippet, score: result.score };
+    const page = result.url.split('#')[0];
+    const group = groups[positions.get(page)];
+    if (group) {
+      group.score += result.score;
+      for (const term of result.matches) if (!group.matches.includes(term)) group.matches.push(term);
+      group.subMatches.push(subMatch);
+    } else {
+      positions.set(page, groups.length);
+      groups.push({ ...result, url: page, subMatches: [subMatch] });
+    }
+  }
+  return groups.sort((a, b) => b.score - a.score || a.idx - b.idx);
+}
+
+function snippet(text, matches, length, context) {
+  if (!text || length <= 0) return '';
+  const chars = graphemes(text);
+  const lower = text.toLowerCase();
+  let first = 0;
+  let matchLen = 0;
+  let firstPos = -1;
+  for (const m of matches) {
+    const pos = lower.indexOf(m);
+    if (pos !== -1 && (firstPos === -1 || pos < firstPos)) {
+      firstPos = pos;
+      first = graphemes(lower.slice(0, pos)).length;
+      matchLen = graphemes(m).length;
+    }
+  }
+  first = Math.min(first, chars.length);
+  const matchEnd = Math.min(first + matchLen, chars.length);
+  const isSpace = (c) => /^\s+$/.test(c);
+
+  let start = Math.max(0, first - Math.min(context, length - 1));
+  let wordStart = start;
+  while (wordStart > 0 && !isSpace(chars[wordStart - 1])) wordStart--;
+  if (wordStart + length >= matchEnd) {
+    start = wordStart;
+  } else {
+    while (start > 0 && start < first && !isSpace(chars[start - 1])) start++;
+  }
+  while (start < first && isSpace(chars[start])) start++;
+
+  let end = Math.min(chars.length, start + length);
+  if (end < chars.length && !isSpace(chars[end])) {
+    for (let i = end - 1; i >= matchEnd; i--) {
+      if (isSpace(chars[i])) {
+        end = i;
+        break;
+      }
+    }
+  }
+  while (end > start && isSpace(chars[end - 1])) end--;
+
+  let out = chars.slice(start, end).join('');
+  if (start > 0) out = '...' + out;
+  if (chars.slice(end).some((c) => !isSpace(c))) out += '...';
+  return out;
+}
+
+export function createSearch(index) {
+  const docLens = new Map();
+  const docLen = (i) => {
+    if (!docLens.has(i)) docLens.set(i, encoder.encode(index.documents[i].body).length);
+    return docLens.get(i);
+  };
+  const terms = Object.keys(index.index);
+
+  return function search(query, { limit = 10, prefix = true, threshold = 0, groupByPage: grouped = false, tags = [], facets = {}, snippetLength = 150, snippetContext } = {}) {
+    const tokens = tokenize(query);
+    if (!tokens.length || !index.documents.length) return [];
+
+    const scores = new Map();
+    tokens.forEach((token, i) => {
+      const isLast = i === tokens.length - 1;
+      const matching =
+        isLast && prefix && token.length >= 2
+          ? terms.filter((t) => t.startsWith(token))
+          : Object.hasOwn(index.index, token)
+            ? [token]
+            : [];
+      for (const term of matching) {
+        const df = index.df[term] || 1;
+        const idf = Math.log1p((index.doc_count - df + 0.5) / (df + 0.5));
+        for (const p of index.index[term]) {
+          const tf = p.tf;
+          const norm = K1 * (1 - B + (B * docLen(p.doc_idx)) / index.avg_dl) + tf;
+          const score = ((idf * (tf * (K1 + 1))) / norm) * FIELD_BOOST[p.field];
+          let entry = scores.get(p.doc_idx);
+          if (!entry) {
+            entry = { score: 0, matches: [], best: [0, p.field, term] };
+            scores.set(p.doc_idx, entry);
+          }
+          entry.score += score;
+          if (!entry.matches.includes(term)) entry.matches.push(term);
+          if (score > entry.best[0]) entry.best = [score, p.field, term];
+        }
+      }
+    });
+
+    const results = [];
+    for (const [idx, entry] of scores) {
+      const doc = index.documents[idx];
+      const score = entry.score * (doc.boost ?? 1);
+      if (score < threshold || !matchesFilters(doc, tags, facets)) continue;
+      const [, field, term] = entry.best;
+      results.push({
+        idx,
+        id: doc.id,
+        title: doc.title,
+        url: doc.url,
+        score,
+        matches: entry.matches,
+        matchedField: field.toLowerCase(),
+        snippet: snippet(
+          snippetSource(doc, field, term),
+          entry.matches,
+          snippetLength,
+          snippetContext ?? Math.floor(snippetLength / 3),
+        ),
+        tags: doc.tags || [],
+        facets: doc.facets || {},
+        heading: grouped ? sectionHeading(doc, field, term) : undefined,
+      });
+    }
+    results.sort((a, b) => b.score - a.score || a.idx - b.idx);
+    return (grouped ? groupByPage(results) : results.map(({ heading, ...result }) => result))
+      .slice(0, limit)
+      .map(({ idx, ...result }) => result);
+  };
+}
+
+export async function loadSearch(url) {
+  return createSearch(await (await fetch(url)).json());
+}
@@ -0,0 +1,109 @@
+//! Standalone client-side search bundle.
+//!
+//! Generates a small ES module implementing the same tokenizer and BM25
+//! scoring as [`SearchIndex::search`], so sites that don't use the SSG
+//! template can search a prebuilt index in the browser.
+
+use serde::{Deserialize, Serialize};
+
+use crate::index::{Field, SearchIndex};
+use crate::query::{B, K1};
+
+/// Client search module template.
+const CLIENT_JS: &str = include_str!("client.js");
+
+/// A client-side search bundle: the search module and the index it loads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchClientBundle {
+    /// ES module exporting `tokenize`, `createSearch(index)` and `loadSearch(url)`.
+    pub js: String,
+    /// Compact JSON index to pass to `createSearch` or serve for `loadSearch`.
+    pub index: String,
+}
+
+/// Generates a client search bundle for a JSON-serialized index.
+///
+/// The scoring constants (BM25 `k1`/`b` and per-field boosts) are taken
+/// from this crate, and per-document boosts are read from the index, so
+/// client results are ranked the same way as on the server.
+pub fn generate_search_client(index_json: &str) -> Result<SearchClientBundle, serde_json::Error> {
+    let index = SearchIndex::from_json(index_json)?;
+    Ok(SearchClientBundle { js: generate_search_client_js(), index: index.to_json_compact() })
+}
+
+/// Generates the client search module without an index.
+#[must_use]
+pub fn generate_search_client_js() -> String {
+    let field_boosts = [Field::Title, Field::Heading, Field::Body, Field::Code]
+        .iter()
+        .map(|field| format!("{:?}: {:?}", field, field.boost()))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    CLIENT_JS
+        .replace("{{k1}}", &format!("{K1:?}"))
+        .replace("{{b}}", &format!("{B:?}"))
+        .replace("{{field_boosts}}", &format!("{{ {field_boosts} }}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::SearchIndexBuilder;
+
+    #[test]
+    fn test_generate_search_client() {
+        let mut builder = SearchIndexBuilder::new();
+        builder.add_simple("1", "Getting Started", "/getting-started", "Welcome!");
+        let json = builder.build().to_json();
+
+        let bundle = generate_search_client(&json).unwrap();
+        assert!(bundle.js.contains("const K1 = 1.2;"));
+        assert!(bundle.js.contains("const B = 0.75;"));
+        assert!(bundle.js.contains("{ Title: 10.0, Heading: 5.0, Body: 1.0, Code: 0.5 }"));
+        assert!(!bundle.js.contains("{{"));
+        assert_eq!(SearchIndex::from_json(&bundle.index).unwrap().len(), 1);
+    }
+
+    /// Runs `script` after the generated client with `index` bound to the
+    /// index, returning its output, or `None` when Node.js isn't installed.
+    fn run_client(index_json: &str, script: &str) -> Option<String> {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+
+        let bundle = generate_search_client(index_json).unwrap();
+        let mut child = Command::new("node")
+            .arg("--input-type=module")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .ok()?;
+        let source = format!("{}\nconst index = {};\n{script}", bundle.js, bundle.index);
+        child.stdin.take().unwrap().write_all(source.as_bytes()).unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        Some(String::from_utf8(output.stdout).unwrap())
+    }
+
+    #[test]
+    fn test_client_ignores_prototype_keys() {
+        let mut builder = SearchIndexBuilder::new();
+        builder.add_simple("1", "Hello", "/hello", "Hello world");
+        let json = builder.build().to_json();
+        let script = r"
+            const search = createSearch(index);
+            for (const query of ['constructor hello', 'hello constructor', '__proto__ hello']) {
+              console.log(search(query, { prefix: false }).map((r) => r.id).join(','));
+            }
+            console.log(search('hello', { facets: { constructor: ['x'] } }).length);
+        ";
+        let Some(output) = run_client(&json, script) else { return };
+        assert_eq!(output, "1\n1\n1\n0\n");
+    }
+
+    #[test]
+    fn test_generate_search_client_invalid_index() {
+        assert!(generate_search_client("not json").is_err());
+    }
+}
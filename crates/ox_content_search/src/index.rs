@@ -1,6 +1,6 @@
 //! Search index data structures.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde::{Deserialize, Serialize};
 
@@ -22,6 +22,25 @@ pub struct SearchDocument {
     /// Code snippets (optional).
     #[serde(default)]
     pub code: Vec<String>,
+    /// Ranking multiplier applied to this document's score (defaults to 1.0).
+    #[serde(default = "default_boost")]
+    pub boost: f64,
+    /// Tags for filtering with [`SearchOptions::tags`], usually from the
+    /// `tags` frontmatter key. Tags are not searched as text.
+    ///
+    /// [`SearchOptions::tags`]: crate::SearchOptions::tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Facet values by facet name, such as `{"version": ["v2"]}`, for
+    /// filtering with [`SearchOptions::facets`].
+    ///
+    /// [`SearchOptions::facets`]: crate::SearchOptions::facets
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub facets: BTreeMap<String, Vec<String>>,
+}
+
+fn default_boost() -> f64 {
+    1.0
 }
 
 /// Posting list entry for inverted index.
@@ -49,6 +68,17 @@ pub enum Field {
 }
 
 impl Field {
+    /// Returns the field name as a lowercase string.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Title => "title",
+            Self::Heading => "heading",
+            Self::Body => "body",
+            Self::Code => "code",
+        }
+    }
+
     /// Returns the boost factor for this field.
     #[must_use]
     pub fn boost(self) -> f64 {
@@ -62,6 +92,11 @@ impl Field {
 }
 
 /// The main search index structure.
+///
+/// A built index is immutable: [`SearchIndex::search`] only takes `&self` and
+/// keeps all per-query state local to the call. The index is `Send + Sync`,
+/// so a single instance can be wrapped in an `Arc` and queried from many
+/// threads at once without a lock or per-request deserialization.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchIndex {
     /// All indexed documents.
@@ -105,6 +140,62 @@ impl SearchIndex {
     pub fn is_empty(&self) -> bool {
         self.documents.is_empty()
     }
+
+    /// Returns the size of the index and its `top` most frequent terms.
+    ///
+    /// Terms are ranked by the number of documents containing them, then by
+    /// total occurrences, so a term found in every document, a likely stop
+    /// word, comes first.
+    #[must_use]
+    pub fn stats(&self, top: usize) -> IndexStats {
+        let mut terms: Vec<TermStats> = self
+            .index
+            .iter()
+            .map(|(term, postings)| TermStats {
+                term: term.clone(),
+                documents: postings.len(),
+                occurrences: postings.iter().map(|posting| u64::from(posting.tf)).sum(),
+            })
+            .collect();
+        terms.sort_by(|a, b| {
+            b.documents
+                .cmp(&a.documents)
+                .then(b.occurrences.cmp(&a.occurrences))
+                .then_with(|| a.term.cmp(&b.term))
+        });
+        terms.truncate(top);
+
+        IndexStats {
+            documents: self.documents.len(),
+            terms: self.index.len(),
+            avg_doc_length: self.avg_dl,
+            top_terms: terms,
+        }
+    }
+}
+
+/// Summary of what a [`SearchIndex`] contains, from [`SearchIndex::stats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexStats {
+    /// Number of documents.
+    pub documents: usize,
+    /// Number of distinct terms (the vocabulary size).
+    pub terms: usize,
+    /// Average number of body tokens per document, as used for BM25.
+    pub avg_doc_length: f64,
+    /// The most frequent terms, most frequent first.
+    pub top_terms: Vec<TermStats>,
+}
+
+/// How often a term occurs in a [`SearchIndex`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TermStats {
+    /// The indexed term.
+    pub term: String,
+    /// Number of documents containing the term.
+    pub documents: usize,
+    /// Occurrences across all documents and fields.
+    pub occurrences: u64,
 }
 
 /// Builder for constructing a search index.
@@ -135,6 +226,9 @@ impl SearchIndexBuilder {
             body: body.to_string(),
             headings: Vec::new(),
             code: Vec::new(),
+            boost: 1.0,
+            tags: Vec::new(),
+            facets: BTreeMap::new(),
         });
         self
     }
@@ -237,4 +331,34 @@ mod tests {
         assert_eq!(restored.len(), 1);
         assert_eq!(restored.documents[0].title, "Test");
     }
+
+    #[test]
+    fn test_stats() {
+        let mut builder = SearchIndexBuilder::new();
+        builder.add_simple("1", "Install", "/install", "Run cargo install, then cargo build");
+        builder.add_simple("2", "Usage", "/usage", "Call cargo test");
+        let index = builder.build();
+
+        let stats = index.stats(2);
+        assert_eq!(stats.documents, 2);
+        assert_eq!(stats.terms, index.index.len());
+        assert!((stats.avg_doc_length - index.avg_dl).abs() < f64::EPSILON);
+        assert_eq!(
+            stats.top_terms[0],
+            TermStats { term: "cargo".to_string(), documents: 2, occurrences: 3 }
+        );
+        assert_eq!(stats.top_terms.len(), 2);
+        assert_eq!(stats.top_terms[1].documents, 1);
+
+        let empty = SearchIndexBuilder::new().build().stats(10);
+        assert_eq!((empty.documents, empty.terms), (0, 0));
+        assert!(empty.top_terms.is_empty());
+    }
+
+    #[test]
+    fn test_deserialize_missing_boost() {
+        let json = r#"{"id":"1","title":"T","url":"/t","body":"b","headings":[]}"#;
+        let doc: SearchDocument = serde_json::from_str(json).unwrap();
+        assert!((doc.boost - 1.0).abs() < f64::EPSILON);
+    }
 }
@@ -1,12 +1,26 @@
 //! Document indexer using the Visitor pattern.
 
-use ox_content_ast::{walk_document, CodeBlock, Document, Heading, InlineCode, Node, Text, Visit};
+use ox_content_ast::{
+    walk_document, Abbreviation, CodeBlock, Document, Heading, InlineCode, Node, Text, Visit,
+};
 
 use crate::index::SearchDocument;
 
+/// Options controlling how document content is split into search fields.
+#[derive(Debug, Clone, Default)]
+pub struct IndexerOptions {
+    /// Also append inline code and code blocks to the body text.
+    ///
+    /// By default code is only indexed under the `code` field so that
+    /// syntax tokens don't flood prose matches.
+    pub include_code_in_body: bool,
+}
+
 /// Extracts searchable content from a Markdown AST using the Visitor pattern.
 #[derive(Debug, Default)]
 pub struct DocumentIndexer {
+    /// Indexer options.
+    options: IndexerOptions,
     /// Collected title (first h1 heading).
     title: Option<String>,
     /// All headings in the document.
@@ -28,6 +42,12 @@ impl DocumentIndexer {
         Self::default()
     }
 
+    /// Creates a new document indexer with the specified options.
+    #[must_use]
+    pub fn with_options(options: IndexerOptions) -> Self {
+        Self { options, ..Self::default() }
+    }
+
     /// Extracts searchable content from a document.
     pub fn extract<'a>(&mut self, doc: &Document<'a>) {
         walk_document(self, doc);
@@ -38,18 +58,24 @@ impl DocumentIndexer {
     pub fn into_search_document(self, id: String, url: String) -> SearchDocument {
         SearchDocument {
             id,
-            title: self.title.unwrap_or_default(),
+            title: self.title.or_else(|| self.headings.first().cloned()).unwrap_or_default(),
             url,
             body: self.body,
             headings: self.headings,
             code: self.code,
+            boost: 1.0,
+            tags: Vec::new(),
+            facets: std::collections::BTreeMap::new(),
         }
     }
 
     /// Returns the extracted title.
+    ///
+    /// This is the first H1 heading, falling back to the first heading of
+    /// any level.
     #[must_use]
     pub fn title(&self) -> Option<&str> {
-        self.title.as_deref()
+        self.title.as_deref().or_else(|| self.headings.first().map(String::as_str))
     }
 
     /// Returns the extracted body text.
@@ -71,6 +97,51 @@ impl DocumentIndexer {
     }
 }
 
+/// Derives a human-readable title from a URL or file path.
+///
+/// Uses the last path segment (or the parent directory for `index` pages),
+/// strips any file extension, and title-cases the words separated by `-` or
+/// `_`. For example, `/guide/getting-started.html` becomes "Getting Started".
+#[must_use]
+pub fn title_from_url(url: &str) -> String {
+    let path = url.split(['#', '?']).next().unwrap_or_default();
+    let mut segments = path
+        .split(['/', '\\'])
+        .filter(|s| !s.is_empty())
+        .map(|s| s.rsplit_once('.').map_or(s, |(stem, _)| stem))
+        .filter(|s| !s.is_empty())
+        .rev();
+    let slug = match segments.next() {
+        Some("index") => segments.next().unwrap_or_default(),
+        Some(segment) => segment,
+        None => "",
+    };
+
+    slug.split(['-', '_'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect::<String>()
+            })
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl DocumentIndexer {
+    /// Records a code fragment, also adding it to the body if configured.
+    fn push_code(&mut self, code: &str) {
+        self.code.push(code.to_string());
+        if self.options.include_code_in_body {
+            if !self.body.is_empty() {
+                self.body.push(' ');
+            }
+            self.body.push_str(code);
+        }
+    }
+}
+
 impl<'a> Visit<'a> for DocumentIndexer {
     fn visit_heading(&mut self, heading: &Heading<'a>) {
         self.in_heading = true;
@@ -104,24 +175,26 @@ impl<'a> Visit<'a> for DocumentIndexer {
         }
     }
 
+    fn visit_abbreviation(&mut self, abbreviation: &Abbreviation<'a>) {
+        self.visit_text(&Text { value: abbreviation.value, span: abbreviation.span });
+    }
+
     fn visit_inline_code(&mut self, inline_code: &InlineCode<'a>) {
         if self.in_heading {
             self.current_heading.push_str(inline_code.value);
         } else {
-            if !self.body.is_empty() {
-                self.body.push(' ');
-            }
-            self.body.push_str(inline_code.value);
+            self.push_code(inline_code.value);
         }
     }
 
     fn visit_code_block(&mut self, code_block: &CodeBlock<'a>) {
-        self.code.push(code_block.value.to_string());
+        self.push_code(code_block.value);
     }
 
     fn visit_node(&mut self, node: &Node<'a>) {
         match node {
             Node::Text(n) => self.visit_text(n),
+            Node::Abbreviation(n) => self.visit_abbreviation(n),
             Node::Heading(n) => self.visit_heading(n),
             Node::InlineCode(n) => self.visit_inline_code(n),
             Node::CodeBlock(n) => self.visit_code_block(n),
@@ -181,6 +254,11 @@ impl<'a> Visit<'a> for DocumentIndexer {
                     self.visit_node(child);
                 }
             }
+            Node::InlineFootnote(n) => {
+                for child in &n.children {
+                    self.visit_node(child);
+                }
+            }
             _ => {}
         }
     }
@@ -192,6 +270,9 @@ mod tests {
     use ox_content_allocator::Allocator;
     use ox_content_ast::Span;
 
+    use crate::index::{Field, SearchIndexBuilder};
+    use crate::query::SearchOptions;
+
     #[test]
     fn test_extract_title() {
         let allocator = Allocator::new();
@@ -204,6 +285,8 @@ mod tests {
         children.push(Node::Heading(Heading {
             depth: 1,
             children: heading_children,
+            id: None,
+            classes: ox_content_allocator::Vec::new_in(&allocator),
             span: Span::new(0, 12),
         }));
 
@@ -214,4 +297,58 @@ mod tests {
 
         assert_eq!(indexer.title(), Some("Test Title"));
     }
+
+    #[test]
+    fn test_title_falls_back_to_any_heading() {
+        let doc = index_source("## Overview\n\n### Details\n", IndexerOptions::default());
+        assert_eq!(doc.title, "Overview");
+
+        let doc = index_source("## Overview\n\n# Real Title\n", IndexerOptions::default());
+        assert_eq!(doc.title, "Real Title");
+    }
+
+    #[test]
+    fn test_title_from_url() {
+        assert_eq!(title_from_url("/guide/getting-started.html"), "Getting Started");
+        assert_eq!(title_from_url("/api/render_options/"), "Render Options");
+        assert_eq!(title_from_url("/docs/plugins/index.md#usage"), "Plugins");
+        assert_eq!(title_from_url("/"), "");
+    }
+
+    fn index_source(source: &str, options: IndexerOptions) -> SearchDocument {
+        let allocator = Allocator::new();
+        let doc = ox_content_parser::Parser::new(&allocator, source).parse().unwrap();
+        let mut indexer = DocumentIndexer::with_options(options);
+        indexer.extract(&doc);
+        indexer.into_search_document("1".to_string(), "/page".to_string())
+    }
+
+    #[test]
+    fn test_code_excluded_from_body() {
+        let source = "# Setup\n\nCall `frobnicate` once.\n\n```rust\nfn reticulate() {}\n```\n";
+        let doc = index_source(source, IndexerOptions::default());
+
+        assert!(!doc.body.contains("frobnicate"));
+        assert!(!doc.body.contains("reticulate"));
+        assert_eq!(doc.code, vec!["frobnicate", "fn reticulate() {}\n"]);
+
+        let mut builder = SearchIndexBuilder::new();
+        builder.add_document(doc);
+        let index = builder.build();
+        let options = SearchOptions { prefix: false, ..SearchOptions::default() };
+        let results = index.search("reticulate", &options);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].matched_field, Field::Code);
+        assert!(index.search("once", &options)[0].matched_field == Field::Body);
+    }
+
+    #[test]
+    fn test_code_included_in_body() {
+        let source = "Call `frobnicate` once.\n\n```rust\nfn reticulate() {}\n```\n";
+        let doc = index_source(source, IndexerOptions { include_code_in_body: true });
+
+        assert!(doc.body.contains("frobnicate"));
+        assert!(doc.body.contains("reticulate"));
+        assert_eq!(doc.code.len(), 2);
+    }
 }
@@ -8,7 +8,9 @@
 //! - TF-IDF based scoring for relevance ranking
 //! - Multi-field search (title, body, headings, code)
 //! - Prefix matching for autocomplete
-//! - Serializable index for build-time generation
+//! - Standalone browser search client generated from the same scoring code
+//! - Serializable index for build-time generation (JSON or compact binary)
+//! - Immutable, `Send + Sync` index that can be shared across threads
 //!
 //! # Example
 //!
@@ -23,15 +25,26 @@
 //! // Serialize for client-side use
 //! let json = index.to_json();
 //!
+//! // Or use the compact binary format in production
+//! let bytes = index.to_bytes();
+//! let index = SearchIndex::from_bytes(&bytes)?;
+//!
 //! // Search at runtime
 //! let results = index.search("getting started", &SearchOptions::default());
 //! ```
 
+mod binary;
+mod client;
 mod index;
 mod indexer;
 mod query;
+mod text;
 mod tokenizer;
 
-pub use index::{Field, Posting, SearchDocument, SearchIndex, SearchIndexBuilder};
-pub use indexer::DocumentIndexer;
-pub use query::{SearchOptions, SearchResult};
+pub use binary::{BinaryError, BinaryResult};
+pub use client::{generate_search_client, generate_search_client_js, SearchClientBundle};
+pub use index::{
+    Field, IndexStats, Posting, SearchDocument, SearchIndex, SearchIndexBuilder, TermStats,
+};
+pub use indexer::{title_from_url, DocumentIndexer, IndexerOptions};
+pub use query::{SearchOptions, SearchResult, SubMatch};
@@ -1,10 +1,11 @@
 //! Search query engine with BM25 scoring.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde::{Deserialize, Serialize};
 
-use crate::index::SearchIndex;
+use crate::index::{Field, SearchDocument, SearchIndex};
+use crate::text::{graphemes, is_whitespace};
 use crate::tokenizer::tokenize_query;
 
 /// Search options.
@@ -22,6 +23,28 @@ pub struct SearchOptions {
     /// Minimum score threshold (0.0 - 1.0).
     #[serde(default)]
     pub threshold: f64,
+    /// Group results whose URLs differ only in the `#fragment` into one
+    /// result per page, with each section listed in `sub_matches`.
+    #[serde(default)]
+    pub group_by_page: bool,
+    /// Only return documents that have all of these tags, compared
+    /// case-insensitively.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Only return documents that, for every facet listed, have at least
+    /// one of the listed values, compared case-insensitively. For example
+    /// `{"version": ["v1", "v2"], "lang": ["en"]}` matches English
+    /// documents of either version.
+    #[serde(default)]
+    pub facets: BTreeMap<String, Vec<String>>,
+    /// Maximum snippet length in characters, not counting the `...` added
+    /// where text was cut. `0` leaves snippets empty.
+    #[serde(default = "default_snippet_length")]
+    pub snippet_length: usize,
+    /// Characters of context shown before the first match, or a third of
+    /// `snippet_length` when `None`.
+    #[serde(default)]
+    pub snippet_context: Option<usize>,
 }
 
 fn default_limit() -> usize {
@@ -32,9 +55,40 @@ fn default_prefix() -> bool {
     true
 }
 
+const fn default_snippet_length() -> usize {
+    150
+}
+
+impl SearchOptions {
+    /// Returns whether `doc` passes the tag and facet filters.
+    #[must_use]
+    pub fn matches_filters(&self, doc: &SearchDocument) -> bool {
+        let has = |values: &[String], wanted: &str| {
+            let wanted = wanted.to_lowercase();
+            values.iter().any(|value| value.to_lowercase() == wanted)
+        };
+        self.tags.iter().all(|tag| has(&doc.tags, tag))
+            && self.facets.iter().all(|(name, wanted)| {
+                doc.facets
+                    .get(name)
+                    .is_some_and(|values| wanted.iter().any(|value| has(values, value)))
+            })
+    }
+}
+
 impl Default for SearchOptions {
     fn default() -> Self {
-        Self { limit: 10, prefix: true, fuzzy: false, threshold: 0.0 }
+        Self {
+            limit: 10,
+            prefix: true,
+            fuzzy: false,
+            threshold: 0.0,
+            group_by_page: false,
+            tags: Vec::new(),
+            facets: BTreeMap::new(),
+            snippet_length: default_snippet_length(),
+            snippet_context: None,
+        }
     }
 }
 
@@ -51,16 +105,66 @@ pub struct SearchResult {
     pub score: f64,
     /// Matched terms.
     pub matches: Vec<String>,
-    /// Content snippet with highlights.
+    /// Field that produced the highest-scoring match.
+    pub matched_field: Field,
+    /// Content snippet with highlights, taken from `matched_field`.
+    pub snippet: String,
+    /// Document tags.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Document facet values by facet name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub facets: BTreeMap<String, Vec<String>>,
+    /// Matching sections of the page, best first, when results are grouped
+    /// with [`SearchOptions::group_by_page`].
+    ///
+    /// A grouped result takes its ID, title, snippet and matched field from
+    /// the best section, its URL without the fragment, and the sum of the
+    /// section scores.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sub_matches: Vec<SubMatch>,
+}
+
+/// A matching section within a grouped search result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubMatch {
+    /// Document ID of the section.
+    pub id: String,
+    /// Section URL, including its fragment.
+    pub url: String,
+    /// Heading the match was found under: the matched heading, or else the
+    /// section's first heading or title.
+    pub heading: String,
+    /// Content snippet for the match.
     pub snippet: String,
+    /// Relevance score of the section.
+    pub score: f64,
+}
+
+/// Accumulated score for a single document during a search.
+struct DocScore {
+    /// Total score.
+    score: f64,
+    /// Matched terms.
+    matches: Vec<String>,
+    /// Score, field, and term of the best single match.
+    best: (f64, Field, String),
 }
 
 /// BM25 parameters.
-const K1: f64 = 1.2;
-const B: f64 = 0.75;
+pub const K1: f64 = 1.2;
+pub const B: f64 = 0.75;
+
+// Searching is read-only, so a shared index must stay usable across threads.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<SearchIndex>();
+};
 
 impl SearchIndex {
     /// Searches the index with the given query.
+    ///
+    /// Safe to call concurrently on a shared index; no state is mutated.
     #[must_use]
     pub fn search(&self, query: &str, options: &SearchOptions) -> Vec<SearchResult> {
         if query.is_empty() || self.is_empty() {
@@ -72,8 +176,9 @@ impl SearchIndex {
             return Vec::new();
         }
 
-        // Calculate scores for each document
-        let mut doc_scores: HashMap<usize, (f64, Vec<String>)> = HashMap::new();
+        // Calculate scores for each document, kept in document order so that
+        // equal scores rank deterministically
+        let mut doc_scores: BTreeMap<usize, DocScore> = BTreeMap::new();
 
         for (i, token) in tokens.iter().enumerate() {
             let is_last = i == tokens.len() - 1;
@@ -98,10 +203,17 @@ impl SearchIndex {
                                 / K1.mul_add(1.0 - B + B * doc_len / self.avg_dl, tf))
                             * posting.field.boost();
 
-                        let entry = doc_scores.entry(posting.doc_idx).or_insert((0.0, Vec::new()));
-                        entry.0 += score;
-                        if !entry.1.contains(&term) {
-                            entry.1.push(term.clone());
+                        let entry = doc_scores.entry(posting.doc_idx).or_insert_with(|| DocScore {
+                            score: 0.0,
+                            matches: Vec::new(),
+                            best: (0.0, posting.field, term.clone()),
+                        });
+                        entry.score += score;
+                        if !entry.matches.contains(&term) {
+                            entry.matches.push(term.clone());
+                        }
+                        if score > entry.best.0 {
+                            entry.best = (score, posting.field, term.clone());
                         }
                     }
                 }
@@ -109,25 +221,51 @@ impl SearchIndex {
         }
 
         // Convert to results and sort by score
-        let mut results: Vec<SearchResult> = doc_scores
+        let mut results: Vec<(Option<String>, SearchResult)> = doc_scores
             .into_iter()
-            .filter(|(_, (score, _))| *score >= options.threshold)
-            .map(|(doc_idx, (score, matches))| {
+            .filter(|(doc_idx, entry)| {
+                let doc = &self.documents[*doc_idx];
+                entry.score * doc.boost >= options.threshold && options.matches_filters(doc)
+            })
+            .map(|(doc_idx, entry)| {
+                let (_, matched_field, best_term) = entry.best;
+                let source = self.snippet_source(doc_idx, matched_field, &best_term);
+                let snippet = generate_snippet(
+                    source,
+                    &entry.matches,
+                    options.snippet_length,
+                    options.snippet_context.unwrap_or(options.snippet_length / 3),
+                );
+                let heading = options
+                    .group_by_page
+                    .then(|| self.section_heading(doc_idx, matched_field, &best_term));
                 let doc = &self.documents[doc_idx];
-                let snippet = self.generate_snippet(&doc.body, &matches, 150);
-                SearchResult {
+                let result = SearchResult {
                     id: doc.id.clone(),
                     title: doc.title.clone(),
                     url: doc.url.clone(),
-                    score,
-                    matches,
+                    score: entry.score * doc.boost,
+                    matches: entry.matches,
+                    matched_field,
                     snippet,
-                }
+                    tags: doc.tags.clone(),
+                    facets: doc.facets.clone(),
+                    sub_matches: Vec::new(),
+                };
+                (heading, result)
             })
             .collect();
 
-        // Sort by score descending
-        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        // Sort by score descending (stable, so ties keep document order)
+        results.sort_by(|(_, a), (_, b)| {
+            b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let mut results = if options.group_by_page {
+            group_by_page(results)
+        } else {
+            results.into_iter().map(|(_, result)| result).collect()
+        };
 
         // Limit results
         results.truncate(options.limit);
@@ -154,59 +292,133 @@ impl SearchIndex {
         }
     }
 
-    /// Generates a snippet of text around matched terms.
-    #[allow(clippy::unused_self)]
-    fn generate_snippet(&self, body: &str, matches: &[String], max_len: usize) -> String {
-        if body.is_empty() {
-            return String::new();
-        }
-
-        let body_lower = body.to_lowercase();
-
-        // Find the first match position
-        let mut first_match_pos = None;
-        for term in matches {
-            if let Some(pos) = body_lower.find(term) {
-                if first_match_pos.is_none() || pos < first_match_pos.unwrap() {
-                    first_match_pos = Some(pos);
-                }
-            }
+    /// Returns the text a snippet should be generated from for a match in `field`.
+    ///
+    /// Heading and code matches use the heading or code block containing the
+    /// term. Title matches fall back to the body, since the title is already
+    /// shown alongside the result.
+    fn snippet_source(&self, doc_idx: usize, field: Field, term: &str) -> &str {
+        let doc = &self.documents[doc_idx];
+        match field {
+            Field::Heading => find_containing(&doc.headings, term).unwrap_or(&doc.body),
+            Field::Code => find_containing(&doc.code, term).unwrap_or(&doc.body),
+            Field::Title | Field::Body => &doc.body,
         }
+    }
 
-        let start_pos = first_match_pos.unwrap_or(0);
-
-        // Calculate snippet window
-        let chars: Vec<char> = body.chars().collect();
-        let total_chars = chars.len();
+    /// Returns the heading a match in `field` falls under, for sub-matches.
+    fn section_heading(&self, doc_idx: usize, field: Field, term: &str) -> String {
+        let doc = &self.documents[doc_idx];
+        let matched = match field {
+            Field::Heading => find_containing(&doc.headings, term),
+            Field::Title | Field::Body | Field::Code => None,
+        };
+        matched
+            .or_else(|| doc.headings.first().map(String::as_str))
+            .unwrap_or(&doc.title)
+            .to_string()
+    }
+}
 
-        // Find a good start position (at word boundary, before match)
-        let context_before = max_len / 3;
-        let mut snippet_start = start_pos.saturating_sub(context_before);
+/// Generates a snippet of at most `length` characters around the first
+/// matched term, starting up to `context` characters before it.
+///
+/// Lengths count grapheme clusters, so emoji sequences and accented
+/// letters are never split. The window is moved to the start of a word
+/// and cut after a whole word, unless a single word fills it, and `...`
+/// marks the text left out.
+fn generate_snippet(body: &str, matches: &[String], length: usize, context: usize) -> String {
+    if body.is_empty() || length == 0 {
+        return String::new();
+    }
 
-        // Adjust to word boundary
-        while snippet_start > 0 && !chars[snippet_start].is_whitespace() {
-            snippet_start -= 1;
-        }
-        if snippet_start > 0 {
-            snippet_start += 1; // Skip the whitespace
+    let body_lower = body.to_lowercase();
+    let (first_match, match_len) = matches
+        .iter()
+        .filter_map(|term| body_lower.find(term.as_str()).map(|pos| (pos, term)))
+        .min()
+        .map_or((0, 0), |(pos, term)| (graphemes(&body_lower[..pos]).len(), graphemes(term).len()));
+
+    let chars = graphemes(body);
+    let total_chars = chars.len();
+    let first_match = first_match.min(total_chars);
+    let match_end = (first_match + match_len).min(total_chars);
+
+    // Start at the beginning of the word the context reaches into, or of
+    // the next word if going back would push the match out of the window
+    let mut start = first_match.saturating_sub(context.min(length - 1));
+    let mut word_start = start;
+    while word_start > 0 && !is_whitespace(chars[word_start - 1]) {
+        word_start -= 1;
+    }
+    if word_start + length >= match_end {
+        start = word_start;
+    } else {
+        while start > 0 && start < first_match && !is_whitespace(chars[start - 1]) {
+            start += 1;
         }
+    }
+    while start < first_match && is_whitespace(chars[start]) {
+        start += 1;
+    }
 
-        // Calculate end position
-        let snippet_end = (snippet_start + max_len).min(total_chars);
+    // End after the last whole word that fits, keeping a cut-off match
+    let mut end = (start + length).min(total_chars);
+    if end < total_chars && !is_whitespace(chars[end]) {
+        if let Some(space) = (match_end..end).rev().find(|&i| is_whitespace(chars[i])) {
+            end = space;
+        }
+    }
+    while end > start && is_whitespace(chars[end - 1]) {
+        end -= 1;
+    }
 
-        // Build snippet
-        let mut snippet: String = chars[snippet_start..snippet_end].iter().collect();
+    let mut snippet = chars[start..end].concat();
+    if start > 0 {
+        snippet = format!("...{snippet}");
+    }
+    if chars[end..].iter().any(|c| !is_whitespace(c)) {
+        snippet.push_str("...");
+    }
+    snippet
+}
 
-        // Add ellipsis if needed
-        if snippet_start > 0 {
-            snippet = format!("...{snippet}");
-        }
-        if snippet_end < total_chars {
-            snippet = format!("{snippet}...");
+/// Merges results sorted by score into one result per page URL, keeping the
+/// best-first order of each page's sections.
+fn group_by_page(results: Vec<(Option<String>, SearchResult)>) -> Vec<SearchResult> {
+    let mut groups: Vec<SearchResult> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for (heading, result) in results {
+        let sub_match = SubMatch {
+            id: result.id.clone(),
+            url: result.url.clone(),
+            heading: heading.unwrap_or_default(),
+            snippet: result.snippet.clone(),
+            score: result.score,
+        };
+        let page = result.url.split_once('#').map_or(result.url.as_str(), |(page, _)| page);
+        if let Some(&i) = positions.get(page) {
+            let group = &mut groups[i];
+            group.score += result.score;
+            for term in result.matches {
+                if !group.matches.contains(&term) {
+                    group.matches.push(term);
+                }
+            }
+            group.sub_matches.push(sub_match);
+        } else {
+            let page = page.to_string();
+            positions.insert(page.clone(), groups.len());
+            groups.push(SearchResult { url: page, sub_matches: vec![sub_match], ..result });
         }
-
-        snippet
     }
+    groups.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    groups
+}
+
+/// Returns the first text that contains `term` (case-insensitively).
+fn find_containing<'a>(texts: &'a [String], term: &str) -> Option<&'a str> {
+    texts.iter().find(|t| t.to_lowercase().contains(term)).map(String::as_str)
 }
 
 #[cfg(test)]
@@ -248,6 +460,39 @@ mod tests {
         assert_eq!(results[0].id, "2");
     }
 
+    #[test]
+    fn test_search_concurrent() {
+        let mut builder = SearchIndexBuilder::new();
+        for i in 0..50 {
+            builder.add_simple(
+                &i.to_string(),
+                &format!("Page {i}"),
+                &format!("/page-{i}"),
+                &format!("Content for page number {i} about rendering and parsing."),
+            );
+        }
+        let index = std::sync::Arc::new(builder.build());
+        let options = SearchOptions::default();
+        let expected = index.search("rendering", &options);
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    let index = std::sync::Arc::clone(&index);
+                    let options = options.clone();
+                    scope.spawn(move || {
+                        (0..20).map(|_| index.search("rendering", &options)).next_back().unwrap()
+                    })
+                })
+                .collect();
+            for handle in handles {
+                let results = handle.join().unwrap();
+                assert_eq!(results.len(), expected.len());
+                assert!(results.iter().zip(&expected).all(|(a, b)| a.id == b.id));
+            }
+        });
+    }
+
     #[test]
     fn test_search_prefix() {
         let mut builder = SearchIndexBuilder::new();
@@ -287,4 +532,192 @@ mod tests {
         let results = index.search("test", &options);
         assert_eq!(results.len(), 5);
     }
+
+    #[test]
+    fn test_search_heading_snippet() {
+        let mut builder = SearchIndexBuilder::new();
+        builder.add_document(crate::index::SearchDocument {
+            id: "1".to_string(),
+            title: "API".to_string(),
+            url: "/api".to_string(),
+            body: "This page lists every function exported by the package.".to_string(),
+            headings: vec!["Overview".to_string(), "createRenderer options".to_string()],
+            code: Vec::new(),
+            boost: 1.0,
+            tags: Vec::new(),
+            facets: BTreeMap::new(),
+        });
+
+        let index = builder.build();
+        let results = index.search("createrenderer", &SearchOptions::default());
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].matched_field, Field::Heading);
+        assert_eq!(results[0].snippet, "createRenderer options");
+
+        let results = index.search("exported", &SearchOptions::default());
+        assert_eq!(results[0].matched_field, Field::Body);
+        assert!(results[0].snippet.contains("exported"));
+    }
+
+    #[test]
+    fn test_generate_snippet() {
+        let body = "The quick brown fox jumps over the lazy dog near the riverbank";
+        let lazy = ["lazy".to_string()];
+        assert_eq!(generate_snippet(body, &lazy, 20, 6), "...over the lazy dog...");
+        assert_eq!(generate_snippet(body, &lazy, 20, 0), "...lazy dog near the...");
+        assert_eq!(generate_snippet(body, &lazy, 200, 50), body);
+        assert_eq!(generate_snippet(body, &lazy, 0, 0), "");
+        assert_eq!(generate_snippet(body, &[], 12, 4), "The quick...");
+
+        // A word longer than the window is cut, keeping the match
+        let word = ["super".to_string()];
+        assert_eq!(generate_snippet("Supercalifragilistic words", &word, 5, 0), "Super...");
+        assert_eq!(generate_snippet("Supercalifragilistic", &word, 3, 2), "Sup...");
+        let ship = ["船".to_string()];
+        assert_eq!(generate_snippet("très grand 船 ici", &ship, 6, 2), "...船 ici");
+        assert_eq!(generate_snippet("très grand 船 ici", &ship, 8, 2), "...grand 船...");
+
+        // Emoji, flags and combining accents are never split
+        let family = "👨‍👩‍👧👨‍👩‍👧👨‍👩‍👧";
+        assert_eq!(generate_snippet(family, &[], 2, 0), "👨‍👩‍👧👨‍👩‍👧...");
+        assert_eq!(generate_snippet("🇯🇵🇫🇷🇩🇪", &[], 1, 0), "🇯🇵...");
+        let cafe = ["cafe".to_string()];
+        assert_eq!(generate_snippet("cafe\u{301}cafe\u{301}", &cafe, 5, 0), "cafe\u{301}c...");
+    }
+
+    #[test]
+    fn test_search_snippet_options() {
+        let mut builder = SearchIndexBuilder::new();
+        builder.add_simple(
+            "1",
+            "Renderer",
+            "/renderer",
+            "The renderer turns the parsed document into HTML, escaping text as it goes.",
+        );
+        let index = builder.build();
+
+        let results = index.search("parsed", &SearchOptions::default());
+        assert_eq!(results[0].snippet, index.documents[0].body);
+
+        let options = SearchOptions {
+            snippet_length: 30,
+            snippet_context: Some(0),
+            ..SearchOptions::default()
+        };
+        let results = index.search("parsed", &options);
+        assert_eq!(results[0].snippet, "...parsed document into HTML,...");
+    }
+
+    #[test]
+    fn test_search_group_by_page() {
+        let mut builder = SearchIndexBuilder::new();
+        let section = |id: &str, heading: &str, body: &str| crate::index::SearchDocument {
+            id: id.to_string(),
+            title: "Reference".to_string(),
+            url: format!("/reference#{id}"),
+            body: body.to_string(),
+            headings: vec![heading.to_string()],
+            code: Vec::new(),
+            boost: 1.0,
+            tags: Vec::new(),
+            facets: BTreeMap::new(),
+        };
+        builder.add_document(section("config", "Config", "The render option sets the output."));
+        builder.add_document(section("render", "Render options", "How render works."));
+        builder.add_simple("guide", "Guide", "/guide", "A guide to render pages.");
+
+        let index = builder.build();
+        let ungrouped = index.search("render", &SearchOptions::default());
+        assert_eq!(ungrouped.len(), 3);
+        assert!(ungrouped.iter().all(|r| r.sub_matches.is_empty()));
+
+        let options = SearchOptions { group_by_page: true, ..Default::default() };
+        let results = index.search("render", &options);
+        assert_eq!(results.len(), 2);
+        let reference = &results[0];
+        assert_eq!(reference.url, "/reference");
+        assert_eq!(reference.id, "render");
+        let subs: Vec<_> =
+            reference.sub_matches.iter().map(|s| (s.url.as_str(), s.heading.as_str())).collect();
+        assert_eq!(
+            subs,
+            [("/reference#render", "Render options"), ("/reference#config", "Config")]
+        );
+        let total: f64 = reference.sub_matches.iter().map(|s| s.score).sum();
+        assert!((reference.score - total).abs() < 1e-9);
+        assert_eq!(results[1].sub_matches.len(), 1);
+        assert_eq!(results[1].sub_matches[0].heading, "Guide");
+    }
+
+    #[test]
+    fn test_search_filters() {
+        let mut builder = SearchIndexBuilder::new();
+        let page = |id: &str, tags: &[&str], version: &str| crate::index::SearchDocument {
+            id: id.to_string(),
+            title: format!("Plugins {id}"),
+            url: format!("/{id}"),
+            body: "Writing plugins for the renderer.".to_string(),
+            headings: Vec::new(),
+            code: Vec::new(),
+            boost: 1.0,
+            tags: tags.iter().map(ToString::to_string).collect(),
+            facets: BTreeMap::from([("version".to_string(), vec![version.to_string()])]),
+        };
+        builder.add_document(page("a", &["guide", "rust"], "v1"));
+        builder.add_document(page("b", &["guide"], "v2"));
+        builder.add_document(page("c", &["api", "rust"], "v2"));
+        builder.add_simple("d", "Plugins", "/d", "Writing plugins.");
+        let index = builder.build();
+
+        let ids = |options: SearchOptions| {
+            let mut ids: Vec<_> =
+                index.search("plugins", &options).into_iter().map(|r| r.id).collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(ids(SearchOptions::default()), ["a", "b", "c", "d"]);
+        let tags = |tags: &[&str]| SearchOptions {
+            tags: tags.iter().map(ToString::to_string).collect(),
+            ..SearchOptions::default()
+        };
+        assert_eq!(ids(tags(&["Rust"])), ["a", "c"]);
+        assert_eq!(ids(tags(&["guide", "rust"])), ["a"]);
+
+        let facets = |values: &[&str]| {
+            BTreeMap::from([(
+                "version".to_string(),
+                values.iter().map(ToString::to_string).collect(),
+            )])
+        };
+        let options = SearchOptions { facets: facets(&["v2"]), ..tags(&["guide"]) };
+        assert_eq!(ids(options), ["b"]);
+        let options = SearchOptions { facets: facets(&["v1", "v2"]), ..SearchOptions::default() };
+        assert_eq!(ids(options), ["a", "b", "c"]);
+
+        let results = index.search("plugins", &tags(&["api"]));
+        assert_eq!(results[0].tags, ["api", "rust"]);
+        assert_eq!(results[0].facets["version"], ["v2"]);
+    }
+
+    #[test]
+    fn test_search_boost() {
+        let mut builder = SearchIndexBuilder::new();
+        builder.add_simple("1", "Plugins", "/plugins", "Writing plugins for the renderer.");
+        builder.add_document(crate::index::SearchDocument {
+            id: "2".to_string(),
+            title: "Plugins".to_string(),
+            url: "/guide/plugins".to_string(),
+            body: "Writing plugins for the renderer.".to_string(),
+            headings: Vec::new(),
+            code: Vec::new(),
+            boost: 3.0,
+            tags: Vec::new(),
+            facets: BTreeMap::new(),
+        });
+
+        let index = builder.build();
+        let results = index.search("plugins", &SearchOptions::default());
+        assert_eq!(results[0].id, "2");
+        assert!((results[0].score / results[1].score - 3.0).abs() < 1e-9);
+    }
 }
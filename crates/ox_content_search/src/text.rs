@@ -0,0 +1,35 @@
+//! Grapheme-aware text helpers.
+//!
+//! Snippets and previews are cut at grapheme cluster boundaries, so that
+//! an emoji sequence, a flag or a letter with combining accents is never
+//! split into broken characters.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Splits `text` into its grapheme clusters.
+pub fn graphemes(text: &str) -> Vec<&str> {
+    text.graphemes(true).collect()
+}
+
+/// Returns whether a grapheme cluster is whitespace.
+pub fn is_whitespace(grapheme: &str) -> bool {
+    grapheme.chars().all(char::is_whitespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graphemes() {
+        // Emoji with a skin tone modifier
+        assert_eq!(graphemes("👍🏽👍🏽"), ["👍🏽", "👍🏽"]);
+        // Flags are pairs of regional indicators
+        assert_eq!(graphemes("🇯🇵🇫🇷"), ["🇯🇵", "🇫🇷"]);
+        // ZWJ sequences
+        assert_eq!(graphemes("👨‍👩‍👧 🏳️‍🌈"), ["👨‍👩‍👧", " ", "🏳️‍🌈"]);
+        // Combining accents stay with their letter
+        assert_eq!(graphemes("a🇯🇵e\u{301}"), ["a", "🇯🇵", "e\u{301}"]);
+        assert!(is_whitespace(" ") && is_whitespace("\r\n") && !is_whitespace("e\u{301}"));
+    }
+}
@@ -44,12 +44,41 @@ pub struct PageData {
     pub content: String,
     /// Table of contents entries.
     pub toc: Vec<TocEntry>,
+    /// Whether the page shows a table of contents.
+    ///
+    /// When disabled the TOC aside is left out entirely; when enabled it is
+    /// shown even without entries, unless [`SsgConfig::toc_min_entries`]
+    /// hides it.
+    #[serde(default = "default_toc_enabled")]
+    pub toc_enabled: bool,
     /// URL path.
     pub path: String,
+    /// Page language as a BCP 47 tag such as `en` or `pt-BR`, usually from
+    /// the `lang` frontmatter key. Falls back to [`SsgConfig::lang`].
+    #[serde(default)]
+    pub lang: Option<String>,
+}
+
+impl Default for PageData {
+    fn default() -> Self {
+        Self {
+            title: String::new(),
+            description: None,
+            content: String::new(),
+            toc: Vec::new(),
+            toc_enabled: default_toc_enabled(),
+            path: String::new(),
+            lang: None,
+        }
+    }
+}
+
+const fn default_toc_enabled() -> bool {
+    true
 }
 
 /// SSG configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SsgConfig {
     /// Site name.
     pub site_name: String,
@@ -57,6 +86,128 @@ pub struct SsgConfig {
     pub base: String,
     /// OG image URL.
     pub og_image: Option<String>,
+    /// Hide the TOC on pages with fewer entries than this (default: 0).
+    #[serde(default)]
+    pub toc_min_entries: usize,
+    /// Search index JSON to embed in every page instead of fetching
+    /// `search-index.json` when search is first used.
+    ///
+    /// The index is repeated in each page, so this only suits small sites:
+    /// keep it to a few tens of kilobytes, and use the fetched file once
+    /// the index grows beyond that.
+    #[serde(default)]
+    pub inline_search_index: Option<String>,
+    /// Language of pages that do not set their own (default: `en`).
+    #[serde(default)]
+    pub lang: Option<String>,
+    /// Nonce added to every `<style>`, `<script>` and stylesheet `<link>`
+    /// tag, for sites whose Content-Security-Policy allows
+    /// `'nonce-<value>'`. Use a fresh random value for each response.
+    #[serde(default)]
+    pub csp_nonce: Option<String>,
+    /// Link the stylesheet and scripts from the files returned by
+    /// [`generate_assets`] under [`base`](Self::base) instead of inlining
+    /// them, so pages work under a policy without `'unsafe-inline'`.
+    #[serde(default)]
+    pub external_assets: bool,
+    /// Logo image shown in the header (default: `logo.svg`).
+    ///
+    /// This and the other site paths are resolved against
+    /// [`base`](Self::base) unless they are full URLs.
+    #[serde(default)]
+    pub logo_path: Option<String>,
+    /// Search index fetched when search is first used (default:
+    /// `search-index.json`). Unused with
+    /// [`inline_search_index`](Self::inline_search_index).
+    #[serde(default)]
+    pub search_index_url: Option<String>,
+    /// Page the site name in the header links to (default: `index.html`).
+    #[serde(default)]
+    pub home_url: Option<String>,
+}
+
+impl SsgConfig {
+    /// Resolves a site path against [`base`](Self::base). Full URLs
+    /// (`scheme://...`) are kept as they are; other paths, including those
+    /// starting with `/`, are joined to the base with a single `/`.
+    #[must_use]
+    pub fn site_url(&self, path: &str) -> String {
+        if path.contains("://") {
+            return path.to_string();
+        }
+        if let Some(path) = path.strip_prefix('/') {
+            return format!("{}/{path}", self.base.trim_end_matches('/'));
+        }
+        let path = path.trim_start_matches("./");
+        if self.base.is_empty() || self.base.ends_with('/') {
+            format!("{}{path}", self.base)
+        } else {
+            format!("{}/{path}", self.base)
+        }
+    }
+}
+
+/// A stylesheet or script file that pages generated with
+/// [`SsgConfig::external_assets`] link to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SsgAsset {
+    /// File path relative to the site base, such as `ssg.css`.
+    pub path: &'static str,
+    /// File content.
+    pub content: String,
+}
+
+/// Page metadata written to the `<title>` and `<meta>` tags of the head.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageMeta {
+    /// Page title, shown before the site name. Empty for the site name alone.
+    pub title: String,
+    /// Page description.
+    pub description: Option<String>,
+    /// Social card image URL. Falls back to [`SsgConfig::og_image`].
+    pub image: Option<String>,
+    /// Page author.
+    pub author: Option<String>,
+    /// Keywords, written as a comma-separated list.
+    pub keywords: Vec<String>,
+    /// Page language. Falls back to [`SsgConfig::lang`].
+    pub lang: Option<String>,
+}
+
+impl PageMeta {
+    /// Reads metadata from parsed frontmatter: the `title`, `description`,
+    /// `image`, `author` and `lang` strings, and `tags` as keywords, either
+    /// a list or a comma-separated string. Other keys are ignored.
+    #[must_use]
+    pub fn from_frontmatter(frontmatter: &serde_json::Map<String, serde_json::Value>) -> Self {
+        let string = |key: &str| {
+            frontmatter
+                .get(key)
+                .and_then(serde_json::Value::as_str)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(String::from)
+        };
+        let keywords = match frontmatter.get("tags") {
+            Some(serde_json::Value::Array(tags)) => {
+                tags.iter().filter_map(serde_json::Value::as_str).map(String::from).collect()
+            }
+            Some(serde_json::Value::String(tags)) => tags.split(',').map(String::from).collect(),
+            _ => Vec::new(),
+        };
+        Self {
+            title: string("title").unwrap_or_default(),
+            description: string("description"),
+            image: string("image"),
+            author: string("author"),
+            keywords: keywords
+                .into_iter()
+                .map(|keyword| keyword.trim().to_string())
+                .filter(|keyword| !keyword.is_empty())
+                .collect(),
+            lang: string("lang"),
+        }
+    }
 }
 
 /// CSS styles for SSG pages.
@@ -65,46 +216,86 @@ const SSG_CSS: &str = include_str!("ssg.css");
 /// JavaScript for SSG pages.
 const SSG_JS: &str = include_str!("ssg.js");
 
+/// Sets the color theme in the head, before the page is first painted.
+const THEME_JS: &str = "document.documentElement.setAttribute('data-theme',localStorage.getItem('theme')||(matchMedia('(prefers-color-scheme:dark)').matches?'dark':'light'))";
+
+/// Returns the files pages link to with [`SsgConfig::external_assets`]:
+/// the stylesheet, the theme script loaded in the head and the page
+/// script. Write them under the site base.
+///
+/// The page script contains the search index URL and the inlined search
+/// index of `config`, so the assets must be generated with the same config as the
+/// pages.
+#[must_use]
+pub fn generate_assets(config: &SsgConfig) -> Vec<SsgAsset> {
+    vec![
+        SsgAsset { path: "ssg.css", content: SSG_CSS.to_string() },
+        SsgAsset { path: "ssg-theme.js", content: THEME_JS.to_string() },
+        SsgAsset { path: "ssg.js", content: page_script(config) },
+    ]
+}
+
+/// Returns the page script with the search index URL and inlined search
+/// index of `config`.
+fn page_script(config: &SsgConfig) -> String {
+    let search_index_url =
+        config.site_url(config.search_index_url.as_deref().unwrap_or("search-index.json"));
+    // The URL is written into a single-quoted JS string
+    let search_index_url =
+        search_index_url.replace('\\', "\\\\").replace('\'', "\\'").replace('<', "\\u003c");
+    SSG_JS
+        .replace("{{search_index_url}}", &search_index_url)
+        .replace("{{search_index}}", &inline_search_index(config))
+}
+
 /// Generates a complete HTML page for SSG.
 ///
 /// This function creates a full HTML document with navigation sidebar,
 /// content area, table of contents, search functionality, and theme toggle.
 pub fn generate_html(page_data: &PageData, nav_groups: &[NavGroup], config: &SsgConfig) -> String {
     let nav_html = generate_nav_html(nav_groups, &page_data.path);
+    let toc_html = generate_toc_html(page_data, config);
 
-    let description_meta = page_data.description.as_ref().map_or(String::new(), |d| {
-        format!(
-            r#"<meta name="description" content="{}">
-  <meta property="og:description" content="{}">
-  <meta name="twitter:description" content="{}">"#,
-            html_escape(d),
-            html_escape(d),
-            html_escape(d)
-        )
-    });
+    let meta = PageMeta {
+        title: page_data.title.clone(),
+        description: page_data.description.clone(),
+        lang: page_data.lang.clone(),
+        ..PageMeta::default()
+    };
+    let meta_tags = render_meta_tags(&meta, config).replace('\n', "\n  ");
 
-    let og_image_meta = config.og_image.as_ref().map_or(String::new(), |img| {
-        format!(
-            r#"<meta property="og:image" content="{img}">
-  <meta name="twitter:image" content="{img}">"#
+    let pagination_links = generate_pagination_links(nav_groups, &page_data.path);
+    let lang = page_language(page_data.lang.as_deref(), config);
+
+    let nonce = config
+        .csp_nonce
+        .as_deref()
+        .map_or_else(String::new, |nonce| format!(" nonce=\"{}\"", html_escape(nonce)));
+    let asset_url = |path: &str| html_escape(&config.site_url(path));
+    let (styles, theme_script, page_script) = if config.external_assets {
+        (
+            format!(r#"<link rel="stylesheet" href="{}"{nonce}>"#, asset_url("ssg.css")),
+            format!(r#"<script src="{}"{nonce}></script>"#, asset_url("ssg-theme.js")),
+            format!(r#"<script src="{}"{nonce}></script>"#, asset_url("ssg.js")),
+        )
+    } else {
+        (
+            format!("<style{nonce}>{SSG_CSS}</style>"),
+            format!("<script{nonce}>{THEME_JS}</script>"),
+            format!("<script{nonce}>{}</script>", page_script(config)),
         )
-    });
+    };
 
     format!(
         r#"<!DOCTYPE html>
-<html lang="en">
+<html lang="{lang}">
 <head>
   <meta charset="UTF-8">
   <meta name="viewport" content="width=device-width, initial-scale=1.0">
-  <title>{title} - {site_name}</title>
-  {description_meta}
-  <meta property="og:type" content="website">
-  <meta property="og:title" content="{title} - {site_name}">
-  {og_image_meta}
-  <meta name="twitter:card" content="summary_large_image">
-  <meta name="twitter:title" content="{title} - {site_name}">
-  <style>{css}</style>
-  <script>document.documentElement.setAttribute('data-theme',localStorage.getItem('theme')||(matchMedia('(prefers-color-scheme:dark)').matches?'dark':'light'))</script>
+  {meta_tags}
+  {pagination_links}
+  {styles}
+  {theme_script}
 </head>
 <body>
   <header class="header">
@@ -113,8 +304,8 @@ pub fn generate_html(page_data: &PageData, nav_groups: &[NavGroup], config: &Ssg
         <path d="M3 12h18M3 6h18M3 18h18"/>
       </svg>
     </button>
-    <a href="{base}index.html" class="header-title">
-      <img src="{base}logo.svg" alt="" width="28" height="28" style="margin-right: 8px; vertical-align: middle;" />
+    <a href="{home_url}" class="header-title">
+      <img src="{logo_url}" alt="" width="28" height="28" />
       {site_name}
     </a>
     <div class="header-actions">
@@ -163,27 +354,138 @@ pub fn generate_html(page_data: &PageData, nav_groups: &[NavGroup], config: &Ssg
       <article class="content">
 {content}
       </article>
-    </main>
+    </main>{toc}
   </div>
-  <script>{js}</script>
+  {page_script}
 </body>
 </html>"#,
-        title = html_escape(&page_data.title),
+        lang = html_escape(lang),
+        home_url = asset_url(config.home_url.as_deref().unwrap_or("index.html")),
+        logo_url = asset_url(config.logo_path.as_deref().unwrap_or("logo.svg")),
         site_name = html_escape(&config.site_name),
-        base = &config.base,
-        description_meta = description_meta,
-        og_image_meta = og_image_meta,
-        css = SSG_CSS,
+        meta_tags = meta_tags,
+        pagination_links = pagination_links,
         navigation = nav_html,
         content = page_data.content,
-        js = SSG_JS.replace("{{base}}", &config.base),
+        toc = toc_html,
     )
 }
 
+/// Returns the JS expression the search index starts as: the inlined index,
+/// or `null` to fetch it on first use.
+///
+/// `<` is escaped so the index cannot close the surrounding `<script>`.
+fn inline_search_index(config: &SsgConfig) -> String {
+    config
+        .inline_search_index
+        .as_deref()
+        .map_or_else(|| "null".to_string(), |index| index.trim().replace('<', "\\u003c"))
+}
+
+/// Renders the `<title>` and the description, author, keyword, Open Graph
+/// and Twitter card `<meta>` tags of a page head, one per line.
+///
+/// These are the tags [`generate_html`] writes, for pages whose head is
+/// assembled separately. The title is followed by the site name, and every
+/// value is HTML-escaped.
+pub fn render_meta_tags(meta: &PageMeta, config: &SsgConfig) -> String {
+    let title = match (meta.title.is_empty(), config.site_name.is_empty()) {
+        (false, false) => format!("{} - {}", meta.title, config.site_name),
+        (false, true) => meta.title.clone(),
+        (true, _) => config.site_name.clone(),
+    };
+    let title = html_escape(&title);
+    let lang = page_language(meta.lang.as_deref(), config);
+
+    let mut tags = vec![format!("<title>{title}</title>")];
+    if let Some(description) = &meta.description {
+        let description = html_escape(description);
+        tags.push(format!(r#"<meta name="description" content="{description}">"#));
+        tags.push(format!(r#"<meta property="og:description" content="{description}">"#));
+        tags.push(format!(r#"<meta name="twitter:description" content="{description}">"#));
+    }
+    if let Some(author) = &meta.author {
+        tags.push(format!(r#"<meta name="author" content="{}">"#, html_escape(author)));
+    }
+    if !meta.keywords.is_empty() {
+        let keywords = html_escape(&meta.keywords.join(", "));
+        tags.push(format!(r#"<meta name="keywords" content="{keywords}">"#));
+    }
+    tags.push(r#"<meta property="og:type" content="website">"#.to_string());
+    tags.push(format!(
+        r#"<meta property="og:locale" content="{}">"#,
+        html_escape(&lang.replace('-', "_"))
+    ));
+    tags.push(format!(r#"<meta property="og:title" content="{title}">"#));
+    if let Some(image) = meta.image.as_ref().or(config.og_image.as_ref()) {
+        let image = html_escape(image);
+        tags.push(format!(r#"<meta property="og:image" content="{image}">"#));
+        tags.push(format!(r#"<meta name="twitter:image" content="{image}">"#));
+    }
+    tags.push(r#"<meta name="twitter:card" content="summary_large_image">"#.to_string());
+    tags.push(format!(r#"<meta name="twitter:title" content="{title}">"#));
+    tags.join("\n")
+}
+
+/// Returns the language of a page: its own, the site's, or `en`.
+fn page_language<'d>(page_lang: Option<&'d str>, config: &'d SsgConfig) -> &'d str {
+    [page_lang, config.lang.as_deref()]
+        .into_iter()
+        .filter_map(|lang| lang.map(str::trim))
+        .find(|lang| !lang.is_empty())
+        .unwrap_or("en")
+}
+
 fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
 }
 
+/// Renders the "On this page" aside, or nothing when the TOC is hidden.
+fn generate_toc_html(page_data: &PageData, config: &SsgConfig) -> String {
+    if !page_data.toc_enabled || page_data.toc.len() < config.toc_min_entries {
+        return String::new();
+    }
+    let items = page_data
+        .toc
+        .iter()
+        .map(|entry| {
+            format!(
+                r##"        <li class="toc-item toc-depth-{}"><a href="#{}">{}</a></li>"##,
+                entry.depth,
+                html_escape(&entry.slug),
+                html_escape(&entry.text)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        r#"
+    <aside class="toc" aria-label="On this page">
+      <div class="toc-title">On this page</div>
+      <ul class="toc-list">
+{items}
+      </ul>
+    </aside>"#
+    )
+}
+
+/// Renders `<link rel="prev">` and `<link rel="next">` for the pages around
+/// the current one in navigation order, leaving out those that do not exist.
+fn generate_pagination_links(nav_groups: &[NavGroup], current_path: &str) -> String {
+    let items: Vec<&NavItem> = nav_groups.iter().flat_map(|group| &group.items).collect();
+    let Some(current) = items.iter().position(|item| item.path == current_path) else {
+        return String::new();
+    };
+    let prev = current.checked_sub(1).map(|i| ("prev", items[i]));
+    let next = items.get(current + 1).map(|item| ("next", *item));
+    prev.into_iter()
+        .chain(next)
+        .map(|(rel, item)| format!(r#"<link rel="{rel}" href="{}">"#, html_escape(&item.href)))
+        .collect::<Vec<_>>()
+        .join("\n  ")
+}
+
 fn generate_nav_html(nav_groups: &[NavGroup], current_path: &str) -> String {
     nav_groups
         .iter()
@@ -235,6 +537,7 @@ mod tests {
             content: "<h1>Hello</h1>".to_string(),
             toc: vec![TocEntry { depth: 1, text: "Hello".to_string(), slug: "hello".to_string() }],
             path: "test".to_string(),
+            ..Default::default()
         };
 
         let nav_groups = vec![NavGroup {
@@ -249,7 +552,7 @@ mod tests {
         let config = SsgConfig {
             site_name: "Test Site".to_string(),
             base: "/docs/".to_string(),
-            og_image: None,
+            ..Default::default()
         };
 
         let html = generate_html(&page_data, &nav_groups, &config);
@@ -257,5 +560,239 @@ mod tests {
         assert!(html.contains("Test Page - Test Site"));
         assert!(html.contains("<h1>Hello</h1>"));
         assert!(html.contains("Guide"));
+        assert!(
+            html.contains(r##"<li class="toc-item toc-depth-1"><a href="#hello">Hello</a></li>"##)
+        );
+    }
+
+    #[test]
+    fn test_toc_visibility() {
+        let mut page_data = PageData {
+            title: "Empty".to_string(),
+            content: "<p>No headings.</p>".to_string(),
+            path: "empty".to_string(),
+            ..Default::default()
+        };
+        let mut config = SsgConfig {
+            site_name: "Test Site".to_string(),
+            base: "/".to_string(),
+            ..Default::default()
+        };
+
+        // Enabled without entries: an empty "On this page" box.
+        assert!(generate_html(&page_data, &[], &config).contains(r#"<aside class="toc""#));
+
+        page_data.toc_enabled = false;
+        assert!(!generate_html(&page_data, &[], &config).contains(r#"<aside class="toc""#));
+
+        page_data.toc_enabled = true;
+        page_data.toc =
+            vec![TocEntry { depth: 2, text: "Only".to_string(), slug: "only".to_string() }];
+        config.toc_min_entries = 2;
+        assert!(!generate_html(&page_data, &[], &config).contains(r#"<aside class="toc""#));
+    }
+
+    #[test]
+    fn test_pagination_links() {
+        let item = |path: &str| NavItem {
+            title: path.to_string(),
+            path: path.to_string(),
+            href: format!("/{path}/index.html"),
+        };
+        let nav_groups = vec![
+            NavGroup { title: "Guide".to_string(), items: vec![item("intro"), item("setup")] },
+            NavGroup { title: "API".to_string(), items: vec![item("api")] },
+        ];
+
+        assert_eq!(
+            generate_pagination_links(&nav_groups, "intro"),
+            r#"<link rel="next" href="/setup/index.html">"#
+        );
+        assert_eq!(
+            generate_pagination_links(&nav_groups, "setup"),
+            "<link rel=\"prev\" href=\"/intro/index.html\">\n  \
+             <link rel=\"next\" href=\"/api/index.html\">"
+        );
+        assert_eq!(
+            generate_pagination_links(&nav_groups, "api"),
+            r#"<link rel="prev" href="/setup/index.html">"#
+        );
+        assert_eq!(generate_pagination_links(&nav_groups, "404"), "");
+    }
+
+    #[test]
+    fn test_page_language() {
+        let mut page_data = PageData {
+            title: "Accueil".to_string(),
+            toc_enabled: false,
+            path: "index".to_string(),
+            ..Default::default()
+        };
+        let mut config = SsgConfig {
+            site_name: "Docs".to_string(),
+            base: "/".to_string(),
+            ..Default::default()
+        };
+
+        let html = generate_html(&page_data, &[], &config);
+        assert!(html.contains(r#"<html lang="en">"#));
+        assert!(html.contains(r#"<meta property="og:locale" content="en">"#));
+
+        config.lang = Some("ja".to_string());
+        assert!(generate_html(&page_data, &[], &config).contains(r#"<html lang="ja">"#));
+
+        page_data.lang = Some("fr-CA".to_string());
+        let html = generate_html(&page_data, &[], &config);
+        assert!(html.contains(r#"<html lang="fr-CA">"#));
+        assert!(html.contains(r#"<meta property="og:locale" content="fr_CA">"#));
+    }
+
+    #[test]
+    fn test_render_meta_tags() {
+        let frontmatter = serde_json::json!({
+            "title": "Plugins & \"Hooks\"",
+            "description": "Extend <ox>",
+            "author": "Ada",
+            "tags": ["rust", "plugins"],
+            "image": "/og/plugins.png",
+            "lang": "pt-BR",
+            "layout": "doc",
+        });
+        let serde_json::Value::Object(frontmatter) = frontmatter else { unreachable!() };
+        let meta = PageMeta::from_frontmatter(&frontmatter);
+        assert_eq!(meta.keywords, ["rust", "plugins"]);
+
+        let config = SsgConfig {
+            site_name: "Docs".to_string(),
+            base: "/".to_string(),
+            og_image: Some("/og.png".to_string()),
+            ..Default::default()
+        };
+        let tags = render_meta_tags(&meta, &config);
+        assert_eq!(
+            tags,
+            [
+                "<title>Plugins &amp; &quot;Hooks&quot; - Docs</title>",
+                r#"<meta name="description" content="Extend &lt;ox&gt;">"#,
+                r#"<meta property="og:description" content="Extend &lt;ox&gt;">"#,
+                r#"<meta name="twitter:description" content="Extend &lt;ox&gt;">"#,
+                r#"<meta name="author" content="Ada">"#,
+                r#"<meta name="keywords" content="rust, plugins">"#,
+                r#"<meta property="og:type" content="website">"#,
+                r#"<meta property="og:locale" content="pt_BR">"#,
+                r#"<meta property="og:title" content="Plugins &amp; &quot;Hooks&quot; - Docs">"#,
+                r#"<meta property="og:image" content="/og/plugins.png">"#,
+                r#"<meta name="twitter:image" content="/og/plugins.png">"#,
+                r#"<meta name="twitter:card" content="summary_large_image">"#,
+                r#"<meta name="twitter:title" content="Plugins &amp; &quot;Hooks&quot; - Docs">"#,
+            ]
+            .join("\n")
+        );
+
+        let meta = PageMeta::from_frontmatter(&serde_json::Map::new());
+        let tags = render_meta_tags(&meta, &config);
+        assert!(tags.starts_with("<title>Docs</title>\n"));
+        assert!(tags.contains(r#"<meta property="og:image" content="/og.png">"#));
+        assert!(!tags.contains("description") && !tags.contains("keywords"));
+    }
+
+    #[test]
+    fn test_csp_assets() {
+        let page_data = PageData {
+            title: "Home".to_string(),
+            toc_enabled: false,
+            path: "index".to_string(),
+            ..Default::default()
+        };
+        let mut config = SsgConfig {
+            site_name: "Docs".to_string(),
+            base: "/docs/".to_string(),
+            csp_nonce: Some("r4nd\"om".to_string()),
+            ..Default::default()
+        };
+
+        let html = generate_html(&page_data, &[], &config);
+        assert!(html.contains(r#"<style nonce="r4nd&quot;om">"#));
+        assert_eq!(html.matches(r#"<script nonce="r4nd&quot;om">"#).count(), 2);
+        assert!(!html.contains("<script>") && !html.contains(" style="));
+
+        config.csp_nonce = None;
+        config.external_assets = true;
+        let html = generate_html(&page_data, &[], &config);
+        assert!(html.contains(r#"<link rel="stylesheet" href="/docs/ssg.css">"#));
+        assert!(html.contains(r#"<script src="/docs/ssg-theme.js"></script>"#));
+        assert!(html.contains(r#"<script src="/docs/ssg.js"></script>"#));
+        assert!(!html.contains("<style") && !html.contains("<script>"));
+
+        let assets = generate_assets(&config);
+        let paths: Vec<_> = assets.iter().map(|asset| asset.path).collect();
+        assert_eq!(paths, ["ssg.css", "ssg-theme.js", "ssg.js"]);
+        assert!(assets[2].content.contains("fetch('/docs/search-index.json')"));
+    }
+
+    #[test]
+    fn test_inline_search_index() {
+        let page_data = PageData {
+            title: "Home".to_string(),
+            toc_enabled: false,
+            path: "index".to_string(),
+            ..Default::default()
+        };
+        let mut config = SsgConfig {
+            site_name: "Tiny".to_string(),
+            base: "/".to_string(),
+            ..Default::default()
+        };
+
+        let html = generate_html(&page_data, &[], &config);
+        assert!(html.contains("let searchIndex=null,"));
+        assert!(html.contains("fetch('/search-index.json')"));
+
+        config.inline_search_index = Some(r#"{"documents":[{"body":"</script>"}]}"#.to_string());
+        let html = generate_html(&page_data, &[], &config);
+        assert!(html.contains(r#"let searchIndex={"documents":[{"body":"\u003c/script>"}]},"#));
+        assert_eq!(html.matches("</script>").count(), 2);
+    }
+
+    #[test]
+    fn test_site_paths() {
+        let page_data = PageData {
+            title: "Home".to_string(),
+            toc_enabled: false,
+            path: "index".to_string(),
+            ..Default::default()
+        };
+        let mut config = SsgConfig {
+            site_name: "Docs".to_string(),
+            base: "/preview/pr-42".to_string(),
+            external_assets: true,
+            ..Default::default()
+        };
+
+        let html = generate_html(&page_data, &[], &config);
+        assert!(html.contains(r#"<a href="/preview/pr-42/index.html" class="header-title">"#));
+        assert!(html.contains(r#"<img src="/preview/pr-42/logo.svg""#));
+        assert!(html.contains(r#"href="/preview/pr-42/ssg.css""#));
+
+        config.logo_path = Some("./img/brand.png".to_string());
+        config.home_url = Some("/".to_string());
+        config.search_index_url = Some("https://cdn.example.com/it's.json".to_string());
+        let html = generate_html(&page_data, &[], &config);
+        assert!(html.contains(r#"<a href="/preview/pr-42/" class="header-title">"#));
+        assert!(html.contains(r#"<img src="/preview/pr-42/img/brand.png""#));
+        assert!(generate_assets(&config)[2]
+            .content
+            .contains(r"fetch('https://cdn.example.com/it\'s.json')"));
+
+        config.home_url = Some("https://docs.example.com/".to_string());
+        let html = generate_html(&page_data, &[], &config);
+        assert!(html.contains(r#"<a href="https://docs.example.com/" class="header-title">"#));
+        assert_eq!(config.site_url("/img/brand.png"), "/preview/pr-42/img/brand.png");
+
+        config.base = "/docs/".to_string();
+        assert_eq!(config.site_url("/logo.svg"), "/docs/logo.svg");
+        config.base = String::new();
+        assert_eq!(config.site_url("logo.svg"), "logo.svg");
+        assert_eq!(config.site_url("/logo.svg"), "/logo.svg");
     }
 }
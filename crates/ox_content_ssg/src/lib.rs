@@ -9,6 +9,7 @@
 //! - Full HTML page generation with responsive layout
 //! - Navigation sidebar with grouping
 //! - Table of contents generation
+//! - Head meta tags from frontmatter
 //! - Client-side search integration
 //! - Dark/light theme support
 //! - Mobile-friendly responsive design
@@ -24,6 +25,8 @@
 //!     content: "<h1>Getting Started</h1><p>Welcome!</p>".to_string(),
 //!     toc: vec![TocEntry { depth: 1, text: "Getting Started".to_string(), slug: "getting-started".to_string() }],
 //!     path: "getting-started".to_string(),
+//!     lang: Some("en".to_string()),
+//!     ..PageData::default()
 //! };
 //!
 //! let nav_groups = vec![NavGroup {
@@ -39,6 +42,8 @@
 //!     site_name: "My Docs".to_string(),
 //!     base: "/docs/".to_string(),
 //!     og_image: None,
+//!     toc_min_entries: 2,
+//!     ..SsgConfig::default()
 //! };
 //!
 //! let html = generate_html(&page_data, &nav_groups, &config);
@@ -46,4 +51,7 @@
 
 mod html;
 
-pub use html::{generate_html, NavGroup, NavItem, PageData, SsgConfig, TocEntry};
+pub use html::{
+    generate_assets, generate_html, render_meta_tags, NavGroup, NavItem, PageData, PageMeta,
+    SsgAsset, SsgConfig, TocEntry,
+};
@@ -1,15 +1,20 @@
 //! WebAssembly bindings for Ox Content.
 //!
 //! This crate provides WASM bindings for using Ox Content in browsers
-//! and other WebAssembly environments.
+//! and other WebAssembly environments, such as Cloudflare Workers. It
+//! mirrors the transform and search functions of the Node.js bindings.
 
-use std::collections::HashMap;
+use serde_json::{Map, Value};
 use wasm_bindgen::prelude::*;
 
 use ox_content_allocator::Allocator;
-use ox_content_ast::{Document, Heading, Node};
+use ox_content_ast::{Document, Node};
+use ox_content_frontmatter::{parse_frontmatter, Frontmatter};
 use ox_content_parser::{Parser, ParserOptions};
-use ox_content_renderer::HtmlRenderer;
+use ox_content_renderer::{
+    heading_slug, heading_text, HtmlRenderer, HtmlRendererOptions, SlugStyle, UniqueSlugs,
+};
+use ox_content_search::{SearchDocument, SearchIndex, SearchIndexBuilder, SearchOptions};
 
 /// Table of contents entry.
 #[derive(serde::Serialize)]
@@ -21,9 +26,14 @@ pub struct TocEntry {
 
 /// Transform result containing HTML, frontmatter, and TOC.
 #[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TransformResult {
     pub html: String,
-    pub frontmatter: HashMap<String, serde_json::Value>,
+    pub frontmatter: Map<String, Value>,
+    /// Detected frontmatter format (`"yaml"`, `"toml"` or `"json"`), if any.
+    pub frontmatter_format: Option<String>,
+    /// The frontmatter exactly as written between its delimiters, or empty.
+    pub frontmatter_raw: String,
     pub toc: Vec<TocEntry>,
     pub errors: Vec<String>,
 }
@@ -38,6 +48,10 @@ pub struct WasmParserOptions {
     tables: bool,
     strikethrough: bool,
     autolinks: bool,
+    abbreviations: bool,
+    attributes: bool,
+    parse_inside_html_blocks: bool,
+    toc_min_depth: u8,
     toc_max_depth: u8,
 }
 
@@ -52,6 +66,10 @@ impl WasmParserOptions {
             tables: false,
             strikethrough: false,
             autolinks: false,
+            abbreviations: false,
+            attributes: false,
+            parse_inside_html_blocks: false,
+            toc_min_depth: 2,
             toc_max_depth: 3,
         }
     }
@@ -86,6 +104,26 @@ impl WasmParserOptions {
         self.autolinks = value;
     }
 
+    #[wasm_bindgen(setter)]
+    pub fn set_abbreviations(&mut self, value: bool) {
+        self.abbreviations = value;
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_attributes(&mut self, value: bool) {
+        self.attributes = value;
+    }
+
+    #[wasm_bindgen(setter = parseInsideHtmlBlocks)]
+    pub fn set_parse_inside_html_blocks(&mut self, value: bool) {
+        self.parse_inside_html_blocks = value;
+    }
+
+    #[wasm_bindgen(setter = tocMinDepth)]
+    pub fn set_toc_min_depth(&mut self, value: u8) {
+        self.toc_min_depth = value;
+    }
+
     #[wasm_bindgen(setter = tocMaxDepth)]
     pub fn set_toc_max_depth(&mut self, value: u8) {
         self.toc_max_depth = value;
@@ -101,12 +139,28 @@ impl From<&WasmParserOptions> for ParserOptions {
         options.tables = opts.tables;
         options.strikethrough = opts.strikethrough;
         options.autolinks = opts.autolinks;
+        options.abbreviations = opts.abbreviations;
+        options.attributes = opts.attributes;
+        options.parse_inside_html_blocks = opts.parse_inside_html_blocks;
 
         options
     }
 }
 
+/// Returns the renderer options for `opts`: GFM also applies the GFM
+/// tagfilter to raw HTML.
+fn renderer_options(opts: &WasmParserOptions) -> HtmlRendererOptions {
+    if opts.gfm {
+        HtmlRendererOptions::gfm()
+    } else {
+        HtmlRendererOptions::new()
+    }
+}
+
 /// Parses Markdown and renders to HTML.
+///
+/// When parsing stops at an error, the blocks before it are still rendered
+/// and the error is reported in `errors`.
 #[wasm_bindgen(js_name = parseAndRender)]
 pub fn parse_and_render(source: &str, options: Option<WasmParserOptions>) -> JsValue {
     let opts = options.unwrap_or_default();
@@ -114,130 +168,142 @@ pub fn parse_and_render(source: &str, options: Option<WasmParserOptions>) -> JsV
     let parser_options = ParserOptions::from(&opts);
     let parser = Parser::with_options(&allocator, source, parser_options);
 
-    let result = parser.parse();
-    match result {
-        Ok(doc) => {
-            let mut renderer = HtmlRenderer::new();
-            let html = renderer.render(&doc);
-            serde_wasm_bindgen::to_value(&serde_json::json!({
-                "html": html,
-                "errors": Vec::<String>::new()
-            }))
-            .unwrap_or(JsValue::NULL)
-        }
-        Err(e) => serde_wasm_bindgen::to_value(&serde_json::json!({
-            "html": "",
-            "errors": [e.to_string()]
-        }))
-        .unwrap_or(JsValue::NULL),
-    }
+    let (doc, error) = parser.parse_partial();
+    let mut renderer = HtmlRenderer::with_options(renderer_options(&opts));
+    let html = renderer.render(&doc);
+    let errors: Vec<String> = error.iter().map(ToString::to_string).collect();
+    serde_wasm_bindgen::to_value(&serde_json::json!({ "html": html, "errors": errors }))
+        .unwrap_or(JsValue::NULL)
 }
 
 /// Transforms Markdown source into HTML, frontmatter, and TOC.
+///
+/// Like [`parse_and_render`], renders the blocks before a parse error.
 #[wasm_bindgen]
 pub fn transform(source: &str, options: Option<WasmParserOptions>) -> JsValue {
     let opts = options.unwrap_or_default();
+    let toc_min_depth = opts.toc_min_depth.max(1);
     let toc_max_depth = opts.toc_max_depth;
 
     // Parse frontmatter
-    let (content, frontmatter) = parse_frontmatter(source);
+    let Frontmatter { content, data: frontmatter, errors: frontmatter_errors, format, raw } =
+        parse_frontmatter(source);
+    let frontmatter_format = format.map(|format| format.as_str().to_string());
+    let mut errors: Vec<String> = frontmatter_errors.iter().map(ToString::to_string).collect();
 
     // Parse markdown
     let allocator = Allocator::new();
     let parser_options = ParserOptions::from(&opts);
     let parser = Parser::with_options(&allocator, &content, parser_options);
 
-    let result = parser.parse();
-    match result {
-        Ok(doc) => {
-            // Extract TOC from headings
-            let toc = extract_toc(&doc, toc_max_depth);
+    let (doc, error) = parser.parse_partial();
+    errors.extend(error.map(|e| e.to_string()));
 
-            // Render to HTML
-            let mut renderer = HtmlRenderer::new();
-            let html = renderer.render(&doc);
+    // Extract TOC from headings
+    let toc = extract_toc(&doc, toc_min_depth, toc_max_depth);
+    if toc_min_depth > toc_max_depth {
+        errors.push(format!(
+            "`tocMinDepth` ({toc_min_depth}) is greater than `tocMaxDepth` ({toc_max_depth})"
+        ));
+    }
 
-            let transform_result = TransformResult { html, frontmatter, toc, errors: vec![] };
+    // Render to HTML
+    let mut renderer = HtmlRenderer::with_options(renderer_options(&opts));
+    let html = renderer.render(&doc);
+
+    let transform_result = TransformResult {
+        html,
+        frontmatter,
+        frontmatter_format,
+        frontmatter_raw: raw,
+        toc,
+        errors,
+    };
 
-            serde_wasm_bindgen::to_value(&transform_result).unwrap_or(JsValue::NULL)
-        }
-        Err(e) => {
-            let transform_result = TransformResult {
-                html: String::new(),
-                frontmatter: HashMap::new(),
-                toc: vec![],
-                errors: vec![e.to_string()],
-            };
-
-            serde_wasm_bindgen::to_value(&transform_result).unwrap_or(JsValue::NULL)
-        }
-    }
+    serde_wasm_bindgen::to_value(&transform_result).unwrap_or(JsValue::NULL)
 }
 
-/// Returns the version of ox_content_wasm.
-#[wasm_bindgen]
-pub fn version() -> String {
-    env!("CARGO_PKG_VERSION").to_string()
+/// Builds a search index from an array of documents and returns it as JSON.
+///
+/// Each document has `id`, `title`, `url`, `body` and `headings`, and
+/// optionally `code` and `boost`.
+#[wasm_bindgen(js_name = buildSearchIndex)]
+pub fn build_search_index(documents: JsValue) -> Result<String, JsError> {
+    Ok(build_index(documents)?.to_json())
 }
 
-/// Parses YAML frontmatter from Markdown content.
-fn parse_frontmatter(source: &str) -> (String, HashMap<String, serde_json::Value>) {
-    let mut frontmatter = HashMap::new();
-
-    if !source.starts_with("---") {
-        return (source.to_string(), frontmatter);
-    }
+/// Builds a search index in the compact binary format.
+#[wasm_bindgen(js_name = buildSearchIndexBinary)]
+pub fn build_search_index_binary(documents: JsValue) -> Result<Vec<u8>, JsError> {
+    Ok(build_index(documents)?.to_bytes())
+}
 
-    let rest = &source[3..];
-    let Some(end_pos) = rest.find("\n---") else {
-        return (source.to_string(), frontmatter);
+/// Searches a JSON index built by `buildSearchIndex`.
+///
+/// `options` accepts `limit`, `prefix`, `fuzzy` and `threshold`. An invalid
+/// index returns no results.
+#[wasm_bindgen(js_name = searchIndex)]
+pub fn search_index(index_json: &str, query: &str, options: JsValue) -> Result<JsValue, JsError> {
+    let Ok(index) = SearchIndex::from_json(index_json) else {
+        return Ok(js_sys::Array::new().into());
     };
+    search_loaded_index(&index, query, options)
+}
 
-    let frontmatter_str = rest[..end_pos].trim_start_matches('\n');
-    let content = rest[end_pos + 4..].trim_start_matches('\n');
-
-    for line in frontmatter_str.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') {
-            continue;
-        }
+/// Searches a binary index built by `buildSearchIndexBinary`.
+#[wasm_bindgen(js_name = searchIndexBinary)]
+pub fn search_index_binary(
+    index: &[u8],
+    query: &str,
+    options: JsValue,
+) -> Result<JsValue, JsError> {
+    let Ok(index) = SearchIndex::from_bytes(index) else {
+        return Ok(js_sys::Array::new().into());
+    };
+    search_loaded_index(&index, query, options)
+}
 
-        if let Some(colon_pos) = line.find(':') {
-            let key = line[..colon_pos].trim().to_string();
-            let value_str = line[colon_pos + 1..].trim();
-
-            let value = if value_str == "true" {
-                serde_json::Value::Bool(true)
-            } else if value_str == "false" {
-                serde_json::Value::Bool(false)
-            } else if let Ok(n) = value_str.parse::<i64>() {
-                serde_json::Value::Number(n.into())
-            } else if let Ok(n) = value_str.parse::<f64>() {
-                serde_json::Number::from_f64(n).map_or_else(
-                    || serde_json::Value::String(value_str.to_string()),
-                    serde_json::Value::Number,
-                )
-            } else {
-                let s = value_str.trim_matches('"').trim_matches('\'');
-                serde_json::Value::String(s.to_string())
-            };
-
-            frontmatter.insert(key, value);
-        }
+/// Builds a search index from JavaScript documents.
+fn build_index(documents: JsValue) -> Result<SearchIndex, JsError> {
+    let documents: Vec<SearchDocument> = serde_wasm_bindgen::from_value(documents)?;
+    let mut builder = SearchIndexBuilder::new();
+    for document in documents {
+        builder.add_document(document);
     }
+    Ok(builder.build())
+}
+
+/// Runs a query against a loaded index.
+fn search_loaded_index(
+    index: &SearchIndex,
+    query: &str,
+    options: JsValue,
+) -> Result<JsValue, JsError> {
+    let options: SearchOptions = if options.is_undefined() || options.is_null() {
+        SearchOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(options)?
+    };
+    Ok(serde_wasm_bindgen::to_value(&index.search(query, &options))?)
+}
 
-    (content.to_string(), frontmatter)
+/// Returns the version of ox_content_wasm.
+#[wasm_bindgen]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
 }
 
-/// Extracts table of contents from document headings.
-fn extract_toc(doc: &Document, max_depth: u8) -> Vec<TocEntry> {
+/// Extracts table of contents from the headings from `min_depth` to
+/// `max_depth`.
+fn extract_toc(doc: &Document, min_depth: u8, max_depth: u8) -> Vec<TocEntry> {
     let mut entries = Vec::new();
+    let mut slugs = UniqueSlugs::new();
 
     for node in &doc.children {
         if let Node::Heading(heading) = node {
-            if heading.depth <= max_depth {
-                let text = extract_heading_text(heading);
-                let slug = slugify(&text);
+            let text = heading_text(heading);
+            let slug = slugs.unique(heading_slug(heading, SlugStyle::GitHub));
+            if (min_depth..=max_depth).contains(&heading.depth) {
                 entries.push(TocEntry { depth: heading.depth, text, slug });
             }
         }
@@ -245,52 +311,3 @@ fn extract_toc(doc: &Document, max_depth: u8) -> Vec<TocEntry> {
 
     entries
 }
-
-/// Extracts plain text from a heading node.
-fn extract_heading_text(heading: &Heading) -> String {
-    let mut text = String::new();
-    for child in &heading.children {
-        collect_text(child, &mut text);
-    }
-    text
-}
-
-/// Recursively collects text from nodes.
-fn collect_text(node: &Node, text: &mut String) {
-    match node {
-        Node::Text(t) => text.push_str(t.value),
-        Node::Emphasis(e) => {
-            for child in &e.children {
-                collect_text(child, text);
-            }
-        }
-        Node::Strong(s) => {
-            for child in &s.children {
-                collect_text(child, text);
-            }
-        }
-        Node::InlineCode(c) => text.push_str(c.value),
-        Node::Delete(d) => {
-            for child in &d.children {
-                collect_text(child, text);
-            }
-        }
-        Node::Link(l) => {
-            for child in &l.children {
-                collect_text(child, text);
-            }
-        }
-        _ => {}
-    }
-}
-
-/// Converts text to URL-friendly slug.
-fn slugify(text: &str) -> String {
-    text.to_lowercase()
-        .chars()
-        .map(|c| if c.is_alphanumeric() || c == ' ' || c == '-' { c } else { ' ' })
-        .collect::<String>()
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join("-")
-}
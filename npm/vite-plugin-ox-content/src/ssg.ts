@@ -32,6 +32,11 @@ export interface SsgPageData {
   description?: string;
   content: string;
   toc: TocEntry[];
+  /**
+   * Show the "On this page" aside, even when `toc` is empty.
+   * @default true
+   */
+  tocEnabled?: boolean;
   frontmatter: Record<string, unknown>;
   path: string;
   href: string;
@@ -402,6 +407,8 @@ export const DEFAULT_HTML_TEMPLATE = `<!DOCTYPE html>
       margin: 1.5rem 0;
       font-size: 0.875rem;
     }
+    .content .table-wrapper { overflow-x: auto; margin: 1.5rem 0; }
+    .content .table-wrapper table { margin: 0; }
     .content th, .content td {
       border: 1px solid var(--color-border);
       padding: 0.75rem 1rem;
@@ -946,7 +953,9 @@ export async function generateHtmlPage(
       description: pageData.description,
       content: pageData.content,
       toc: tocForRust,
+      tocEnabled: pageData.tocEnabled,
       path: pageData.path,
+      lang: typeof pageData.frontmatter.lang === 'string' ? pageData.frontmatter.lang : undefined,
     },
     navGroupsForRust,
     {
@@ -1249,6 +1258,7 @@ export async function buildSsg(
               height: options.ogImageOptions.height,
               backgroundColor: options.ogImageOptions.background,
               textColor: options.ogImageOptions.textColor,
+              layout: options.ogImageOptions.layout,
             }
           : undefined;
 
@@ -1274,6 +1284,7 @@ export async function buildSsg(
           description,
           content: result.html,
           toc: result.toc,
+          tocEnabled: options.toc,
           frontmatter: result.frontmatter,
           path: getUrlPath(inputPath, srcDir),
           href: getHref(inputPath, srcDir, base, ssgOptions.extension),
@@ -107,6 +107,8 @@ export interface OgImageConfig {
   titleFontSize?: number;
   /** Description font size */
   descriptionFontSize?: number;
+  /** Card layout */
+  layout?: 'default' | 'centered' | 'split' | 'minimal' | 'card';
 }
 
 /**
@@ -261,6 +261,12 @@ export interface OgImageOptions {
    * @default 630
    */
   height?: number;
+
+  /**
+   * Card layout.
+   * @default 'default'
+   */
+  layout?: 'default' | 'centered' | 'split' | 'minimal' | 'card';
 }
 
 /**